@@ -241,6 +241,7 @@ export async function POST() {
           .createEscrow(seed, amount, milestones, expiresAt)
           .accounts({
             maker: buyer.publicKey,
+            payer: buyer.publicKey,
             taker: seller.publicKey,
             escrowConfig: configPDA,
             mint,
@@ -277,6 +278,7 @@ export async function POST() {
           .mintReceipt()
           .accounts({
             beneficiary: seller.publicKey,
+            payer: seller.publicKey,
             escrowState: escrowPDA,
             receiptMint,
             beneficiaryReceiptAta,
@@ -177,6 +177,7 @@ function formatEscrowState(escrow: Awaited<ReturnType<EscrowClient["fetchEscrow"
     console.log(`\nDispute:`);
     console.log(`  Initiator:    ${d.initiator.toBase58()}`);
     console.log(`  Initiated At: ${new Date(d.initiatedAt.toNumber() * 1000).toISOString()}`);
+    console.log(`  Deadline:     ${new Date(d.deadline.toNumber() * 1000).toISOString()}`);
     if (d.resolution) {
       console.log(`  Resolution:   ${statusLabel(d.resolution)}`);
     } else {
@@ -210,6 +211,7 @@ interface RawMilestone {
   amount: number | string;
   description?: string;
   descriptionHash?: number[];
+  beneficiary?: string;
 }
 
 function parseMilestones(raw: string): MilestoneInput[] {
@@ -243,7 +245,13 @@ function parseMilestones(raw: string): MilestoneInput[] {
       descriptionHash = new Array(32).fill(0);
     }
 
-    return { amount, descriptionHash };
+    return {
+      amount,
+      descriptionHash,
+      feeExempt: m.feeExempt === true,
+      selfCertify: m.selfCertify === true,
+      beneficiary: m.beneficiary ? new PublicKey(m.beneficiary) : null,
+    };
   });
 }
 
@@ -1,8 +1,11 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import {
+  Ed25519Program,
   PublicKey,
   SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  TransactionInstruction,
   TransactionSignature,
 } from "@solana/web3.js";
 import {
@@ -14,8 +17,30 @@ import {
   findEscrowConfigPDA,
   findEscrowPDA,
   findReceiptMintPDA,
+  findUnwrapPDA,
+  findShareMintPDA,
+  findProposalPDA,
+  findEscrowMetadataPDA,
+  findEscrowRequestPDA,
+  findKeeperPDA,
+  findMakerProfilePDA,
+  findApprovalPDA,
+  findEscrowArchivePDA,
+  findHoldingPDA,
+  findCrankSchedulePDA,
+  findEmergencyWithdrawalPDA,
+  findListingPDA,
+  proposalTermsHash,
   findMetadataPDA,
   findMasterEditionPDA,
+  buildApprovalMessage,
+  deriveEscrowSeed,
+  DISPUTE_INITIATORS_ALL,
+  ESCROW_CATEGORY_OFFSET,
+  LISTING_CATEGORY_OFFSET,
+  MAX_BATCH_PROGRESS_ACCOUNTS,
+  SETTLEMENT_HOLDING_INDEX,
+  HASH_ALGO_SHA256,
   TOKEN_METADATA_PROGRAM_ID,
 } from "./pda";
 
@@ -32,6 +57,19 @@ type AnchorProvider = anchor.AnchorProvider;
 export interface MilestoneInput {
   amount: BN;
   descriptionHash: number[]; // [u8; 32]
+  feeExempt: boolean;
+  selfCertify: boolean;
+  beneficiary: PublicKey | null; // overrides the escrow beneficiary for this milestone
+}
+
+/** Negotiable terms stored on a `Proposal` and funded by `acceptProposal`. */
+export interface EscrowTerms {
+  amount: BN;
+  milestones: MilestoneInput[];
+  expiresAt: BN;
+  category: number;
+  initialBeneficiary: PublicKey | null;
+  hashAlgo: number;
 }
 
 export type EscrowStatus =
@@ -39,7 +77,9 @@ export type EscrowStatus =
   | { completed: Record<string, never> }
   | { disputed: Record<string, never> }
   | { cancelled: Record<string, never> }
-  | { expired: Record<string, never> };
+  | { expired: Record<string, never> }
+  | { awaitingAcceptance: Record<string, never> }
+  | { awaitingFunding: Record<string, never> };
 
 export type MilestoneStatus =
   | { pending: Record<string, never> }
@@ -51,6 +91,18 @@ export interface Milestone {
   amount: BN;
   descriptionHash: number[];
   status: MilestoneStatus;
+  approvedAt: BN;
+  subEscrow: PublicKey | null;
+  heldUntil: BN;
+  feeExempt: boolean;
+  selfCertify: boolean;
+  feeReserved: BN;
+  beneficiary: PublicKey | null;
+}
+
+export interface SubEscrowLink {
+  escrow: PublicKey;
+  milestoneIndex: number;
 }
 
 export type DisputeResolution =
@@ -58,12 +110,30 @@ export type DisputeResolution =
   | { takerWins: Record<string, never> }
   | { split: { makerBps: number } };
 
+export interface ResolutionBreakdown {
+  remaining: BN;
+  makerAmount: BN;
+  beneficiaryAmount: BN;
+  fee: BN;
+}
+
+export interface EscrowProgress {
+  escrow: PublicKey;
+  status: EscrowStatus;
+  releasedBps: number;
+  settledMilestones: number;
+  milestoneCount: number;
+}
+
 export interface Dispute {
   initiator: PublicKey;
   reasonHash: number[];
   initiatedAt: BN;
   timeout: BN;
+  deadline: BN;
   resolution: DisputeResolution | null;
+  resolvedBy: PublicKey;
+  resolvedAt: BN;
 }
 
 export interface EscrowState {
@@ -71,11 +141,14 @@ export interface EscrowState {
   taker: PublicKey;
   beneficiary: PublicKey;
   mint: PublicKey;
+  vault: PublicKey;
+  tokenProgram: PublicKey;
   amount: BN;
   releasedAmount: BN;
   refundedAmount: BN;
   seed: BN;
   status: EscrowStatus;
+  category: number;
   milestones: Milestone[];
   createdAt: BN;
   expiresAt: BN;
@@ -83,6 +156,149 @@ export interface EscrowState {
   feeBpsAtCreation: number;
   bump: number;
   receiptMint: PublicKey | null;
+  releaseDelay: BN;
+  onHold: boolean;
+  beneficiaryAta: PublicKey | null;
+  renewalCount: number;
+  approvalNonce: BN;
+  disputeCount: number;
+  hashAlgo: number;
+  creator: PublicKey;
+  acceptanceDeadline: BN;
+  nonce: BN;
+  shareMint: PublicKey | null;
+  claimPool: BN;
+  metadata: PublicKey | null;
+  feesPaid: BN;
+  parent: SubEscrowLink | null;
+  autoRelease: boolean;
+  crankTip: BN;
+  tipJar: BN;
+  clawBackSeconds: BN;
+  feesRefunded: BN;
+  approvalExtension: BN;
+  expiryExtensionBudget: BN;
+  fundsDistributed: boolean;
+  remainderPolicy: RemainderPolicy;
+  disputesEnabled: boolean;
+  countedOpen: boolean;
+  disputeInitiators: number;
+  expiryExtensionPerApproval: BN;
+  voidedReceiptMint: PublicKey | null;
+  flatFeeAtCreation: BN;
+  feeOnTop: boolean;
+  receiptMetadataMutable: boolean;
+  cancelFeeBpsAtCreation: number;
+  cancelFeeRecipient: CancelFeeRecipient;
+  agreementHash: number[] | null;
+  agreementAcknowledged: boolean;
+  agreementAcknowledgedAt: BN;
+  feeFreeBelowAtCreation: BN;
+  takerBond: BN;
+  bondBalance: BN;
+  settlementHeldUntil: BN;
+  /** Config `cancelRoutes` at creation, indexed by `CancelReason`. */
+  cancelRoutesAtCreation: CancelRouting[];
+}
+
+export interface EscrowMetadata {
+  escrow: PublicKey;
+  data: Buffer;
+  bump: number;
+}
+
+export interface Proposal {
+  maker: PublicKey;
+  taker: PublicKey;
+  mint: PublicKey;
+  seed: BN;
+  creator: PublicKey;
+  terms: EscrowTerms;
+  makerTurn: boolean;
+  revision: number;
+  expiresAt: BN;
+  bump: number;
+}
+
+export interface EscrowRequest {
+  taker: PublicKey;
+  maker: PublicKey;
+  mint: PublicKey;
+  seed: BN;
+  terms: EscrowTerms;
+  expiresAt: BN;
+  bump: number;
+}
+
+export interface MakerProfile {
+  maker: PublicKey;
+  openEscrows: number;
+  bump: number;
+}
+
+export interface EscrowArchive {
+  escrow: PublicKey;
+  maker: PublicKey;
+  taker: PublicKey;
+  beneficiary: PublicKey;
+  mint: PublicKey;
+  amount: BN;
+  releasedAmount: BN;
+  refundedAmount: BN;
+  feesPaid: BN;
+  status: EscrowStatus;
+  createdAt: BN;
+  closedAt: BN;
+  bump: number;
+}
+
+export interface Approval {
+  escrow: PublicKey;
+  approver: PublicKey;
+  actionHash: number[];
+  nonce: BN;
+  expiresAt: BN;
+  bump: number;
+}
+
+export interface Keeper {
+  keeper: PublicKey;
+  bond: BN;
+  registeredAt: BN;
+  cranks: BN;
+  tipsEarned: BN;
+  bump: number;
+}
+
+export interface CrankSchedule {
+  escrow: PublicKey;
+  creator: PublicKey;
+  /** `{ releaseMilestone: { index, interval } }`, `{ claimActiveExpired: {} }` or `{ claimDisputeTimeout: {} }`. */
+  kind: object;
+  eta: BN;
+  executions: number;
+  bump: number;
+}
+
+export interface Listing {
+  escrow: PublicKey;
+  maker: PublicKey;
+  mint: PublicKey;
+  category: number;
+  amount: BN;
+  titleHash: number[];
+  listedAt: BN;
+  bump: number;
+}
+
+export interface EmergencyWithdrawal {
+  escrow: PublicKey;
+  proposedBy: PublicKey;
+  recovery: PublicKey;
+  proposedAt: BN;
+  executableAt: BN;
+  bump: number;
+  escrowCreatedAt: BN;
 }
 
 export interface EscrowConfig {
@@ -91,8 +307,125 @@ export interface EscrowConfig {
   feeCollector: PublicKey;
   disputeTimeout: BN;
   bump: number;
+  releaseDelay: BN;
+  maxDisputesPerEscrow: number;
+  acceptedHashAlgos: number;
+  closeDustThreshold: BN;
+  disputeTimeoutPolicy: DisputeTimeoutPolicy;
+  requestTtl: BN;
+  clawBackSeconds: BN;
+  approvalExtension: BN;
+  maxExpiryExtension: BN;
+  remainderPolicy: RemainderPolicy;
+  allowFeeExempt: boolean;
+  maxOpenEscrowsPerMaker: number;
+  expiryExtensionPerApproval: BN;
+  flatFee: BN;
+  flatFeeMint: PublicKey;
+  cancelFeeBps: number;
+  cancelFeeRecipient: CancelFeeRecipient;
+  receiptsEnabled: boolean;
+  feeFreeBelow: BN;
+  maxMilestoneSkewBps: number;
+  configVersion: number;
+  creationPaused: boolean;
+  /** Refund routing per `CancelReason`, in declaration order; entry 0 mirrors `cancelFeeBps`. */
+  cancelRoutes: CancelRouting[];
+}
+
+/** Returned by `verify_receipt`: what a receipt NFT currently entitles its holder to. */
+export interface ReceiptVerification {
+  escrow: PublicKey;
+  receiptMint: PublicKey;
+  beneficiary: PublicKey;
+  remainingAmount: BN;
+  nonce: BN;
+}
+
+/** Returned by `get_version`; `featureBits` holds the `FEATURE_*` flags from pda.ts. */
+export interface ProgramVersion {
+  major: number;
+  minor: number;
+  patch: number;
+  featureBits: BN;
+}
+
+/** Versioned config view returned by `get_config`; fields are only appended. */
+export interface EffectiveConfig {
+  version: number;
+  authority: PublicKey;
+  feeBps: number;
+  feeCollector: PublicKey;
+  disputeTimeout: BN;
+  releaseDelay: BN;
+  maxDisputesPerEscrow: number;
+  acceptedHashAlgos: number;
+  closeDustThreshold: BN;
+  disputeTimeoutPolicy: DisputeTimeoutPolicy;
+  requestTtl: BN;
+  clawBackSeconds: BN;
+  approvalExtension: BN;
+  maxExpiryExtension: BN;
+  remainderPolicy: RemainderPolicy;
+  allowFeeExempt: boolean;
+  maxOpenEscrowsPerMaker: number;
+  expiryExtensionPerApproval: BN;
+  flatFee: BN;
+  flatFeeMint: PublicKey;
+  minExpirationDuration: BN;
+  maxClawBackSeconds: BN;
+  renewalWindow: BN;
+  maxMilestones: number;
+  // Version 2
+  cancelFeeBps: number;
+  cancelFeeRecipient: CancelFeeRecipient;
+  // Version 3
+  receiptsEnabled: boolean;
+  // Version 4
+  feeFreeBelow: BN;
+  // Version 5
+  maxMilestoneSkewBps: number;
+  // Version 6
+  configVersion: number;
+  creationPaused: boolean;
+  // Version 7
+  cancelRoutes: CancelRouting[];
+}
+
+export type DisputeTimeoutPolicy =
+  | { splitOnTimeout: {} }
+  | { favorNonInitiator: {} };
+
+export type CancelReason =
+  | { makerCancel: {} }
+  | { disputeRuling: {} }
+  | { expiry: {} }
+  | { disputeTimeout: {} };
+
+export type FeeRefundRecipient = { beneficiary: {} } | { maker: {} };
+
+export type CancelFeeRecipient = { feeCollector: {} } | { beneficiary: {} };
+
+/** Share of a cancellation refund withheld from the maker, and who receives it. */
+export interface CancelRouting {
+  feeBps: number;
+  feeRecipient: CancelFeeRecipient;
+}
+
+/** `updateConfig` argument replacing the routing of one `CancelReason`. */
+export interface CancelRouteUpdate {
+  reason: CancelReason;
+  routing: CancelRouting;
 }
 
+export type RemainderPolicy =
+  | { toMaker: {} }
+  | { toBeneficiary: {} }
+  | { toFeeCollector: {} };
+
+/** Where an escrow stands relative to its expiry, for "claimable now" vs "already claimed" UIs. */
+export type ExpiryState = "notExpired" | "claimable" | "settled";
+
 // ─── Client ───────────────────────────────────────────────────────────────────
 
 export class EscrowClient {
@@ -129,7 +462,19 @@ export class EscrowClient {
 
   /**
    * Create a new escrow. Caller is the maker.
-   * milestones: array of { amount: BN, descriptionHash: number[] (32 bytes) }
+   * milestones: array of { amount: BN, descriptionHash: number[] (32 bytes), feeExempt: boolean, selfCertify: boolean,
+   *   beneficiary: PublicKey | null (pays that milestone's releases instead of the escrow beneficiary) }
+   * initialBeneficiary: payout recipient if different from the taker (defaults to taker)
+   * hashAlgo: convention used for milestone/reason hashes (HASH_ALGO_SHA256 matches createDescriptionHash)
+   * acceptanceDeadline: if set, the escrow awaits `acceptEscrow` by the taker until this time
+   * clawBackSeconds: hold each release this long before the beneficiary can withdraw it
+   *   (the config's window applies if longer; 0 keeps instant payouts)
+   * disputesEnabled: false opts out of arbitration; disputes, holds and clawbacks are rejected
+   * disputeInitiators: DISPUTE_INITIATOR_* bits of the roles allowed to open a dispute
+   * feeOnTop: fund each milestone's fee on top of its amount so the beneficiary
+   *   receives the full face value (the maker needs amount + fees)
+   * agreementHash: hash of an off-chain agreement; releases wait until the taker
+   *   calls `acknowledgeAgreement` with the same hash
    */
   async createEscrow(
     taker: PublicKey,
@@ -137,7 +482,18 @@ export class EscrowClient {
     seed: BN,
     amount: BN,
     milestones: MilestoneInput[],
-    expiresAt: BN
+    expiresAt: BN,
+    category = 0,
+    initialBeneficiary: PublicKey | null = null,
+    hashAlgo = HASH_ALGO_SHA256,
+    acceptanceDeadline: BN | null = null,
+    autoRelease = false,
+    clawBackSeconds: BN = new BN(0),
+    disputesEnabled = true,
+    disputeInitiators = DISPUTE_INITIATORS_ALL,
+    feeOnTop = false,
+    agreementHash: number[] | null = null,
+    takerBond: BN = new BN(0)
   ): Promise<TransactionSignature> {
     const maker = this.provider.wallet.publicKey;
     const [escrowStatePDA] = findEscrowPDA(maker, seed, this.program.programId);
@@ -145,9 +501,10 @@ export class EscrowClient {
     const makerTokenAccount = getAssociatedTokenAddressSync(mint, maker);
 
     return this.program.methods
-      .createEscrow(seed, amount, milestones, expiresAt)
+      .createEscrow(seed, amount, milestones, expiresAt, category, initialBeneficiary, hashAlgo, acceptanceDeadline, autoRelease, clawBackSeconds, disputesEnabled, disputeInitiators, feeOnTop, agreementHash, takerBond)
       .accounts({
         maker,
+        payer: maker,
         taker,
         mint,
         escrowState: escrowStatePDA,
@@ -161,240 +518,1224 @@ export class EscrowClient {
   }
 
   /**
-   * Approve a milestone. Caller must be the maker.
+   * Create an escrow whose seed is derived from (maker, taker, mint, externalRef),
+   * so its address can be recomputed later with `deriveEscrowSeed` + `findEscrowPDA`.
+   * externalRef: 32 bytes of business data (e.g. a hashed invoice id).
    */
-  async approveMilestone(
-    escrowPDA: PublicKey,
-    milestoneIndex: number
+  async createEscrowWithRef(
+    taker: PublicKey,
+    mint: PublicKey,
+    externalRef: number[],
+    amount: BN,
+    milestones: MilestoneInput[],
+    expiresAt: BN,
+    category = 0,
+    initialBeneficiary: PublicKey | null = null,
+    hashAlgo = HASH_ALGO_SHA256,
+    acceptanceDeadline: BN | null = null,
+    autoRelease = false,
+    clawBackSeconds: BN = new BN(0),
+    disputesEnabled = true,
+    disputeInitiators = DISPUTE_INITIATORS_ALL,
+    feeOnTop = false,
+    agreementHash: number[] | null = null,
+    takerBond: BN = new BN(0)
   ): Promise<TransactionSignature> {
+    const maker = this.provider.wallet.publicKey;
+    const seed = deriveEscrowSeed(maker, taker, mint, externalRef);
+    const [escrowStatePDA] = findEscrowPDA(maker, seed, this.program.programId);
+    const vault = getAssociatedTokenAddressSync(mint, escrowStatePDA, true);
+    const makerTokenAccount = getAssociatedTokenAddressSync(mint, maker);
+
     return this.program.methods
-      .approveMilestone(milestoneIndex)
+      .createEscrowWithRef(seed, amount, milestones, expiresAt, category, externalRef, initialBeneficiary, hashAlgo, acceptanceDeadline, autoRelease, clawBackSeconds, disputesEnabled, disputeInitiators, feeOnTop, agreementHash, takerBond)
       .accounts({
-        maker: this.provider.wallet.publicKey,
-        escrowState: escrowPDA,
+        maker,
+        payer: maker,
+        taker,
+        mint,
+        escrowState: escrowStatePDA,
+        vault,
+        makerTokenAccount,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
       })
       .rpc();
   }
 
   /**
-   * Release a milestone payment to the beneficiary. Anyone can crank this after approval.
+   * Start a new funding cycle on a Completed (or near-expiry) escrow.
+   * Caller is the maker; the current beneficiary must co-sign, or pass `null`
+   * to use the beneficiary's recorded approval for these exact arguments.
    */
-  async releaseMilestone(
+  async renewEscrow(
     escrowPDA: PublicKey,
-    milestoneIndex: number,
-    beneficiaryTokenAccount: PublicKey,
-    feeCollectorTokenAccount: PublicKey
+    beneficiary: anchor.web3.Keypair | null,
+    topUp: BN,
+    milestones: MilestoneInput[],
+    expiresAt: BN
   ): Promise<TransactionSignature> {
-    // Fetch escrow to get mint
+    const maker = this.provider.wallet.publicKey;
     const escrow = await this.fetchEscrow(escrowPDA);
     const mint = escrow.mint;
-    const [configPDA] = findEscrowConfigPDA(this.program.programId);
-    const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
+    const makerTokenAccount = getAssociatedTokenAddressSync(mint, maker, false, escrow.tokenProgram);
+    // A Completed escrow takes an open slot again; the profile is only needed under a cap
+    const [profilePDA] = findMakerProfilePDA(escrow.creator, this.program.programId);
+    const makerProfile =
+      "completed" in escrow.status && (await this.provider.connection.getAccountInfo(profilePDA)) ? profilePDA : null;
 
     return this.program.methods
-      .releaseMilestone(milestoneIndex)
+      .renewEscrow(topUp, milestones, expiresAt)
       .accounts({
-        payer: this.provider.wallet.publicKey,
+        maker,
+        beneficiary: beneficiary ? beneficiary.publicKey : null,
         escrowState: escrowPDA,
-        escrowConfig: configPDA,
+        escrowConfig: findEscrowConfigPDA(this.program.programId)[0],
         mint,
-        vault,
-        beneficiaryTokenAccount,
-        feeCollectorTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        vault: escrow.vault,
+        makerTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        approval: beneficiary
+          ? null
+          : findApprovalPDA(escrowPDA, escrow.beneficiary, this.program.programId)[0],
+        makerProfile,
       })
+      .signers(beneficiary ? [beneficiary] : [])
       .rpc();
   }
 
   /**
-   * Initiate a dispute. Caller must be maker, taker, or beneficiary.
-   * reasonHash: 32-byte array (e.g. sha256 of the reason text)
+   * Record the caller's consent to one mutual action (see `renewActionHash`)
+   * so the counterparty can execute it without a co-signature. Voided by any
+   * change to the escrow's nonce; caller must be the maker or beneficiary.
    */
-  async initiateDispute(
+  async recordApproval(
     escrowPDA: PublicKey,
-    reasonHash: number[]
+    actionHash: number[],
+    expiresAt: BN,
+    action: object = { renewEscrow: {} }
   ): Promise<TransactionSignature> {
-    const [configPDA] = findEscrowConfigPDA(this.program.programId);
-
+    const approver = this.provider.wallet.publicKey;
     return this.program.methods
-      .initiateDispute(reasonHash)
+      .recordApproval(action, actionHash, expiresAt)
       .accounts({
-        initiator: this.provider.wallet.publicKey,
+        approver,
         escrowState: escrowPDA,
-        escrowConfig: configPDA,
+        approval: findApprovalPDA(escrowPDA, approver, this.program.programId)[0],
+        systemProgram: SystemProgram.programId,
       })
       .rpc();
   }
 
-  /**
-   * Resolve a dispute. Caller must be the config authority.
-   */
-  async resolveDispute(
-    escrowPDA: PublicKey,
-    resolution: DisputeResolution,
-    makerTokenAccount: PublicKey,
-    beneficiaryTokenAccount: PublicKey,
-    feeCollectorTokenAccount: PublicKey
-  ): Promise<TransactionSignature> {
-    const escrow = await this.fetchEscrow(escrowPDA);
-    const mint = escrow.mint;
-    const [configPDA] = findEscrowConfigPDA(this.program.programId);
-    const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
-
+  /** Revoke the caller's approval on an escrow and reclaim its rent. */
+  async closeApproval(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    const approver = this.provider.wallet.publicKey;
     return this.program.methods
-      .resolveDispute(resolution)
+      .closeApproval()
       .accounts({
-        authority: this.provider.wallet.publicKey,
-        escrowConfig: configPDA,
-        escrowState: escrowPDA,
-        mint,
-        vault,
-        makerTokenAccount,
-        beneficiaryTokenAccount,
-        feeCollectorTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        approver,
+        approval: findApprovalPDA(escrowPDA, approver, this.program.programId)[0],
       })
       .rpc();
   }
 
   /**
-   * Cancel an active escrow. Caller must be the maker.
+   * Queue a permissionless crank for automation: `kind` fires on the escrow at
+   * or after `eta`. The caller pays the schedule's rent, which goes to whoever
+   * executes it last.
    */
-  async cancelEscrow(
-    escrowPDA: PublicKey,
-    makerTokenAccount: PublicKey
-  ): Promise<TransactionSignature> {
-    const escrow = await this.fetchEscrow(escrowPDA);
-    const mint = escrow.mint;
-    const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
-
+  async scheduleCrank(escrowPDA: PublicKey, kind: object, eta: BN): Promise<TransactionSignature> {
+    const creator = this.provider.wallet.publicKey;
     return this.program.methods
-      .cancelEscrow()
+      .scheduleCrank(kind, eta)
       .accounts({
-        maker: this.provider.wallet.publicKey,
+        creator,
         escrowState: escrowPDA,
-        mint,
-        vault,
-        makerTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        crankSchedule: findCrankSchedulePDA(escrowPDA, creator, kind, this.program.programId)[0],
+        systemProgram: SystemProgram.programId,
       })
       .rpc();
   }
 
   /**
-   * Claim funds from an expired escrow back to maker. Permissionless crank.
+   * Fire a due schedule. `target` is the scheduled instruction as the caller
+   * would send it directly (e.g. `releaseMilestone(...).instruction()` with the
+   * caller as `payer`); its accounts are forwarded, its data is not.
    */
-  async claimExpired(
-    escrowPDA: PublicKey,
-    makerTokenAccount: PublicKey,
-    beneficiaryTokenAccount: PublicKey,
-    feeCollectorTokenAccount: PublicKey
+  async executeScheduledCrank(
+    schedulePDA: PublicKey,
+    target: TransactionInstruction
   ): Promise<TransactionSignature> {
-    const escrow = await this.fetchEscrow(escrowPDA);
-    const mint = escrow.mint;
-    const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
-    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    return this.program.methods
+      .executeScheduledCrank()
+      .accounts({
+        executor: this.provider.wallet.publicKey,
+        crankSchedule: schedulePDA,
+      })
+      .remainingAccounts(target.keys)
+      .rpc();
+  }
 
+  /** Drop one of the caller's schedules and reclaim its rent. */
+  async cancelCrankSchedule(schedulePDA: PublicKey): Promise<TransactionSignature> {
     return this.program.methods
-      .claimExpired()
+      .cancelCrankSchedule()
       .accounts({
-        payer: this.provider.wallet.publicKey,
-        escrowState: escrowPDA,
-        escrowConfig: configPDA,
-        mint,
-        vault,
-        makerTokenAccount,
-        beneficiaryTokenAccount,
-        feeCollectorTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        creator: this.provider.wallet.publicKey,
+        crankSchedule: schedulePDA,
       })
       .rpc();
   }
 
   /**
-   * Transfer payment claim to a new beneficiary. Caller must be the current beneficiary.
+   * Accept an escrow created with an acceptance deadline, making it Active.
+   * Caller must be the taker or beneficiary.
    */
-  async transferClaim(
-    escrowPDA: PublicKey,
-    newBeneficiary: PublicKey
-  ): Promise<TransactionSignature> {
+  async acceptEscrow(escrowPDA: PublicKey): Promise<TransactionSignature> {
     return this.program.methods
-      .transferClaim()
+      .acceptEscrow()
       .accounts({
-        beneficiary: this.provider.wallet.publicKey,
+        acceptor: this.provider.wallet.publicKey,
         escrowState: escrowPDA,
-        newBeneficiary,
+        ...(await this.listingAccounts(escrowPDA)),
       })
       .rpc();
   }
 
   /**
-   * Mint a Receipt NFT for an escrow. Caller must be the current beneficiary.
-   * Only works on Active (non-expired) escrows.
+   * Publish an escrow awaiting acceptance in the on-chain listing registry.
+   * Caller must be the maker, who pays the listing rent.
    */
-  async mintReceipt(
-    escrowPDA: PublicKey
+  async listEscrow(
+    escrowPDA: PublicKey,
+    category: number,
+    titleHash: number[]
   ): Promise<TransactionSignature> {
-    const beneficiary = this.provider.wallet.publicKey;
-    const [receiptMint] = findReceiptMintPDA(escrowPDA, this.program.programId);
-    const [metadata] = findMetadataPDA(receiptMint);
-    const [masterEdition] = findMasterEditionPDA(receiptMint);
-    const beneficiaryReceiptAta = getAssociatedTokenAddressSync(receiptMint, beneficiary);
-
     return this.program.methods
-      .mintReceipt()
+      .listEscrow(category, titleHash)
       .accounts({
-        beneficiary,
+        maker: this.provider.wallet.publicKey,
         escrowState: escrowPDA,
-        receiptMint,
-        beneficiaryReceiptAta,
-        metadata,
-        masterEdition,
-        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        listing: findListingPDA(escrowPDA, this.program.programId)[0],
         systemProgram: SystemProgram.programId,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  }
+
+  /** Withdraw the escrow's listing and reclaim its rent. Caller must be the listing maker. */
+  async delistEscrow(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    return this.program.methods
+      .delistEscrow()
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        listing: findListingPDA(escrowPDA, this.program.programId)[0],
       })
       .rpc();
   }
 
   /**
-   * Sync the escrow beneficiary to the current Receipt NFT holder.
-   * Permissionless — anyone can call this after the NFT has been transferred.
+   * Close a listing whose escrow is closed, past its acceptance deadline or no
+   * longer awaiting acceptance. Permissionless; rent goes to the listing maker.
    */
-  async syncBeneficiary(
-    escrowPDA: PublicKey,
-    receiptTokenAccount: PublicKey
-  ): Promise<TransactionSignature> {
-    const [receiptMint] = findReceiptMintPDA(escrowPDA, this.program.programId);
+  async closeStaleListing(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    const [listingPDA] = findListingPDA(escrowPDA, this.program.programId);
+    const listing = await this.fetchListing(escrowPDA);
 
     return this.program.methods
-      .syncBeneficiary()
+      .closeStaleListing()
       .accounts({
         payer: this.provider.wallet.publicKey,
+        maker: listing.maker,
+        listing: listingPDA,
         escrowState: escrowPDA,
-        receiptMint,
-        receiptTokenAccount,
       })
       .rpc();
   }
 
   /**
-   * Revoke a burned Receipt NFT. Permissionless — anyone can call after the receipt is burned (supply == 0).
-   * This clears receipt_mint, re-enabling transfer_claim.
+   * Post the taker bond required at creation, which also accepts the escrow.
+   * Caller must be the taker.
    */
-  async revokeReceipt(
-    escrowPDA: PublicKey
-  ): Promise<TransactionSignature> {
-    const [receiptMint] = findReceiptMintPDA(escrowPDA, this.program.programId);
+  async postBond(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    const taker = this.provider.wallet.publicKey;
+    const escrow = await this.fetchEscrow(escrowPDA);
 
     return this.program.methods
-      .revokeReceipt()
+      .postBond()
       .accounts({
-        payer: this.provider.wallet.publicKey,
+        taker,
         escrowState: escrowPDA,
-        receiptMint,
+        mint: escrow.mint,
+        vault: escrow.vault,
+        takerTokenAccount: getAssociatedTokenAddressSync(escrow.mint, taker, false, escrow.tokenProgram),
+        tokenProgram: escrow.tokenProgram,
+        ...(await this.listingAccounts(escrowPDA)),
+      })
+      .rpc();
+  }
+
+  /**
+   * Pay out the taker bond of a terminal escrow: forfeited to the maker by a
+   * MakerWins ruling, split by a Split ruling's bps, returned to the taker
+   * otherwise. Permissionless; required before the escrow can be closed.
+   */
+  async settleBond(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+
+    return this.program.methods
+      .settleBond()
+      .accounts({
+        payer: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        mint: escrow.mint,
+        vault: escrow.vault,
+        makerTokenAccount: getAssociatedTokenAddressSync(escrow.mint, escrow.maker, true, escrow.tokenProgram),
+        takerTokenAccount: getAssociatedTokenAddressSync(escrow.mint, escrow.taker, true, escrow.tokenProgram),
+        tokenProgram: escrow.tokenProgram,
+      })
+      .rpc();
+  }
+
+  /**
+   * Acknowledge the agreement attached at creation. Caller must be the taker;
+   * `agreementHash` must be the hash of the document they reviewed.
+   */
+  async acknowledgeAgreement(escrowPDA: PublicKey, agreementHash: number[]): Promise<TransactionSignature> {
+    return this.program.methods
+      .acknowledgeAgreement(agreementHash)
+      .accounts({
+        taker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Approve a milestone. Caller must be the maker.
+   * Pass the reviewed amount / description hash to guard against index races;
+   * the zero defaults skip those checks. `expectedNonce` fails the call with
+   * StaleState if the escrow changed since it was read.
+   */
+  async approveMilestone(
+    escrowPDA: PublicKey,
+    milestoneIndex: number,
+    expectedAmount: BN = new BN(0),
+    expectedDescriptionHash: number[] = new Array(32).fill(0),
+    expectedNonce: BN | null = null
+  ): Promise<TransactionSignature> {
+    return this.program.methods
+      .approveMilestone(
+        milestoneIndex,
+        expectedAmount,
+        expectedDescriptionHash,
+        expectedNonce
+      )
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Approve a milestone the maker flagged `selfCertify` at creation.
+   * Caller must be the beneficiary; release then follows the normal flow.
+   */
+  async certifyMilestone(
+    escrowPDA: PublicKey,
+    milestoneIndex: number
+  ): Promise<TransactionSignature> {
+    return this.program.methods
+      .certifyMilestone(milestoneIndex)
+      .accounts({
+        beneficiary: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Relay a maker approval signed offline. `makerSignature` is the maker's
+   * ed25519 signature over `buildApprovalMessage(escrowPDA, milestoneIndex, nonce)`.
+   * Permissionless — the wallet only pays fees.
+   */
+  async approveMilestoneSigned(
+    escrowPDA: PublicKey,
+    milestoneIndex: number,
+    nonce: BN,
+    makerSignature: Uint8Array
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const verifyIx = Ed25519Program.createInstructionWithPublicKey({
+      publicKey: escrow.maker.toBytes(),
+      message: buildApprovalMessage(escrowPDA, milestoneIndex, nonce, this.program.programId),
+      signature: makerSignature,
+    });
+
+    return this.program.methods
+      .approveMilestoneSigned(milestoneIndex, nonce)
+      .accounts({
+        payer: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+      })
+      .preInstructions([verifyIx])
+      .rpc();
+  }
+
+  /**
+   * Revert an Approved milestone to Pending while its challenge window is open.
+   * Caller must be the maker.
+   */
+  async unapproveMilestone(
+    escrowPDA: PublicKey,
+    milestoneIndex: number
+  ): Promise<TransactionSignature> {
+    return this.program.methods
+      .unapproveMilestone(milestoneIndex)
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Release a milestone payment to the beneficiary. Anyone can crank this after approval.
+   * A milestone with its own `beneficiary` pays that account's owner instead.
+   * For wSOL escrows, `unwrapToNative` pays the beneficiary in native SOL; the caller
+   * fronts the scratch account's rent, which is forwarded to the beneficiary.
+   * `autoClose` (caller must be the maker) closes the escrow in the same
+   * transaction when this release completes it.
+   */
+  async releaseMilestone(
+    escrowPDA: PublicKey,
+    milestoneIndex: number,
+    beneficiaryTokenAccount: PublicKey,
+    feeCollectorTokenAccount: PublicKey,
+    unwrapToNative = false,
+    expectedNonce: BN | null = null,
+    autoClose = false
+  ): Promise<TransactionSignature> {
+    // Fetch escrow to get mint
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const mint = escrow.mint;
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    const vault = escrow.vault;
+
+    return this.program.methods
+      .releaseMilestone(milestoneIndex, expectedNonce, autoClose)
+      .accounts({
+        payer: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount,
+        feeCollectorTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        unwrapAccount: unwrapToNative
+          ? findUnwrapPDA(escrowPDA, this.program.programId)[0]
+          : null,
+        beneficiary: unwrapToNative ? escrow.milestones[milestoneIndex].beneficiary ?? escrow.beneficiary : null,
+        systemProgram:
+          unwrapToNative || !escrow.clawBackSeconds.isZero() ? SystemProgram.programId : null,
+        ...(await this.subEscrowAccounts(escrow, milestoneIndex)),
+        keeper: await this.keeperAccount(),
+        holdingAccount: this.holdingAccount(escrowPDA, escrow, milestoneIndex),
+        ...this.autoCloseAccounts(escrowPDA, escrow, autoClose ? escrow.maker : null),
+        makerProfile: this.makerProfileAccount(escrow),
+      })
+      .rpc();
+  }
+
+  /**
+   * Approve a pending milestone and pay it out in the same transaction.
+   * Caller must be the maker.
+   */
+  async approveAndRelease(
+    escrowPDA: PublicKey,
+    milestoneIndex: number,
+    beneficiaryTokenAccount: PublicKey,
+    feeCollectorTokenAccount: PublicKey
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const mint = escrow.mint;
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    const vault = escrow.vault;
+
+    return this.program.methods
+      .approveAndRelease(milestoneIndex)
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount,
+        feeCollectorTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        ...(await this.subEscrowAccounts(escrow, milestoneIndex)),
+        holdingAccount: this.holdingAccount(escrowPDA, escrow, milestoneIndex),
+        systemProgram: SystemProgram.programId,
+        makerProfile: this.makerProfileAccount(escrow),
+      })
+      .rpc();
+  }
+
+  /**
+   * Approve a milestone on an `autoRelease` escrow; it is paid out in the same
+   * instruction. Caller must be the maker.
+   */
+  async approveMilestoneAuto(
+    escrowPDA: PublicKey,
+    milestoneIndex: number,
+    beneficiaryTokenAccount: PublicKey,
+    feeCollectorTokenAccount: PublicKey,
+    expectedAmount: BN = new BN(0),
+    expectedDescriptionHash: number[] = new Array(32).fill(0),
+    expectedNonce: BN | null = null
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .approveMilestoneAuto(milestoneIndex, expectedAmount, expectedDescriptionHash, expectedNonce)
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint: escrow.mint,
+        vault: escrow.vault,
+        beneficiaryTokenAccount,
+        feeCollectorTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        ...(await this.subEscrowAccounts(escrow, milestoneIndex)),
+        holdingAccount: this.holdingAccount(escrowPDA, escrow, milestoneIndex),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  /** Claw-back holding to pass on release when the escrow has a claw-back window. */
+  private holdingAccount(
+    escrowPDA: PublicKey,
+    escrow: EscrowState,
+    milestoneIndex: number
+  ): PublicKey | null {
+    if (escrow.clawBackSeconds.isZero()) return null;
+    return findHoldingPDA(escrowPDA, milestoneIndex, this.program.programId)[0];
+  }
+
+  /**
+   * Remaining accounts a bulk settlement routes payouts to: under a claw-back
+   * window, the holdings it may open (one per Approved milestone, or the
+   * `SETTLEMENT_HOLDING_INDEX` slot for a dispute payout); otherwise the token
+   * accounts of Approved milestones with their own beneficiary.
+   */
+  private bulkSettlementAccounts(
+    escrowPDA: PublicKey,
+    escrow: EscrowState,
+    dispute: boolean
+  ): { pubkey: PublicKey; isWritable: boolean; isSigner: boolean }[] {
+    const approved = escrow.milestones.flatMap((m, i) => ("approved" in m.status ? [{ m, i }] : []));
+    let pubkeys: PublicKey[];
+    if (!escrow.clawBackSeconds.isZero()) {
+      pubkeys = (dispute ? [SETTLEMENT_HOLDING_INDEX] : approved.map(({ i }) => i)).map(
+        (i) => findHoldingPDA(escrowPDA, i, this.program.programId)[0]
+      );
+    } else {
+      pubkeys = dispute
+        ? []
+        : approved
+            .filter(({ m }) => m.beneficiary)
+            .map(({ m }) => getAssociatedTokenAddressSync(escrow.mint, m.beneficiary!, true, escrow.tokenProgram));
+    }
+    return pubkeys.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }));
+  }
+
+  /** Close accounts for an `autoClose` release; all null when `maker` is null. */
+  private autoCloseAccounts(
+    escrowPDA: PublicKey,
+    escrow: EscrowState,
+    maker: PublicKey | null
+  ): { maker: PublicKey | null; makerTokenAccount: PublicKey | null; escrowMetadata: PublicKey | null } {
+    if (!maker) return { maker: null, makerTokenAccount: null, escrowMetadata: null };
+    return {
+      maker,
+      makerTokenAccount: getAssociatedTokenAddressSync(escrow.mint, maker, false, escrow.tokenProgram),
+      escrowMetadata: escrow.metadata
+        ? findEscrowMetadataPDA(escrowPDA, this.program.programId)[0]
+        : null,
+    };
+  }
+
+  /**
+   * Withdraw a milestone's held payout once its claw-back window has passed.
+   * Caller must be the beneficiary.
+   */
+  async withdrawHeld(
+    escrowPDA: PublicKey,
+    milestoneIndex: number,
+    beneficiaryTokenAccount: PublicKey
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+
+    return this.program.methods
+      .withdrawHeld(milestoneIndex)
+      .accounts({
+        beneficiary: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        mint: escrow.mint,
+        holdingAccount: findHoldingPDA(escrowPDA, milestoneIndex, this.program.programId)[0],
+        beneficiaryTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+      })
+      .rpc();
+  }
+
+  /**
+   * Return a milestone's held payout to the maker while its claw-back window
+   * is open. Caller must be the beneficiary or the config authority.
+   */
+  async reclaimHeld(
+    escrowPDA: PublicKey,
+    milestoneIndex: number,
+    makerTokenAccount: PublicKey
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .reclaimHeld(milestoneIndex)
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint: escrow.mint,
+        holdingAccount: findHoldingPDA(escrowPDA, milestoneIndex, this.program.programId)[0],
+        makerTokenAccount,
+        beneficiary: escrow.beneficiary,
+        tokenProgram: escrow.tokenProgram,
+      })
+      .rpc();
+  }
+
+  /**
+   * Reverse every release on an escrow whose claw-back window is still open,
+   * returning the held payouts to the maker. Caller must be the config authority.
+   */
+  async clawback(
+    escrowPDA: PublicKey,
+    makerTokenAccount: PublicKey
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    const now = Math.floor(Date.now() / 1000);
+    const holdings = escrow.milestones
+      .map((m, i) => ({ heldUntil: m.heldUntil.toNumber(), i }))
+      .concat([{ heldUntil: escrow.settlementHeldUntil.toNumber(), i: SETTLEMENT_HOLDING_INDEX }])
+      .filter(({ heldUntil }) => heldUntil > now)
+      .map(({ i }) => ({
+        pubkey: findHoldingPDA(escrowPDA, i, this.program.programId)[0],
+        isWritable: true,
+        isSigner: false,
+      }));
+
+    return this.program.methods
+      .clawback()
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        mint: escrow.mint,
+        makerTokenAccount,
+        beneficiary: escrow.beneficiary,
+        tokenProgram: escrow.tokenProgram,
+      })
+      .remainingAccounts(holdings)
+      .rpc();
+  }
+
+  /** The wallet's keeper entry if it is registered, so cranks collect tips. */
+  private async keeperAccount(): Promise<PublicKey | null> {
+    const [keeperPDA] = findKeeperPDA(this.provider.wallet.publicKey, this.program.programId);
+    const info = await this.provider.connection.getAccountInfo(keeperPDA);
+    return info ? keeperPDA : null;
+  }
+
+  /** The creator's profile when the escrow is counted against the open-escrow cap. */
+  private makerProfileAccount(escrow: EscrowState): PublicKey | null {
+    return escrow.countedOpen ? findMakerProfilePDA(escrow.creator, this.program.programId)[0] : null;
+  }
+
+  /** Child escrow and vault to pass when releasing a milestone linked to a sub-escrow. */
+  private async subEscrowAccounts(
+    escrow: EscrowState,
+    milestoneIndex: number
+  ): Promise<{ subEscrow: PublicKey | null; subEscrowVault: PublicKey | null }> {
+    const child = escrow.milestones[milestoneIndex]?.subEscrow ?? null;
+    if (!child) return { subEscrow: null, subEscrowVault: null };
+    const childState = await this.fetchEscrow(child);
+    return { subEscrow: child, subEscrowVault: childState.vault };
+  }
+
+  /** Listing to close on acceptance, when the escrow has one. */
+  private async listingAccounts(
+    escrowPDA: PublicKey
+  ): Promise<{ listing: PublicKey | null; listingMaker: PublicKey | null }> {
+    const [listingPDA] = findListingPDA(escrowPDA, this.program.programId);
+    const listing = await this.program.account.listing.fetchNullable(listingPDA);
+    if (!listing) return { listing: null, listingMaker: null };
+    return { listing: listingPDA, listingMaker: listing.maker };
+  }
+
+  /**
+   * Initiate a dispute. Caller must be maker, taker, or beneficiary.
+   * reasonHash: 32-byte array (e.g. sha256 of the reason text)
+   * When the escrow has a Receipt NFT, pass the current receipt token account.
+   */
+  async initiateDispute(
+    escrowPDA: PublicKey,
+    reasonHash: number[],
+    receiptTokenAccount: PublicKey | null = null
+  ): Promise<TransactionSignature> {
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .initiateDispute(reasonHash)
+      .accounts({
+        initiator: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+      })
+      .remainingAccounts(
+        receiptTokenAccount
+          ? [{ pubkey: receiptTokenAccount, isSigner: false, isWritable: false }]
+          : []
+      )
+      .rpc();
+  }
+
+  /**
+   * Resolve a dispute. Caller must be the config authority.
+   * Passing `autoCloseMaker` (who co-signs) also closes the settled escrow to them.
+   * Passing `loserTokenAccount` charges the fee to the losing party instead of the
+   * escrow balance; its owner must first delegate the fee to the escrow PDA.
+   */
+  async resolveDispute(
+    escrowPDA: PublicKey,
+    resolution: DisputeResolution,
+    makerTokenAccount: PublicKey,
+    beneficiaryTokenAccount: PublicKey,
+    feeCollectorTokenAccount: PublicKey,
+    expectedNonce: BN | null = null,
+    autoCloseMaker: anchor.web3.Keypair | null = null,
+    loserTokenAccount: PublicKey | null = null
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const mint = escrow.mint;
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    const vault = escrow.vault;
+
+    return this.program.methods
+      .resolveDispute(resolution, expectedNonce, autoCloseMaker !== null, loserTokenAccount !== null)
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount,
+        beneficiaryTokenAccount,
+        feeCollectorTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        maker: autoCloseMaker?.publicKey ?? null,
+        escrowMetadata:
+          autoCloseMaker && escrow.metadata
+            ? findEscrowMetadataPDA(escrowPDA, this.program.programId)[0]
+            : null,
+        makerProfile: this.makerProfileAccount(escrow),
+        loserTokenAccount,
+      })
+      .remainingAccounts(this.bulkSettlementAccounts(escrowPDA, escrow, true))
+      .signers(autoCloseMaker ? [autoCloseMaker] : [])
+      .rpc();
+  }
+
+  /**
+   * Preview what a ruling would pay each party. Read-only; works in any status.
+   */
+  async simulateResolution(
+    escrowPDA: PublicKey,
+    resolution: DisputeResolution
+  ): Promise<ResolutionBreakdown> {
+    const raw = await this.program.methods
+      .simulateResolution(resolution)
+      .accounts({ escrowState: escrowPDA })
+      .view();
+    return raw as ResolutionBreakdown;
+  }
+
+  /**
+   * Check a receipt NFT before buying it: fails unless it is the escrow's
+   * current receipt with supply 1 and the escrow is still Active.
+   */
+  async verifyReceipt(escrowPDA: PublicKey): Promise<ReceiptVerification> {
+    const raw = await this.program.methods
+      .verifyReceipt()
+      .accounts({
+        escrowState: escrowPDA,
+        receiptMint: findReceiptMintPDA(escrowPDA, this.program.programId)[0],
+      })
+      .view();
+    return raw as ReceiptVerification;
+  }
+
+  /**
+   * Progress summaries for many escrows, in input order. Read-only; issues one
+   * simulated call per `MAX_BATCH_PROGRESS_ACCOUNTS` escrows.
+   */
+  async batchEscrowProgress(escrowPDAs: PublicKey[]): Promise<EscrowProgress[]> {
+    const summaries: EscrowProgress[] = [];
+    for (let i = 0; i < escrowPDAs.length; i += MAX_BATCH_PROGRESS_ACCOUNTS) {
+      const chunk = escrowPDAs.slice(i, i + MAX_BATCH_PROGRESS_ACCOUNTS);
+      const raw = await this.program.methods
+        .batchEscrowProgress()
+        .accounts({})
+        .remainingAccounts(chunk.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+        .view();
+      summaries.push(...(raw as EscrowProgress[]));
+    }
+    return summaries;
+  }
+
+  /**
+   * Cancel an active escrow. Caller must be the maker.
+   */
+  async cancelEscrow(
+    escrowPDA: PublicKey,
+    makerTokenAccount: PublicKey,
+    expectedNonce: BN | null = null
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const mint = escrow.mint;
+    const vault = escrow.vault;
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    // Cancelling before acceptance is always free
+    const routing = escrow.cancelRoutesAtCreation[0]; // MakerCancel
+    let cancelFeeTokenAccount: PublicKey | null = null;
+    if (routing.feeBps > 0 && !("awaitingAcceptance" in escrow.status)) {
+      const recipient =
+        "beneficiary" in routing.feeRecipient
+          ? escrow.beneficiary
+          : (await this.fetchConfig()).feeCollector;
+      cancelFeeTokenAccount = getAssociatedTokenAddressSync(mint, recipient, true, escrow.tokenProgram);
+    }
+
+    return this.program.methods
+      .cancelEscrow(expectedNonce)
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount,
+        escrowConfig: configPDA,
+        cancelFeeTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        makerProfile: this.makerProfileAccount(escrow),
+      })
+      .rpc();
+  }
+
+  /**
+   * Settle every open milestone in one transaction: refund Pending to the
+   * maker, pay Approved to the beneficiary minus fees. Caller must be the maker.
+   */
+  async cancelAndRelease(
+    escrowPDA: PublicKey,
+    makerTokenAccount: PublicKey,
+    beneficiaryTokenAccount: PublicKey,
+    feeCollectorTokenAccount: PublicKey,
+    expectedNonce: BN | null = null
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .cancelAndRelease(expectedNonce)
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint: escrow.mint,
+        vault: escrow.vault,
+        makerTokenAccount,
+        beneficiaryTokenAccount,
+        feeCollectorTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        makerProfile: this.makerProfileAccount(escrow),
+      })
+      .remainingAccounts(this.bulkSettlementAccounts(escrowPDA, escrow, false))
+      .rpc();
+  }
+
+  /**
+   * Claim funds from an expired escrow back to maker. Permissionless crank.
+   * @deprecated Use `claimActiveExpired` or `claimDisputeTimeout`.
+   */
+  async claimExpired(
+    escrowPDA: PublicKey,
+    makerTokenAccount: PublicKey,
+    beneficiaryTokenAccount: PublicKey,
+    feeCollectorTokenAccount: PublicKey
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const mint = escrow.mint;
+    const vault = escrow.vault;
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .claimExpired()
+      .accounts({
+        payer: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        makerTokenAccount,
+        beneficiaryTokenAccount,
+        feeCollectorTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        makerProfile: this.makerProfileAccount(escrow),
+      })
+      .remainingAccounts(this.bulkSettlementAccounts(escrowPDA, escrow, "disputed" in escrow.status))
+      .rpc();
+  }
+
+  /**
+   * Settle an Active escrow past its expiry: refund Pending milestones to the maker
+   * and pay Approved ones to the beneficiary. Permissionless crank. The beneficiary
+   * and fee collector accounts may be null when no milestone is Approved and the
+   * escrow's `expiry` cancel route withholds no fee.
+   */
+  async claimActiveExpired(
+    escrowPDA: PublicKey,
+    makerTokenAccount: PublicKey,
+    beneficiaryTokenAccount: PublicKey | null = null,
+    feeCollectorTokenAccount: PublicKey | null = null
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .claimActiveExpired()
+      .accounts({
+        payer: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint: escrow.mint,
+        vault: escrow.vault,
+        makerTokenAccount,
+        beneficiaryTokenAccount,
+        feeCollectorTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        keeper: await this.keeperAccount(),
+        makerProfile: this.makerProfileAccount(escrow),
+      })
+      .remainingAccounts(this.bulkSettlementAccounts(escrowPDA, escrow, false))
+      .rpc();
+  }
+
+  /**
+   * Settle a dispute whose deadline passed without a ruling, per the config's
+   * dispute timeout policy. Permissionless crank.
+   */
+  async claimDisputeTimeout(
+    escrowPDA: PublicKey,
+    makerTokenAccount: PublicKey,
+    beneficiaryTokenAccount: PublicKey,
+    feeCollectorTokenAccount: PublicKey
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .claimDisputeTimeout()
+      .accounts({
+        payer: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint: escrow.mint,
+        vault: escrow.vault,
+        makerTokenAccount,
+        beneficiaryTokenAccount,
+        feeCollectorTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        keeper: await this.keeperAccount(),
+        makerProfile: this.makerProfileAccount(escrow),
+      })
+      .remainingAccounts(this.bulkSettlementAccounts(escrowPDA, escrow, true))
+      .rpc();
+  }
+
+  /**
+   * Transfer payment claim to a new beneficiary. Caller must be the current beneficiary.
+   */
+  async transferClaim(
+    escrowPDA: PublicKey,
+    newBeneficiary: PublicKey,
+    expectedNonce: BN | null = null
+  ): Promise<TransactionSignature> {
+    return this.program.methods
+      .transferClaim(expectedNonce)
+      .accounts({
+        beneficiary: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        newBeneficiary,
+      })
+      .rpc();
+  }
+
+  /**
+   * Point a mis-assigned claim back at the original taker. Caller must be the
+   * maker; only allowed before any milestone is approved and without a receipt NFT.
+   */
+  async resetBeneficiary(
+    escrowPDA: PublicKey,
+    expectedNonce: BN | null = null
+  ): Promise<TransactionSignature> {
+    return this.program.methods
+      .resetBeneficiary(expectedNonce)
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Hand the maker role (refund rights, approval authority) to a new owner.
+   * Caller must be the current maker. The escrow address does not change.
+   */
+  async transferMaker(
+    escrowPDA: PublicKey,
+    newMaker: PublicKey
+  ): Promise<TransactionSignature> {
+    return this.program.methods
+      .transferMaker()
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        newMaker,
+      })
+      .rpc();
+  }
+
+  /**
+   * Mint a Receipt NFT for an escrow. Caller must be the current beneficiary.
+   * Only works on Active (non-expired) escrows.
+   */
+  async mintReceipt(
+    escrowPDA: PublicKey
+  ): Promise<TransactionSignature> {
+    const beneficiary = this.provider.wallet.publicKey;
+    const [receiptMint] = findReceiptMintPDA(escrowPDA, this.program.programId);
+    const [metadata] = findMetadataPDA(receiptMint);
+    const [masterEdition] = findMasterEditionPDA(receiptMint);
+    const beneficiaryReceiptAta = getAssociatedTokenAddressSync(receiptMint, beneficiary);
+
+    return this.program.methods
+      .mintReceipt()
+      .accounts({
+        beneficiary,
+        payer: beneficiary,
+        escrowState: escrowPDA,
+        escrowConfig: findEscrowConfigPDA(this.program.programId)[0],
+        receiptMint,
+        beneficiaryReceiptAta,
+        metadata,
+        masterEdition,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  }
+
+  /**
+   * Create and fund an escrow and mint its Receipt NFT to the beneficiary in one
+   * transaction. Caller is the maker; `beneficiary` must co-sign and match the
+   * escrow beneficiary (taker, or `initialBeneficiary` when set).
+   */
+  async createEscrowWithReceipt(
+    taker: PublicKey,
+    beneficiary: anchor.web3.Keypair,
+    mint: PublicKey,
+    seed: BN,
+    amount: BN,
+    milestones: MilestoneInput[],
+    expiresAt: BN,
+    category = 0,
+    initialBeneficiary: PublicKey | null = null,
+    hashAlgo = HASH_ALGO_SHA256
+  ): Promise<TransactionSignature> {
+    const maker = this.provider.wallet.publicKey;
+    const [escrowStatePDA] = findEscrowPDA(maker, seed, this.program.programId);
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    const vault = getAssociatedTokenAddressSync(mint, escrowStatePDA, true);
+    const makerTokenAccount = getAssociatedTokenAddressSync(mint, maker);
+    const [receiptMint] = findReceiptMintPDA(escrowStatePDA, this.program.programId);
+    const [metadata] = findMetadataPDA(receiptMint);
+    const [masterEdition] = findMasterEditionPDA(receiptMint);
+    const beneficiaryReceiptAta = getAssociatedTokenAddressSync(receiptMint, beneficiary.publicKey);
+
+    return this.program.methods
+      .createEscrowWithReceipt(seed, amount, milestones, expiresAt, category, initialBeneficiary, hashAlgo)
+      .accounts({
+        maker,
+        taker,
+        beneficiary: beneficiary.publicKey,
+        escrowConfig: configPDA,
+        mint,
+        escrowState: escrowStatePDA,
+        vault,
+        makerTokenAccount,
+        receiptMint,
+        beneficiaryReceiptAta,
+        metadata,
+        masterEdition,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([beneficiary])
+      .rpc();
+  }
+
+  /**
+   * Sync the escrow beneficiary to the current Receipt NFT holder.
+   * Permissionless — anyone can call this after the NFT has been transferred.
+   */
+  async syncBeneficiary(
+    escrowPDA: PublicKey,
+    receiptTokenAccount: PublicKey
+  ): Promise<TransactionSignature> {
+    const [receiptMint] = findReceiptMintPDA(escrowPDA, this.program.programId);
+
+    return this.program.methods
+      .syncBeneficiary()
+      .accounts({
+        payer: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        receiptMint,
+        receiptTokenAccount,
+        keeper: await this.keeperAccount(),
+      })
+      .rpc();
+  }
+
+  /**
+   * Revoke a burned Receipt NFT. Permissionless — anyone can call after the receipt is burned (supply == 0).
+   * This clears receipt_mint, re-enabling transfer_claim.
+   */
+  async revokeReceipt(
+    escrowPDA: PublicKey
+  ): Promise<TransactionSignature> {
+    const [receiptMint] = findReceiptMintPDA(escrowPDA, this.program.programId);
+
+    return this.program.methods
+      .revokeReceipt()
+      .accounts({
+        payer: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        receiptMint,
+      })
+      .rpc();
+  }
+
+  /**
+   * Detach a receipt NFT that is stuck (burned wallet, exchange) and assign the
+   * claim to `newBeneficiary`. Caller is the maker; `coSigner` is the current
+   * beneficiary, or the config authority (`asAuthority`) once the escrow has
+   * been expired for the recovery delay. The old receipt is voided for good.
+   */
+  async recoverReceipt(
+    escrowPDA: PublicKey,
+    newBeneficiary: PublicKey,
+    coSigner: anchor.web3.Keypair,
+    asAuthority: boolean = false
+  ): Promise<TransactionSignature> {
+    return this.program.methods
+      .recoverReceipt(newBeneficiary)
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        beneficiary: asAuthority ? null : coSigner.publicKey,
+        authority: asAuthority ? coSigner.publicKey : null,
+        escrowConfig: findEscrowConfigPDA(this.program.programId)[0],
+        escrowState: escrowPDA,
+      })
+      .signers([coSigner])
+      .rpc();
+  }
+
+  /**
+   * Split the claim into `totalShares` fungible shares minted to the caller
+   * (the beneficiary). From then on payouts accrue to the escrow's claim pool.
+   */
+  async fractionalizeClaim(
+    escrowPDA: PublicKey,
+    totalShares: BN
+  ): Promise<TransactionSignature> {
+    const beneficiary = this.provider.wallet.publicKey;
+    const [shareMint] = findShareMintPDA(escrowPDA, this.program.programId);
+
+    return this.program.methods
+      .fractionalizeClaim(totalShares)
+      .accounts({
+        beneficiary,
+        escrowState: escrowPDA,
+        shareMint,
+        beneficiaryShareAta: getAssociatedTokenAddressSync(shareMint, beneficiary),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  /**
+   * Burn `shares` of the caller's claim shares and withdraw their pro-rata
+   * portion of the funds accrued in the claim pool so far.
+   */
+  async redeemShares(
+    escrowPDA: PublicKey,
+    shares: BN,
+    holderTokenAccount: PublicKey
+  ): Promise<TransactionSignature> {
+    const holder = this.provider.wallet.publicKey;
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const [shareMint] = findShareMintPDA(escrowPDA, this.program.programId);
+
+    return this.program.methods
+      .redeemShares(shares)
+      .accounts({
+        holder,
+        escrowState: escrowPDA,
+        shareMint,
+        holderShareAccount: getAssociatedTokenAddressSync(shareMint, holder),
+        mint: escrow.mint,
+        vault: escrow.vault,
+        holderTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        shareTokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
   }
@@ -406,27 +1747,238 @@ export class EscrowClient {
     feeCollector: PublicKey,
     newAuthority?: PublicKey,
     feeBps?: number,
-    disputeTimeout?: BN
+    disputeTimeout?: BN,
+    releaseDelay?: BN,
+    maxDisputesPerEscrow?: number,
+    acceptedHashAlgos?: number,
+    closeDustThreshold?: BN,
+    disputeTimeoutPolicy?: DisputeTimeoutPolicy,
+    requestTtl?: BN,
+    clawBackSeconds?: BN,
+    approvalExtension?: BN,
+    maxExpiryExtension?: BN,
+    remainderPolicy?: RemainderPolicy,
+    allowFeeExempt?: boolean,
+    maxOpenEscrowsPerMaker?: number,
+    expiryExtensionPerApproval?: BN,
+    flatFee?: BN,
+    flatFeeMint?: PublicKey,
+    cancelFeeBps?: number,
+    cancelFeeRecipient?: CancelFeeRecipient,
+    receiptsEnabled?: boolean,
+    feeFreeBelow?: BN,
+    maxMilestoneSkewBps?: number,
+    creationPaused?: boolean,
+    cancelRoute?: CancelRouteUpdate
+  ): Promise<TransactionSignature> {
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .updateConfig(
+        newAuthority ?? null,
+        feeBps ?? null,
+        disputeTimeout ?? null,
+        releaseDelay ?? null,
+        maxDisputesPerEscrow ?? null,
+        acceptedHashAlgos ?? null,
+        closeDustThreshold ?? null,
+        disputeTimeoutPolicy ?? null,
+        requestTtl ?? null,
+        clawBackSeconds ?? null,
+        approvalExtension ?? null,
+        maxExpiryExtension ?? null,
+        remainderPolicy ?? null,
+        allowFeeExempt ?? null,
+        maxOpenEscrowsPerMaker ?? null,
+        expiryExtensionPerApproval ?? null,
+        flatFee ?? null,
+        flatFeeMint ?? null,
+        cancelFeeBps ?? null,
+        cancelFeeRecipient ?? null,
+        receiptsEnabled ?? null,
+        feeFreeBelow ?? null,
+        maxMilestoneSkewBps ?? null,
+        creationPaused ?? null,
+        cancelRoute ?? null
+      )
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        escrowConfig: configPDA,
+        feeCollector,
+      })
+      .rpc();
+  }
+
+  /**
+   * Upgrade a baseline config account to the current layout, defaulting every
+   * field added since. Caller must be the config authority and pays the rent
+   * for the added bytes.
+   */
+  async migrateConfig(): Promise<TransactionSignature> {
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .migrateConfig()
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        escrowConfig: configPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Rebate part of the fees collected on an escrow to its beneficiary or maker,
+   * paid from the fee collector's token account. Caller must be the config
+   * authority; the fee collector co-signs.
+   */
+  async refundFee(
+    escrowPDA: PublicKey,
+    amount: BN,
+    recipient: FeeRefundRecipient,
+    feeCollector: anchor.web3.Keypair,
+    feeCollectorTokenAccount: PublicKey,
+    recipientTokenAccount: PublicKey
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .refundFee(amount, recipient)
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        feeCollector: feeCollector.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        mint: escrow.mint,
+        feeCollectorTokenAccount,
+        recipientTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+      })
+      .signers([feeCollector])
+      .rpc();
+  }
+
+  /**
+   * Place a legal hold on an escrow. Caller must be the config authority.
+   */
+  async holdEscrow(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .holdEscrow()
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Lift a legal hold from an escrow. Caller must be the config authority.
+   */
+  async releaseHold(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .releaseHold()
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Propose draining an escrow's vault to `recovery` (a wallet) once the
+   * 7-day timelock has passed. Caller must be the config authority.
+   */
+  async proposeEmergencyWithdraw(
+    escrowPDA: PublicKey,
+    recovery: PublicKey
+  ): Promise<TransactionSignature> {
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .proposeEmergencyWithdraw(recovery)
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        emergencyWithdrawal: findEmergencyWithdrawalPDA(escrowPDA, this.program.programId)[0],
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  /**
+   * Execute a proposed emergency withdrawal after its timelock, moving the
+   * whole vault to `recoveryTokenAccount` (owned by the proposal's recovery
+   * address). Caller must be the config authority.
+   */
+  async executeEmergencyWithdraw(
+    escrowPDA: PublicKey,
+    recoveryTokenAccount: PublicKey
   ): Promise<TransactionSignature> {
     const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    const escrow = await this.fetchEscrow(escrowPDA);
+
+    return this.program.methods
+      .executeEmergencyWithdraw()
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        emergencyWithdrawal: findEmergencyWithdrawalPDA(escrowPDA, this.program.programId)[0],
+        mint: escrow.mint,
+        vault: escrow.vault,
+        recoveryTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        makerProfile: this.makerProfileAccount(escrow),
+      })
+      .rpc();
+  }
+
+  /**
+   * Withdraw a pending emergency-withdrawal proposal. Caller must be the
+   * config authority; the proposal's rent is returned to the caller.
+   */
+  async cancelEmergencyWithdraw(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .cancelEmergencyWithdraw()
+      .accounts({
+        authority: this.provider.wallet.publicKey,
+        escrowConfig: configPDA,
+        emergencyWithdrawal: findEmergencyWithdrawalPDA(escrowPDA, this.program.programId)[0],
+      })
+      .rpc();
+  }
+
+  /**
+   * Close an emergency-withdrawal proposal whose escrow is closed, terminal or
+   * re-created since. Permissionless; rent goes to the proposing authority.
+   */
+  async closeStaleEmergencyWithdraw(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    const proposal = await this.fetchEmergencyWithdrawal(escrowPDA);
 
     return this.program.methods
-      .updateConfig(
-        newAuthority ?? null,
-        feeBps ?? null,
-        disputeTimeout ?? null
-      )
+      .closeStaleEmergencyWithdraw()
       .accounts({
-        authority: this.provider.wallet.publicKey,
-        escrowConfig: configPDA,
-        feeCollector,
+        payer: this.provider.wallet.publicKey,
+        proposedBy: proposal.proposedBy,
+        emergencyWithdrawal: findEmergencyWithdrawalPDA(escrowPDA, this.program.programId)[0],
+        escrowState: escrowPDA,
       })
       .rpc();
   }
 
   /**
    * Close a terminal escrow account and reclaim rent. Caller must be the maker.
-   * Sweeps any dust in the vault back to maker before closing.
+   * Sweeps any dust in the vault back to maker before closing; dust below the
+   * config's `closeDustThreshold` is burned instead. Attached metadata is closed too.
    */
   async closeEscrow(
     escrowPDA: PublicKey,
@@ -434,17 +1986,363 @@ export class EscrowClient {
   ): Promise<TransactionSignature> {
     const escrow = await this.fetchEscrow(escrowPDA);
     const mint = escrow.mint;
-    const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
+    const vault = escrow.vault;
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
 
     return this.program.methods
       .closeEscrow()
       .accounts({
         maker: this.provider.wallet.publicKey,
         escrowState: escrowPDA,
+        escrowConfig: configPDA,
         mint,
         vault,
         makerTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        escrowMetadata: escrow.metadata,
+        ...this.receiptSealAccounts(escrowPDA, escrow),
+      })
+      .rpc();
+  }
+
+  /** Receipt metadata accounts a close must pass so the program can seal it. */
+  private receiptSealAccounts(escrowPDA: PublicKey, escrow: EscrowState) {
+    if (!escrow.receiptMetadataMutable) {
+      return { receiptMetadata: null, tokenMetadataProgram: null };
+    }
+    const [receiptMint] = findReceiptMintPDA(escrowPDA, this.program.programId);
+    return {
+      receiptMetadata: findMetadataPDA(receiptMint)[0],
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+    };
+  }
+
+  /**
+   * `closeEscrow`, but first records the escrow's final figures in an
+   * immutable `EscrowArchive` PDA. Caller must be the maker.
+   */
+  async archiveAndClose(
+    escrowPDA: PublicKey,
+    makerTokenAccount: PublicKey
+  ): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+
+    return this.program.methods
+      .archiveAndClose()
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowArchive: findEscrowArchivePDA(escrowPDA, this.program.programId)[0],
+        escrowConfig: configPDA,
+        mint: escrow.mint,
+        vault: escrow.vault,
+        makerTokenAccount,
+        tokenProgram: escrow.tokenProgram,
+        escrowMetadata: escrow.metadata,
+        ...this.receiptSealAccounts(escrowPDA, escrow),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  /** Reclaim an archive's rent once its one-year retention period has passed. */
+  async closeArchive(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    return this.program.methods
+      .closeArchive()
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowArchive: findEscrowArchivePDA(escrowPDA, this.program.programId)[0],
+      })
+      .rpc();
+  }
+
+  /**
+   * Attach an on-chain metadata blob (at most 1024 bytes) to an escrow.
+   * Caller must be the maker. Can only be done once per escrow.
+   */
+  async attachMetadata(
+    escrowPDA: PublicKey,
+    data: Buffer
+  ): Promise<TransactionSignature> {
+    const [escrowMetadata] = findEscrowMetadataPDA(escrowPDA, this.program.programId);
+
+    return this.program.methods
+      .attachMetadata(data)
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        escrowMetadata,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  /**
+   * Open a negotiation for the escrow at (maker, seed). Caller must be the
+   * maker or the taker; the other party responds next.
+   */
+  async proposeEscrow(
+    maker: PublicKey,
+    taker: PublicKey,
+    mint: PublicKey,
+    seed: BN,
+    terms: EscrowTerms,
+    expiresAt: BN
+  ): Promise<TransactionSignature> {
+    const [proposal] = findProposalPDA(maker, seed, this.program.programId);
+
+    return this.program.methods
+      .proposeEscrow(seed, terms, expiresAt)
+      .accounts({
+        proposer: this.provider.wallet.publicKey,
+        maker,
+        taker,
+        mint,
+        proposal,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  /**
+   * Replace the proposal's terms. Caller must be the party whose turn it is.
+   */
+  async counterProposal(
+    proposalPDA: PublicKey,
+    terms: EscrowTerms
+  ): Promise<TransactionSignature> {
+    return this.program.methods
+      .counterProposal(terms)
+      .accounts({
+        proposer: this.provider.wallet.publicKey,
+        proposal: proposalPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Accept the proposal and fund the escrow. Caller must be the party whose
+   * turn it is; the maker always signs as the funder. `reviewedTerms` defaults
+   * to the currently stored terms — pass the terms you reviewed to guard
+   * against a swap between fetch and send.
+   */
+  async acceptProposal(
+    proposalPDA: PublicKey,
+    reviewedTerms?: EscrowTerms,
+    maker?: anchor.web3.Signer
+  ): Promise<TransactionSignature> {
+    const proposal = await this.fetchProposal(proposalPDA);
+    const [escrowStatePDA] = findEscrowPDA(proposal.maker, proposal.seed, this.program.programId);
+    const vault = getAssociatedTokenAddressSync(proposal.mint, escrowStatePDA, true);
+
+    const builder = this.program.methods
+      .acceptProposal(proposalTermsHash(reviewedTerms ?? proposal.terms))
+      .accounts({
+        acceptor: this.provider.wallet.publicKey,
+        maker: proposal.maker,
+        taker: proposal.taker,
+        creator: proposal.creator,
+        proposal: proposalPDA,
+        mint: proposal.mint,
+        escrowState: escrowStatePDA,
+        vault,
+        makerTokenAccount: getAssociatedTokenAddressSync(proposal.mint, proposal.maker),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+    return maker ? builder.signers([maker]).rpc() : builder.rpc();
+  }
+
+  /**
+   * Withdraw a proposal and reclaim its rent. Caller must be its creator.
+   */
+  async closeProposal(proposalPDA: PublicKey): Promise<TransactionSignature> {
+    return this.program.methods
+      .closeProposal()
+      .accounts({
+        creator: this.provider.wallet.publicKey,
+        proposal: proposalPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Ask `maker` to fund an escrow on `terms`, with the caller as taker. The
+   * request lapses after the config's `requestTtl`.
+   */
+  async requestEscrow(
+    maker: PublicKey,
+    mint: PublicKey,
+    seed: BN,
+    terms: EscrowTerms
+  ): Promise<TransactionSignature> {
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    const [escrowRequest] = findEscrowRequestPDA(maker, seed, this.program.programId);
+
+    return this.program.methods
+      .requestEscrow(seed, terms)
+      .accounts({
+        requester: this.provider.wallet.publicKey,
+        maker,
+        mint,
+        escrowConfig: configPDA,
+        escrowRequest,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  /**
+   * Fund a pending request addressed to the caller, opening the escrow as
+   * maker with the requester as taker.
+   */
+  async fundRequest(seed: BN): Promise<TransactionSignature> {
+    const maker = this.provider.wallet.publicKey;
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    const [requestPDA] = findEscrowRequestPDA(maker, seed, this.program.programId);
+    const request = await this.fetchEscrowRequest(requestPDA);
+    const [escrowStatePDA] = findEscrowPDA(maker, seed, this.program.programId);
+
+    return this.program.methods
+      .fundRequest(seed)
+      .accounts({
+        maker,
+        taker: request.taker,
+        escrowRequest: requestPDA,
+        escrowConfig: configPDA,
+        mint: request.mint,
+        escrowState: escrowStatePDA,
+        vault: getAssociatedTokenAddressSync(request.mint, escrowStatePDA, true),
+        makerTokenAccount: getAssociatedTokenAddressSync(request.mint, maker),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  /**
+   * Withdraw a request and reclaim its rent. Caller must be the requester.
+   */
+  async cancelRequest(requestPDA: PublicKey): Promise<TransactionSignature> {
+    return this.program.methods
+      .cancelRequest()
+      .accounts({
+        requester: this.provider.wallet.publicKey,
+        escrowRequest: requestPDA,
+      })
+      .rpc();
+  }
+
+  /**
+   * Subcontract part of a parent milestone. Caller must be the parent's
+   * beneficiary; the child stays AwaitingFunding until the parent milestone is
+   * released, which moves `terms.amount` of the net payout into the child vault.
+   */
+  async createSubEscrow(
+    parentEscrowPDA: PublicKey,
+    parentMilestoneIndex: number,
+    subcontractor: PublicKey,
+    seed: BN,
+    terms: EscrowTerms
+  ): Promise<TransactionSignature> {
+    const maker = this.provider.wallet.publicKey;
+    const parent = await this.fetchEscrow(parentEscrowPDA);
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    const [escrowStatePDA] = findEscrowPDA(maker, seed, this.program.programId);
+
+    return this.program.methods
+      .createSubEscrow(seed, parentMilestoneIndex, terms)
+      .accounts({
+        maker,
+        taker: subcontractor,
+        parentEscrow: parentEscrowPDA,
+        escrowConfig: configPDA,
+        mint: parent.mint,
+        escrowState: escrowStatePDA,
+        vault: getAssociatedTokenAddressSync(parent.mint, escrowStatePDA, true, parent.tokenProgram),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: parent.tokenProgram,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  /**
+   * Abandon a sub-escrow that has not been funded yet and unlink its parent
+   * milestone. Caller must be the child's maker.
+   */
+  async cancelSubEscrow(escrowPDA: PublicKey): Promise<TransactionSignature> {
+    const escrow = await this.fetchEscrow(escrowPDA);
+    if (!escrow.parent) throw new Error("Not a sub-escrow");
+
+    return this.program.methods
+      .cancelSubEscrow()
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        parentEscrow: escrow.parent.escrow,
+      })
+      .rpc();
+  }
+
+  /**
+   * Register the wallet as a keeper, bonding `bond` lamports (may be 0).
+   * Registered keepers earn escrow tips for state-changing cranks.
+   */
+  async registerKeeper(bond: BN = new BN(0)): Promise<TransactionSignature> {
+    const keeper = this.provider.wallet.publicKey;
+    const [keeperAccount] = findKeeperPDA(keeper, this.program.programId);
+
+    return this.program.methods
+      .registerKeeper(bond)
+      .accounts({
+        keeper,
+        keeperAccount,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  /** Deregister the wallet as a keeper, reclaiming rent, bond and unclaimed tips. */
+  async deregisterKeeper(): Promise<TransactionSignature> {
+    const keeper = this.provider.wallet.publicKey;
+    const [keeperAccount] = findKeeperPDA(keeper, this.program.programId);
+
+    return this.program.methods
+      .deregisterKeeper()
+      .accounts({ keeper, keeperAccount })
+      .rpc();
+  }
+
+  /** Withdraw accumulated keeper tips without deregistering. */
+  async claimKeeperTips(): Promise<TransactionSignature> {
+    const keeper = this.provider.wallet.publicKey;
+    const [keeperAccount] = findKeeperPDA(keeper, this.program.programId);
+
+    return this.program.methods
+      .claimKeeperTips()
+      .accounts({ keeper, keeperAccount })
+      .rpc();
+  }
+
+  /**
+   * Set the per-crank keeper tip (lamports) and deposit `amount` lamports into
+   * the escrow's tip jar. Caller must be the maker. Leftovers return on close.
+   */
+  async fundTipJar(
+    escrowPDA: PublicKey,
+    crankTip: BN,
+    amount: BN
+  ): Promise<TransactionSignature> {
+    return this.program.methods
+      .fundTipJar(crankTip, amount)
+      .accounts({
+        maker: this.provider.wallet.publicKey,
+        escrowState: escrowPDA,
+        systemProgram: SystemProgram.programId,
       })
       .rpc();
   }
@@ -456,9 +2354,167 @@ export class EscrowClient {
     return raw as EscrowState;
   }
 
+  /**
+   * Classify an escrow against its expiry. `claimable` means it is still Active
+   * past `expires_at`; `settled` means an expiry claim already paid out the vault.
+   */
+  expiryState(escrow: EscrowState, now: number = Math.floor(Date.now() / 1000)): ExpiryState {
+    if (escrow.fundsDistributed) return "settled";
+    if ("active" in escrow.status && now > escrow.expiresAt.toNumber()) return "claimable";
+    return "notExpired";
+  }
+
+  /** All escrows tagged with `category`, filtered server-side via memcmp. */
+  async fetchEscrowsByCategory(
+    category: number
+  ): Promise<{ publicKey: PublicKey; account: EscrowState }[]> {
+    const raw = await this.program.account.escrowState.all([
+      {
+        memcmp: {
+          offset: ESCROW_CATEGORY_OFFSET,
+          bytes: anchor.utils.bytes.bs58.encode([category]),
+        },
+      },
+    ]);
+    return raw.map((r) => ({ publicKey: r.publicKey, account: r.account as EscrowState }));
+  }
+
+  /** Every crank schedule whose ETA has passed, oldest first: the automation queue. */
+  async fetchDueCrankSchedules(
+    now: number = Math.floor(Date.now() / 1000)
+  ): Promise<{ publicKey: PublicKey; account: CrankSchedule }[]> {
+    const raw = await this.program.account.crankSchedule.all();
+    return raw
+      .map((r) => ({ publicKey: r.publicKey, account: r.account as CrankSchedule }))
+      .filter((r) => r.account.eta.toNumber() <= now)
+      .sort((a, b) => a.account.eta.cmp(b.account.eta));
+  }
+
+  async fetchCrankSchedule(schedulePDA: PublicKey): Promise<CrankSchedule> {
+    const raw = await this.program.account.crankSchedule.fetch(schedulePDA);
+    return raw as CrankSchedule;
+  }
+
+  async fetchListing(escrowPDA: PublicKey): Promise<Listing> {
+    const [listingPDA] = findListingPDA(escrowPDA, this.program.programId);
+    const raw = await this.program.account.listing.fetch(listingPDA);
+    return raw as Listing;
+  }
+
+  /** Listed escrows, optionally only those tagged with `category` (memcmp filter). */
+  async fetchListings(
+    category: number | null = null
+  ): Promise<{ publicKey: PublicKey; account: Listing }[]> {
+    const filters =
+      category === null
+        ? []
+        : [
+            {
+              memcmp: {
+                offset: LISTING_CATEGORY_OFFSET,
+                bytes: anchor.utils.bytes.bs58.encode([category]),
+              },
+            },
+          ];
+    const raw = await this.program.account.listing.all(filters);
+    return raw.map((r) => ({ publicKey: r.publicKey, account: r.account as Listing }));
+  }
+
+  async fetchEmergencyWithdrawal(escrowPDA: PublicKey): Promise<EmergencyWithdrawal> {
+    const [proposalPDA] = findEmergencyWithdrawalPDA(escrowPDA, this.program.programId);
+    const raw = await this.program.account.emergencyWithdrawal.fetch(proposalPDA);
+    return raw as EmergencyWithdrawal;
+  }
+
+  async fetchMetadata(escrowPDA: PublicKey): Promise<EscrowMetadata> {
+    const [metadataPDA] = findEscrowMetadataPDA(escrowPDA, this.program.programId);
+    const raw = await this.program.account.escrowMetadata.fetch(metadataPDA);
+    return raw as EscrowMetadata;
+  }
+
+  async fetchProposal(proposalPDA: PublicKey): Promise<Proposal> {
+    const raw = await this.program.account.proposal.fetch(proposalPDA);
+    return raw as Proposal;
+  }
+
+  async fetchKeeper(keeperPDA: PublicKey): Promise<Keeper> {
+    const raw = await this.program.account.keeper.fetch(keeperPDA);
+    return raw as Keeper;
+  }
+
+  async fetchMakerProfile(maker: PublicKey): Promise<MakerProfile> {
+    const [profilePDA] = findMakerProfilePDA(maker, this.program.programId);
+    const raw = await this.program.account.makerProfile.fetch(profilePDA);
+    return raw as MakerProfile;
+  }
+
+  async fetchEscrowArchive(escrowPDA: PublicKey): Promise<EscrowArchive> {
+    const [archivePDA] = findEscrowArchivePDA(escrowPDA, this.program.programId);
+    const raw = await this.program.account.escrowArchive.fetch(archivePDA);
+    return raw as EscrowArchive;
+  }
+
+  async fetchApproval(escrowPDA: PublicKey, approver: PublicKey): Promise<Approval> {
+    const [approvalPDA] = findApprovalPDA(escrowPDA, approver, this.program.programId);
+    const raw = await this.program.account.approval.fetch(approvalPDA);
+    return raw as Approval;
+  }
+
+  async fetchEscrowRequest(requestPDA: PublicKey): Promise<EscrowRequest> {
+    const raw = await this.program.account.escrowRequest.fetch(requestPDA);
+    return raw as EscrowRequest;
+  }
+
   async fetchConfig(): Promise<EscrowConfig> {
     const [configPDA] = findEscrowConfigPDA(this.program.programId);
     const raw = await this.program.account.escrowConfig.fetch(configPDA);
     return raw as EscrowConfig;
   }
+
+  /**
+   * The effective config, including program-wide limits, via the `get_config`
+   * view. Prefer this over `fetchConfig` when only reading settings.
+   */
+  async getConfig(): Promise<EffectiveConfig> {
+    const [configPDA] = findEscrowConfigPDA(this.program.programId);
+    const raw = await this.program.methods
+      .getConfig()
+      .accounts({ escrowConfig: configPDA })
+      .view();
+    return raw as EffectiveConfig;
+  }
+
+  /**
+   * The deployed program's version and compiled-in subsystems via the
+   * `get_version` view. Works before the config is initialized.
+   */
+  async getVersion(): Promise<ProgramVersion> {
+    const raw = await this.program.methods
+      .getVersion()
+      .accounts({ systemProgram: SystemProgram.programId })
+      .view();
+    return raw as ProgramVersion;
+  }
+
+  /**
+   * The vault ATA `create_escrow` will initialize for `escrowPDA`, via the
+   * `derive_vault_address` view. Fails if `mint` isn't owned by `tokenProgram`.
+   */
+  async deriveVaultAddress(
+    escrowPDA: PublicKey,
+    mint: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+  ): Promise<PublicKey> {
+    const vault = await this.program.methods
+      .deriveVaultAddress(escrowPDA)
+      .accounts({ mint, tokenProgram })
+      .view();
+    return vault as PublicKey;
+  }
+
+  /** Whether the deployed program was built with `feature` (a `FEATURE_*` flag). */
+  async supportsFeature(feature: number): Promise<boolean> {
+    const { featureBits } = await this.getVersion();
+    return !featureBits.and(new BN(feature)).isZero();
+  }
 }
@@ -12,9 +12,59 @@ export const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
   "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
 );
 
+/** Byte offset of `EscrowState.category` for `getProgramAccounts` memcmp filters. */
+export const ESCROW_CATEGORY_OFFSET = 8 + 6 * 32 + 4 * 8 + 1;
+
+/** Byte offset of `Listing.category` for `getProgramAccounts` memcmp filters. */
+export const LISTING_CATEGORY_OFFSET = 8 + 3 * 32;
+
+/** `hash_algo` values: convention used for description/reason hashes. */
+export const HASH_ALGO_SHA256 = 0;
+export const HASH_ALGO_KECCAK256 = 1;
+
+/** `dispute_initiators` bits: which escrow roles may open a dispute. */
+export const DISPUTE_INITIATOR_MAKER = 1 << 0;
+export const DISPUTE_INITIATOR_TAKER = 1 << 1;
+export const DISPUTE_INITIATOR_BENEFICIARY = 1 << 2;
+export const DISPUTE_INITIATORS_ALL =
+  DISPUTE_INITIATOR_MAKER | DISPUTE_INITIATOR_TAKER | DISPUTE_INITIATOR_BENEFICIARY;
+
+/** Escrows per `batch_escrow_progress` call (1024-byte return-data limit). */
+export const MAX_BATCH_PROGRESS_ACCOUNTS = 27;
+
+/** Holding slot of a dispute payout held under a claw-back window (`findHoldingPDA` index). */
+export const SETTLEMENT_HOLDING_INDEX = 255;
+
+/** `EscrowConfig.configVersion` of the current account layout; older configs need `migrateConfig`. */
+export const CONFIG_VERSION = 3;
+
+/** `EffectiveConfig.version` this client was written against. */
+export const CONFIG_VIEW_VERSION = 7;
+
+/** Entries of `EscrowConfig.cancelRoutes`, one per `CancelReason` in declaration order. */
+export const CANCEL_REASON_COUNT = 4;
+
+/** `ProgramVersion.featureBits` flags (see programs/escrow/src/version.rs). */
+export const FEATURE_RECEIPTS = 1 << 0;
+export const FEATURE_TOKEN_2022 = 1 << 1;
+export const FEATURE_PULL_PAYMENTS = 1 << 2;
+
 const ESCROW_CONFIG_SEED = Buffer.from("escrow_config");
 const ESCROW_SEED = Buffer.from("escrow");
 const RECEIPT_SEED = Buffer.from("receipt");
+const UNWRAP_SEED = Buffer.from("unwrap");
+const SHARE_SEED = Buffer.from("shares");
+const PROPOSAL_SEED = Buffer.from("proposal");
+const METADATA_SEED = Buffer.from("escrow_metadata");
+const REQUEST_SEED = Buffer.from("escrow_request");
+const KEEPER_SEED = Buffer.from("keeper");
+const HOLDING_SEED = Buffer.from("holding");
+const MAKER_PROFILE_SEED = Buffer.from("maker_profile");
+const APPROVAL_SEED = Buffer.from("approval");
+const ARCHIVE_SEED = Buffer.from("archive");
+const CRANK_SCHEDULE_SEED = Buffer.from("crank_schedule");
+const EMERGENCY_WITHDRAW_SEED = Buffer.from("emergency_withdraw");
+const LISTING_SEED = Buffer.from("listing");
 
 // ─── PDA helpers ─────────────────────────────────────────────────────────────
 
@@ -45,6 +95,147 @@ export function findReceiptMintPDA(
   );
 }
 
+export function findUnwrapPDA(
+  escrowPDA: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [UNWRAP_SEED, escrowPDA.toBuffer()],
+    programId
+  );
+}
+
+export function findShareMintPDA(
+  escrowPDA: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SHARE_SEED, escrowPDA.toBuffer()],
+    programId
+  );
+}
+
+export function findProposalPDA(
+  maker: PublicKey,
+  seed: BN,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [PROPOSAL_SEED, maker.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+    programId
+  );
+}
+
+export function findEscrowMetadataPDA(
+  escrowPDA: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [METADATA_SEED, escrowPDA.toBuffer()],
+    programId
+  );
+}
+
+export function findEscrowRequestPDA(
+  maker: PublicKey,
+  seed: BN,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [REQUEST_SEED, maker.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+    programId
+  );
+}
+
+export function findKeeperPDA(
+  keeper: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [KEEPER_SEED, keeper.toBuffer()],
+    programId
+  );
+}
+
+export function findHoldingPDA(
+  escrowPDA: PublicKey,
+  milestoneIndex: number,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [HOLDING_SEED, escrowPDA.toBuffer(), Buffer.from([milestoneIndex])],
+    programId
+  );
+}
+
+export function findMakerProfilePDA(
+  maker: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [MAKER_PROFILE_SEED, maker.toBuffer()],
+    programId
+  );
+}
+
+export function findApprovalPDA(
+  escrow: PublicKey,
+  approver: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [APPROVAL_SEED, escrow.toBuffer(), approver.toBuffer()],
+    programId
+  );
+}
+
+export function findEscrowArchivePDA(
+  escrow: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [ARCHIVE_SEED, escrow.toBuffer()],
+    programId
+  );
+}
+
+export function findEmergencyWithdrawalPDA(
+  escrow: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [EMERGENCY_WITHDRAW_SEED, escrow.toBuffer()],
+    programId
+  );
+}
+
+export function findListingPDA(
+  escrow: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([LISTING_SEED, escrow.toBuffer()], programId);
+}
+
+/** Seed byte of a `CrankKind`, e.g. `{ claimActiveExpired: {} }`. */
+export function crankKindTag(kind: object): number {
+  if ("releaseMilestone" in kind) return 0;
+  if ("claimActiveExpired" in kind) return 1;
+  if ("claimDisputeTimeout" in kind) return 2;
+  throw new Error("Unknown crank kind");
+}
+
+export function findCrankSchedulePDA(
+  escrow: PublicKey,
+  creator: PublicKey,
+  kind: object,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [CRANK_SCHEDULE_SEED, escrow.toBuffer(), creator.toBuffer(), Buffer.from([crankKindTag(kind)])],
+    programId
+  );
+}
+
 export function findMetadataPDA(mint: PublicKey): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
     [
@@ -74,14 +265,141 @@ export function createDescriptionHash(text: string): number[] {
   return Array.from(crypto.createHash("sha256").update(text).digest());
 }
 
+/**
+ * Deterministic escrow seed used by `create_escrow_with_ref`: first 8 bytes (LE)
+ * of sha256(maker || taker || mint || externalRef). Mirrors `derive_escrow_seed`.
+ */
+export function deriveEscrowSeed(
+  maker: PublicKey,
+  taker: PublicKey,
+  mint: PublicKey,
+  externalRef: number[] | Uint8Array
+): BN {
+  const digest = crypto
+    .createHash("sha256")
+    .update(maker.toBuffer())
+    .update(taker.toBuffer())
+    .update(mint.toBuffer())
+    .update(Buffer.from(externalRef))
+    .digest();
+  return new BN(digest.subarray(0, 8), "le");
+}
+
+/**
+ * Canonical message a maker signs offline for `approve_milestone_signed`:
+ * program id || escrow || milestone index (u8) || nonce (u64 LE).
+ */
+export function buildApprovalMessage(
+  escrowPDA: PublicKey,
+  milestoneIndex: number,
+  nonce: BN,
+  programId: PublicKey = PROGRAM_ID
+): Buffer {
+  return Buffer.concat([
+    programId.toBuffer(),
+    escrowPDA.toBuffer(),
+    Buffer.from([milestoneIndex]),
+    nonce.toArrayLike(Buffer, "le", 8),
+  ]);
+}
+
+/** Borsh encoding of `Option<Pubkey>`. */
+function encodeOptionalPubkey(key: PublicKey | null): Buffer {
+  return key ? Buffer.concat([Buffer.from([1]), key.toBuffer()]) : Buffer.from([0]);
+}
+
+/**
+ * Borsh encoding of `EscrowTerms`. Mirrors the layout pinned by the program's
+ * `proposal_terms_hash` test.
+ */
+export function encodeEscrowTerms(terms: {
+  amount: BN;
+  milestones: { amount: BN; descriptionHash: number[]; feeExempt: boolean; selfCertify: boolean; beneficiary: PublicKey | null }[];
+  expiresAt: BN;
+  category: number;
+  initialBeneficiary: PublicKey | null;
+  hashAlgo: number;
+}): Buffer {
+  const count = Buffer.alloc(4);
+  count.writeUInt32LE(terms.milestones.length);
+  return Buffer.concat([
+    terms.amount.toArrayLike(Buffer, "le", 8),
+    count,
+    ...terms.milestones.flatMap((m) => [
+      m.amount.toArrayLike(Buffer, "le", 8),
+      Buffer.from(m.descriptionHash),
+      Buffer.from([m.feeExempt ? 1 : 0, m.selfCertify ? 1 : 0]),
+      encodeOptionalPubkey(m.beneficiary),
+    ]),
+    terms.expiresAt.toTwos(64).toArrayLike(Buffer, "le", 8),
+    Buffer.from([terms.category]),
+    encodeOptionalPubkey(terms.initialBeneficiary),
+    Buffer.from([terms.hashAlgo]),
+  ]);
+}
+
+/** Commitment passed to `accept_proposal`: sha256 of `encodeEscrowTerms`. */
+export function proposalTermsHash(
+  terms: Parameters<typeof encodeEscrowTerms>[0]
+): number[] {
+  return Array.from(
+    crypto.createHash("sha256").update(encodeEscrowTerms(terms)).digest()
+  );
+}
+
+/** `MutualAction::RenewEscrow` discriminant in the program's `mutual_action_hash`. */
+export const MUTUAL_ACTION_RENEW_ESCROW = 0;
+
+/**
+ * Hash the beneficiary records via `record_approval` to pre-approve
+ * `renew_escrow(topUp, milestones, expiresAt)` at the escrow's current nonce.
+ */
+export function renewActionHash(
+  escrow: PublicKey,
+  nonce: BN,
+  topUp: BN,
+  milestones: { amount: BN; descriptionHash: number[]; feeExempt: boolean; selfCertify: boolean; beneficiary: PublicKey | null }[],
+  expiresAt: BN
+): number[] {
+  const count = Buffer.alloc(4);
+  count.writeUInt32LE(milestones.length);
+  return Array.from(
+    crypto
+      .createHash("sha256")
+      .update(
+        Buffer.concat([
+          Buffer.from([MUTUAL_ACTION_RENEW_ESCROW]),
+          escrow.toBuffer(),
+          nonce.toArrayLike(Buffer, "le", 8),
+          topUp.toArrayLike(Buffer, "le", 8),
+          count,
+          ...milestones.flatMap((m) => [
+            m.amount.toArrayLike(Buffer, "le", 8),
+            Buffer.from(m.descriptionHash),
+            Buffer.from([m.feeExempt ? 1 : 0, m.selfCertify ? 1 : 0]),
+            encodeOptionalPubkey(m.beneficiary),
+          ]),
+          expiresAt.toTwos(64).toArrayLike(Buffer, "le", 8),
+        ])
+      )
+      .digest()
+  );
+}
+
 export function makeMilestones(
   amounts: BN[],
-  descriptions: string[]
-): { amount: BN; descriptionHash: number[] }[] {
+  descriptions: string[],
+  feeExempt: boolean[] = [],
+  selfCertify: boolean[] = [],
+  beneficiaries: (PublicKey | null)[] = []
+): { amount: BN; descriptionHash: number[]; feeExempt: boolean; selfCertify: boolean; beneficiary: PublicKey | null }[] {
   return amounts.map((amount, i) => ({
     amount,
     descriptionHash: createDescriptionHash(
       descriptions[i] ?? `milestone-${i}`
     ),
+    feeExempt: feeExempt[i] ?? false,
+    selfCertify: selfCertify[i] ?? false,
+    beneficiary: beneficiaries[i] ?? null,
   }));
 }
@@ -0,0 +1,340 @@
+#![allow(unexpected_cfgs)]
+
+//! Minimal wrapper that takes part in escrows through a data-holding PDA.
+//!
+//! Exists to exercise the escrow program's party-gated instructions from a
+//! CPI caller: the `party` PDA signs as maker, initiator or beneficiary via
+//! `invoke_signed`, while its owner pays any rent the escrow program needs.
+//! Not meant for production use.
+
+use anchor_lang::prelude::*;
+use escrow::cpi::accounts::{CancelEscrow, CloseEscrow, CreateEscrow, InitiateDispute, MintReceipt};
+use escrow::program::Escrow;
+use escrow::state::{MilestoneInput, DISPUTE_INITIATORS_ALL};
+
+declare_id!("EsMFz15QwMavZMifoUGoxTCDi5tEpYsxYM8CfhhwzuAU");
+
+pub const PARTY_SEED: &[u8] = b"party";
+
+#[program]
+pub mod escrow_party {
+    use super::*;
+
+    pub fn init_party(ctx: Context<InitParty>) -> Result<()> {
+        let party = &mut ctx.accounts.party;
+        party.owner = ctx.accounts.owner.key();
+        party.bump = ctx.bumps.party;
+        Ok(())
+    }
+
+    pub fn create_escrow(
+        ctx: Context<PartyCreateEscrow>,
+        seed: u64,
+        amount: u64,
+        milestones: Vec<MilestoneInput>,
+        expires_at: i64,
+    ) -> Result<()> {
+        let a = &ctx.accounts;
+        let owner = a.owner.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[PARTY_SEED, owner.as_ref(), &[a.party.bump]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            a.escrow_program.to_account_info(),
+            CreateEscrow {
+                maker: a.party.to_account_info(),
+                payer: a.owner.to_account_info(),
+                taker: a.taker.to_account_info(),
+                escrow_config: a.escrow_config.to_account_info(),
+                mint: a.mint.to_account_info(),
+                escrow_state: a.escrow_state.to_account_info(),
+                maker_profile: a.maker_profile.to_account_info(),
+                vault: a.vault.to_account_info(),
+                maker_token_account: a.party_token_account.to_account_info(),
+                associated_token_program: a.associated_token_program.to_account_info(),
+                token_program: a.token_program.to_account_info(),
+                system_program: a.system_program.to_account_info(),
+            },
+            signer_seeds,
+        );
+        escrow::cpi::create_escrow(
+            cpi_ctx,
+            seed,
+            amount,
+            milestones,
+            expires_at,
+            0,
+            None,
+            0,
+            None,
+            false,
+            0,
+            true,
+            DISPUTE_INITIATORS_ALL,
+            false,
+            None,
+            0,
+        )
+    }
+
+    pub fn cancel_escrow(ctx: Context<PartyCancelEscrow>) -> Result<()> {
+        let a = &ctx.accounts;
+        let owner = a.owner.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[PARTY_SEED, owner.as_ref(), &[a.party.bump]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            a.escrow_program.to_account_info(),
+            CancelEscrow {
+                maker: a.party.to_account_info(),
+                escrow_state: a.escrow_state.to_account_info(),
+                mint: a.mint.to_account_info(),
+                vault: a.vault.to_account_info(),
+                maker_token_account: a.party_token_account.to_account_info(),
+                escrow_config: a.escrow_config.to_account_info(),
+                cancel_fee_token_account: a.cancel_fee_token_account.as_ref().map(|acc| acc.to_account_info()),
+                token_program: a.token_program.to_account_info(),
+                maker_profile: a.maker_profile.as_ref().map(|acc| acc.to_account_info()),
+            },
+            signer_seeds,
+        );
+        escrow::cpi::cancel_escrow(cpi_ctx, None)
+    }
+
+    pub fn close_escrow(ctx: Context<PartyCloseEscrow>) -> Result<()> {
+        let a = &ctx.accounts;
+        let owner = a.owner.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[PARTY_SEED, owner.as_ref(), &[a.party.bump]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            a.escrow_program.to_account_info(),
+            CloseEscrow {
+                maker: a.party.to_account_info(),
+                escrow_state: a.escrow_state.to_account_info(),
+                escrow_config: a.escrow_config.to_account_info(),
+                mint: a.mint.to_account_info(),
+                vault: a.vault.to_account_info(),
+                maker_token_account: a.party_token_account.to_account_info(),
+                token_program: a.token_program.to_account_info(),
+                escrow_metadata: None,
+                receipt_metadata: None,
+                token_metadata_program: None,
+            },
+            signer_seeds,
+        );
+        escrow::cpi::close_escrow(cpi_ctx)
+    }
+
+    pub fn initiate_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PartyInitiateDispute<'info>>,
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        let a = &ctx.accounts;
+        let owner = a.owner.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[PARTY_SEED, owner.as_ref(), &[a.party.bump]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            a.escrow_program.to_account_info(),
+            InitiateDispute {
+                initiator: a.party.to_account_info(),
+                escrow_state: a.escrow_state.to_account_info(),
+                escrow_config: a.escrow_config.to_account_info(),
+            },
+            signer_seeds,
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        escrow::cpi::initiate_dispute(cpi_ctx, reason_hash)
+    }
+
+    pub fn mint_receipt(ctx: Context<PartyMintReceipt>) -> Result<()> {
+        let a = &ctx.accounts;
+        let owner = a.owner.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[PARTY_SEED, owner.as_ref(), &[a.party.bump]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            a.escrow_program.to_account_info(),
+            MintReceipt {
+                beneficiary: a.party.to_account_info(),
+                payer: a.owner.to_account_info(),
+                escrow_state: a.escrow_state.to_account_info(),
+                escrow_config: a.escrow_config.to_account_info(),
+                receipt_mint: a.receipt_mint.to_account_info(),
+                beneficiary_receipt_ata: a.party_receipt_ata.to_account_info(),
+                metadata: a.metadata.to_account_info(),
+                master_edition: a.master_edition.to_account_info(),
+                token_metadata_program: a.token_metadata_program.to_account_info(),
+                token_program: a.token_program.to_account_info(),
+                associated_token_program: a.associated_token_program.to_account_info(),
+                system_program: a.system_program.to_account_info(),
+                rent: a.rent.to_account_info(),
+            },
+            signer_seeds,
+        );
+        escrow::cpi::mint_receipt(cpi_ctx)
+    }
+}
+
+/// Program-owned party. Holds data, so it can sign for the escrow program
+/// but cannot pay rent through the System Program.
+#[account]
+#[derive(InitSpace)]
+pub struct Party {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct InitParty<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Party::INIT_SPACE,
+        seeds = [PARTY_SEED, owner.key().as_ref()],
+        bump,
+    )]
+    pub party: Account<'info, Party>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Accounts forwarded to the escrow program are unchecked here; the escrow
+// program validates every one of them.
+
+#[derive(Accounts)]
+pub struct PartyCreateEscrow<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [PARTY_SEED, owner.key().as_ref()], bump = party.bump, has_one = owner)]
+    pub party: Account<'info, Party>,
+
+    /// CHECK: validated by the escrow program.
+    pub taker: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub escrow_config: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub escrow_state: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub maker_profile: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub party_token_account: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub associated_token_program: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub escrow_program: Program<'info, Escrow>,
+}
+
+#[derive(Accounts)]
+pub struct PartyCancelEscrow<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [PARTY_SEED, owner.key().as_ref()], bump = party.bump, has_one = owner)]
+    pub party: Account<'info, Party>,
+
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub escrow_state: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub party_token_account: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub escrow_config: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub cancel_fee_token_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: validated by the escrow program.
+    pub token_program: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub maker_profile: Option<UncheckedAccount<'info>>,
+    pub escrow_program: Program<'info, Escrow>,
+}
+
+#[derive(Accounts)]
+pub struct PartyCloseEscrow<'info> {
+    pub owner: Signer<'info>,
+
+    /// Receives the escrow's rent back.
+    #[account(mut, seeds = [PARTY_SEED, owner.key().as_ref()], bump = party.bump, has_one = owner)]
+    pub party: Account<'info, Party>,
+
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub escrow_state: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub escrow_config: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub party_token_account: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub token_program: UncheckedAccount<'info>,
+    pub escrow_program: Program<'info, Escrow>,
+}
+
+#[derive(Accounts)]
+pub struct PartyInitiateDispute<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [PARTY_SEED, owner.key().as_ref()], bump = party.bump, has_one = owner)]
+    pub party: Account<'info, Party>,
+
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub escrow_state: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub escrow_config: UncheckedAccount<'info>,
+    pub escrow_program: Program<'info, Escrow>,
+}
+
+#[derive(Accounts)]
+pub struct PartyMintReceipt<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [PARTY_SEED, owner.key().as_ref()], bump = party.bump, has_one = owner)]
+    pub party: Account<'info, Party>,
+
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub escrow_state: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub escrow_config: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub receipt_mint: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub party_receipt_ata: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub token_metadata_program: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub token_program: UncheckedAccount<'info>,
+    /// CHECK: validated by the escrow program.
+    pub associated_token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: validated by the escrow program.
+    pub rent: UncheckedAccount<'info>,
+    pub escrow_program: Program<'info, Escrow>,
+}
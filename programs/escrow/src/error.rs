@@ -115,4 +115,361 @@ pub enum EscrowError {
 
     #[msg("Mints with a freeze authority are not supported (vault freeze griefing risk)")]
     MintHasFreezeAuthority,
+
+    #[msg("Release delay must be between 0 and 30 days")]
+    InvalidReleaseDelay,
+
+    #[msg("Milestone is still within its challenge window — release not yet allowed")]
+    ReleaseDelayNotElapsed,
+
+    #[msg("Challenge window has closed — milestone can no longer be unapproved")]
+    ChallengeWindowClosed,
+
+    #[msg("Escrow is on hold by the config authority")]
+    EscrowOnHold,
+
+    #[msg("Escrow is not on hold")]
+    EscrowNotOnHold,
+
+    #[msg("Beneficiary token account does not match the recorded beneficiary ATA")]
+    BeneficiaryAtaMismatch,
+
+    #[msg("Vault account does not match the escrow's recorded vault")]
+    VaultMismatch,
+
+    #[msg("Proportional shares must not exceed 10000 basis points")]
+    InvalidShareDistribution,
+
+    #[msg("Token program does not match the escrow's recorded token program")]
+    TokenProgramMismatch,
+
+    #[msg("Escrow can only be renewed when Completed or Active within the renewal window")]
+    RenewalNotAllowed,
+
+    #[msg("Missing or malformed ed25519 signature verification instruction")]
+    InvalidApprovalSignature,
+
+    #[msg("Offline approval was not signed by the escrow maker")]
+    ApprovalSignerMismatch,
+
+    #[msg("Offline approval nonce does not match the escrow's next nonce")]
+    InvalidApprovalNonce,
+
+    #[msg("This escrow has reached the configured maximum number of disputes")]
+    DisputeLimitReached,
+
+    #[msg("Dispute cap must be at least 1")]
+    InvalidDisputeCap,
+
+    #[msg("Native SOL unwrap is only available for wrapped-SOL escrows")]
+    UnwrapRequiresNativeMint,
+
+    #[msg("Unwrap requires both the scratch wSOL account and the beneficiary wallet")]
+    IncompleteUnwrapAccounts,
+
+    #[msg("Seed does not match the derivation from maker, taker, mint and external reference")]
+    SeedDerivationMismatch,
+
+    #[msg("Hash algorithm is not accepted by the escrow config")]
+    UnsupportedHashAlgo,
+
+    #[msg("New maker must differ from the current maker, taker and beneficiary")]
+    InvalidMaker,
+
+    #[msg("Milestone at this index does not match the expected amount or description hash")]
+    MilestoneMismatch,
+
+    #[msg("Acceptance deadline must be in the future and no later than expiration")]
+    InvalidAcceptanceDeadline,
+
+    #[msg("Escrow is not awaiting acceptance")]
+    NotAwaitingAcceptance,
+
+    #[msg("Acceptance deadline has passed")]
+    AcceptanceDeadlinePassed,
+
+    #[msg("Escrow state changed since the transaction was built (nonce mismatch)")]
+    StaleState,
+
+    #[msg("Claim has been fractionalized into shares")]
+    ClaimIsFractionalized,
+
+    #[msg("Claim has not been fractionalized")]
+    ClaimNotFractionalized,
+
+    #[msg("Share amount redeems nothing from the claim pool")]
+    NothingToRedeem,
+
+    #[msg("Claim pool still holds unredeemed funds")]
+    ClaimPoolNotEmpty,
+
+    #[msg("Proposal expiry must be in the future")]
+    InvalidProposalExpiry,
+
+    #[msg("Proposal has expired")]
+    ProposalExpired,
+
+    #[msg("It is the other party's turn to respond to this proposal")]
+    NotYourTurn,
+
+    #[msg("Proposal terms differ from the terms being accepted")]
+    ProposalTermsMismatch,
+
+    #[msg("Only the proposal creator can close it")]
+    NotProposalCreator,
+
+    #[msg("Escrow metadata is already attached")]
+    MetadataAlreadyAttached,
+
+    #[msg("Escrow metadata exceeds the maximum size")]
+    MetadataTooLarge,
+
+    #[msg("Attached escrow metadata account must be passed to close it")]
+    MetadataAccountMissing,
+
+    #[msg("Request TTL must be positive and within the maximum")]
+    InvalidRequestTtl,
+
+    #[msg("Escrow request has expired")]
+    RequestExpired,
+
+    #[msg("Only the requester can cancel this escrow request")]
+    NotRequester,
+
+    #[msg("Milestone already funds a sub-escrow")]
+    MilestoneAlreadyLinked,
+
+    #[msg("Sub-escrow amount exceeds the parent milestone's net payout")]
+    SubEscrowExceedsPayout,
+
+    #[msg("Linked sub-escrow and its vault must be passed to release this milestone")]
+    SubEscrowAccountMissing,
+
+    #[msg("Sub-escrow account does not match the milestone link")]
+    SubEscrowMismatch,
+
+    #[msg("Escrow is not awaiting funding from a parent milestone")]
+    NotAwaitingFunding,
+
+    #[msg("Beneficiary and fee collector token accounts are required to pay approved milestones")]
+    PayoutAccountMissing,
+
+    #[msg("Too many remaining accounts passed for this instruction")]
+    TooManyAccounts,
+
+    #[msg("Escrow uses auto-release; approve with approve_milestone_auto")]
+    AutoReleaseRequired,
+
+    #[msg("Escrow was not created with auto-release")]
+    AutoReleaseDisabled,
+
+    #[msg("Crank tip must be greater than zero")]
+    InvalidCrankTip,
+
+    #[msg("Keeper has no unclaimed tips")]
+    NoTipsToClaim,
+
+    #[msg("Claw-back window must be between 0 and 30 days")]
+    InvalidClawBackWindow,
+
+    #[msg("Claw-back holding account is required while a claw-back window is configured")]
+    HoldingAccountMissing,
+
+    #[msg("Funds for this milestone are not held in a claw-back account")]
+    NothingHeld,
+
+    #[msg("Claw-back window is still open")]
+    ClawBackWindowOpen,
+
+    #[msg("Claw-back window has closed")]
+    ClawBackWindowClosed,
+
+    #[msg("Only the beneficiary or the config authority can reclaim held funds")]
+    NotReclaimAuthority,
+
+    #[msg("Released funds are still held in claw-back accounts")]
+    FundsStillHeld,
+
+    #[msg("wSOL unwrap is unavailable while a claw-back window is configured")]
+    ClawBackUnwrapUnsupported,
+
+    #[msg("A milestone has already been approved or settled")]
+    WorkAlreadyApproved,
+
+    #[msg("Beneficiary is already the original taker")]
+    BeneficiaryNotReassigned,
+
+    #[msg("Fee refund exceeds the fees collected for this escrow")]
+    FeeRefundExceedsCollected,
+
+    #[msg("Approval extension must be between 0 and 30 days")]
+    InvalidApprovalExtension,
+
+    #[msg("Fee-exempt milestones are not enabled in the config")]
+    FeeExemptionNotAllowed,
+
+    #[msg("Milestone was not flagged for beneficiary self-certification")]
+    NotSelfCertifiable,
+
+    #[msg("auto_close requires the maker's signature and token account")]
+    CloseAccountMissing,
+
+    #[msg("Disputes and authority intervention are disabled for this escrow")]
+    DisputesDisabled,
+
+    #[msg("Maker already has the maximum number of open escrows")]
+    TooManyOpenEscrows,
+
+    #[msg("The escrow is counted on its creator's maker profile, which must be passed")]
+    MakerProfileMissing,
+
+    #[msg("Signer's escrow role is not allowed to initiate disputes on this escrow")]
+    DisputeInitiatorNotAllowed,
+
+    #[msg("dispute_initiators must be a non-empty set of maker, taker and beneficiary bits")]
+    InvalidDisputeInitiators,
+
+    #[msg("An even split has no losing party to charge the dispute fee")]
+    NoDisputeLoser,
+
+    #[msg("loser_pays_fee requires the losing party's token account")]
+    LoserFeeAccountMissing,
+
+    #[msg("Losing party has not delegated the dispute fee to the escrow")]
+    FeeNotPreAuthorized,
+
+    #[msg("Approval expiry must be in the future and at most 30 days out")]
+    InvalidApprovalExpiry,
+
+    #[msg("Neither the co-signer nor a matching approval was provided")]
+    CoSignerMissing,
+
+    #[msg("Approval does not match this action, its parameters or the escrow nonce")]
+    ApprovalMismatch,
+
+    #[msg("Approval has expired")]
+    ApprovalExpired,
+
+    #[msg("Escrow has no receipt NFT to recover")]
+    NoReceipt,
+
+    #[msg("Receipt recovery needs the beneficiary's signature, or the config authority's once the recovery delay has passed")]
+    RecoveryCoSignerMissing,
+
+    #[msg("Receipt NFT was voided by recover_receipt")]
+    ReceiptVoided,
+
+    #[msg("Archive retention period has not elapsed")]
+    ArchiveRetentionActive,
+
+    #[msg("A payout destination cannot be the escrow's own vault")]
+    DestinationIsVault,
+
+    #[msg("The receipt metadata and token metadata program are required to seal the receipt on close")]
+    ReceiptMetadataMissing,
+
+    #[msg("Account is not this escrow's receipt metadata")]
+    ReceiptMetadataMismatch,
+
+    #[msg("A cancellation fee applies; the recipient's token account is required")]
+    CancelFeeAccountMissing,
+
+    #[msg("Receipt minting is disabled by the config authority")]
+    ReceiptsDisabled,
+
+    #[msg("Agreement hash must be non-zero")]
+    InvalidAgreementHash,
+
+    #[msg("Escrow has no agreement to acknowledge")]
+    NoAgreement,
+
+    #[msg("Agreement already acknowledged")]
+    AgreementAlreadyAcknowledged,
+
+    #[msg("Acknowledged hash does not match the escrow's agreement")]
+    AgreementHashMismatch,
+
+    #[msg("The taker has not acknowledged the escrow's agreement")]
+    AgreementNotAcknowledged,
+
+    #[msg("Crank schedule is invalid")]
+    InvalidCrankSchedule,
+
+    #[msg("Scheduled crank is not due yet")]
+    CrankNotDue,
+
+    #[msg("Accounts do not match the scheduled crank")]
+    CrankTargetMismatch,
+
+    #[msg("Receipt mint supply must be exactly 1")]
+    ReceiptSupplyInvalid,
+
+    #[msg("Vault has a delegate set")]
+    VaultHasDelegate,
+
+    #[msg("Vault has a close authority set")]
+    VaultHasCloseAuthority,
+
+    #[msg("Vault is frozen")]
+    VaultFrozen,
+
+    #[msg("Payout destination token account is frozen")]
+    DestinationFrozen,
+
+    #[msg("Payout destination token account has been closed")]
+    DestinationClosed,
+
+    #[msg("A milestone deviates from an even split by more than the config allows")]
+    MilestoneSkewExceeded,
+
+    #[msg("Only the taker can perform this action")]
+    NotTaker,
+
+    #[msg("This escrow does not require a taker bond")]
+    NoBondRequired,
+
+    #[msg("The taker bond must be posted before the escrow can be accepted")]
+    BondNotPosted,
+
+    #[msg("No taker bond is held by this escrow")]
+    NoBondHeld,
+
+    #[msg("The taker bond must be settled first")]
+    BondNotSettled,
+
+    #[msg("Emergency withdrawal timelock has not elapsed")]
+    EmergencyWithdrawTimelocked,
+
+    #[msg("Invalid recovery address")]
+    InvalidRecoveryAddress,
+
+    #[msg("Listing rent recipient missing or does not match the listing maker")]
+    ListingMakerMismatch,
+
+    #[msg("Escrow is still open for acceptance; only the maker can delist it")]
+    ListingStillLive,
+
+    #[msg("A milestone beneficiary cannot be the maker")]
+    MilestoneBeneficiaryIsMaker,
+
+    #[msg("Config account layout version is not the one this program understands; run migrate_config")]
+    ConfigVersionMismatch,
+
+    #[msg("Config account is already at the current layout version")]
+    ConfigAlreadyMigrated,
+
+    #[msg("Escrow creation is paused by the config authority")]
+    CreationPaused,
+
+    #[msg("Emergency withdrawal was proposed for an earlier escrow at this address")]
+    EmergencyWithdrawStale,
+
+    #[msg("Escrow is still open; only the config authority can cancel its emergency withdrawal")]
+    EmergencyWithdrawStillLive,
+
+    #[msg("Token account of a milestone's own beneficiary is missing from remaining accounts")]
+    PayeeAccountMissing,
+
+    #[msg("A dispute cannot settle open milestones that name their own beneficiary")]
+    DisputeWithMilestoneBeneficiary,
 }
@@ -1,21 +1,61 @@
 use anchor_lang::prelude::*;
-use crate::state::DisputeResolution;
+use crate::state::{
+    CancelFeeRecipient, CancelReason, CancelRouting, CrankInstruction, CrankKind, Dispute, DisputeResolution, DisputeTimeoutPolicy, EscrowStatus, MutualAction,
+    RemainderPolicy, CANCEL_REASON_COUNT,
+};
 
 #[event]
 pub struct EscrowCreated {
     pub maker: Pubkey,
     pub taker: Pubkey,
+    pub beneficiary: Pubkey,
     pub mint: Pubkey,
+    pub vault: Pubkey,
     pub amount: u64,
     pub seed: u64,
     pub milestones_count: u8,
     pub expires_at: i64,
+    pub category: u8,
+    pub hash_algo: u8,
+    pub acceptance_deadline: i64,
+    pub auto_release: bool,
+    /// Claw-back window applied to this escrow's releases (0 = instant payout).
+    pub claw_back_seconds: i64,
+    /// False when the parties opted out of protocol arbitration.
+    pub disputes_enabled: bool,
+    pub dispute_initiators: u8,
+    /// Milestones the beneficiary may approve itself via `certify_milestone`.
+    pub self_certify_indices: Vec<u8>,
+    /// Milestone fees were funded on top of `amount`.
+    pub fee_on_top: bool,
+    /// Off-chain agreement the taker must acknowledge before releases.
+    pub agreement_hash: Option<[u8; 32]>,
+    pub taker_bond: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct EscrowAccepted {
+    pub escrow: Pubkey,
+    pub accepted_by: Pubkey,
+    pub nonce: u64,
 }
 
 #[event]
 pub struct MilestoneApproved {
     pub escrow: Pubkey,
     pub milestone_index: u8,
+    /// Seconds the approval pushed `expires_at` out by (0 when unchanged).
+    pub expiry_extension: i64,
+    pub expires_at: i64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct MilestoneUnapproved {
+    pub escrow: Pubkey,
+    pub milestone_index: u8,
+    pub nonce: u64,
 }
 
 #[event]
@@ -24,32 +64,126 @@ pub struct MilestoneReleased {
     pub milestone_index: u8,
     pub amount: u64,
     pub fee: u64,
+    /// The milestone was released without the platform fee.
+    pub fee_exempt: bool,
+    /// Fraction of the escrow released so far, in basis points (floored).
+    pub percent_complete_bps: u16,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct MilestoneCancelled {
+    pub escrow: Pubkey,
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub reason: CancelReason,
+    pub nonce: u64,
 }
 
 #[event]
 pub struct DisputeInitiated {
     pub escrow: Pubkey,
     pub initiator: Pubkey,
+    pub dispute_count: u8,
+    /// Config cap in force; 0 = unlimited.
+    pub max_disputes: u8,
+    pub hash_algo: u8,
+    pub nonce: u64,
 }
 
 #[event]
 pub struct DisputeResolved {
     pub escrow: Pubkey,
     pub resolution: DisputeResolution,
+    pub resolved_by: Pubkey,
+    pub resolved_at: i64,
+    /// Losing party that paid the fee from its own account; `None` when the
+    /// fee came out of the escrow balance or the ruling charged none.
+    pub fee_paid_by: Option<Pubkey>,
+    pub nonce: u64,
 }
 
 #[event]
 pub struct EscrowCancelled {
     pub escrow: Pubkey,
+    /// Refund moved to the maker by this call, net of `cancel_fee`.
     pub refunded_amount: u64,
+    /// Withheld from the refund and paid to the escrow's cancel-fee recipient.
+    pub cancel_fee: u64,
+    pub total_released: u64,
+    pub total_refunded: u64,
+    pub total_fees_paid: u64,
+    pub milestones_released: u8,
+    pub milestones_cancelled: u8,
+    pub beneficiary: Pubkey,
+    pub nonce: u64,
+    pub reason: CancelReason,
+    /// Routing applied to the refund (`cancel_fee` is its `fee_bps` share).
+    pub routing: CancelRouting,
 }
 
 #[event]
 pub struct EscrowCompleted {
     pub escrow: Pubkey,
     pub total_released: u64,
+    pub total_refunded: u64,
+    pub total_fees_paid: u64,
+    pub milestones_released: u8,
+    pub milestones_cancelled: u8,
+    pub beneficiary: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct EscrowExpired {
+    pub escrow: Pubkey,
+    pub total_released: u64,
+    pub total_refunded: u64,
+    pub total_fees_paid: u64,
+    pub milestones_released: u8,
+    pub milestones_cancelled: u8,
+    pub beneficiary: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct EscrowRenewed {
+    pub escrow: Pubkey,
+    pub renewal_count: u32,
+    pub top_up: u64,
+    pub carried_over: u64,
+    pub previous_released: u64,
+    pub previous_refunded: u64,
+    pub previous_fees_paid: u64,
+    pub expires_at: i64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct ActiveExpiryClaimed {
+    pub escrow: Pubkey,
+    pub approved_released: u64,
+    pub pending_refunded: u64,
+    pub fee: u64,
+    pub nonce: u64,
+    /// Withheld from `pending_refunded` per the escrow's `Expiry` routing.
+    pub cancel_fee: u64,
+}
+
+#[event]
+pub struct DisputeTimeoutClaimed {
+    pub escrow: Pubkey,
+    pub policy: DisputeTimeoutPolicy,
+    pub maker_share: u64,
+    pub taker_share: u64,
+    pub fee: u64,
+    pub resolved_at: i64,
+    pub nonce: u64,
+    /// Withheld from `maker_share` per the escrow's `DisputeTimeout` routing.
+    pub cancel_fee: u64,
 }
 
+/// Emitted only by the deprecated `claim_expired` dispatcher.
 #[event]
 pub struct ExpiredFundsClaimed {
     pub escrow: Pubkey,
@@ -58,6 +192,17 @@ pub struct ExpiredFundsClaimed {
     pub pending_refunded: u64,
     pub dispute_maker_share: u64,
     pub dispute_taker_share: u64,
+    pub resolved_by: Pubkey,
+    pub resolved_at: i64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct MakerTransferred {
+    pub escrow: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub nonce: u64,
 }
 
 #[event]
@@ -65,6 +210,7 @@ pub struct ClaimTransferred {
     pub escrow: Pubkey,
     pub from: Pubkey,
     pub to: Pubkey,
+    pub nonce: u64,
 }
 
 #[event]
@@ -72,6 +218,25 @@ pub struct ReceiptMinted {
     pub escrow: Pubkey,
     pub mint: Pubkey,
     pub beneficiary: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct ClaimFractionalized {
+    pub escrow: Pubkey,
+    pub share_mint: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total_shares: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct SharesRedeemed {
+    pub escrow: Pubkey,
+    pub holder: Pubkey,
+    pub shares: u64,
+    pub amount: u64,
+    pub nonce: u64,
 }
 
 #[event]
@@ -79,12 +244,25 @@ pub struct BeneficiarySynced {
     pub escrow: Pubkey,
     pub old_beneficiary: Pubkey,
     pub new_beneficiary: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct ReceiptRecovered {
+    pub escrow: Pubkey,
+    pub voided_receipt_mint: Pubkey,
+    pub old_beneficiary: Pubkey,
+    pub new_beneficiary: Pubkey,
+    /// The beneficiary or, on the fallback path, the config authority.
+    pub co_signer: Pubkey,
+    pub nonce: u64,
 }
 
 #[event]
 pub struct ReceiptRevoked {
     pub escrow: Pubkey,
     pub receipt_mint: Pubkey,
+    pub nonce: u64,
 }
 
 #[event]
@@ -93,6 +271,28 @@ pub struct ConfigInitialized {
     pub fee_bps: u16,
     pub fee_collector: Pubkey,
     pub dispute_timeout: i64,
+    pub release_delay: i64,
+    pub max_disputes_per_escrow: u8,
+    pub accepted_hash_algos: u8,
+    pub close_dust_threshold: u64,
+    pub dispute_timeout_policy: DisputeTimeoutPolicy,
+    pub request_ttl: i64,
+    pub claw_back_seconds: i64,
+    pub approval_extension: i64,
+    pub max_expiry_extension: i64,
+    pub remainder_policy: RemainderPolicy,
+    pub allow_fee_exempt: bool,
+    pub max_open_escrows_per_maker: u16,
+    pub expiry_extension_per_approval: i64,
+    pub flat_fee: u64,
+    pub flat_fee_mint: Pubkey,
+    pub cancel_fee_bps: u16,
+    pub cancel_fee_recipient: CancelFeeRecipient,
+    pub receipts_enabled: bool,
+    pub fee_free_below: u64,
+    pub max_milestone_skew_bps: u16,
+    pub creation_paused: bool,
+    pub cancel_routes: [CancelRouting; CANCEL_REASON_COUNT],
 }
 
 #[event]
@@ -101,10 +301,393 @@ pub struct ConfigUpdated {
     pub fee_bps: u16,
     pub fee_collector: Pubkey,
     pub dispute_timeout: i64,
+    pub release_delay: i64,
+    pub max_disputes_per_escrow: u8,
+    pub accepted_hash_algos: u8,
+    pub close_dust_threshold: u64,
+    pub dispute_timeout_policy: DisputeTimeoutPolicy,
+    pub request_ttl: i64,
+    pub claw_back_seconds: i64,
+    pub approval_extension: i64,
+    pub max_expiry_extension: i64,
+    pub remainder_policy: RemainderPolicy,
+    pub allow_fee_exempt: bool,
+    pub max_open_escrows_per_maker: u16,
+    pub expiry_extension_per_approval: i64,
+    pub flat_fee: u64,
+    pub flat_fee_mint: Pubkey,
+    pub cancel_fee_bps: u16,
+    pub cancel_fee_recipient: CancelFeeRecipient,
+    pub receipts_enabled: bool,
+    pub fee_free_below: u64,
+    pub max_milestone_skew_bps: u16,
+    pub creation_paused: bool,
+    pub cancel_routes: [CancelRouting; CANCEL_REASON_COUNT],
+}
+
+#[event]
+pub struct MetadataAttached {
+    pub escrow: Pubkey,
+    pub metadata: Pubkey,
+    pub len: u32,
+    pub nonce: u64,
 }
 
 #[event]
 pub struct EscrowClosed {
     pub escrow: Pubkey,
     pub maker: Pubkey,
+    pub dust_swept: u64,
+    pub dust_burned: u64,
+    /// Disputes opened over the escrow's lifetime.
+    pub dispute_count: u8,
+    /// The last dispute, with its resolution (if any), so the outcome
+    /// outlives the closed account.
+    pub dispute: Option<Dispute>,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct EscrowHeld {
+    pub escrow: Pubkey,
+    pub authority: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct EscrowHoldReleased {
+    pub escrow: Pubkey,
+    pub authority: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub proposal: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub proposer: Pubkey,
+    pub seed: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct ProposalCountered {
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub revision: u32,
+}
+
+#[event]
+pub struct ProposalAccepted {
+    pub proposal: Pubkey,
+    pub escrow: Pubkey,
+    pub acceptor: Pubkey,
+    pub revision: u32,
+}
+
+#[event]
+pub struct ProposalClosed {
+    pub proposal: Pubkey,
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct EscrowRequested {
+    pub request: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub seed: u64,
+    pub amount: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct RequestFunded {
+    pub request: Pubkey,
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+}
+
+#[event]
+pub struct RequestCanceled {
+    pub request: Pubkey,
+    pub taker: Pubkey,
+}
+
+#[event]
+pub struct SubEscrowCreated {
+    pub parent: Pubkey,
+    pub parent_milestone_index: u8,
+    pub child: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct SubEscrowFunded {
+    pub parent: Pubkey,
+    pub parent_milestone_index: u8,
+    pub child: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct SubEscrowCancelled {
+    pub parent: Pubkey,
+    pub child: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct KeeperRegistered {
+    pub keeper: Pubkey,
+    pub bond: u64,
+}
+
+#[event]
+pub struct KeeperDeregistered {
+    pub keeper: Pubkey,
+    pub bond: u64,
+    pub tips_earned: u64,
+}
+
+#[event]
+pub struct KeeperTipsClaimed {
+    pub keeper: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TipJarFunded {
+    pub escrow: Pubkey,
+    pub crank_tip: u64,
+    pub amount: u64,
+    pub tip_jar: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct KeeperTipPaid {
+    pub escrow: Pubkey,
+    pub keeper: Pubkey,
+    pub amount: u64,
+    pub tip_jar: u64,
+    pub nonce: u64,
+}
+
+/// Emitted next to `KeeperTipPaid`, naming the crank that earned the tip so
+/// keepers can reconcile earnings per action.
+#[event]
+pub struct CrankRewarded {
+    pub escrow: Pubkey,
+    pub keeper: Pubkey,
+    pub reward: u64,
+    pub instruction: CrankInstruction,
+}
+
+#[event]
+pub struct HeldFundsWithdrawn {
+    pub escrow: Pubkey,
+    pub milestone_index: u8,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct HeldFundsReclaimed {
+    pub escrow: Pubkey,
+    pub milestone_index: u8,
+    pub reclaimed_by: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct BeneficiaryReset {
+    pub escrow: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct FeeRefunded {
+    pub escrow: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub fees_refunded: u64,
+    pub fees_paid: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct EscrowClawedBack {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub milestones_reclaimed: u8,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct MilestoneCertified {
+    pub escrow: Pubkey,
+    pub milestone_index: u8,
+    pub beneficiary: Pubkey,
+    /// Seconds the certification pushed `expires_at` out by (0 when unchanged).
+    pub expiry_extension: i64,
+    pub expires_at: i64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct ApprovalRecorded {
+    pub escrow: Pubkey,
+    pub approver: Pubkey,
+    pub action: MutualAction,
+    pub action_hash: [u8; 32],
+    pub nonce: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct ApprovalClosed {
+    pub escrow: Pubkey,
+    pub approver: Pubkey,
+}
+
+#[event]
+pub struct EscrowArchived {
+    pub escrow: Pubkey,
+    pub archive: Pubkey,
+    pub maker: Pubkey,
+    pub status: EscrowStatus,
+    pub closed_at: i64,
+}
+
+#[event]
+pub struct ArchiveClosed {
+    pub escrow: Pubkey,
+    pub archive: Pubkey,
+    pub maker: Pubkey,
+}
+
+#[event]
+pub struct ReceiptMetadataSealed {
+    pub escrow: Pubkey,
+    pub metadata: Pubkey,
+}
+
+#[event]
+pub struct ReceiptMintingToggled {
+    pub authority: Pubkey,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct AgreementAcknowledged {
+    pub escrow: Pubkey,
+    pub taker: Pubkey,
+    pub agreement_hash: [u8; 32],
+    pub acknowledged_at: i64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct CrankScheduled {
+    pub schedule: Pubkey,
+    pub escrow: Pubkey,
+    pub creator: Pubkey,
+    pub kind: CrankKind,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ScheduledCrankExecuted {
+    pub schedule: Pubkey,
+    pub escrow: Pubkey,
+    pub executor: Pubkey,
+    pub kind: CrankKind,
+    /// Set when the schedule re-armed instead of closing.
+    pub next_eta: Option<i64>,
+}
+
+#[event]
+pub struct CrankScheduleCancelled {
+    pub schedule: Pubkey,
+    pub escrow: Pubkey,
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct BondPosted {
+    pub escrow: Pubkey,
+    pub taker: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct BondSettled {
+    pub escrow: Pubkey,
+    pub to_maker: u64,
+    pub to_taker: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct EmergencyWithdrawProposed {
+    pub escrow: Pubkey,
+    pub authority: Pubkey,
+    pub recovery: Pubkey,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawExecuted {
+    pub escrow: Pubkey,
+    pub authority: Pubkey,
+    pub recovery: Pubkey,
+    pub amount: u64,
+    /// Open milestone amounts cancelled by the drain (`emergency_withdrawn`).
+    pub cancelled_amount: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct EscrowListed {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub category: u8,
+    pub amount: u64,
+    pub title_hash: [u8; 32],
+}
+
+#[event]
+pub struct EscrowDelisted {
+    pub escrow: Pubkey,
+    /// Signer that closed the listing (maker, acceptor, or any caller for a stale listing).
+    pub closed_by: Pubkey,
+}
+
+#[event]
+pub struct ConfigMigrated {
+    pub authority: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    /// Account size after the realloc, discriminator included.
+    pub size: u64,
+}
+
+#[event]
+pub struct EmergencyWithdrawCancelled {
+    pub escrow: Pubkey,
+    /// The config authority, or any caller for a stale proposal.
+    pub closed_by: Pubkey,
 }
@@ -1,17 +1,69 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::TokenAccount as SplTokenAccount;
-use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::system_program;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::state::{
+    Account as Token2022Account, AccountState, Mint as Token2022Mint,
+};
+use anchor_spl::token_interface::{self, InitializeAccount3, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 use crate::error::EscrowError;
-use crate::state::{EscrowState, ESCROW_SEED};
+use crate::state::{
+    Approval, CancelFeeRecipient, CancelReason, CancelRouting, CrankKind, DisputeResolution, EffectiveConfig, EscrowConfig, EscrowProgress, EscrowState, EscrowStatus, EscrowTerms, Keeper,
+    MakerProfile, Milestone, MilestoneInput, MilestoneStatus, MutualAction, RemainderPolicy, ResolutionBreakdown,
+    APPROVAL_MESSAGE_LEN, CONFIG_VIEW_VERSION, ESCROW_CONFIG_SEED, ESCROW_SEED, HOLDING_SEED, MAX_CLAW_BACK_SECONDS, MAX_MILESTONES,
+    MIN_EXPIRATION_DURATION, RECEIPT_SEED, RENEWAL_WINDOW,
+};
 
-/// Build escrow PDA signer seeds inner array.
+/// Build escrow PDA signer seeds inner array. `creator` is the maker at creation.
 pub fn escrow_seeds<'a>(
-    maker: &'a Pubkey,
+    creator: &'a Pubkey,
     seed_bytes: &'a [u8; 8],
     bump: &'a [u8; 1],
 ) -> [&'a [u8]; 4] {
-    [ESCROW_SEED, maker.as_ref(), seed_bytes.as_ref(), bump]
+    [ESCROW_SEED, creator.as_ref(), seed_bytes.as_ref(), bump]
+}
+
+/// Escrow PDA and bump for `maker` (the maker at creation) and `seed`.
+pub fn find_escrow_address(maker: &Pubkey, seed: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_SEED, maker.as_ref(), &seed.to_le_bytes()], &crate::ID)
+}
+
+/// Global config PDA and bump.
+pub fn find_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_CONFIG_SEED], &crate::ID)
+}
+
+/// Receipt NFT mint PDA and bump for `escrow`.
+pub fn find_receipt_mint(escrow: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_SEED, escrow.as_ref()], &crate::ID)
+}
+
+/// Defense in depth: the vault must never carry a delegate or close authority
+/// and must not be frozen. Nothing in the program sets either, so a violation
+/// means something outside it has touched the vault.
+pub fn assert_vault_clean(vault: &TokenAccount) -> Result<()> {
+    require!(vault.delegate.is_none(), EscrowError::VaultHasDelegate);
+    require!(vault.close_authority.is_none(), EscrowError::VaultHasCloseAuthority);
+    require!(vault.state == AccountState::Initialized, EscrowError::VaultFrozen);
+    Ok(())
+}
+
+/// Catch payout destinations the token program would reject with an opaque
+/// custom error: closed since the instruction's accounts were loaded, or
+/// frozen by the mint's freeze authority. Logs which account is at fault.
+/// There is no fallback destination to route to, so both fail the payout.
+pub fn verify_payout_destination(info: &AccountInfo, destination: &TokenAccount) -> Result<()> {
+    if info.lamports() == 0 || info.data_is_empty() || *info.owner == anchor_lang::system_program::ID {
+        msg!("Payout destination {} is closed", info.key);
+        return err!(EscrowError::DestinationClosed);
+    }
+    if destination.state == AccountState::Frozen {
+        msg!("Payout destination {} is frozen", info.key);
+        return err!(EscrowError::DestinationFrozen);
+    }
+    Ok(())
 }
 
 /// Transfer tokens from vault using PDA signer. Skips if amount == 0.
@@ -29,6 +81,7 @@ pub fn transfer_from_vault<'info>(
     if amount == 0 {
         return Ok(());
     }
+    verify_payout_destination(&destination.to_account_info(), destination)?;
     let transfer_accounts = TransferChecked {
         from: vault.to_account_info(),
         mint: mint.to_account_info(),
@@ -43,21 +96,723 @@ pub fn transfer_from_vault<'info>(
     token_interface::transfer_checked(cpi_ctx, amount, decimals)
 }
 
+/// Refund `refund` to the maker per `escrow.cancel_routing(reason)`: the
+/// routing's fee share goes to the token account of its `fee_recipient`, found
+/// by owner among `fee_accounts`, the rest to `maker_token_account`.
+/// `refunded_amount` must already include the whole refund.
+/// Returns the routing applied and the fee withheld.
+#[allow(clippy::too_many_arguments)]
+pub fn refund_maker<'info>(
+    escrow: &Account<'info, EscrowState>,
+    reason: CancelReason,
+    refund: u64,
+    fee_collector: Pubkey,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    maker_token_account: &InterfaceAccount<'info, TokenAccount>,
+    fee_accounts: &[Option<&InterfaceAccount<'info, TokenAccount>>],
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<(CancelRouting, u64)> {
+    let routing = escrow.cancel_routing(reason);
+    // The routing's fee is withheld from the refund (never more than all of it)
+    let (cancel_fee, maker_refund) = calculate_fee(refund, routing.fee_bps as u64, 0, 0)?;
+
+    transfer_from_vault(
+        vault, mint, maker_token_account,
+        escrow.to_account_info(), token_program,
+        signer_seeds, maker_refund, mint.decimals,
+    )?;
+
+    if cancel_fee > 0 {
+        let recipient = match routing.fee_recipient {
+            CancelFeeRecipient::FeeCollector => fee_collector,
+            CancelFeeRecipient::Beneficiary => escrow.beneficiary,
+        };
+        let Some(fee_account) = fee_accounts.iter().flatten().find(|account| account.owner == recipient) else {
+            // A lone caller-chosen account that belongs to someone else
+            if let [Some(_)] = fee_accounts {
+                return err!(EscrowError::OwnerMismatch);
+            }
+            return err!(EscrowError::CancelFeeAccountMissing);
+        };
+        require!(fee_account.key() != vault.key(), EscrowError::DestinationIsVault);
+        transfer_from_vault(
+            vault, mint, fee_account,
+            escrow.to_account_info(), token_program,
+            signer_seeds, cancel_fee, mint.decimals,
+        )?;
+    }
+
+    Ok((routing, cancel_fee))
+}
+
+/// Deterministic escrow seed: first 8 bytes (LE) of
+/// `sha256(maker || taker || mint || external_ref)`.
+pub fn derive_escrow_seed(
+    maker: &Pubkey,
+    taker: &Pubkey,
+    mint: &Pubkey,
+    external_ref: &[u8; 32],
+) -> u64 {
+    let digest = solana_sha256_hasher::hashv(&[maker.as_ref(), taker.as_ref(), mint.as_ref(), external_ref]).to_bytes();
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&digest[..8]);
+    u64::from_le_bytes(seed)
+}
+
+/// Commitment to a proposal's terms: `sha256(borsh(terms))`. The acceptor
+/// passes the hash of the terms they reviewed so a swapped proposal can't be funded.
+pub fn proposal_terms_hash(terms: &EscrowTerms) -> Result<[u8; 32]> {
+    let mut data = Vec::new();
+    terms.serialize(&mut data)?;
+    Ok(solana_sha256_hasher::hashv(&[&data]).to_bytes())
+}
+
+/// Commitment to a mutual action: `sha256(borsh(action) || escrow || nonce (LE) || params)`,
+/// where `params` is the borsh encoding of the instruction's arguments.
+pub fn mutual_action_hash(action: MutualAction, escrow: &Pubkey, nonce: u64, params: &[u8]) -> Result<[u8; 32]> {
+    let mut tag = Vec::new();
+    action.serialize(&mut tag)?;
+    Ok(solana_sha256_hasher::hashv(&[&tag, escrow.as_ref(), &nonce.to_le_bytes(), params]).to_bytes())
+}
+
+/// Accept `approval` in place of its approver's signature: it must be for this
+/// escrow, carry `action_hash`, have been recorded at the current nonce and be unexpired.
+pub fn verify_approval(approval: &Approval, escrow: &Pubkey, nonce: u64, action_hash: &[u8; 32], now: i64) -> Result<()> {
+    require!(
+        approval.escrow == *escrow && approval.nonce == nonce && approval.action_hash == *action_hash,
+        EscrowError::ApprovalMismatch
+    );
+    require!(now <= approval.expires_at, EscrowError::ApprovalExpired);
+    Ok(())
+}
+
 /// Calculate fee and net amount. Returns (fee, net) where net = amount - fee.
+/// The fee is max(bps fee, `flat_fee`), never more than `amount`, and zero for
+/// any `amount` below `fee_free_below`.
 /// Uses u128 intermediate to avoid overflow for large amounts.
-pub fn calculate_fee(amount: u64, fee_bps: u64) -> Result<(u64, u64)> {
+pub fn calculate_fee(amount: u64, fee_bps: u64, flat_fee: u64, fee_free_below: u64) -> Result<(u64, u64)> {
+    if amount < fee_free_below {
+        return Ok((0, amount));
+    }
     let fee_128 = (amount as u128)
         .checked_mul(fee_bps as u128)
         .ok_or(EscrowError::Overflow)?
         .checked_div(10_000)
         .ok_or(EscrowError::Overflow)?;
-    let fee = u64::try_from(fee_128).map_err(|_| error!(EscrowError::Overflow))?;
+    let fee = u64::try_from(fee_128)
+        .map_err(|_| error!(EscrowError::Overflow))?
+        .max(flat_fee)
+        .min(amount);
     let net = amount
         .checked_sub(fee)
         .ok_or(EscrowError::Overflow)?;
     Ok((fee, net))
 }
 
+/// (fee, net) for releasing `milestone`: fee-exempt milestones pay out in
+/// full, flat fee included. A fee funded on top at creation is paid out of
+/// its reserve, leaving the face amount as the net payout.
+pub fn milestone_fee(milestone: &Milestone, fee_bps: u16, flat_fee: u64, fee_free_below: u64) -> Result<(u64, u64)> {
+    if milestone.fee_exempt {
+        return Ok((0, milestone.amount));
+    }
+    if milestone.fee_reserved > 0 {
+        let net = milestone
+            .amount
+            .checked_sub(milestone.fee_reserved)
+            .ok_or(EscrowError::Overflow)?;
+        return Ok((milestone.fee_reserved, net));
+    }
+    calculate_fee(milestone.amount, fee_bps as u64, flat_fee, fee_free_below)
+}
+
+/// Net payout of each of the escrow's Approved milestones and their total
+/// fee, each charged as `release_milestone` would (`fee_exempt`,
+/// `fee_reserved` and `fee_free_below` included). Used when an expiry claim
+/// pays them in bulk.
+pub fn approved_milestone_payouts(escrow: &EscrowState) -> Result<(Vec<(u8, u64)>, u64)> {
+    let mut payouts = Vec::new();
+    let mut total_fee: u64 = 0;
+    for (i, milestone) in escrow.milestones.iter().enumerate() {
+        if milestone.status != MilestoneStatus::Approved {
+            continue;
+        }
+        let (fee, net) = milestone_fee(
+            milestone,
+            escrow.fee_bps_at_creation,
+            escrow.flat_fee_at_creation,
+            escrow.fee_free_below_at_creation,
+        )?;
+        total_fee = total_fee.checked_add(fee).ok_or(EscrowError::Overflow)?;
+        payouts.push((i as u8, net));
+    }
+    Ok((payouts, total_fee))
+}
+
+/// Destinations of milestone payouts settled in bulk, per `route_milestone_payouts`.
+#[derive(Debug, Default, PartialEq)]
+pub struct RoutedPayouts {
+    /// `(index, net)` to move into each milestone's claw-back holding.
+    pub held: Vec<(u8, u64)>,
+    /// `(index, net)` to pay a milestone's own beneficiary directly.
+    pub to_payees: Vec<(u8, u64)>,
+    /// Total to pay the escrow beneficiary directly.
+    pub to_beneficiary: u64,
+}
+
+/// Bulk-settlement counterpart of a single release: each `(index, net)`
+/// milestone payout is routed as `route_milestone_payout` would, held in the
+/// milestone's holding account while the escrow has a claw-back window, and
+/// otherwise paid to the milestone's payee. Records each `held_until`.
+pub fn route_milestone_payouts(
+    escrow: &mut EscrowState,
+    payouts: &[(u8, u64)],
+    now: i64,
+) -> Result<RoutedPayouts> {
+    let mut routed = RoutedPayouts::default();
+    for &(index, net) in payouts {
+        let net = escrow.route_milestone_payout(index, net)?;
+        let held_until = claw_back_deadline(escrow.claw_back_seconds, net, now)?;
+        escrow.set_held_until(index, held_until)?;
+        if held_until != 0 {
+            routed.held.push((index, net));
+        } else if escrow.milestone_beneficiary(index).is_some() {
+            routed.to_payees.push((index, net));
+        } else {
+            routed.to_beneficiary = routed.to_beneficiary.checked_add(net).ok_or(EscrowError::Overflow)?;
+        }
+    }
+    Ok(routed)
+}
+
+/// The token account in `remaining_accounts` that pays milestone `index`'s own
+/// beneficiary: owned by that payee, of the escrow's mint and token program.
+pub fn payee_token_account<'info>(
+    escrow: &EscrowState,
+    index: u8,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<InterfaceAccount<'info, TokenAccount>> {
+    let payee = escrow.milestone_payee(index);
+    for info in remaining_accounts.iter().filter(|info| *info.owner == escrow.token_program) {
+        if let Ok(account) = InterfaceAccount::<TokenAccount>::try_from(info) {
+            if account.owner == payee && account.mint == escrow.mint {
+                return Ok(account);
+            }
+        }
+    }
+    err!(EscrowError::PayeeAccountMissing)
+}
+
+/// Create holding slot `index`'s token account (owned by the escrow PDA, rent
+/// from `payer`) and move `amount` from the vault into it: the `init` a single
+/// release gets from its accounts struct, for paths that may hold several
+/// payouts at once. The holding PDA must be among `remaining_accounts`.
+#[allow(clippy::too_many_arguments)]
+pub fn fund_holding<'info>(
+    escrow: &Account<'info, EscrowState>,
+    index: u8,
+    amount: u64,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    payer: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    token_program: &Interface<'info, TokenInterface>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let escrow_key = escrow.key();
+    let (expected, holding_bump) =
+        Pubkey::find_program_address(&[HOLDING_SEED, escrow_key.as_ref(), &[index]], &crate::ID);
+    let holding = remaining_accounts
+        .iter()
+        .find(|info| info.key() == expected)
+        .ok_or(EscrowError::HoldingAccountMissing)?;
+    let holding_seeds: &[&[u8]] = &[HOLDING_SEED, escrow_key.as_ref(), &[index], &[holding_bump]];
+
+    let space = token_account_space(mint)?;
+    let lamports = Rent::get()?.minimum_balance(space);
+    let system = system_program.to_account_info();
+    if holding.lamports() == 0 {
+        let create = system_program::CreateAccount { from: payer, to: holding.clone() };
+        system_program::create_account(
+            CpiContext::new_with_signer(system.clone(), create, &[holding_seeds]),
+            lamports,
+            space as u64,
+            token_program.key,
+        )?;
+    } else {
+        // Pre-funded by a griefer: top up, then allocate and assign in place
+        let top_up = lamports.saturating_sub(holding.lamports());
+        if top_up > 0 {
+            let transfer = system_program::Transfer { from: payer, to: holding.clone() };
+            system_program::transfer(CpiContext::new(system.clone(), transfer), top_up)?;
+        }
+        let allocate = system_program::Allocate { account_to_allocate: holding.clone() };
+        system_program::allocate(
+            CpiContext::new_with_signer(system.clone(), allocate, &[holding_seeds]),
+            space as u64,
+        )?;
+        let assign = system_program::Assign { account_to_assign: holding.clone() };
+        system_program::assign(
+            CpiContext::new_with_signer(system, assign, &[holding_seeds]),
+            token_program.key,
+        )?;
+    }
+    let initialize = InitializeAccount3 {
+        account: holding.clone(),
+        mint: mint.to_account_info(),
+        authority: escrow.to_account_info(),
+    };
+    token_interface::initialize_account3(CpiContext::new(token_program.to_account_info(), initialize))?;
+
+    let holding = InterfaceAccount::<TokenAccount>::try_from(holding)?;
+    transfer_from_vault(
+        vault, mint, &holding,
+        escrow.to_account_info(), token_program,
+        signer_seeds, amount, mint.decimals,
+    )
+}
+
+/// Token account size for `mint`, including the extensions a Token-2022 mint
+/// requires of its accounts (as Anchor's `token::` init computes it).
+fn token_account_space(mint: &InterfaceAccount<Mint>) -> Result<usize> {
+    let info = mint.to_account_info();
+    if *info.owner != anchor_spl::token_2022::ID {
+        return Ok(SplTokenAccount::LEN);
+    }
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<Token2022Mint>::unpack(&data)?;
+    let required = ExtensionType::get_required_init_account_extensions(&state.get_extension_types()?);
+    Ok(ExtensionType::try_calculate_account_len::<Token2022Account>(&required)?)
+}
+
+/// Fee the maker funds on top of a `face` milestone amount when the escrow is
+/// created with `fee_on_top`: exactly what would otherwise be deducted from it.
+pub fn fee_on_top(face: u64, fee_exempt: bool, fee_bps: u16, flat_fee: u64, fee_free_below: u64) -> Result<u64> {
+    if fee_exempt {
+        return Ok(0);
+    }
+    let (fee, _) = calculate_fee(face, fee_bps as u64, flat_fee, fee_free_below)?;
+    Ok(fee)
+}
+
+/// Reject disputes over a drained escrow: with every unit already released or
+/// refunded there is nothing left for a ruling to distribute.
+pub fn verify_disputable_balance(escrow: &EscrowState) -> Result<()> {
+    require!(escrow.remaining_amount()? > 0, EscrowError::NoRefundableAmount);
+    Ok(())
+}
+
+/// Lifetime dispute cap: reject a new dispute once `dispute_count` reaches
+/// `cap`. 0 = unlimited.
+pub fn verify_dispute_cap(escrow: &EscrowState, cap: u8) -> Result<()> {
+    require!(cap == 0 || escrow.dispute_count < cap, EscrowError::DisputeLimitReached);
+    Ok(())
+}
+
+/// Optimistic-concurrency guard: when the caller pins `expected`, the escrow
+/// must not have been mutated since they read it.
+pub fn verify_expected_nonce(escrow: &EscrowState, expected: Option<u64>) -> Result<()> {
+    if let Some(expected) = expected {
+        require!(escrow.nonce == expected, EscrowError::StaleState);
+    }
+    Ok(())
+}
+
+/// Pool payout for burning `shares` out of `supply`: floor(pool * shares / supply).
+/// Rounding dust stays in the pool, so the last holder to redeem drains it.
+pub fn pro_rata_redemption(pool: u64, shares: u64, supply: u64) -> Result<u64> {
+    require!(shares > 0 && shares <= supply, EscrowError::InvalidAmount);
+    let payout = (pool as u128)
+        .checked_mul(shares as u128)
+        .ok_or(EscrowError::Overflow)?
+        / supply as u128;
+    u64::try_from(payout).map_err(|_| error!(EscrowError::Overflow))
+}
+
+/// Part of milestone `idx`'s net payout owed to its linked sub-escrow, if any.
+/// Marks the child funded; the caller moves the tokens into `child.vault`.
+/// The link lapses (returns 0, child stays `AwaitingFunding`) once the claim
+/// has moved away from the child's maker or been fractionalized.
+pub fn take_sub_escrow_funding(
+    escrow: &EscrowState,
+    idx: usize,
+    child: Option<&mut Account<EscrowState>>,
+    child_vault: Option<&InterfaceAccount<TokenAccount>>,
+) -> Result<u64> {
+    let Some(linked) = escrow.milestones[idx].sub_escrow else {
+        return Ok(0);
+    };
+    let (Some(child), Some(child_vault)) = (child, child_vault) else {
+        return err!(EscrowError::SubEscrowAccountMissing);
+    };
+    require!(child.key() == linked, EscrowError::SubEscrowMismatch);
+    require!(child_vault.key() == child.vault, EscrowError::VaultMismatch);
+
+    if child.status != EscrowStatus::AwaitingFunding
+        || child.maker != escrow.beneficiary
+        || escrow.share_mint.is_some()
+    {
+        return Ok(0);
+    }
+    child.status = EscrowStatus::Active;
+    child.bump_nonce()?;
+    Ok(child.amount)
+}
+
+/// Progress of an escrow in basis points: floor(released * 10_000 / amount).
+pub fn percent_complete_bps(released: u64, amount: u64) -> Result<u16> {
+    if amount == 0 {
+        return Ok(0);
+    }
+    let bps = (released as u128)
+        .checked_mul(10_000)
+        .ok_or(EscrowError::Overflow)?
+        / amount as u128;
+    u16::try_from(bps.min(10_000)).map_err(|_| error!(EscrowError::Overflow))
+}
+
+/// Split `amount` three ways: `maker_bps` to the maker, the rest to the
+/// beneficiary less `fee_bps` of it to the fee collector. Each party gets the
+/// floor of its exact share and the remainder (at most 2 units) goes where
+/// `policy` says — or to the beneficiary when that party's exact share is zero,
+/// so dust never opens a leg for a party the settlement owes nothing.
+/// A non-zero beneficiary leg is one payout: its fee is raised to `flat_fee`
+/// where needed, out of the beneficiary's share.
+/// Returns (maker, beneficiary, fee), which always sum to `amount`.
+pub fn split_amount(
+    amount: u64,
+    maker_bps: u16,
+    fee_bps: u16,
+    flat_fee: u64,
+    policy: RemainderPolicy,
+) -> Result<(u64, u64, u64)> {
+    require!(
+        maker_bps <= 10_000 && fee_bps <= 10_000,
+        EscrowError::InvalidShareDistribution
+    );
+    // Exact shares over a common denominator of 10_000²; the numerators sum to amount * 10_000²
+    const DENOM: u128 = 100_000_000;
+    let amount_128 = amount as u128;
+    let taker_bps = 10_000 - maker_bps as u128;
+    let maker_num = amount_128 * maker_bps as u128 * 10_000;
+    let fee_num = amount_128 * taker_bps * fee_bps as u128;
+    let beneficiary_num = amount_128 * taker_bps * (10_000 - fee_bps as u128);
+
+    let (mut maker, mut beneficiary, mut fee) = (
+        (maker_num / DENOM) as u64,
+        (beneficiary_num / DENOM) as u64,
+        (fee_num / DENOM) as u64,
+    );
+    let remainder = amount - maker - beneficiary - fee;
+    match policy {
+        RemainderPolicy::ToMaker if maker_num > 0 => maker += remainder,
+        RemainderPolicy::ToFeeCollector if fee_num > 0 => fee += remainder,
+        _ => beneficiary += remainder,
+    }
+    if beneficiary_num > 0 && fee < flat_fee {
+        let top_up = (flat_fee - fee).min(beneficiary);
+        beneficiary -= top_up;
+        fee += top_up;
+    }
+    Ok((maker, beneficiary, fee))
+}
+
+/// How a terminal escrow's held taker bond is paid out: (maker, taker).
+/// Only a dispute ruling forfeits any of it — all of it on `MakerWins`, the
+/// ruling's `maker_bps` on a `Split` (remainder routed per `remainder_policy`).
+/// Completion, cancellation and every expiry claim return it to the taker.
+pub fn bond_split(escrow: &EscrowState) -> Result<(u64, u64)> {
+    let ruling = escrow.dispute.as_ref().and_then(|d| d.resolution.as_ref());
+    let maker_bps = match (&escrow.status, ruling) {
+        (EscrowStatus::Cancelled, Some(DisputeResolution::MakerWins)) => 10_000,
+        (EscrowStatus::Completed, Some(DisputeResolution::Split { maker_bps })) => *maker_bps,
+        (EscrowStatus::Completed | EscrowStatus::Cancelled | EscrowStatus::Expired, _) => 0,
+        _ => return err!(EscrowError::EscrowNotTerminal),
+    };
+    let (maker, taker, _) = split_amount(escrow.bond_balance, maker_bps, 0, 0, escrow.remainder_policy)?;
+    Ok((maker, taker))
+}
+
+/// Dashboard summary of `escrow`, as returned by `batch_escrow_progress`.
+pub fn escrow_progress(escrow_key: Pubkey, escrow: &EscrowState) -> Result<EscrowProgress> {
+    let settled_milestones = escrow
+        .milestones
+        .iter()
+        .filter(|m| m.status == MilestoneStatus::Released || m.status == MilestoneStatus::Cancelled)
+        .count() as u8;
+    Ok(EscrowProgress {
+        escrow: escrow_key,
+        status: escrow.status.clone(),
+        released_bps: percent_complete_bps(escrow.released_amount, escrow.amount)?,
+        settled_milestones,
+        milestone_count: escrow.milestones.len() as u8,
+    })
+}
+
+/// Versioned snapshot of `config` plus the program-wide limits, as returned by `get_config`.
+pub fn effective_config(config: &EscrowConfig) -> EffectiveConfig {
+    EffectiveConfig {
+        version: CONFIG_VIEW_VERSION,
+        authority: config.authority,
+        fee_bps: config.fee_bps,
+        fee_collector: config.fee_collector,
+        dispute_timeout: config.dispute_timeout,
+        release_delay: config.release_delay,
+        max_disputes_per_escrow: config.max_disputes_per_escrow,
+        accepted_hash_algos: config.accepted_hash_algos,
+        close_dust_threshold: config.close_dust_threshold,
+        dispute_timeout_policy: config.dispute_timeout_policy,
+        request_ttl: config.request_ttl,
+        claw_back_seconds: config.claw_back_seconds,
+        approval_extension: config.approval_extension,
+        max_expiry_extension: config.max_expiry_extension,
+        remainder_policy: config.remainder_policy,
+        allow_fee_exempt: config.allow_fee_exempt,
+        max_open_escrows_per_maker: config.max_open_escrows_per_maker,
+        expiry_extension_per_approval: config.expiry_extension_per_approval,
+        flat_fee: config.flat_fee,
+        flat_fee_mint: config.flat_fee_mint,
+        min_expiration_duration: MIN_EXPIRATION_DURATION,
+        max_claw_back_seconds: MAX_CLAW_BACK_SECONDS,
+        renewal_window: RENEWAL_WINDOW,
+        max_milestones: MAX_MILESTONES,
+        cancel_fee_bps: config.cancel_fee_bps,
+        cancel_fee_recipient: config.cancel_fee_recipient,
+        receipts_enabled: config.receipts_enabled,
+        fee_free_below: config.fee_free_below,
+        max_milestone_skew_bps: config.max_milestone_skew_bps,
+        config_version: config.config_version,
+        creation_paused: config.creation_paused,
+        cancel_routes: config.cancel_routes,
+    }
+}
+
+/// Compute what `resolution` pays each party out of the escrow's unsettled balance.
+/// The beneficiary's share is charged the creation-time fee; the maker's is not.
+pub fn resolution_breakdown(
+    escrow: &EscrowState,
+    resolution: &DisputeResolution,
+) -> Result<ResolutionBreakdown> {
+    let remaining = escrow.remaining_amount()?;
+
+    let maker_bps = match resolution {
+        DisputeResolution::MakerWins => 10_000,
+        DisputeResolution::TakerWins => 0,
+        DisputeResolution::Split { maker_bps } => {
+            require!(*maker_bps <= 10_000, EscrowError::InvalidDisputeResolution);
+            *maker_bps
+        }
+    };
+    let (maker_amount, beneficiary_amount, fee) = split_amount(
+        remaining,
+        maker_bps,
+        escrow.fee_bps_at_creation,
+        escrow.flat_fee_at_creation,
+        escrow.remainder_policy,
+    )?;
+
+    Ok(ResolutionBreakdown {
+        remaining,
+        maker_amount,
+        beneficiary_amount,
+        fee,
+    })
+}
+
+/// The party a ruling goes against: the beneficiary under `MakerWins`, the
+/// maker under `TakerWins`, and whoever gets the smaller share of a `Split`.
+pub fn dispute_loser(escrow: &EscrowState, resolution: &DisputeResolution) -> Result<Pubkey> {
+    match resolution {
+        DisputeResolution::MakerWins => Ok(escrow.beneficiary),
+        DisputeResolution::TakerWins => Ok(escrow.maker),
+        DisputeResolution::Split { maker_bps } => match maker_bps.cmp(&5_000) {
+            std::cmp::Ordering::Less => Ok(escrow.maker),
+            std::cmp::Ordering::Greater => Ok(escrow.beneficiary),
+            std::cmp::Ordering::Equal => err!(EscrowError::NoDisputeLoser),
+        },
+    }
+}
+
+/// Canonical message a maker signs offline to approve a milestone:
+/// `program_id || escrow || milestone_index || nonce (LE)`.
+pub fn approval_message(
+    escrow: &Pubkey,
+    milestone_index: u8,
+    nonce: u64,
+) -> [u8; APPROVAL_MESSAGE_LEN] {
+    let mut msg = [0u8; APPROVAL_MESSAGE_LEN];
+    msg[..32].copy_from_slice(crate::ID.as_ref());
+    msg[32..64].copy_from_slice(escrow.as_ref());
+    msg[64] = milestone_index;
+    msg[65..].copy_from_slice(&nonce.to_le_bytes());
+    msg
+}
+
+/// Verify that `ix` is an ed25519 program instruction carrying exactly one
+/// signature by `signer` over `message`, with all data inline (no references
+/// to other instructions in the transaction).
+pub fn verify_ed25519_ix(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    const HEADER_LEN: usize = 2; // num_signatures (u8) + padding (u8)
+    const OFFSETS_LEN: usize = 14; // 7 x u16
+    const SIGNATURE_LEN: usize = 64;
+    const PUBKEY_LEN: usize = 32;
+
+    require!(
+        ix.program_id == solana_sdk_ids::ed25519_program::ID,
+        EscrowError::InvalidApprovalSignature
+    );
+    require!(ix.accounts.is_empty(), EscrowError::InvalidApprovalSignature);
+
+    let data = &ix.data;
+    require!(
+        data.len() >= HEADER_LEN + OFFSETS_LEN && data[0] == 1,
+        EscrowError::InvalidApprovalSignature
+    );
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_offset = read_u16(2) as usize;
+    let signature_ix_index = read_u16(4);
+    let pubkey_offset = read_u16(6) as usize;
+    let pubkey_ix_index = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix_index = read_u16(14);
+
+    // All referenced data must live inside this ed25519 instruction
+    require!(
+        signature_ix_index == u16::MAX && pubkey_ix_index == u16::MAX && message_ix_index == u16::MAX,
+        EscrowError::InvalidApprovalSignature
+    );
+
+    let signature_end = signature_offset
+        .checked_add(SIGNATURE_LEN)
+        .ok_or(EscrowError::InvalidApprovalSignature)?;
+    let pubkey_end = pubkey_offset
+        .checked_add(PUBKEY_LEN)
+        .ok_or(EscrowError::InvalidApprovalSignature)?;
+    let message_end = message_offset
+        .checked_add(message_size)
+        .ok_or(EscrowError::InvalidApprovalSignature)?;
+    require!(
+        signature_end <= data.len() && pubkey_end <= data.len() && message_end <= data.len(),
+        EscrowError::InvalidApprovalSignature
+    );
+
+    require!(
+        &data[pubkey_offset..pubkey_end] == signer.as_ref(),
+        EscrowError::ApprovalSignerMismatch
+    );
+    require!(
+        &data[message_offset..message_end] == message,
+        EscrowError::InvalidApprovalSignature
+    );
+    Ok(())
+}
+
+/// Tip owed to a keeper for one crank: nothing unless the crank changed state
+/// (`nonce_after != nonce_before`), otherwise `crank_tip` capped at the jar.
+pub fn crank_tip_due(crank_tip: u64, tip_jar: u64, nonce_before: u64, nonce_after: u64) -> u64 {
+    if nonce_after == nonce_before {
+        return 0;
+    }
+    crank_tip.min(tip_jar)
+}
+
+/// Move the crank tip from the escrow's tip jar into the keeper's account.
+/// Returns the lamports paid (0 without a registered keeper or for a no-op crank).
+pub fn pay_crank_tip<'info>(
+    escrow: &mut Account<'info, EscrowState>,
+    keeper: Option<&mut Account<'info, Keeper>>,
+    nonce_before: u64,
+) -> Result<u64> {
+    let Some(keeper) = keeper else {
+        return Ok(0);
+    };
+    let tip = crank_tip_due(escrow.crank_tip, escrow.tip_jar, nonce_before, escrow.nonce);
+    if tip == 0 {
+        return Ok(0);
+    }
+    escrow.tip_jar = escrow.tip_jar.checked_sub(tip).ok_or(EscrowError::Overflow)?;
+    keeper.cranks = keeper.cranks.checked_add(1).ok_or(EscrowError::Overflow)?;
+    keeper.tips_earned = keeper.tips_earned.checked_add(tip).ok_or(EscrowError::Overflow)?;
+    escrow.sub_lamports(tip)?;
+    keeper.add_lamports(tip)?;
+    Ok(tip)
+}
+
+/// Where a crank schedule goes after firing at `now`: a `ReleaseMilestone`
+/// with an interval moves on to the next milestone while the escrow is still
+/// Active. `None` closes the schedule.
+pub fn next_crank(
+    kind: CrankKind,
+    status: EscrowStatus,
+    milestone_count: usize,
+    now: i64,
+) -> Result<Option<(CrankKind, i64)>> {
+    let CrankKind::ReleaseMilestone { index, interval } = kind else {
+        return Ok(None);
+    };
+    let next = index as usize + 1;
+    if interval == 0 || status != EscrowStatus::Active || next >= milestone_count {
+        return Ok(None);
+    }
+    let eta = now.checked_add(interval).ok_or(EscrowError::Overflow)?;
+    Ok(Some((CrankKind::ReleaseMilestone { index: next as u8, interval }, eta)))
+}
+
+/// Give a terminal escrow's slot back to its creator's `MakerProfile`.
+/// No-op while the escrow is still open or was never counted; a counted
+/// escrow fails with `MakerProfileMissing` when the profile isn't passed.
+/// Every settling instruction therefore takes an optional, writable
+/// `maker_profile` (seeded by `escrow_state.creator`), which callers must
+/// supply once the escrow turns terminal if it was counted against
+/// `max_open_escrows_per_maker`.
+pub fn release_open_slot(escrow: &mut EscrowState, profile: Option<&mut MakerProfile>) -> Result<()> {
+    let terminal = matches!(
+        escrow.status,
+        EscrowStatus::Completed | EscrowStatus::Cancelled | EscrowStatus::Expired
+    );
+    if !terminal || !escrow.counted_open {
+        return Ok(());
+    }
+    let profile = profile.ok_or(EscrowError::MakerProfileMissing)?;
+    profile.open_escrows = profile.open_escrows.saturating_sub(1);
+    escrow.counted_open = false;
+    Ok(())
+}
+
+/// Count an escrow a renewal reopens against its creator's `MakerProfile`,
+/// with the same cap check as creation. No-op while the escrow is still
+/// counted or no cap is set; fails with `MakerProfileMissing` when the cap
+/// applies and the profile isn't passed.
+pub fn reserve_open_slot(escrow: &mut EscrowState, profile: Option<&mut MakerProfile>, cap: u16) -> Result<()> {
+    if escrow.counted_open || cap == 0 {
+        return Ok(());
+    }
+    let profile = profile.ok_or(EscrowError::MakerProfileMissing)?;
+    escrow.counted_open = profile.open_escrow(cap)?;
+    Ok(())
+}
+
+/// When a released net payout of `net` must sit in holding: the time it becomes
+/// withdrawable, `now + window`. 0 when no claw-back window applies.
+pub fn claw_back_deadline(window: i64, net: u64, now: i64) -> Result<i64> {
+    if window == 0 || net == 0 {
+        return Ok(0);
+    }
+    Ok(now.checked_add(window).ok_or(EscrowError::Overflow)?)
+}
+
+/// Fail fast when a caller passes more `remaining_accounts` than the
+/// instruction can use, before any state is touched.
+pub fn verify_remaining_accounts(remaining_accounts: &[AccountInfo], max: usize) -> Result<()> {
+    require!(remaining_accounts.len() <= max, EscrowError::TooManyAccounts);
+    Ok(())
+}
+
 /// Verify that the receipt NFT holder matches `escrow.beneficiary`.
 /// Must be called when `escrow.receipt_mint.is_some()`.
 /// Expects `remaining_accounts[0]` to be the receipt token account.
@@ -88,3 +843,1159 @@ pub fn verify_receipt_sync(
     );
     Ok(())
 }
+
+/// Pre-checks shared by `claim_active_expired` and `claim_dispute_timeout`.
+/// Returns the unsettled vault balance the claim distributes.
+pub fn verify_expiry_claimable(
+    escrow: &EscrowState,
+    remaining_accounts: &[AccountInfo],
+) -> Result<u64> {
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+
+    let remaining = escrow.remaining_amount()?;
+    require!(remaining > 0, EscrowError::NoRefundableAmount);
+
+    // If a receipt NFT exists, verify beneficiary is synced with current NFT holder.
+    if escrow.receipt_mint.is_some() {
+        verify_receipt_sync(escrow, remaining_accounts)?;
+    }
+    Ok(remaining)
+}
+
+/// Assert the core milestone accounting invariant: milestone amounts sum to
+/// `escrow.amount`, and released + refunded + emergency-withdrawn never exceeds it.
+/// Call at the end of every handler that mutates milestones.
+pub fn verify_milestone_sum(escrow: &EscrowState) -> Result<()> {
+    let mut milestone_sum: u64 = 0;
+    for m in &escrow.milestones {
+        milestone_sum = milestone_sum
+            .checked_add(m.amount)
+            .ok_or(EscrowError::Overflow)?;
+    }
+    require!(milestone_sum == escrow.amount, EscrowError::MilestoneAmountMismatch);
+
+    let settled = escrow
+        .released_amount
+        .checked_add(escrow.refunded_amount)
+        .and_then(|sum| sum.checked_add(escrow.emergency_withdrawn))
+        .ok_or(EscrowError::Overflow)?;
+    require!(settled <= escrow.amount, EscrowError::MilestoneAmountMismatch);
+    Ok(())
+}
+
+/// Check that each input milestone is non-zero and that they sum to exactly
+/// `total`. The running sum is checked (`Overflow`) and the loop stops with
+/// `MilestoneAmountMismatch` as soon as it passes `total`.
+pub fn verify_milestone_inputs(milestones: &[MilestoneInput], total: u64) -> Result<()> {
+    let mut milestone_sum: u64 = 0;
+    for m in milestones {
+        require!(m.amount > 0, EscrowError::InvalidAmount);
+        milestone_sum = milestone_sum
+            .checked_add(m.amount)
+            .ok_or(EscrowError::Overflow)?;
+        require!(milestone_sum <= total, EscrowError::MilestoneAmountMismatch);
+    }
+    require!(milestone_sum == total, EscrowError::MilestoneAmountMismatch);
+    Ok(())
+}
+
+/// Milestone-level beneficiaries may name anyone but the maker.
+pub fn verify_milestone_beneficiaries(milestones: &[MilestoneInput], maker: &Pubkey) -> Result<()> {
+    require!(
+        milestones.iter().all(|m| m.beneficiary.as_ref() != Some(maker)),
+        EscrowError::MilestoneBeneficiaryIsMaker
+    );
+    Ok(())
+}
+
+/// With `max_skew_bps` set, every milestone must be within that many bps of
+/// an even split of `total` (`|amount - total / n| <= total / n * bps / 10_000`),
+/// so no milestone can be made tiny or back-loaded. 0 disables the check.
+pub fn verify_milestone_skew(milestones: &[MilestoneInput], total: u64, max_skew_bps: u16) -> Result<()> {
+    if max_skew_bps == 0 {
+        return Ok(());
+    }
+    // Scaled by n to stay in integers: |amount * n - total| * 10_000 <= total * bps
+    let n = milestones.len() as u128;
+    let limit = total as u128 * max_skew_bps as u128;
+    for m in milestones {
+        let deviation = (m.amount as u128 * n).abs_diff(total as u128);
+        require!(deviation * 10_000 <= limit, EscrowError::MilestoneSkewExceeded);
+    }
+    Ok(())
+}
+
+/// Releases wait for the taker to acknowledge an attached agreement.
+/// Escrows without one are unaffected.
+pub fn verify_agreement_acknowledged(escrow: &EscrowState) -> Result<()> {
+    require!(
+        escrow.agreement_hash.is_none() || escrow.agreement_acknowledged,
+        EscrowError::AgreementNotAcknowledged
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_option::COption;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token_2022::spl_token_2022::state::Account as SplTokenState;
+    use crate::state::{
+        CancelFeeRecipient, CancelReason, CancelRouting, EscrowStatus, MilestoneInput, MilestoneStatus, DISPUTE_INITIATORS_ALL,
+        DISPUTE_INITIATOR_BENEFICIARY, DISPUTE_INITIATOR_MAKER, DISPUTE_INITIATOR_TAKER,
+        CANCEL_REASON_COUNT, HASH_ALGO_SHA256, MAX_BATCH_PROGRESS_ACCOUNTS, MAX_RECEIPT_SYNC_ACCOUNTS, SETTLEMENT_HOLDING_INDEX,
+    };
+
+    /// Deterministic xorshift so the property loop is reproducible without extra deps.
+    fn next(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    const REMAINDER_POLICIES: [RemainderPolicy; 3] = [
+        RemainderPolicy::ToMaker,
+        RemainderPolicy::ToBeneficiary,
+        RemainderPolicy::ToFeeCollector,
+    ];
+
+    #[test]
+    fn split_amount_sums_to_amount_for_every_policy_and_bps() {
+        let mut state = 0x2545_F491_4F6C_DD1D;
+        for maker_bps in 0..=10_000u16 {
+            let amount = match maker_bps % 3 {
+                0 => next(&mut state) % 1_000,
+                1 => u64::MAX - next(&mut state) % 1_000,
+                _ => next(&mut state),
+            };
+            let fee_bps = (next(&mut state) % 10_001) as u16;
+            let flat_fee = if maker_bps % 2 == 0 { 0 } else { next(&mut state) };
+            for policy in REMAINDER_POLICIES {
+                let (maker, beneficiary, fee) = split_amount(amount, maker_bps, fee_bps, flat_fee, policy).unwrap();
+                let sum = maker as u128 + beneficiary as u128 + fee as u128;
+                assert_eq!(sum, amount as u128, "{:?} maker {} fee {} amount {}", policy, maker_bps, fee_bps, amount);
+            }
+        }
+        for fee_bps in 0..=10_000u16 {
+            for policy in REMAINDER_POLICIES {
+                let (maker, beneficiary, fee) = split_amount(999_999, 3_333, fee_bps, 0, policy).unwrap();
+                assert_eq!(maker + beneficiary + fee, 999_999);
+            }
+        }
+    }
+
+    #[test]
+    fn split_amount_routes_remainder_per_policy() {
+        // 101 * 50% = 50.5 to the maker; the beneficiary's 50.5 pays 2.5% = 1.2625 in fees
+        assert_eq!(split_amount(101, 5_000, 250, 0, RemainderPolicy::ToBeneficiary).unwrap(), (50, 50, 1));
+        assert_eq!(split_amount(101, 5_000, 250, 0, RemainderPolicy::ToMaker).unwrap(), (51, 49, 1));
+        assert_eq!(split_amount(101, 5_000, 250, 0, RemainderPolicy::ToFeeCollector).unwrap(), (50, 49, 2));
+        // The maker has no stake in a plain fee split, so its dust falls to the beneficiary
+        assert_eq!(split_amount(99, 0, 250, 0, RemainderPolicy::ToMaker).unwrap(), (0, 97, 2));
+        assert_eq!(split_amount(99, 0, 250, 0, RemainderPolicy::ToFeeCollector).unwrap(), (0, 96, 3));
+        // Nothing to round when one party takes everything
+        assert_eq!(split_amount(99, 10_000, 250, 0, RemainderPolicy::ToFeeCollector).unwrap(), (99, 0, 0));
+        assert!(split_amount(99, 10_001, 0, 0, RemainderPolicy::ToMaker).is_err());
+        assert!(split_amount(99, 0, 10_001, 0, RemainderPolicy::ToMaker).is_err());
+    }
+
+    #[test]
+    fn timeout_split_odd_unit_follows_remainder_policy() {
+        // SplitOnTimeout over remaining = 3: both halves floor to 1, the odd unit goes per policy
+        assert_eq!(split_amount(3, 5_000, 0, 0, RemainderPolicy::ToMaker).unwrap(), (2, 1, 0));
+        assert_eq!(split_amount(3, 5_000, 0, 0, RemainderPolicy::ToBeneficiary).unwrap(), (1, 2, 0));
+        // No fee leg to absorb it, so it falls back to the beneficiary
+        assert_eq!(split_amount(3, 5_000, 0, 0, RemainderPolicy::ToFeeCollector).unwrap(), (1, 2, 0));
+        // The fee (3.75% of a unit) floors to 0 and the odd unit still goes per policy
+        assert_eq!(split_amount(3, 5_000, 250, 0, RemainderPolicy::ToMaker).unwrap(), (2, 1, 0));
+        assert_eq!(split_amount(3, 5_000, 250, 0, RemainderPolicy::ToFeeCollector).unwrap(), (1, 1, 1));
+    }
+
+    #[test]
+    fn fee_free_below_threshold_boundary() {
+        // One unit under the threshold pays nothing, flat fee included
+        assert_eq!(calculate_fee(9_999, 250, 100, 10_000).unwrap(), (0, 9_999));
+        // At the threshold the regular fee (here the flat floor) applies
+        assert_eq!(calculate_fee(10_000, 250, 100, 10_000).unwrap(), (250, 9_750));
+        assert_eq!(calculate_fee(10_000, 250, 300, 10_000).unwrap(), (300, 9_700));
+        // 0 = off: a zero amount still pays zero
+        assert_eq!(calculate_fee(0, 250, 100, 0).unwrap(), (0, 0));
+
+        // Fees funded on top stay consistent with what a release charges
+        let mut escrow = escrow_fixture();
+        escrow.milestones[0].amount = 9_999;
+        assert_eq!(fee_on_top(9_999, false, 250, 0, 10_000).unwrap(), 0);
+        assert_eq!(milestone_fee(&escrow.milestones[0], 250, 0, 10_000).unwrap(), (0, 9_999));
+    }
+
+    #[test]
+    fn flat_fee_is_a_floor_capped_at_the_amount() {
+        let mut state = 0x1234_5678_9ABC_DEF1;
+        for _ in 0..10_000 {
+            let amount = next(&mut state) % 10_000_000;
+            let fee_bps = next(&mut state) % 10_001;
+            let flat_fee = next(&mut state) % 5_000_000;
+            let (fee, net) = calculate_fee(amount, fee_bps, flat_fee, 0).unwrap();
+            assert_eq!(fee + net, amount);
+            assert_eq!(fee, (amount * fee_bps / 10_000).max(flat_fee).min(amount));
+        }
+        // 2.5% of 1_000 is 25, below the 2_000 floor; the floor never exceeds the payout
+        assert_eq!(calculate_fee(100_000, 250, 2_000, 0).unwrap(), (2_500, 97_500));
+        assert_eq!(calculate_fee(1_000, 250, 2_000, 0).unwrap(), (1_000, 0));
+        assert_eq!(calculate_fee(1_000, 250, 0, 0).unwrap(), (25, 975));
+
+        // A ruling's beneficiary leg is one payout; a maker-only leg pays no fee
+        assert_eq!(split_amount(10_000, 5_000, 250, 1_000, RemainderPolicy::ToBeneficiary).unwrap(), (5_000, 4_000, 1_000));
+        assert_eq!(split_amount(10_000, 9_990, 250, 1_000, RemainderPolicy::ToBeneficiary).unwrap(), (9_990, 0, 10));
+        assert_eq!(split_amount(10_000, 10_000, 250, 1_000, RemainderPolicy::ToBeneficiary).unwrap(), (10_000, 0, 0));
+    }
+
+    const DATA_START: usize = 16;
+
+    /// Build an ed25519 verify instruction with inline pubkey, signature and message.
+    /// The signature bytes are irrelevant here — the runtime verifies them, the
+    /// program only checks which key signed which message.
+    fn ed25519_ix(signer: &Pubkey, message: &[u8]) -> Instruction {
+        let pubkey_offset = DATA_START;
+        let signature_offset = pubkey_offset + 32;
+        let message_offset = signature_offset + 64;
+        let mut data = vec![1u8, 0];
+        for v in [
+            signature_offset as u16,
+            u16::MAX,
+            pubkey_offset as u16,
+            u16::MAX,
+            message_offset as u16,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[7u8; 64]);
+        data.extend_from_slice(message);
+        Instruction {
+            program_id: solana_sdk_ids::ed25519_program::ID,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    fn set_u16(ix: &mut Instruction, at: usize, v: u16) {
+        ix.data[at..at + 2].copy_from_slice(&v.to_le_bytes());
+    }
+
+    #[test]
+    fn verify_ed25519_ix_accepts_canonical_approval() {
+        let maker = Pubkey::new_unique();
+        let msg = approval_message(&Pubkey::new_unique(), 2, 5);
+        assert!(verify_ed25519_ix(&ed25519_ix(&maker, &msg), &maker, &msg).is_ok());
+    }
+
+    #[test]
+    fn verify_ed25519_ix_rejects_malformed_introspection() {
+        let maker = Pubkey::new_unique();
+        let msg = approval_message(&Pubkey::new_unique(), 0, 0);
+        let invalid: Error = EscrowError::InvalidApprovalSignature.into();
+
+        let mut wrong_program = ed25519_ix(&maker, &msg);
+        wrong_program.program_id = Pubkey::new_unique();
+        assert_eq!(verify_ed25519_ix(&wrong_program, &maker, &msg).unwrap_err(), invalid);
+
+        let mut two_sigs = ed25519_ix(&maker, &msg);
+        two_sigs.data[0] = 2;
+        assert_eq!(verify_ed25519_ix(&two_sigs, &maker, &msg).unwrap_err(), invalid);
+
+        let mut truncated = ed25519_ix(&maker, &msg);
+        truncated.data.truncate(10);
+        assert_eq!(verify_ed25519_ix(&truncated, &maker, &msg).unwrap_err(), invalid);
+
+        // Pubkey pulled from another instruction in the transaction
+        let mut cross_ix = ed25519_ix(&maker, &msg);
+        set_u16(&mut cross_ix, 8, 0);
+        assert_eq!(verify_ed25519_ix(&cross_ix, &maker, &msg).unwrap_err(), invalid);
+
+        // Message offset pointing past the end of the data
+        let mut out_of_bounds = ed25519_ix(&maker, &msg);
+        set_u16(&mut out_of_bounds, 10, u16::MAX - 1);
+        assert_eq!(verify_ed25519_ix(&out_of_bounds, &maker, &msg).unwrap_err(), invalid);
+    }
+
+    #[test]
+    fn verify_ed25519_ix_rejects_wrong_signer_and_replayed_message() {
+        let maker = Pubkey::new_unique();
+        let escrow = Pubkey::new_unique();
+        let msg = approval_message(&escrow, 1, 3);
+
+        let forged = ed25519_ix(&Pubkey::new_unique(), &msg);
+        assert_eq!(
+            verify_ed25519_ix(&forged, &maker, &msg).unwrap_err(),
+            EscrowError::ApprovalSignerMismatch.into()
+        );
+
+        // A signature over an already-consumed nonce does not match the expected message
+        let replayed = ed25519_ix(&maker, &approval_message(&escrow, 1, 2));
+        assert_eq!(
+            verify_ed25519_ix(&replayed, &maker, &msg).unwrap_err(),
+            EscrowError::InvalidApprovalSignature.into()
+        );
+    }
+
+    /// Active escrow with two milestones (600 + 400) and nothing settled yet.
+    fn escrow_fixture() -> EscrowState {
+        let milestone = |amount| Milestone {
+            amount,
+            description_hash: [0u8; 32],
+            status: MilestoneStatus::Pending,
+            approved_at: 0,
+            sub_escrow: None,
+            held_until: 0,
+            fee_exempt: false,
+            self_certify: false,
+            fee_reserved: 0,
+            beneficiary: None,
+        };
+        EscrowState {
+            maker: Pubkey::new_unique(),
+            taker: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            token_program: Pubkey::new_unique(),
+            amount: 1_000,
+            released_amount: 0,
+            refunded_amount: 0,
+            seed: 0,
+            status: EscrowStatus::Active,
+            category: 0,
+            milestones: vec![milestone(600), milestone(400)],
+            created_at: 0,
+            expires_at: i64::MAX,
+            dispute: None,
+            fee_bps_at_creation: 250,
+            bump: 255,
+            receipt_mint: None,
+            release_delay: 0,
+            on_hold: false,
+            beneficiary_ata: None,
+            renewal_count: 0,
+            approval_nonce: 0,
+            dispute_count: 0,
+            hash_algo: HASH_ALGO_SHA256,
+            creator: Pubkey::new_unique(),
+            acceptance_deadline: 0,
+            nonce: 0,
+            share_mint: None,
+            claim_pool: 0,
+            metadata: None,
+            fees_paid: 0,
+            parent: None,
+            auto_release: false,
+            crank_tip: 0,
+            tip_jar: 0,
+            claw_back_seconds: 0,
+            fees_refunded: 0,
+            approval_extension: 0,
+            expiry_extension_budget: 0,
+            funds_distributed: false,
+            remainder_policy: RemainderPolicy::ToBeneficiary,
+            disputes_enabled: true,
+            counted_open: false,
+            dispute_initiators: DISPUTE_INITIATORS_ALL,
+            expiry_extension_per_approval: 0,
+            voided_receipt_mint: None,
+            flat_fee_at_creation: 0,
+            fee_on_top: false,
+            receipt_metadata_mutable: false,
+            cancel_fee_bps_at_creation: 0,
+            cancel_fee_recipient: CancelFeeRecipient::FeeCollector,
+            agreement_hash: None,
+            agreement_acknowledged: false,
+            agreement_acknowledged_at: 0,
+            fee_free_below_at_creation: 0,
+            taker_bond: 0,
+            bond_balance: 0,
+            settlement_held_until: 0,
+            cancel_routes_at_creation: [CancelRouting::FULL_MAKER_REFUND; CANCEL_REASON_COUNT],
+            emergency_withdrawn: 0,
+        }
+    }
+
+    #[test]
+    fn verify_disputable_balance_rejects_drained_escrow() {
+        let mut escrow = escrow_fixture();
+        assert!(verify_disputable_balance(&escrow).is_ok());
+
+        // Economically complete but still Active: one milestone released, one refunded
+        escrow.milestones[0].status = MilestoneStatus::Released;
+        escrow.milestones[1].status = MilestoneStatus::Cancelled;
+        escrow.released_amount = 600;
+        escrow.refunded_amount = 400;
+        assert_eq!(
+            verify_disputable_balance(&escrow).unwrap_err(),
+            EscrowError::NoRefundableAmount.into()
+        );
+    }
+
+    #[test]
+    fn derive_escrow_seed_matches_reference_vector() {
+        // Same vector is asserted by the SDK's deriveEscrowSeed
+        let seed = derive_escrow_seed(
+            &Pubkey::new_from_array([1; 32]),
+            &Pubkey::new_from_array([2; 32]),
+            &Pubkey::new_from_array([3; 32]),
+            &[4; 32],
+        );
+        assert_eq!(seed, 0xad09_0d76_600b_fefe);
+    }
+
+    #[test]
+    fn pro_rata_redemption_leaves_dust_for_last_holder() {
+        // 100 units over 3 shares: two holders floor to 33, the last takes 34
+        let mut pool = 100;
+        let mut supply = 3;
+        for expected in [33, 33, 34] {
+            let payout = pro_rata_redemption(pool, 1, supply).unwrap();
+            assert_eq!(payout, expected);
+            pool -= payout;
+            supply -= 1;
+        }
+        assert_eq!(pool, 0);
+
+        assert_eq!(pro_rata_redemption(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert!(pro_rata_redemption(100, 0, 3).is_err());
+        assert!(pro_rata_redemption(100, 4, 3).is_err());
+    }
+
+    #[test]
+    fn address_helpers_match_account_seed_constraints() {
+        let maker = Pubkey::new_unique();
+        let seed = 42u64;
+
+        // `seeds = [ESCROW_SEED, maker/creator, seed.to_le_bytes()]`, and the
+        // signer seeds every handler builds via `escrow_seeds` must re-derive it
+        let (escrow, bump) = find_escrow_address(&maker, seed);
+        let seed_bytes = seed.to_le_bytes();
+        let bump = [bump];
+        let signer = escrow_seeds(&maker, &seed_bytes, &bump);
+        assert_eq!(Pubkey::create_program_address(&signer, &crate::ID).unwrap(), escrow);
+        assert_ne!(find_escrow_address(&maker, seed + 1).0, escrow);
+
+        // `seeds = [ESCROW_CONFIG_SEED]`
+        let (config, bump) = find_config_address();
+        assert_eq!(
+            Pubkey::create_program_address(&[b"escrow_config", &[bump]], &crate::ID).unwrap(),
+            config
+        );
+
+        // `seeds = [RECEIPT_SEED, escrow_state.key()]`
+        let (receipt, bump) = find_receipt_mint(&escrow);
+        assert_eq!(
+            Pubkey::create_program_address(&[b"receipt", escrow.as_ref(), &[bump]], &crate::ID)
+                .unwrap(),
+            receipt
+        );
+    }
+
+    #[test]
+    fn percent_complete_bps_floors_without_overflow() {
+        assert_eq!(percent_complete_bps(0, 1_000_000).unwrap(), 0);
+        assert_eq!(percent_complete_bps(400_000, 1_000_000).unwrap(), 4_000);
+        assert_eq!(percent_complete_bps(1, 3).unwrap(), 3_333);
+        assert_eq!(percent_complete_bps(2, 3).unwrap(), 6_666);
+        assert_eq!(percent_complete_bps(u64::MAX - 1, u64::MAX).unwrap(), 9_999);
+        assert_eq!(percent_complete_bps(u64::MAX, u64::MAX).unwrap(), 10_000);
+    }
+
+    #[test]
+    fn settled_milestone_counts_and_fee_totals() {
+        let mut escrow = escrow_fixture();
+        assert_eq!(escrow.settled_milestone_counts(), (0, 0));
+        escrow.milestones[0].status = MilestoneStatus::Released;
+        escrow.record_fee(25).unwrap();
+        escrow.record_fee(10).unwrap();
+        assert_eq!(escrow.fees_paid, 35);
+        for m in escrow.milestones.iter_mut().skip(1) {
+            m.status = MilestoneStatus::Cancelled;
+        }
+        let cancelled = escrow.milestones.len() as u8 - 1;
+        assert_eq!(escrow.settled_milestone_counts(), (1, cancelled));
+        escrow.fees_paid = u64::MAX;
+        assert!(escrow.record_fee(1).is_err());
+    }
+
+    #[test]
+    fn releases_wait_for_agreement_acknowledgment() {
+        let mut escrow = escrow_fixture();
+        assert!(verify_agreement_acknowledged(&escrow).is_ok());
+        escrow.agreement_hash = Some([7u8; 32]);
+        assert!(verify_agreement_acknowledged(&escrow).is_err());
+        escrow.agreement_acknowledged = true;
+        assert!(verify_agreement_acknowledged(&escrow).is_ok());
+    }
+
+    #[test]
+    fn approval_extension_boundaries() {
+        let mut escrow = escrow_fixture();
+        escrow.expires_at = 10_000;
+        // Off by default
+        assert_eq!(escrow.extend_expiry_for_approval(9_990).unwrap(), 0);
+
+        escrow.approval_extension = 600;
+        escrow.expiry_extension_budget = 1_000;
+        // Exactly `approval_extension` left: no change
+        assert_eq!(escrow.extend_expiry_for_approval(9_400).unwrap(), 0);
+        assert_eq!(escrow.expires_at, 10_000);
+        // One second inside the window: pushed to now + window
+        assert_eq!(escrow.extend_expiry_for_approval(9_401).unwrap(), 1);
+        assert_eq!(escrow.expires_at, 10_001);
+        assert_eq!(escrow.extend_expiry_for_approval(10_001).unwrap(), 600);
+        assert_eq!(escrow.expires_at, 10_601);
+        // Budget caps the total drift past the original expiry
+        assert_eq!(escrow.extend_expiry_for_approval(10_601).unwrap(), 399);
+        assert_eq!(escrow.expires_at, 11_000);
+        assert_eq!(escrow.expiry_extension_budget, 0);
+        assert_eq!(escrow.extend_expiry_for_approval(11_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn expiry_extension_per_approval_keeps_progressing_escrows_alive() {
+        let mut escrow = escrow_fixture();
+        escrow.expires_at = 10_000;
+        escrow.expiry_extension_per_approval = 3_600;
+        // No horizon configured: nothing to draw from
+        assert_eq!(escrow.extend_expiry_for_approval(1_000).unwrap(), 0);
+
+        escrow.expiry_extension_budget = 8_000;
+        // Far from expiry, still pushed out by the flat amount
+        assert_eq!(escrow.extend_expiry_for_approval(1_000).unwrap(), 3_600);
+        assert_eq!(escrow.expires_at, 13_600);
+        // Stacks with the near-expiry window
+        escrow.approval_extension = 600;
+        assert_eq!(escrow.extend_expiry_for_approval(13_500).unwrap(), 500 + 3_600);
+        assert_eq!(escrow.expires_at, 17_700);
+        // The horizon caps the last one
+        assert_eq!(escrow.extend_expiry_for_approval(1_000).unwrap(), 300);
+        assert_eq!(escrow.expires_at, 18_000);
+        assert_eq!(escrow.expiry_extension_budget, 0);
+    }
+
+    #[test]
+    fn fee_refunds_bounded_by_fees_paid() {
+        let mut escrow = escrow_fixture();
+        escrow.record_fee(100).unwrap();
+        assert!(escrow.record_fee_refund(0).is_err());
+        escrow.record_fee_refund(60).unwrap();
+        escrow.record_fee_refund(40).unwrap();
+        assert_eq!(escrow.fees_refunded, 100);
+        assert!(escrow.record_fee_refund(1).is_err());
+        assert_eq!(escrow.fees_refunded, 100);
+    }
+
+    #[test]
+    fn route_beneficiary_payout_accrues_to_pool_once_fractionalized() {
+        let mut escrow = escrow_fixture();
+        assert_eq!(escrow.route_beneficiary_payout(585).unwrap(), 585);
+        assert_eq!(escrow.claim_pool, 0);
+
+        escrow.share_mint = Some(Pubkey::new_unique());
+        assert_eq!(escrow.route_beneficiary_payout(585).unwrap(), 0);
+        assert_eq!(escrow.route_beneficiary_payout(390).unwrap(), 0);
+        assert_eq!(escrow.claim_pool, 975);
+    }
+
+    #[test]
+    fn dispute_cap_zero_is_unlimited() {
+        let mut escrow = escrow_fixture();
+        let limit: Error = EscrowError::DisputeLimitReached.into();
+        escrow.dispute_count = 2;
+        assert!(verify_dispute_cap(&escrow, 3).is_ok());
+        assert_eq!(verify_dispute_cap(&escrow, 2).unwrap_err(), limit);
+        assert_eq!(verify_dispute_cap(&escrow, 1).unwrap_err(), limit);
+        escrow.dispute_count = u8::MAX - 1;
+        assert!(verify_dispute_cap(&escrow, 0).is_ok());
+    }
+
+    #[test]
+    fn cancel_routing_follows_snapshotted_table() {
+        let mut escrow = escrow_fixture();
+        for reason in [CancelReason::MakerCancel, CancelReason::DisputeRuling, CancelReason::Expiry, CancelReason::DisputeTimeout] {
+            assert_eq!(escrow.cancel_routing(reason), CancelRouting::FULL_MAKER_REFUND);
+        }
+        // The table covers every reason
+        assert_eq!(CancelReason::DisputeTimeout as usize + 1, CANCEL_REASON_COUNT);
+
+        let fee = CancelRouting { fee_bps: 500, fee_recipient: CancelFeeRecipient::Beneficiary };
+        escrow.cancel_routes_at_creation[CancelReason::Expiry as usize] = fee;
+        assert_eq!(escrow.cancel_routing(CancelReason::Expiry), fee);
+        assert_eq!(escrow.cancel_routing(CancelReason::MakerCancel), CancelRouting::FULL_MAKER_REFUND);
+
+        // Withdrawing before acceptance always refunds in full
+        escrow.cancel_routes_at_creation[CancelReason::MakerCancel as usize] = fee;
+        escrow.status = EscrowStatus::AwaitingAcceptance;
+        assert_eq!(escrow.cancel_routing(CancelReason::MakerCancel), CancelRouting::FULL_MAKER_REFUND);
+    }
+
+    #[test]
+    fn milestone_beneficiary_overrides_payee_and_bypasses_pool() {
+        let mut escrow = escrow_fixture();
+        let designer = Pubkey::new_unique();
+        escrow.milestones[1].beneficiary = Some(designer);
+        assert_eq!(escrow.milestone_payee(0), escrow.beneficiary);
+        assert_eq!(escrow.milestone_payee(1), designer);
+        assert_eq!(escrow.milestone_payee(9), escrow.beneficiary);
+
+        escrow.share_mint = Some(Pubkey::new_unique());
+        assert_eq!(escrow.route_milestone_payout(0, 585).unwrap(), 0);
+        assert_eq!(escrow.route_milestone_payout(1, 390).unwrap(), 390);
+        assert_eq!(escrow.claim_pool, 585);
+
+        let input = |beneficiary| MilestoneInput {
+            amount: 1,
+            description_hash: [0; 32],
+            fee_exempt: false,
+            self_certify: false,
+            beneficiary,
+        };
+        assert!(verify_milestone_beneficiaries(&[input(None), input(Some(designer))], &escrow.maker).is_ok());
+        assert_eq!(
+            verify_milestone_beneficiaries(&[input(Some(escrow.maker))], &escrow.maker).unwrap_err(),
+            EscrowError::MilestoneBeneficiaryIsMaker.into()
+        );
+    }
+
+    #[test]
+    fn proposal_terms_hash_commits_to_borsh_layout() {
+        let beneficiary = Pubkey::new_from_array([7; 32]);
+        let terms = EscrowTerms {
+            amount: 1_000,
+            milestones: vec![
+                MilestoneInput { amount: 600, description_hash: [1; 32], fee_exempt: false, self_certify: true, beneficiary: None },
+                MilestoneInput {
+                    amount: 400,
+                    description_hash: [2; 32],
+                    fee_exempt: true,
+                    self_certify: false,
+                    beneficiary: Some(beneficiary),
+                },
+            ],
+            expires_at: 1_700_000_000,
+            category: 3,
+            initial_beneficiary: Some(beneficiary),
+            hash_algo: HASH_ALGO_SHA256,
+        };
+
+        // Layout mirrored by the SDK's encodeEscrowTerms
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&1_000u64.to_le_bytes());
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend_from_slice(&600u64.to_le_bytes());
+        expected.extend_from_slice(&[1; 32]);
+        expected.extend_from_slice(&[0, 1, 0]);
+        expected.extend_from_slice(&400u64.to_le_bytes());
+        expected.extend_from_slice(&[2; 32]);
+        expected.extend_from_slice(&[1, 0, 1]);
+        expected.extend_from_slice(beneficiary.as_ref());
+        expected.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        expected.push(3);
+        expected.push(1);
+        expected.extend_from_slice(beneficiary.as_ref());
+        expected.push(HASH_ALGO_SHA256);
+        assert_eq!(
+            proposal_terms_hash(&terms).unwrap(),
+            solana_sha256_hasher::hashv(&[&expected]).to_bytes()
+        );
+
+        // Any change to the terms changes the commitment
+        let mut countered = terms.clone();
+        countered.milestones[1].amount = 399;
+        assert_ne!(proposal_terms_hash(&countered).unwrap(), proposal_terms_hash(&terms).unwrap());
+    }
+
+    #[test]
+    fn expiry_bookkeeping_splits_approved_and_pending() {
+        let mut escrow = escrow_fixture();
+        escrow.milestones[0].status = MilestoneStatus::Approved;
+        assert_eq!(verify_expiry_claimable(&escrow, &[]).unwrap(), 1_000);
+
+        assert_eq!(escrow.settle_milestones_on_expiry().unwrap(), (600, 400));
+        assert_eq!(escrow.settled_milestone_counts(), (1, 1));
+        assert_eq!((escrow.released_amount, escrow.refunded_amount), (600, 400));
+        assert!(verify_expiry_claimable(&escrow, &[]).is_err());
+
+        let mut escrow = escrow_fixture();
+        escrow.milestones[1].status = MilestoneStatus::Approved;
+        assert_eq!(escrow.cancel_open_milestones(), vec![(0, 600), (1, 400)]);
+        assert_eq!(escrow.settled_milestone_counts(), (0, 2));
+    }
+
+    #[test]
+    fn verify_remaining_accounts_enforces_bound() {
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let (mut lamports_a, mut lamports_b) = (0u64, 0u64);
+        let (mut data_a, mut data_b) = ([0u8; 0], [0u8; 0]);
+        let accounts = [
+            AccountInfo::new(&key, false, false, &mut lamports_a, &mut data_a, &owner, false, 0),
+            AccountInfo::new(&key, false, false, &mut lamports_b, &mut data_b, &owner, false, 0),
+        ];
+        assert!(verify_remaining_accounts(&[], MAX_RECEIPT_SYNC_ACCOUNTS).is_ok());
+        assert!(verify_remaining_accounts(&accounts[..1], MAX_RECEIPT_SYNC_ACCOUNTS).is_ok());
+        assert!(verify_remaining_accounts(&accounts, MAX_RECEIPT_SYNC_ACCOUNTS).is_err());
+    }
+
+    #[test]
+    fn crank_tip_due_requires_state_change_and_funds() {
+        assert_eq!(crank_tip_due(5_000, 20_000, 3, 4), 5_000);
+        // No-op crank: nonce unchanged, nothing paid
+        assert_eq!(crank_tip_due(5_000, 20_000, 4, 4), 0);
+        // Last tip drains whatever is left in the jar
+        assert_eq!(crank_tip_due(5_000, 1_200, 4, 5), 1_200);
+        assert_eq!(crank_tip_due(5_000, 0, 4, 5), 0);
+    }
+
+    fn vault_with(edit: impl FnOnce(&mut SplTokenState)) -> TokenAccount {
+        let mut state = SplTokenState {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount: 1_000,
+            state: AccountState::Initialized,
+            ..SplTokenState::default()
+        };
+        edit(&mut state);
+        let mut data = [0u8; SplTokenState::LEN];
+        SplTokenState::pack(state, &mut data).unwrap();
+        TokenAccount::try_deserialize_unchecked(&mut &data[..]).unwrap()
+    }
+
+    #[test]
+    fn assert_vault_clean_rejects_each_violation() {
+        assert!(assert_vault_clean(&vault_with(|_| {})).is_ok());
+
+        let delegated = vault_with(|v| {
+            v.delegate = COption::Some(Pubkey::new_unique());
+            v.delegated_amount = 1;
+        });
+        assert_eq!(assert_vault_clean(&delegated).unwrap_err(), EscrowError::VaultHasDelegate.into());
+
+        let closable = vault_with(|v| v.close_authority = COption::Some(Pubkey::new_unique()));
+        assert_eq!(assert_vault_clean(&closable).unwrap_err(), EscrowError::VaultHasCloseAuthority.into());
+
+        let frozen = vault_with(|v| v.state = AccountState::Frozen);
+        assert_eq!(assert_vault_clean(&frozen).unwrap_err(), EscrowError::VaultFrozen.into());
+    }
+
+    #[test]
+    fn verify_payout_destination_names_frozen_and_closed_accounts() {
+        let key = Pubkey::new_unique();
+        let token_program = anchor_spl::token::ID;
+        let open = vault_with(|_| {});
+        let frozen = vault_with(|v| v.state = AccountState::Frozen);
+
+        let mut lamports = 2_039_280;
+        let mut data = [0u8; SplTokenState::LEN];
+        let live = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &token_program, false, 0);
+        assert!(verify_payout_destination(&live, &open).is_ok());
+        assert_eq!(verify_payout_destination(&live, &frozen).unwrap_err(), EscrowError::DestinationFrozen.into());
+
+        // Closed mid-instruction: drained, emptied and handed back to the system program
+        let mut lamports = 0;
+        let mut data = [0u8; 0];
+        let system = anchor_lang::system_program::ID;
+        let closed = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &system, false, 0);
+        assert_eq!(verify_payout_destination(&closed, &open).unwrap_err(), EscrowError::DestinationClosed.into());
+    }
+
+    #[test]
+    fn verify_milestone_skew_balanced_vs_skewed() {
+        let inputs = |amounts: &[u64]| -> Vec<MilestoneInput> {
+            amounts
+                .iter()
+                .map(|&amount| MilestoneInput {
+                    amount,
+                    description_hash: [0; 32],
+                    fee_exempt: false,
+                    self_certify: false,
+                    beneficiary: None,
+                })
+                .collect()
+        };
+        let skewed = EscrowError::MilestoneSkewExceeded.into();
+
+        // Off: anything goes
+        assert!(verify_milestone_skew(&inputs(&[1, 999]), 1_000, 0).is_ok());
+        // 50% of the even 500 share: 250..=750
+        assert!(verify_milestone_skew(&inputs(&[500, 500]), 1_000, 5_000).is_ok());
+        assert!(verify_milestone_skew(&inputs(&[250, 750]), 1_000, 5_000).is_ok());
+        assert_eq!(verify_milestone_skew(&inputs(&[249, 751]), 1_000, 5_000).unwrap_err(), skewed);
+        // Back-loaded: a tiny first milestone is as skewed as a huge last one
+        assert_eq!(verify_milestone_skew(&inputs(&[100, 450, 450]), 1_000, 5_000).unwrap_err(), skewed);
+        // Uneven totals compare exactly, without rounding the even share
+        assert!(verify_milestone_skew(&inputs(&[333, 333, 334]), 1_000, 100).is_ok());
+        // A single milestone is the even split
+        assert!(verify_milestone_skew(&inputs(&[1_000]), 1_000, 1).is_ok());
+    }
+
+    #[test]
+    fn program_version_matches_build() {
+        use crate::version::*;
+
+        let current = ProgramVersion::CURRENT;
+        let crate_version = format!("{}.{}.{}", current.major, current.minor, current.patch);
+        assert_eq!(crate_version, env!("CARGO_PKG_VERSION"));
+
+        // Only subsystems present in this build are flagged
+        assert_ne!(current.feature_bits & FEATURE_RECEIPTS, 0);
+        assert_eq!(current.feature_bits & FEATURE_TOKEN_2022, 0);
+        assert_ne!(current.feature_bits & FEATURE_PULL_PAYMENTS, 0);
+        assert_eq!(current.feature_bits & !(FEATURE_RECEIPTS | FEATURE_TOKEN_2022 | FEATURE_PULL_PAYMENTS), 0);
+    }
+
+    #[test]
+    fn next_crank_rearms_interval_releases_only() {
+        let release = |index, interval| CrankKind::ReleaseMilestone { index, interval };
+        assert_eq!(
+            next_crank(release(0, 3600), EscrowStatus::Active, 3, 100).unwrap(),
+            Some((release(1, 3600), 3700))
+        );
+        // Last milestone, one-shot, or escrow no longer Active: close
+        assert_eq!(next_crank(release(2, 3600), EscrowStatus::Active, 3, 100).unwrap(), None);
+        assert_eq!(next_crank(release(0, 0), EscrowStatus::Active, 3, 100).unwrap(), None);
+        assert_eq!(next_crank(release(0, 3600), EscrowStatus::Completed, 3, 100).unwrap(), None);
+        assert_eq!(next_crank(CrankKind::ClaimActiveExpired, EscrowStatus::Active, 3, 100).unwrap(), None);
+        assert!(next_crank(release(0, i64::MAX), EscrowStatus::Active, 3, 1).is_err());
+    }
+
+    #[test]
+    fn claw_back_deadline_holds_only_nonzero_payouts() {
+        assert_eq!(claw_back_deadline(0, 1_000, 100).unwrap(), 0);
+        assert_eq!(claw_back_deadline(72 * 3600, 0, 100).unwrap(), 0);
+        assert_eq!(claw_back_deadline(72 * 3600, 1_000, 100).unwrap(), 100 + 72 * 3600);
+        assert!(claw_back_deadline(1, 1_000, i64::MAX).is_err());
+    }
+
+    #[test]
+    fn verify_milestone_sum_rejects_corrupted_totals() {
+        let mismatch: Error = EscrowError::MilestoneAmountMismatch.into();
+        assert!(verify_milestone_sum(&escrow_fixture()).is_ok());
+
+        // Milestones no longer add up to the escrow amount
+        let mut escrow = escrow_fixture();
+        escrow.milestones[1].amount = 500;
+        assert_eq!(verify_milestone_sum(&escrow).unwrap_err(), mismatch);
+
+        // More settled than was ever deposited
+        let mut escrow = escrow_fixture();
+        escrow.released_amount = 600;
+        escrow.refunded_amount = 401;
+        assert_eq!(verify_milestone_sum(&escrow).unwrap_err(), mismatch);
+        escrow.released_amount = u64::MAX;
+        assert_eq!(verify_milestone_sum(&escrow).unwrap_err(), EscrowError::Overflow.into());
+    }
+
+    #[test]
+    fn verify_milestone_inputs_rejects_overflowing_sums() {
+        let inputs = |amounts: &[u64]| -> Vec<MilestoneInput> {
+            amounts
+                .iter()
+                .map(|&amount| MilestoneInput {
+                    amount,
+                    description_hash: [0u8; 32],
+                    fee_exempt: false,
+                    self_certify: false,
+                    beneficiary: None,
+                })
+                .collect()
+        };
+        let overflow: Error = EscrowError::Overflow.into();
+        let mismatch: Error = EscrowError::MilestoneAmountMismatch.into();
+
+        assert!(verify_milestone_inputs(&inputs(&[400, 600]), 1_000).is_ok());
+        assert!(verify_milestone_inputs(&inputs(&[u64::MAX]), u64::MAX).is_ok());
+        // The only total an overflowing set could pass the running check for
+        assert_eq!(verify_milestone_inputs(&inputs(&[u64::MAX, 1]), u64::MAX).unwrap_err(), overflow);
+        assert_eq!(
+            verify_milestone_inputs(&inputs(&[u64::MAX / 2 + 1, u64::MAX / 2 + 1]), u64::MAX).unwrap_err(),
+            overflow
+        );
+        // Any smaller total stops at the first milestone that passes it
+        assert_eq!(verify_milestone_inputs(&inputs(&[u64::MAX, 1]), 1_000).unwrap_err(), mismatch);
+        assert_eq!(verify_milestone_inputs(&inputs(&[600, 600, 0]), 1_000).unwrap_err(), mismatch);
+        assert_eq!(verify_milestone_inputs(&inputs(&[400, 500]), 1_000).unwrap_err(), mismatch);
+        assert_eq!(
+            verify_milestone_inputs(&inputs(&[400, 0, 600]), 1_000).unwrap_err(),
+            Error::from(EscrowError::InvalidAmount)
+        );
+    }
+
+    #[test]
+    fn dispute_loser_is_the_party_the_ruling_favours_less() {
+        let escrow = escrow_fixture();
+        assert_eq!(dispute_loser(&escrow, &DisputeResolution::MakerWins).unwrap(), escrow.beneficiary);
+        assert_eq!(dispute_loser(&escrow, &DisputeResolution::TakerWins).unwrap(), escrow.maker);
+        assert_eq!(
+            dispute_loser(&escrow, &DisputeResolution::Split { maker_bps: 3_000 }).unwrap(),
+            escrow.maker
+        );
+        assert_eq!(
+            dispute_loser(&escrow, &DisputeResolution::Split { maker_bps: 7_000 }).unwrap(),
+            escrow.beneficiary
+        );
+        assert_eq!(
+            dispute_loser(&escrow, &DisputeResolution::Split { maker_bps: 5_000 }).unwrap_err(),
+            Error::from(EscrowError::NoDisputeLoser)
+        );
+    }
+
+    #[test]
+    fn bond_split_forfeits_only_on_a_ruling() {
+        let mut escrow = escrow_fixture();
+        escrow.bond_balance = 1_000;
+        assert_eq!(
+            bond_split(&escrow).unwrap_err(),
+            Error::from(EscrowError::EscrowNotTerminal)
+        );
+
+        for status in [EscrowStatus::Completed, EscrowStatus::Cancelled, EscrowStatus::Expired] {
+            escrow.status = status;
+            assert_eq!(bond_split(&escrow).unwrap(), (0, 1_000));
+        }
+
+        let ruled = |resolution| {
+            Some(crate::state::Dispute {
+                initiator: Pubkey::new_unique(),
+                reason_hash: [0; 32],
+                initiated_at: 0,
+                timeout: 0,
+                deadline: 0,
+                resolution: Some(resolution),
+                resolved_by: Pubkey::new_unique(),
+                resolved_at: 0,
+            })
+        };
+        escrow.status = EscrowStatus::Cancelled;
+        escrow.dispute = ruled(DisputeResolution::MakerWins);
+        assert_eq!(bond_split(&escrow).unwrap(), (1_000, 0));
+
+        escrow.status = EscrowStatus::Completed;
+        escrow.dispute = ruled(DisputeResolution::TakerWins);
+        assert_eq!(bond_split(&escrow).unwrap(), (0, 1_000));
+        escrow.dispute = ruled(DisputeResolution::Split { maker_bps: 3_000 });
+        assert_eq!(bond_split(&escrow).unwrap(), (300, 700));
+    }
+
+    #[test]
+    fn may_initiate_dispute_follows_each_initiator_mask() {
+        let mut escrow = escrow_fixture();
+        let (maker, taker, beneficiary) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        escrow.maker = maker;
+        escrow.taker = taker;
+        escrow.beneficiary = beneficiary;
+
+        for mask in 1..=DISPUTE_INITIATORS_ALL {
+            escrow.dispute_initiators = mask;
+            assert_eq!(escrow.may_initiate_dispute(&maker), mask & DISPUTE_INITIATOR_MAKER != 0);
+            assert_eq!(escrow.may_initiate_dispute(&taker), mask & DISPUTE_INITIATOR_TAKER != 0);
+            assert_eq!(
+                escrow.may_initiate_dispute(&beneficiary),
+                mask & DISPUTE_INITIATOR_BENEFICIARY != 0
+            );
+            assert!(!escrow.may_initiate_dispute(&Pubkey::new_unique()));
+        }
+
+        // Taker who is also the beneficiary needs only one of the two bits
+        escrow.beneficiary = taker;
+        escrow.dispute_initiators = DISPUTE_INITIATOR_BENEFICIARY;
+        assert!(escrow.may_initiate_dispute(&taker));
+        // Fractionalized claims drop the beneficiary role, as in the party check
+        escrow.share_mint = Some(Pubkey::new_unique());
+        assert!(!escrow.may_initiate_dispute(&taker));
+    }
+
+    #[test]
+    fn release_open_slot_only_counts_terminal_tracked_escrows() {
+        let mut escrow = escrow_fixture();
+        escrow.counted_open = true;
+        let mut profile = MakerProfile {
+            maker: escrow.creator,
+            open_escrows: 2,
+            bump: 255,
+        };
+
+        // Still open: slot stays taken, profile not required
+        release_open_slot(&mut escrow, None).unwrap();
+        assert!(escrow.counted_open);
+
+        escrow.status = EscrowStatus::Completed;
+        assert!(release_open_slot(&mut escrow, None).is_err());
+        release_open_slot(&mut escrow, Some(&mut profile)).unwrap();
+        assert_eq!(profile.open_escrows, 1);
+        assert!(!escrow.counted_open);
+
+        // Only given back once
+        release_open_slot(&mut escrow, Some(&mut profile)).unwrap();
+        assert_eq!(profile.open_escrows, 1);
+
+        assert!(!profile.open_escrow(0).unwrap());
+        assert!(profile.open_escrow(2).unwrap());
+        assert!(profile.open_escrow(2).is_err());
+    }
+
+    #[test]
+    fn reserve_open_slot_counts_reopened_escrows_against_the_cap() {
+        let mut escrow = escrow_fixture();
+        let mut profile = MakerProfile {
+            maker: escrow.creator,
+            open_escrows: 1,
+            bump: 255,
+        };
+
+        // No cap: nothing tracked, profile not required
+        reserve_open_slot(&mut escrow, None, 0).unwrap();
+        assert!(!escrow.counted_open);
+
+        assert!(reserve_open_slot(&mut escrow, None, 2).is_err());
+        reserve_open_slot(&mut escrow, Some(&mut profile), 2).unwrap();
+        assert!(escrow.counted_open);
+        assert_eq!(profile.open_escrows, 2);
+
+        // Already counted: reserved only once
+        reserve_open_slot(&mut escrow, Some(&mut profile), 2).unwrap();
+        assert_eq!(profile.open_escrows, 2);
+
+        // A full profile refuses the slot
+        escrow.counted_open = false;
+        assert!(reserve_open_slot(&mut escrow, Some(&mut profile), 2).is_err());
+        assert!(!escrow.counted_open);
+    }
+
+    #[test]
+    fn milestone_fee_skips_exempt_milestones() {
+        let mut escrow = escrow_fixture();
+        let amount = escrow.milestones[0].amount;
+        assert_eq!(milestone_fee(&escrow.milestones[0], 250, 0, 0).unwrap(), calculate_fee(amount, 250, 0, 0).unwrap());
+        assert_eq!(milestone_fee(&escrow.milestones[0], 250, amount, 0).unwrap(), (amount, 0));
+        escrow.milestones[0].fee_exempt = true;
+        // Waivers cover the flat fee too
+        assert_eq!(milestone_fee(&escrow.milestones[0], 250, amount, 0).unwrap(), (0, amount));
+    }
+
+    #[test]
+    fn fee_on_top_pays_the_face_amount_in_full() {
+        let mut escrow = escrow_fixture();
+        let fee = fee_on_top(600, false, 250, 0, 0).unwrap();
+        assert_eq!(fee, 15);
+        assert_eq!(fee_on_top(600, false, 250, 100, 0).unwrap(), 100);
+        assert_eq!(fee_on_top(600, true, 250, 100, 0).unwrap(), 0);
+
+        escrow.milestones[0].amount = 600 + fee;
+        escrow.milestones[0].fee_reserved = fee;
+        // The reserve is used as-is; later config changes don't re-price it
+        assert_eq!(milestone_fee(&escrow.milestones[0], 1_000, 500, 0).unwrap(), (15, 600));
+    }
+
+    #[test]
+    fn approval_binds_escrow_nonce_hash_and_expiry() {
+        let escrow_key = Pubkey::new_unique();
+        let hash = mutual_action_hash(MutualAction::RenewEscrow, &escrow_key, 4, b"params").unwrap();
+        assert_ne!(hash, mutual_action_hash(MutualAction::RenewEscrow, &escrow_key, 5, b"params").unwrap());
+        assert_ne!(hash, mutual_action_hash(MutualAction::RenewEscrow, &escrow_key, 4, b"other").unwrap());
+
+        let approval = Approval {
+            escrow: escrow_key,
+            approver: Pubkey::new_unique(),
+            action_hash: hash,
+            nonce: 4,
+            expires_at: 1_000,
+            bump: 255,
+        };
+        verify_approval(&approval, &escrow_key, 4, &hash, 1_000).unwrap();
+        assert!(verify_approval(&approval, &escrow_key, 5, &hash, 1_000).is_err());
+        assert!(verify_approval(&approval, &Pubkey::new_unique(), 4, &hash, 1_000).is_err());
+        assert!(verify_approval(&approval, &escrow_key, 4, &[0; 32], 1_000).is_err());
+        assert!(verify_approval(&approval, &escrow_key, 4, &hash, 1_001).is_err());
+    }
+
+    #[test]
+    fn escrow_progress_summarises_within_return_data_limit() {
+        let mut escrow = escrow_fixture();
+        escrow.milestones[0].status = MilestoneStatus::Released;
+        escrow.released_amount = 600;
+        let key = Pubkey::new_unique();
+
+        let progress = escrow_progress(key, &escrow).unwrap();
+        assert_eq!(progress.escrow, key);
+        assert!(progress.status == EscrowStatus::Active);
+        assert_eq!(progress.released_bps, 6_000);
+        assert_eq!((progress.settled_milestones, progress.milestone_count), (1, 2));
+
+        let batch = vec![progress; MAX_BATCH_PROGRESS_ACCOUNTS];
+        assert!(batch.try_to_vec().unwrap().len() <= 1024);
+    }
+
+    #[test]
+    fn approved_milestone_payouts_charges_each_milestone_as_released() {
+        let mut escrow = escrow_fixture();
+        escrow.milestones[0].status = MilestoneStatus::Approved;
+        escrow.milestones[1].status = MilestoneStatus::Approved;
+        assert_eq!(approved_milestone_payouts(&escrow).unwrap(), (vec![(0, 585), (1, 390)], 15 + 10));
+
+        escrow.milestones[0].fee_exempt = true;
+        assert_eq!(approved_milestone_payouts(&escrow).unwrap(), (vec![(0, 600), (1, 390)], 10));
+
+        escrow.milestones[1].fee_reserved = 7;
+        assert_eq!(approved_milestone_payouts(&escrow).unwrap(), (vec![(0, 600), (1, 393)], 7));
+
+        escrow.milestones[1].status = MilestoneStatus::Pending;
+        assert_eq!(approved_milestone_payouts(&escrow).unwrap(), (vec![(0, 600)], 0));
+    }
+
+    #[test]
+    fn route_milestone_payouts_holds_only_while_a_window_applies() {
+        let mut escrow = escrow_fixture();
+        let payouts = [(0, 585), (1, 0)];
+        let routed = |to_beneficiary, held: &[(u8, u64)], to_payees: &[(u8, u64)]| RoutedPayouts {
+            held: held.to_vec(),
+            to_payees: to_payees.to_vec(),
+            to_beneficiary,
+        };
+        assert_eq!(route_milestone_payouts(&mut escrow, &payouts, 100).unwrap(), routed(585, &[], &[]));
+        assert!(!escrow.has_held_funds());
+
+        escrow.claw_back_seconds = 3_600;
+        assert_eq!(route_milestone_payouts(&mut escrow, &payouts, 100).unwrap(), routed(0, &[(0, 585)], &[]));
+        assert_eq!((escrow.held_until(0), escrow.held_until(1)), (3_700, 0));
+
+        // A milestone's own beneficiary is paid directly, or held like any other
+        escrow.milestones[0].held_until = 0;
+        escrow.milestones[1].beneficiary = Some(Pubkey::new_unique());
+        let payouts = [(0, 585), (1, 390)];
+        assert_eq!(
+            route_milestone_payouts(&mut escrow, &payouts, 100).unwrap(),
+            routed(0, &[(0, 585), (1, 390)], &[])
+        );
+        escrow.claw_back_seconds = 0;
+        escrow.milestones[0].held_until = 0;
+        escrow.milestones[1].held_until = 0;
+        assert_eq!(route_milestone_payouts(&mut escrow, &payouts, 100).unwrap(), routed(585, &[], &[(1, 390)]));
+
+        // Share-pool claims accrue to the pool rather than a holding
+        escrow.claw_back_seconds = 3_600;
+        escrow.share_mint = Some(Pubkey::new_unique());
+        assert_eq!(route_milestone_payouts(&mut escrow, &payouts, 100).unwrap(), routed(0, &[(1, 390)], &[]));
+        assert_eq!(escrow.claim_pool, 585);
+        escrow.milestones[1].held_until = 0;
+        assert!(!escrow.has_held_funds());
+
+        escrow.set_held_until(SETTLEMENT_HOLDING_INDEX, 3_700).unwrap();
+        assert!(escrow.has_held_funds());
+        assert!(escrow.set_held_until(2, 1).is_err());
+
+        // Disputes are refused while such a milestone is still open
+        assert!(escrow.has_open_milestone_beneficiary());
+        escrow.milestones[1].status = MilestoneStatus::Released;
+        assert!(!escrow.has_open_milestone_beneficiary());
+    }
+}
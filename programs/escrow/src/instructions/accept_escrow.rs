@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::EscrowAccepted;
+use crate::instructions::list_escrow::close_listing;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AcceptEscrow<'info> {
+    /// Taker or current beneficiary consents to the escrow.
+    pub acceptor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = (escrow_state.taker == acceptor.key() || escrow_state.beneficiary == acceptor.key()) @ EscrowError::NotEscrowParty,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// Closed on acceptance when the escrow was listed; rent goes to `listing_maker`.
+    #[account(
+        mut,
+        seeds = [LISTING_SEED, escrow_state.key().as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Option<Account<'info, Listing>>,
+
+    /// CHECK: Required with `listing`; must be the maker that paid for it.
+    #[account(mut)]
+    pub listing_maker: Option<UncheckedAccount<'info>>,
+}
+
+pub fn handler(ctx: Context<AcceptEscrow>) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    require!(
+        escrow.status == EscrowStatus::AwaitingAcceptance,
+        EscrowError::NotAwaitingAcceptance
+    );
+    // A bonded escrow is accepted by posting the bond (post_bond)
+    require!(escrow.taker_bond == 0, EscrowError::BondNotPosted);
+
+    // Past the deadline the maker reclaims via cancel_escrow instead
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp <= escrow.acceptance_deadline,
+        EscrowError::AcceptanceDeadlinePassed
+    );
+
+    escrow.status = EscrowStatus::Active;
+
+    escrow.bump_nonce()?;
+
+    emit!(EscrowAccepted {
+        escrow: escrow.key(),
+        accepted_by: ctx.accounts.acceptor.key(),
+        nonce: escrow.nonce,
+    });
+
+    close_listing(
+        ctx.accounts.listing.as_ref(),
+        ctx.accounts.listing_maker.as_ref(),
+        ctx.accounts.acceptor.key(),
+    )?;
+
+    Ok(())
+}
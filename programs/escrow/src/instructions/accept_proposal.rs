@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::error::EscrowError;
+use crate::events::ProposalAccepted;
+use crate::helpers::proposal_terms_hash;
+use crate::instructions::create_escrow::open_escrow;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AcceptProposal<'info> {
+    /// The party whose turn it is to respond.
+    pub acceptor: Signer<'info>,
+
+    /// Funds the escrow, so signs even when the taker is the acceptor.
+    #[account(mut, address = proposal.maker @ EscrowError::NotMaker)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: Pinned to the proposal's taker.
+    #[account(address = proposal.taker @ EscrowError::NotEscrowParty)]
+    pub taker: UncheckedAccount<'info>,
+
+    /// CHECK: Proposal creator, refunded the proposal rent.
+    #[account(mut, address = proposal.creator @ EscrowError::NotProposalCreator)]
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, proposal.maker.as_ref(), proposal.seed.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        close = creator,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(address = proposal.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + EscrowState::INIT_SPACE,
+        seeds = [ESCROW_SEED, maker.key().as_ref(), proposal.seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint,
+        associated_token::authority = escrow_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = maker_token_account.amount >= proposal.terms.amount @ EscrowError::InsufficientBalance,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Fund the escrow exactly as proposed. `terms_hash` must equal
+/// `proposal_terms_hash` of the stored terms, pinning what the acceptor reviewed.
+pub fn handler(ctx: Context<AcceptProposal>, terms_hash: [u8; 32]) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp <= proposal.expires_at, EscrowError::ProposalExpired);
+
+    let responder = if proposal.maker_turn { proposal.maker } else { proposal.taker };
+    require!(ctx.accounts.acceptor.key() == responder, EscrowError::NotYourTurn);
+
+    let terms = proposal.terms.clone();
+    require!(proposal_terms_hash(&terms)? == terms_hash, EscrowError::ProposalTermsMismatch);
+
+    let seed = proposal.seed;
+    let revision = proposal.revision;
+    let accounts = ctx.accounts;
+    open_escrow(
+        &mut accounts.escrow_state,
+        ctx.bumps.escrow_state,
+        &accounts.maker,
+        accounts.taker.key(),
+        &accounts.escrow_config,
+        &accounts.mint,
+        &accounts.vault,
+        Some(&accounts.maker_token_account),
+        &accounts.token_program,
+        None,
+        seed,
+        terms,
+        None,
+        false,
+        0,
+        true,
+        DISPUTE_INITIATORS_ALL,
+        false,
+        None,
+        0,
+    )?;
+
+    emit!(ProposalAccepted {
+        proposal: accounts.proposal.key(),
+        escrow: accounts.escrow_state.key(),
+        acceptor: responder,
+        revision,
+    });
+
+    Ok(())
+}
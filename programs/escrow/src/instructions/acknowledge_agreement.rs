@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::AgreementAcknowledged;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AcknowledgeAgreement<'info> {
+    /// Only the taker who was offered the agreement can assent to it.
+    pub taker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.taker == taker.key() @ EscrowError::NotEscrowParty,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+}
+
+/// Records the taker's assent to the agreement attached at creation. The
+/// taker passes the hash it reviewed, so the acknowledgment only lands for
+/// that exact document; releases are blocked until it does.
+pub fn handler(ctx: Context<AcknowledgeAgreement>, agreement_hash: [u8; 32]) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    let stored = escrow.agreement_hash.ok_or(EscrowError::NoAgreement)?;
+    require!(!escrow.agreement_acknowledged, EscrowError::AgreementAlreadyAcknowledged);
+    require!(agreement_hash == stored, EscrowError::AgreementHashMismatch);
+    require!(
+        matches!(escrow.status, EscrowStatus::Active | EscrowStatus::AwaitingAcceptance),
+        EscrowError::EscrowNotActive
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    escrow.agreement_acknowledged = true;
+    escrow.agreement_acknowledged_at = now;
+
+    escrow.bump_nonce()?;
+
+    emit!(AgreementAcknowledged {
+        escrow: escrow.key(),
+        taker: ctx.accounts.taker.key(),
+        agreement_hash: stored,
+        acknowledged_at: now,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
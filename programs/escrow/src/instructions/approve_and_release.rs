@@ -0,0 +1,242 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::EscrowError;
+use crate::events::{EscrowCompleted, MilestoneApproved, MilestoneReleased, SubEscrowFunded};
+use crate::helpers::{
+    claw_back_deadline, escrow_seeds, milestone_fee, percent_complete_bps, release_open_slot,
+    take_sub_escrow_funding, transfer_from_vault, verify_agreement_acknowledged, verify_milestone_sum,
+    verify_remaining_accounts,
+};
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct ApproveAndRelease<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+        constraint = beneficiary_token_account.owner == escrow_state.milestone_payee(milestone_index) @ EscrowError::OwnerMismatch,
+        constraint = escrow_state.milestone_beneficiary(milestone_index).is_some()
+            || escrow_state.beneficiary_ata.is_none_or(|ata| ata == beneficiary_token_account.key()) @ EscrowError::BeneficiaryAtaMismatch,
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+        constraint = fee_collector_token_account.owner == escrow_config.fee_collector @ EscrowError::FeeCollectorMismatch,
+    )]
+    pub fee_collector_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Sub-escrow linked to the released milestone, funded from its net payout.
+    #[account(mut)]
+    pub sub_escrow: Option<Account<'info, EscrowState>>,
+
+    #[account(mut)]
+    pub sub_escrow_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Claw-back holding for this milestone; required while the escrow has a
+    /// claw-back window. Rent is fronted by `maker` and returned to the beneficiary.
+    #[account(
+        init,
+        payer = maker,
+        seeds = [HOLDING_SEED, escrow_state.key().as_ref(), &[milestone_index]],
+        bump,
+        token::mint = mint,
+        token::authority = escrow_state,
+        token::token_program = token_program,
+    )]
+    pub holding_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Option<Program<'info, System>>,
+
+    /// Gets the open slot back when this payout completes the escrow.
+    #[account(
+        mut,
+        seeds = [MAKER_PROFILE_SEED, escrow_state.creator.as_ref()],
+        bump = maker_profile.bump,
+    )]
+    pub maker_profile: Option<Account<'info, MakerProfile>>,
+}
+
+pub fn handler(ctx: Context<ApproveAndRelease>, milestone_index: u8) -> Result<()> {
+    verify_remaining_accounts(ctx.remaining_accounts, MAX_RECEIPT_SYNC_ACCOUNTS)?;
+
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+
+    require!(
+        escrow.status == EscrowStatus::Active,
+        EscrowError::EscrowNotActive
+    );
+
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp <= escrow.expires_at, EscrowError::EscrowExpired);
+
+    let idx = milestone_index as usize;
+    require!(idx < escrow.milestones.len(), EscrowError::MilestoneIndexOutOfBounds);
+    require!(
+        escrow.milestones[idx].status == MilestoneStatus::Pending,
+        EscrowError::MilestoneNotPending
+    );
+
+    // If a receipt NFT exists, verify beneficiary is synced with current NFT holder.
+    if escrow.receipt_mint.is_some() {
+        crate::helpers::verify_receipt_sync(escrow, ctx.remaining_accounts)?;
+    }
+
+    verify_agreement_acknowledged(escrow)?;
+
+    // The release delay protects the maker from a mistaken approval; a maker
+    // approving and paying in one signed transaction waives it explicitly.
+    let milestone_amount = escrow.milestones[idx].amount;
+    let fee_exempt = escrow.milestones[idx].fee_exempt;
+    let (fee, taker_amount) = milestone_fee(
+        &escrow.milestones[idx],
+        escrow.fee_bps_at_creation,
+        escrow.flat_fee_at_creation,
+        escrow.fee_free_below_at_creation,
+    )?;
+
+    // Update state BEFORE CPI (checks-effects-interactions)
+    escrow.milestones[idx].approved_at = clock.unix_timestamp;
+    escrow.milestones[idx].status = MilestoneStatus::Released;
+    escrow.released_amount = escrow
+        .released_amount
+        .checked_add(milestone_amount)
+        .ok_or(EscrowError::Overflow)?;
+    escrow.record_fee(fee)?;
+
+    // PDA signer seeds
+    let creator_key = escrow.creator;
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    let decimals = ctx.accounts.mint.decimals;
+    // A linked sub-escrow is funded first; the beneficiary receives the rest
+    let sub_amount = take_sub_escrow_funding(
+        escrow,
+        idx,
+        ctx.accounts.sub_escrow.as_mut(),
+        ctx.accounts.sub_escrow_vault.as_ref(),
+    )?;
+    let taker_amount = taker_amount.checked_sub(sub_amount).ok_or(EscrowError::Overflow)?;
+    let taker_amount = escrow.route_milestone_payout(milestone_index, taker_amount)?;
+
+    // Under a claw-back window the net payout waits in holding instead
+    let held_until = claw_back_deadline(escrow.claw_back_seconds, taker_amount, clock.unix_timestamp)?;
+    escrow.milestones[idx].held_until = held_until;
+    let destination = if held_until != 0 {
+        ctx.accounts.holding_account.as_ref().ok_or(EscrowError::HoldingAccountMissing)?
+    } else {
+        &ctx.accounts.beneficiary_token_account
+    };
+
+    transfer_from_vault(
+        &ctx.accounts.vault, &ctx.accounts.mint,
+        destination,
+        escrow.to_account_info(), &ctx.accounts.token_program,
+        signer_seeds, taker_amount, decimals,
+    )?;
+
+    transfer_from_vault(
+        &ctx.accounts.vault, &ctx.accounts.mint,
+        &ctx.accounts.fee_collector_token_account,
+        escrow.to_account_info(), &ctx.accounts.token_program,
+        signer_seeds, fee, decimals,
+    )?;
+
+    if sub_amount > 0 {
+        if let (Some(child), Some(child_vault)) = (&ctx.accounts.sub_escrow, &ctx.accounts.sub_escrow_vault) {
+            transfer_from_vault(
+                &ctx.accounts.vault, &ctx.accounts.mint,
+                child_vault,
+                escrow.to_account_info(), &ctx.accounts.token_program,
+                signer_seeds, sub_amount, decimals,
+            )?;
+            emit!(SubEscrowFunded {
+                parent: escrow.key(),
+                parent_milestone_index: milestone_index,
+                child: child.key(),
+                amount: sub_amount,
+                nonce: child.nonce,
+            });
+        }
+    }
+
+    escrow.bump_nonce()?;
+
+    emit!(MilestoneApproved {
+        escrow: escrow.key(),
+        milestone_index,
+        expiry_extension: 0,
+        expires_at: escrow.expires_at,
+        nonce: escrow.nonce,
+    });
+
+    emit!(MilestoneReleased {
+        escrow: escrow.key(),
+        milestone_index,
+        amount: milestone_amount,
+        fee,
+        fee_exempt,
+        percent_complete_bps: percent_complete_bps(escrow.released_amount, escrow.amount)?,
+        nonce: escrow.nonce,
+    });
+
+    if escrow.all_milestones_settled() {
+        escrow.status = EscrowStatus::Completed;
+        let (milestones_released, milestones_cancelled) = escrow.settled_milestone_counts();
+        emit!(EscrowCompleted {
+            escrow: escrow.key(),
+            total_released: escrow.released_amount,
+            total_refunded: escrow.refunded_amount,
+            total_fees_paid: escrow.fees_paid,
+            milestones_released,
+            milestones_cancelled,
+            beneficiary: escrow.beneficiary,
+            nonce: escrow.nonce,
+        });
+    }
+
+    verify_milestone_sum(escrow)?;
+    release_open_slot(escrow, ctx.accounts.maker_profile.as_deref_mut())?;
+
+    Ok(())
+}
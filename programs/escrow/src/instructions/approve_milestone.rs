@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::error::EscrowError;
 use crate::events::MilestoneApproved;
+use crate::helpers::{verify_expected_nonce, verify_milestone_sum};
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -11,17 +12,28 @@ pub struct ApproveMilestone<'info> {
 
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_state.maker.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
         bump = escrow_state.bump,
         constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
     )]
     pub escrow_state: Account<'info, EscrowState>,
 }
 
-pub fn handler(ctx: Context<ApproveMilestone>, milestone_index: u8) -> Result<()> {
+/// `expected_amount` / `expected_description_hash` pin the milestone the maker
+/// reviewed; 0 / all-zero skips the respective check.
+pub fn handler(
+    ctx: Context<ApproveMilestone>,
+    milestone_index: u8,
+    expected_amount: u64,
+    expected_description_hash: [u8; 32],
+    expected_nonce: Option<u64>,
+) -> Result<()> {
     let escrow = &mut ctx.accounts.escrow_state;
 
+    verify_expected_nonce(escrow, expected_nonce)?;
+
     require!(escrow.status == EscrowStatus::Active, EscrowError::EscrowNotActive);
+    require!(!escrow.auto_release, EscrowError::AutoReleaseRequired);
 
     let clock = Clock::get()?;
     require!(clock.unix_timestamp <= escrow.expires_at, EscrowError::EscrowExpired);
@@ -33,12 +45,42 @@ pub fn handler(ctx: Context<ApproveMilestone>, milestone_index: u8) -> Result<()
         EscrowError::MilestoneNotPending
     );
 
+    verify_milestone_matches(&escrow.milestones[idx], expected_amount, expected_description_hash)?;
+
     escrow.milestones[idx].status = MilestoneStatus::Approved;
+    escrow.milestones[idx].approved_at = clock.unix_timestamp;
+    // Give a late approval room to be released before the escrow expires
+    let expiry_extension = escrow.extend_expiry_for_approval(clock.unix_timestamp)?;
+
+    escrow.bump_nonce()?;
 
     emit!(MilestoneApproved {
         escrow: escrow.key(),
         milestone_index,
+        expiry_extension,
+        expires_at: escrow.expires_at,
+        nonce: escrow.nonce,
     });
 
+    verify_milestone_sum(escrow)?;
+
+    Ok(())
+}
+
+/// The milestone matches what the maker reviewed; 0 / all-zero skips a check.
+pub(crate) fn verify_milestone_matches(
+    milestone: &Milestone,
+    expected_amount: u64,
+    expected_description_hash: [u8; 32],
+) -> Result<()> {
+    require!(
+        expected_amount == 0 || milestone.amount == expected_amount,
+        EscrowError::MilestoneMismatch
+    );
+    require!(
+        expected_description_hash == [0u8; 32]
+            || milestone.description_hash == expected_description_hash,
+        EscrowError::MilestoneMismatch
+    );
     Ok(())
 }
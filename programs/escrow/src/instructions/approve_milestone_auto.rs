@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::helpers::verify_expected_nonce;
+use crate::instructions::approve_and_release::{self, ApproveAndRelease};
+use crate::instructions::approve_milestone::verify_milestone_matches;
+
+/// Approval for `auto_release` escrows: same checks as `approve_milestone`,
+/// then the milestone is paid out immediately via the `approve_and_release` path.
+pub fn handler(
+    ctx: Context<ApproveAndRelease>,
+    milestone_index: u8,
+    expected_amount: u64,
+    expected_description_hash: [u8; 32],
+    expected_nonce: Option<u64>,
+) -> Result<()> {
+    let escrow = &ctx.accounts.escrow_state;
+
+    verify_expected_nonce(escrow, expected_nonce)?;
+
+    require!(escrow.auto_release, EscrowError::AutoReleaseDisabled);
+
+    let idx = milestone_index as usize;
+    require!(idx < escrow.milestones.len(), EscrowError::MilestoneIndexOutOfBounds);
+    verify_milestone_matches(&escrow.milestones[idx], expected_amount, expected_description_hash)?;
+
+    approve_and_release::handler(ctx, milestone_index)
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+
+use crate::error::EscrowError;
+use crate::events::MilestoneApproved;
+use crate::helpers::{approval_message, verify_ed25519_ix, verify_milestone_sum};
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct ApproveMilestoneSigned<'info> {
+    /// Anyone can relay a maker-signed approval.
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// CHECK: Address-constrained to the instructions sysvar; read via introspection helpers.
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<ApproveMilestoneSigned>, milestone_index: u8, nonce: u64) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    require!(escrow.status == EscrowStatus::Active, EscrowError::EscrowNotActive);
+
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp <= escrow.expires_at, EscrowError::EscrowExpired);
+
+    let idx = milestone_index as usize;
+    require!(idx < escrow.milestones.len(), EscrowError::MilestoneIndexOutOfBounds);
+    require!(
+        escrow.milestones[idx].status == MilestoneStatus::Pending,
+        EscrowError::MilestoneNotPending
+    );
+
+    require!(nonce == escrow.approval_nonce, EscrowError::InvalidApprovalNonce);
+
+    // The ed25519 verify instruction must immediately precede this one
+    let ix_sysvar = ctx.accounts.instructions_sysvar.to_account_info();
+    let current_index = load_current_index_checked(&ix_sysvar)?;
+    require!(current_index > 0, EscrowError::InvalidApprovalSignature);
+    let ed25519_ix = load_instruction_at_checked((current_index - 1) as usize, &ix_sysvar)?;
+    let message = approval_message(&escrow.key(), milestone_index, nonce);
+    verify_ed25519_ix(&ed25519_ix, &escrow.maker, &message)?;
+
+    escrow.approval_nonce = escrow
+        .approval_nonce
+        .checked_add(1)
+        .ok_or(EscrowError::Overflow)?;
+    escrow.milestones[idx].status = MilestoneStatus::Approved;
+    escrow.milestones[idx].approved_at = clock.unix_timestamp;
+    // Give a late approval room to be released before the escrow expires
+    let expiry_extension = escrow.extend_expiry_for_approval(clock.unix_timestamp)?;
+
+    escrow.bump_nonce()?;
+
+    emit!(MilestoneApproved {
+        escrow: escrow.key(),
+        milestone_index,
+        expiry_extension,
+        expires_at: escrow.expires_at,
+        nonce: escrow.nonce,
+    });
+
+    verify_milestone_sum(escrow)?;
+
+    Ok(())
+}
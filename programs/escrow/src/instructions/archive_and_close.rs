@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::Metadata,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::error::EscrowError;
+use crate::events::EscrowArchived;
+use crate::instructions::close_escrow::{close_vault, seal_receipt_metadata};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ArchiveAndClose<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+        close = maker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// Paid for by the maker, who gets the larger escrow and vault rent back
+    /// in the same instruction.
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + EscrowArchive::INIT_SPACE,
+        seeds = [ARCHIVE_SEED, escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub escrow_archive: Account<'info, EscrowArchive>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == escrow_state.maker @ EscrowError::OwnerMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Required when `escrow_state.metadata` is set; closed to the maker.
+    #[account(
+        mut,
+        seeds = [METADATA_SEED, escrow_state.key().as_ref()],
+        bump = escrow_metadata.bump,
+        close = maker,
+    )]
+    pub escrow_metadata: Option<Account<'info, EscrowMetadata>>,
+
+    /// CHECK: Required while `escrow_state.receipt_metadata_mutable`; checked in `seal_receipt_metadata`.
+    #[account(mut)]
+    pub receipt_metadata: Option<UncheckedAccount<'info>>,
+
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// `close_escrow` that first records the escrow's final figures in a compact
+/// `EscrowArchive`, so the history outlives the full `EscrowState`.
+pub fn handler(ctx: Context<ArchiveAndClose>) -> Result<()> {
+    let accounts = ctx.accounts;
+    let escrow = &accounts.escrow_state;
+    let closed_at = Clock::get()?.unix_timestamp;
+
+    let archive = &mut accounts.escrow_archive;
+    archive.escrow = escrow.key();
+    archive.maker = escrow.maker;
+    archive.taker = escrow.taker;
+    archive.beneficiary = escrow.beneficiary;
+    archive.mint = escrow.mint;
+    archive.amount = escrow.amount;
+    archive.released_amount = escrow.released_amount;
+    archive.refunded_amount = escrow.refunded_amount;
+    archive.fees_paid = escrow.fees_paid;
+    archive.status = escrow.status.clone();
+    archive.created_at = escrow.created_at;
+    archive.closed_at = closed_at;
+    archive.bump = ctx.bumps.escrow_archive;
+
+    close_vault(
+        &accounts.escrow_state,
+        &accounts.escrow_config,
+        &accounts.mint,
+        &mut accounts.vault,
+        &accounts.maker_token_account,
+        &accounts.token_program,
+        accounts.maker.to_account_info(),
+        accounts.escrow_metadata.is_some(),
+    )?;
+    seal_receipt_metadata(
+        &accounts.escrow_state,
+        accounts.receipt_metadata.as_ref(),
+        accounts.token_metadata_program.as_ref(),
+    )?;
+
+    emit!(EscrowArchived {
+        escrow: archive.escrow,
+        archive: archive.key(),
+        maker: archive.maker,
+        status: archive.status.clone(),
+        closed_at,
+    });
+
+    Ok(())
+}
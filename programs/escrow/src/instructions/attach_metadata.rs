@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::MetadataAttached;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(data: Vec<u8>)]
+pub struct AttachMetadata<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = EscrowMetadata::space(data.len()),
+        seeds = [METADATA_SEED, escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub escrow_metadata: Account<'info, EscrowMetadata>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Store a free-form blob (at most `MAX_METADATA_LEN` bytes) alongside the escrow.
+pub fn handler(ctx: Context<AttachMetadata>, data: Vec<u8>) -> Result<()> {
+    require!(data.len() <= MAX_METADATA_LEN, EscrowError::MetadataTooLarge);
+
+    let escrow = &mut ctx.accounts.escrow_state;
+    require!(escrow.metadata.is_none(), EscrowError::MetadataAlreadyAttached);
+
+    let len = data.len() as u32;
+    let metadata = &mut ctx.accounts.escrow_metadata;
+    metadata.escrow = escrow.key();
+    metadata.data = data;
+    metadata.bump = ctx.bumps.escrow_metadata;
+
+    escrow.metadata = Some(metadata.key());
+
+    escrow.bump_nonce()?;
+
+    emit!(MetadataAttached {
+        escrow: escrow.key(),
+        metadata: metadata.key(),
+        len,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
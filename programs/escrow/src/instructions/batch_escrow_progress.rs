@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::helpers::{escrow_progress, verify_remaining_accounts};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct BatchEscrowProgress<'info> {
+    /// Unused by the handler; gives the context a lifetime so the generated
+    /// CPI client compiles for programs depending on this crate.
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+}
+
+/// Read-only: summarises every `EscrowState` passed in `remaining_accounts`,
+/// in order, for portfolio views. At most `MAX_BATCH_PROGRESS_ACCOUNTS`.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchEscrowProgress<'info>>,
+) -> Result<Vec<EscrowProgress>> {
+    verify_remaining_accounts(ctx.remaining_accounts, MAX_BATCH_PROGRESS_ACCOUNTS)?;
+
+    ctx.remaining_accounts
+        .iter()
+        .map(|info| {
+            let escrow = Account::<EscrowState>::try_from(info)?;
+            escrow_progress(info.key(), &escrow)
+        })
+        .collect()
+}
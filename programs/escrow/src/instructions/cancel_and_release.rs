@@ -0,0 +1,279 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::EscrowError;
+use crate::events::{EscrowCancelled, EscrowCompleted, MilestoneCancelled, MilestoneReleased};
+use crate::helpers::{
+    escrow_seeds, fund_holding, milestone_fee, payee_token_account, percent_complete_bps, refund_maker, release_open_slot,
+    route_milestone_payouts, transfer_from_vault, verify_agreement_acknowledged, verify_expected_nonce, verify_milestone_sum,
+    verify_remaining_accounts,
+};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CancelAndRelease<'info> {
+    /// Pays the rent of any claw-back holding accounts, which is returned to the beneficiary.
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == escrow_state.maker @ EscrowError::OwnerMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+        constraint = beneficiary_token_account.owner == escrow_state.beneficiary @ EscrowError::OwnerMismatch,
+        constraint = escrow_state.beneficiary_ata.is_none_or(|ata| ata == beneficiary_token_account.key()) @ EscrowError::BeneficiaryAtaMismatch,
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+        constraint = fee_collector_token_account.owner == escrow_config.fee_collector @ EscrowError::FeeCollectorMismatch,
+    )]
+    pub fee_collector_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Gets the open slot back once every milestone is settled.
+    #[account(
+        mut,
+        seeds = [MAKER_PROFILE_SEED, escrow_state.creator.as_ref()],
+        bump = maker_profile.bump,
+    )]
+    pub maker_profile: Option<Account<'info, MakerProfile>>,
+}
+
+/// Settle every open milestone at once: Pending ones are refunded to the maker
+/// (less the escrow's `MakerCancel` routing fee, as in `cancel_escrow`),
+/// Approved ones are paid to the beneficiary minus fees (the maker waives the
+/// release delay). The escrow ends Completed if the released bucket is at
+/// least as large as the refunded one, Cancelled otherwise. Each release is
+/// routed as `release_milestone` would route it: while the escrow has a
+/// claw-back window `remaining_accounts` carry the released milestones'
+/// holding PDAs, otherwise the token accounts of milestones with their own
+/// beneficiary (after the receipt token account, if any).
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelAndRelease<'info>>,
+    expected_nonce: Option<u64>,
+) -> Result<()> {
+    verify_remaining_accounts(ctx.remaining_accounts, MAX_BULK_SETTLEMENT_ACCOUNTS)?;
+
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    verify_expected_nonce(escrow, expected_nonce)?;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+
+    require!(
+        escrow.status == EscrowStatus::Active,
+        EscrowError::EscrowNotActive
+    );
+
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp <= escrow.expires_at, EscrowError::EscrowExpired);
+
+    // If a receipt NFT exists, verify beneficiary is synced with current NFT holder.
+    if escrow.receipt_mint.is_some() {
+        crate::helpers::verify_receipt_sync(escrow, ctx.remaining_accounts)?;
+    }
+
+    // Per-milestone fees match what release_milestone would have charged
+    let fee_bps = escrow.fee_bps_at_creation;
+    let flat_fee = escrow.flat_fee_at_creation;
+    let fee_free_below = escrow.fee_free_below_at_creation;
+    let mut refund_amount: u64 = 0;
+    let mut release_amount: u64 = 0;
+    let mut payouts: Vec<(u8, u64)> = Vec::new();
+    let mut total_fee: u64 = 0;
+    let mut released: Vec<(u8, u64, u64, bool)> = Vec::new();
+    let mut cancelled: Vec<(u8, u64)> = Vec::new();
+
+    // Update state BEFORE CPI (checks-effects-interactions)
+    for (i, milestone) in escrow.milestones.iter_mut().enumerate() {
+        match milestone.status {
+            MilestoneStatus::Pending => {
+                refund_amount = refund_amount
+                    .checked_add(milestone.amount)
+                    .ok_or(EscrowError::Overflow)?;
+                milestone.status = MilestoneStatus::Cancelled;
+                cancelled.push((i as u8, milestone.amount));
+            }
+            MilestoneStatus::Approved => {
+                // Linked sub-escrows need their child accounts: use release_milestone
+                require!(milestone.sub_escrow.is_none(), EscrowError::SubEscrowAccountMissing);
+                let (fee, net) = milestone_fee(milestone, fee_bps, flat_fee, fee_free_below)?;
+                release_amount = release_amount
+                    .checked_add(milestone.amount)
+                    .ok_or(EscrowError::Overflow)?;
+                payouts.push((i as u8, net));
+                total_fee = total_fee.checked_add(fee).ok_or(EscrowError::Overflow)?;
+                milestone.status = MilestoneStatus::Released;
+                released.push((i as u8, milestone.amount, fee, milestone.fee_exempt));
+            }
+            _ => {}
+        }
+    }
+    // Only the release half waits for the agreement; a plain refund never does
+    if release_amount > 0 {
+        verify_agreement_acknowledged(escrow)?;
+    }
+
+    escrow.refunded_amount = escrow
+        .refunded_amount
+        .checked_add(refund_amount)
+        .ok_or(EscrowError::Overflow)?;
+    escrow.released_amount = escrow
+        .released_amount
+        .checked_add(release_amount)
+        .ok_or(EscrowError::Overflow)?;
+    escrow.record_fee(total_fee)?;
+
+    let completed = release_amount >= refund_amount;
+    if completed {
+        escrow.status = EscrowStatus::Completed;
+    } else {
+        escrow.status = EscrowStatus::Cancelled;
+        // Invalidate receipt NFT, as on a plain cancel
+        escrow.receipt_mint = None;
+    }
+
+    // PDA signer seeds
+    let creator_key = escrow.creator;
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    let decimals = ctx.accounts.mint.decimals;
+    let routed = route_milestone_payouts(escrow, &payouts, clock.unix_timestamp)?;
+
+    // CPI transfers (zero-amount legs are skipped by transfer_from_vault)
+    let (routing, cancel_fee) = refund_maker(
+        escrow, CancelReason::MakerCancel, refund_amount, ctx.accounts.escrow_config.fee_collector,
+        &ctx.accounts.vault, &ctx.accounts.mint, &ctx.accounts.maker_token_account,
+        &[Some(&ctx.accounts.fee_collector_token_account), Some(&ctx.accounts.beneficiary_token_account)],
+        &ctx.accounts.token_program, signer_seeds,
+    )?;
+    transfer_from_vault(
+        &ctx.accounts.vault, &ctx.accounts.mint,
+        &ctx.accounts.beneficiary_token_account,
+        escrow.to_account_info(), &ctx.accounts.token_program,
+        signer_seeds, routed.to_beneficiary, decimals,
+    )?;
+    for (index, amount) in routed.to_payees {
+        let payee_token_account = payee_token_account(escrow, index, ctx.remaining_accounts)?;
+        transfer_from_vault(
+            &ctx.accounts.vault, &ctx.accounts.mint,
+            &payee_token_account,
+            escrow.to_account_info(), &ctx.accounts.token_program,
+            signer_seeds, amount, decimals,
+        )?;
+    }
+    for (index, amount) in routed.held {
+        fund_holding(
+            escrow, index, amount, &ctx.accounts.vault, &ctx.accounts.mint,
+            ctx.accounts.maker.to_account_info(), &ctx.accounts.system_program,
+            &ctx.accounts.token_program, ctx.remaining_accounts, signer_seeds,
+        )?;
+    }
+    transfer_from_vault(
+        &ctx.accounts.vault, &ctx.accounts.mint,
+        &ctx.accounts.fee_collector_token_account,
+        escrow.to_account_info(), &ctx.accounts.token_program,
+        signer_seeds, total_fee, decimals,
+    )?;
+
+    escrow.bump_nonce()?;
+
+    let percent_complete_bps = percent_complete_bps(escrow.released_amount, escrow.amount)?;
+    for (milestone_index, amount, fee, fee_exempt) in released {
+        emit!(MilestoneReleased {
+            escrow: escrow.key(),
+            milestone_index,
+            amount,
+            fee,
+            fee_exempt,
+            percent_complete_bps,
+            nonce: escrow.nonce,
+        });
+    }
+    for (milestone_index, amount) in cancelled {
+        emit!(MilestoneCancelled {
+            escrow: escrow.key(),
+            milestone_index,
+            amount,
+            reason: CancelReason::MakerCancel,
+            nonce: escrow.nonce,
+        });
+    }
+
+    let (milestones_released, milestones_cancelled) = escrow.settled_milestone_counts();
+    if completed {
+        emit!(EscrowCompleted {
+            escrow: escrow.key(),
+            total_released: escrow.released_amount,
+            total_refunded: escrow.refunded_amount,
+            total_fees_paid: escrow.fees_paid,
+            milestones_released,
+            milestones_cancelled,
+            beneficiary: escrow.beneficiary,
+            nonce: escrow.nonce,
+        });
+    } else {
+        emit!(EscrowCancelled {
+            escrow: escrow.key(),
+            refunded_amount: refund_amount.checked_sub(cancel_fee).ok_or(EscrowError::Overflow)?,
+            cancel_fee,
+            total_released: escrow.released_amount,
+            total_refunded: escrow.refunded_amount,
+            total_fees_paid: escrow.fees_paid,
+            milestones_released,
+            milestones_cancelled,
+            beneficiary: escrow.beneficiary,
+            nonce: escrow.nonce,
+            reason: CancelReason::MakerCancel,
+            routing,
+        });
+    }
+
+    verify_milestone_sum(escrow)?;
+    release_open_slot(escrow, ctx.accounts.maker_profile.as_deref_mut())?;
+
+    Ok(())
+}
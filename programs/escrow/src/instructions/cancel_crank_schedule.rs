@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::events::CrankScheduleCancelled;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CancelCrankSchedule<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            CRANK_SCHEDULE_SEED,
+            crank_schedule.escrow.as_ref(),
+            creator.key().as_ref(),
+            &[crank_schedule.kind.tag()],
+        ],
+        bump = crank_schedule.bump,
+        close = creator,
+    )]
+    pub crank_schedule: Account<'info, CrankSchedule>,
+}
+
+/// Drops a schedule and returns its rent to the creator. Works whether or not
+/// it is due, and after the escrow itself has been closed.
+pub fn handler(ctx: Context<CancelCrankSchedule>) -> Result<()> {
+    emit!(CrankScheduleCancelled {
+        schedule: ctx.accounts.crank_schedule.key(),
+        escrow: ctx.accounts.crank_schedule.escrow,
+        creator: ctx.accounts.creator.key(),
+    });
+
+    Ok(())
+}
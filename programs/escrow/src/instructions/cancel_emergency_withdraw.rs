@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::EmergencyWithdrawCancelled;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CancelEmergencyWithdraw<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+        constraint = escrow_config.authority == authority.key() @ EscrowError::NotAuthority,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(
+        mut,
+        seeds = [EMERGENCY_WITHDRAW_SEED, emergency_withdrawal.escrow.as_ref()],
+        bump = emergency_withdrawal.bump,
+        close = authority,
+    )]
+    pub emergency_withdrawal: Account<'info, EmergencyWithdrawal>,
+}
+
+/// Withdraw a pending emergency-withdrawal proposal, e.g. once the parties
+/// have settled. Works whatever state the escrow is in, closed included.
+pub fn handler(ctx: Context<CancelEmergencyWithdraw>) -> Result<()> {
+    emit!(EmergencyWithdrawCancelled {
+        escrow: ctx.accounts.emergency_withdrawal.escrow,
+        closed_by: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
@@ -2,8 +2,10 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::error::EscrowError;
-use crate::events::EscrowCancelled;
-use crate::helpers::{escrow_seeds, transfer_from_vault};
+use crate::events::{EscrowCancelled, MilestoneCancelled};
+use crate::helpers::{
+    assert_vault_clean, escrow_seeds, refund_maker, release_open_slot, verify_expected_nonce, verify_milestone_sum,
+};
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -12,7 +14,7 @@ pub struct CancelEscrow<'info> {
 
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_state.maker.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
         bump = escrow_state.bump,
         constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
     )]
@@ -23,9 +25,7 @@ pub struct CancelEscrow<'info> {
 
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = escrow_state,
-        associated_token::token_program = token_program,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
@@ -37,30 +37,70 @@ pub struct CancelEscrow<'info> {
     )]
     pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    /// Receives the cancellation fee: the fee collector's or the beneficiary's
+    /// token account, per the escrow's `MakerCancel` routing. Required when a fee applies.
+    #[account(
+        mut,
+        constraint = cancel_fee_token_account.key() != escrow_state.vault @ EscrowError::DestinationIsVault,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub cancel_fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Gets the open slot back on cancellation.
+    #[account(
+        mut,
+        seeds = [MAKER_PROFILE_SEED, escrow_state.creator.as_ref()],
+        bump = maker_profile.bump,
+    )]
+    pub maker_profile: Option<Account<'info, MakerProfile>>,
 }
 
-pub fn handler(ctx: Context<CancelEscrow>) -> Result<()> {
+pub fn handler(ctx: Context<CancelEscrow>, expected_nonce: Option<u64>) -> Result<()> {
+    assert_vault_clean(&ctx.accounts.vault)?;
+
     let escrow = &mut ctx.accounts.escrow_state;
 
+    verify_expected_nonce(escrow, expected_nonce)?;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+
+    // Before acceptance the maker may withdraw at any time (including after
+    // the acceptance deadline lapses) for a full refund
+    let awaiting_acceptance = escrow.status == EscrowStatus::AwaitingAcceptance;
     require!(
-        escrow.status == EscrowStatus::Active,
+        escrow.status == EscrowStatus::Active || awaiting_acceptance,
         EscrowError::EscrowNotActive
     );
 
     let clock = Clock::get()?;
-    require!(clock.unix_timestamp <= escrow.expires_at, EscrowError::EscrowExpired);
+    require!(
+        awaiting_acceptance || clock.unix_timestamp <= escrow.expires_at,
+        EscrowError::EscrowExpired
+    );
 
     // Sum up amounts for Pending milestones only.
     // Approved milestones are intentionally skipped — they represent accepted work
     // that the taker can still claim via release_milestone.
     let mut refund_amount: u64 = 0;
-    for milestone in escrow.milestones.iter_mut() {
+    let mut cancelled: Vec<(u8, u64)> = Vec::new();
+    for (i, milestone) in escrow.milestones.iter_mut().enumerate() {
         if milestone.status == MilestoneStatus::Pending {
             refund_amount = refund_amount
                 .checked_add(milestone.amount)
                 .ok_or(EscrowError::Overflow)?;
             milestone.status = MilestoneStatus::Cancelled;
+            cancelled.push((i as u8, milestone.amount));
         }
     }
 
@@ -73,18 +113,18 @@ pub fn handler(ctx: Context<CancelEscrow>) -> Result<()> {
         .ok_or(EscrowError::Overflow)?;
 
     // PDA signer seeds
-    let maker_key = escrow.maker;
+    let creator_key = escrow.creator;
     let seed_bytes = escrow.seed.to_le_bytes();
     let bump = [escrow.bump];
-    let inner = escrow_seeds(&maker_key, &seed_bytes, &bump);
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
     let signer_seeds: &[&[&[u8]]] = &[&inner];
 
-    transfer_from_vault(
-        &ctx.accounts.vault, &ctx.accounts.mint,
-        &ctx.accounts.maker_token_account,
-        escrow.to_account_info(), &ctx.accounts.token_program,
-        signer_seeds, refund_amount, ctx.accounts.mint.decimals,
+    let (routing, cancel_fee) = refund_maker(
+        escrow, CancelReason::MakerCancel, refund_amount, ctx.accounts.escrow_config.fee_collector,
+        &ctx.accounts.vault, &ctx.accounts.mint, &ctx.accounts.maker_token_account,
+        &[ctx.accounts.cancel_fee_token_account.as_ref()], &ctx.accounts.token_program, signer_seeds,
     )?;
+    let maker_refund = refund_amount.checked_sub(cancel_fee).ok_or(EscrowError::Overflow)?;
 
     let all_settled = escrow.all_milestones_settled();
 
@@ -96,10 +136,36 @@ pub fn handler(ctx: Context<CancelEscrow>) -> Result<()> {
         escrow.receipt_mint = None;
     }
 
+    escrow.bump_nonce()?;
+
+    for (milestone_index, amount) in cancelled {
+        emit!(MilestoneCancelled {
+            escrow: escrow.key(),
+            milestone_index,
+            amount,
+            reason: CancelReason::MakerCancel,
+            nonce: escrow.nonce,
+        });
+    }
+
+    let (milestones_released, milestones_cancelled) = escrow.settled_milestone_counts();
     emit!(EscrowCancelled {
         escrow: escrow.key(),
-        refunded_amount: refund_amount,
+        refunded_amount: maker_refund,
+        cancel_fee,
+        total_released: escrow.released_amount,
+        total_refunded: escrow.refunded_amount,
+        total_fees_paid: escrow.fees_paid,
+        milestones_released,
+        milestones_cancelled,
+        beneficiary: escrow.beneficiary,
+        nonce: escrow.nonce,
+        reason: CancelReason::MakerCancel,
+        routing,
     });
 
+    verify_milestone_sum(escrow)?;
+    release_open_slot(escrow, ctx.accounts.maker_profile.as_deref_mut())?;
+
     Ok(())
 }
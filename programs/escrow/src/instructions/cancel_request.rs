@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::RequestCanceled;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CancelRequest<'info> {
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [REQUEST_SEED, escrow_request.maker.as_ref(), escrow_request.seed.to_le_bytes().as_ref()],
+        bump = escrow_request.bump,
+        constraint = escrow_request.taker == requester.key() @ EscrowError::NotRequester,
+        close = requester,
+    )]
+    pub escrow_request: Account<'info, EscrowRequest>,
+}
+
+/// Withdraw an open or expired request and reclaim its rent.
+pub fn handler(ctx: Context<CancelRequest>) -> Result<()> {
+    emit!(RequestCanceled {
+        request: ctx.accounts.escrow_request.key(),
+        taker: ctx.accounts.requester.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::SubEscrowCancelled;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CancelSubEscrow<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// CHECK: Parent escrow from the child's link. May already be closed, in
+    /// which case there is no milestone link left to clear.
+    #[account(
+        mut,
+        constraint = escrow_state.parent.is_some_and(|link| link.escrow == parent_escrow.key()) @ EscrowError::SubEscrowMismatch,
+    )]
+    pub parent_escrow: UncheckedAccount<'info>,
+}
+
+/// Abandon a sub-escrow that was never funded. The child becomes `Cancelled`
+/// (and closable) and the parent milestone pays its beneficiary in full again.
+pub fn handler(ctx: Context<CancelSubEscrow>) -> Result<()> {
+    let child = &mut ctx.accounts.escrow_state;
+    require!(child.status == EscrowStatus::AwaitingFunding, EscrowError::NotAwaitingFunding);
+
+    child.status = EscrowStatus::Cancelled;
+    child.bump_nonce()?;
+
+    let parent_info = ctx.accounts.parent_escrow.to_account_info();
+    if parent_info.owner == &crate::ID && !parent_info.data_is_empty() {
+        let mut data = parent_info.try_borrow_mut_data()?;
+        let mut parent = EscrowState::try_deserialize(&mut &data[..])?;
+        // Renewal may have re-indexed the parent's milestones, so match by key
+        let child_key = child.key();
+        if let Some(milestone) = parent
+            .milestones
+            .iter_mut()
+            .find(|m| m.sub_escrow == Some(child_key))
+        {
+            milestone.sub_escrow = None;
+            parent.bump_nonce()?;
+            parent.try_serialize(&mut &mut data[..])?;
+        }
+    }
+
+    emit!(SubEscrowCancelled {
+        parent: ctx.accounts.parent_escrow.key(),
+        child: child.key(),
+        nonce: child.nonce,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::MilestoneCertified;
+use crate::helpers::verify_milestone_sum;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CertifyMilestone<'info> {
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.beneficiary == beneficiary.key() @ EscrowError::NotBeneficiary,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+}
+
+/// Beneficiary-side approval for milestones the maker flagged `self_certify`
+/// at creation. Moves Pending→Approved; release then follows the normal flow,
+/// including the release delay.
+pub fn handler(ctx: Context<CertifyMilestone>, milestone_index: u8) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    require!(escrow.status == EscrowStatus::Active, EscrowError::EscrowNotActive);
+
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp <= escrow.expires_at, EscrowError::EscrowExpired);
+
+    let idx = milestone_index as usize;
+    require!(idx < escrow.milestones.len(), EscrowError::MilestoneIndexOutOfBounds);
+    require!(escrow.milestones[idx].self_certify, EscrowError::NotSelfCertifiable);
+    require!(
+        escrow.milestones[idx].status == MilestoneStatus::Pending,
+        EscrowError::MilestoneNotPending
+    );
+
+    escrow.milestones[idx].status = MilestoneStatus::Approved;
+    escrow.milestones[idx].approved_at = clock.unix_timestamp;
+    let expiry_extension = escrow.extend_expiry_for_approval(clock.unix_timestamp)?;
+
+    escrow.bump_nonce()?;
+
+    emit!(MilestoneCertified {
+        escrow: escrow.key(),
+        milestone_index,
+        beneficiary: escrow.beneficiary,
+        expiry_extension,
+        expires_at: escrow.expires_at,
+        nonce: escrow.nonce,
+    });
+
+    verify_milestone_sum(escrow)?;
+
+    Ok(())
+}
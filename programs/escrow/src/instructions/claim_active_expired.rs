@@ -0,0 +1,272 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::EscrowError;
+use crate::events::{ActiveExpiryClaimed, CrankRewarded, EscrowExpired, KeeperTipPaid, MilestoneCancelled};
+use crate::helpers::{
+    approved_milestone_payouts, assert_vault_clean, escrow_seeds, fund_holding, pay_crank_tip, payee_token_account,
+    refund_maker, release_open_slot, route_milestone_payouts, transfer_from_vault, verify_expiry_claimable, verify_milestone_sum, verify_remaining_accounts,
+};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ClaimActiveExpired<'info> {
+    /// Anyone can crank this permissionless instruction. Pays the rent of any
+    /// claw-back holding accounts, which is returned to the beneficiary.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == escrow_state.maker @ EscrowError::OwnerMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Only needed when Approved milestones are paid out.
+    #[account(
+        mut,
+        constraint = beneficiary_token_account.owner == escrow_state.beneficiary @ EscrowError::OwnerMismatch,
+        constraint = escrow_state.beneficiary_ata.is_none_or(|ata| ata == beneficiary_token_account.key()) @ EscrowError::BeneficiaryAtaMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub beneficiary_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Only needed when Approved milestones are paid out.
+    #[account(
+        mut,
+        constraint = fee_collector_token_account.owner == escrow_config.fee_collector @ EscrowError::FeeCollectorMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub fee_collector_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+
+    /// `payer`'s keeper entry; when present, a state-changing crank pays it the escrow's tip.
+    #[account(
+        mut,
+        seeds = [KEEPER_SEED, payer.key().as_ref()],
+        bump = keeper.bump,
+    )]
+    pub keeper: Option<Account<'info, Keeper>>,
+
+    /// Gets the open slot back when the expiry claim settles the escrow.
+    #[account(
+        mut,
+        seeds = [MAKER_PROFILE_SEED, escrow_state.creator.as_ref()],
+        bump = maker_profile.bump,
+    )]
+    pub maker_profile: Option<Account<'info, MakerProfile>>,
+}
+
+/// Each Approved milestone's net is routed as a release would route it: while
+/// the escrow has a claw-back window `remaining_accounts` carry those
+/// milestones' holding PDAs, otherwise the token accounts of milestones with
+/// their own beneficiary (after the receipt token account, if any).
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimActiveExpired<'info>>) -> Result<()> {
+    assert_vault_clean(&ctx.accounts.vault)?;
+
+    let nonce_before = ctx.accounts.escrow_state.nonce;
+    let accounts = ctx.accounts;
+    settle_active_expiry(
+        &mut accounts.escrow_state,
+        &accounts.escrow_config,
+        &accounts.mint,
+        &accounts.vault,
+        &accounts.maker_token_account,
+        accounts.beneficiary_token_account.as_ref(),
+        accounts.fee_collector_token_account.as_ref(),
+        &accounts.token_program,
+        accounts.payer.to_account_info(),
+        &accounts.system_program,
+        ctx.remaining_accounts,
+    )?;
+
+    release_open_slot(&mut accounts.escrow_state, accounts.maker_profile.as_deref_mut())?;
+
+    let escrow = &mut accounts.escrow_state;
+    let tip = pay_crank_tip(escrow, accounts.keeper.as_mut(), nonce_before)?;
+    if tip > 0 {
+        emit!(KeeperTipPaid {
+            escrow: escrow.key(),
+            keeper: accounts.payer.key(),
+            amount: tip,
+            tip_jar: escrow.tip_jar,
+            nonce: escrow.nonce,
+        });
+        emit!(CrankRewarded {
+            escrow: escrow.key(),
+            keeper: accounts.payer.key(),
+            reward: tip,
+            instruction: CrankInstruction::ClaimActiveExpired,
+        });
+    }
+
+    Ok(())
+}
+
+/// Settle an Active escrow past `expires_at`: Approved milestones are paid to
+/// their payees (minus fee) or into their claw-back holdings, Pending ones
+/// refunded to the maker per the escrow's `Expiry` routing.
+/// Returns the (approved, pending) amounts settled.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn settle_active_expiry<'info>(
+    escrow: &mut Account<'info, EscrowState>,
+    config: &EscrowConfig,
+    mint: &InterfaceAccount<'info, Mint>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    maker_token_account: &InterfaceAccount<'info, TokenAccount>,
+    beneficiary_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    fee_collector_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    token_program: &Interface<'info, TokenInterface>,
+    payer: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<(u64, u64)> {
+    verify_remaining_accounts(remaining_accounts, MAX_BULK_SETTLEMENT_ACCOUNTS)?;
+
+    let clock = Clock::get()?;
+    require!(
+        escrow.status == EscrowStatus::Active && clock.unix_timestamp > escrow.expires_at,
+        EscrowError::EscrowNotExpired
+    );
+    verify_expiry_claimable(escrow, remaining_accounts)?;
+
+    // Update state BEFORE CPI (checks-effects-interactions)
+    let cancelled: Vec<(u8, u64)> = escrow
+        .milestones
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.status == MilestoneStatus::Pending)
+        .map(|(i, m)| (i as u8, m.amount))
+        .collect();
+    let (payouts, approved_fee) = approved_milestone_payouts(escrow)?;
+    let (approved_amount, pending_amount) = escrow.settle_milestones_on_expiry()?;
+    escrow.status = EscrowStatus::Expired;
+    escrow.funds_distributed = true;
+    // Invalidate receipt NFT on terminal state
+    escrow.receipt_mint = None;
+
+    // PDA signer seeds
+    let creator_key = escrow.creator;
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    let decimals = mint.decimals;
+
+    // Refund pending to maker
+    let (_, cancel_fee) = refund_maker(
+        escrow, CancelReason::Expiry, pending_amount, config.fee_collector,
+        vault, mint, maker_token_account,
+        &[fee_collector_token_account, beneficiary_token_account],
+        token_program, signer_seeds,
+    )?;
+
+    // Release approved to beneficiary
+    let mut fee = 0;
+    if approved_amount > 0 {
+        let (Some(beneficiary_token_account), Some(fee_collector_token_account)) =
+            (beneficiary_token_account, fee_collector_token_account)
+        else {
+            return err!(EscrowError::PayoutAccountMissing);
+        };
+
+        fee = approved_fee;
+        let routed = route_milestone_payouts(escrow, &payouts, clock.unix_timestamp)?;
+        escrow.record_fee(fee)?;
+
+        transfer_from_vault(
+            vault, mint, beneficiary_token_account,
+            escrow.to_account_info(), token_program,
+            signer_seeds, routed.to_beneficiary, decimals,
+        )?;
+        for (index, amount) in routed.to_payees {
+            let payee_token_account = payee_token_account(escrow, index, remaining_accounts)?;
+            transfer_from_vault(
+                vault, mint, &payee_token_account,
+                escrow.to_account_info(), token_program,
+                signer_seeds, amount, decimals,
+            )?;
+        }
+        for (index, amount) in routed.held {
+            fund_holding(
+                escrow, index, amount, vault, mint, payer.clone(), system_program,
+                token_program, remaining_accounts, signer_seeds,
+            )?;
+        }
+
+        transfer_from_vault(
+            vault, mint, fee_collector_token_account,
+            escrow.to_account_info(), token_program,
+            signer_seeds, fee, decimals,
+        )?;
+    }
+
+    escrow.bump_nonce()?;
+
+    emit!(ActiveExpiryClaimed {
+        escrow: escrow.key(),
+        approved_released: approved_amount,
+        pending_refunded: pending_amount,
+        fee,
+        nonce: escrow.nonce,
+        cancel_fee,
+    });
+
+    for (milestone_index, amount) in cancelled {
+        emit!(MilestoneCancelled {
+            escrow: escrow.key(),
+            milestone_index,
+            amount,
+            reason: CancelReason::Expiry,
+            nonce: escrow.nonce,
+        });
+    }
+
+    let (milestones_released, milestones_cancelled) = escrow.settled_milestone_counts();
+    emit!(EscrowExpired {
+        escrow: escrow.key(),
+        total_released: escrow.released_amount,
+        total_refunded: escrow.refunded_amount,
+        total_fees_paid: escrow.fees_paid,
+        milestones_released,
+        milestones_cancelled,
+        beneficiary: escrow.beneficiary,
+        nonce: escrow.nonce,
+    });
+
+    verify_milestone_sum(escrow)?;
+
+    Ok((approved_amount, pending_amount))
+}
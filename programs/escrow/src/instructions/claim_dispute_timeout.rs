@@ -0,0 +1,290 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::EscrowError;
+use crate::events::{CrankRewarded, DisputeTimeoutClaimed, EscrowExpired, KeeperTipPaid, MilestoneCancelled};
+use crate::helpers::{
+    assert_vault_clean, claw_back_deadline, escrow_seeds, fund_holding, pay_crank_tip, refund_maker, release_open_slot, split_amount,
+    transfer_from_vault, verify_expiry_claimable, verify_milestone_sum, verify_remaining_accounts,
+};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ClaimDisputeTimeout<'info> {
+    /// Anyone can crank this permissionless instruction. Pays the rent of the
+    /// claw-back holding account, which is returned to the beneficiary.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == escrow_state.maker @ EscrowError::OwnerMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = beneficiary_token_account.owner == escrow_state.beneficiary @ EscrowError::OwnerMismatch,
+        constraint = escrow_state.beneficiary_ata.is_none_or(|ata| ata == beneficiary_token_account.key()) @ EscrowError::BeneficiaryAtaMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = fee_collector_token_account.owner == escrow_config.fee_collector @ EscrowError::FeeCollectorMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub fee_collector_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+
+    /// `payer`'s keeper entry; when present, a state-changing crank pays it the escrow's tip.
+    #[account(
+        mut,
+        seeds = [KEEPER_SEED, payer.key().as_ref()],
+        bump = keeper.bump,
+    )]
+    pub keeper: Option<Account<'info, Keeper>>,
+
+    /// Gets the open slot back when the timed-out dispute is settled.
+    #[account(
+        mut,
+        seeds = [MAKER_PROFILE_SEED, escrow_state.creator.as_ref()],
+        bump = maker_profile.bump,
+    )]
+    pub maker_profile: Option<Account<'info, MakerProfile>>,
+}
+
+/// While the escrow has a claw-back window, the beneficiary's share is held in
+/// the `SETTLEMENT_HOLDING_INDEX` holding, whose PDA `remaining_accounts` then
+/// carry (after the receipt token account, if any).
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimDisputeTimeout<'info>>) -> Result<()> {
+    assert_vault_clean(&ctx.accounts.vault)?;
+
+    let nonce_before = ctx.accounts.escrow_state.nonce;
+    let accounts = ctx.accounts;
+    settle_dispute_timeout(
+        &mut accounts.escrow_state,
+        &accounts.escrow_config,
+        &accounts.mint,
+        &accounts.vault,
+        &accounts.maker_token_account,
+        &accounts.beneficiary_token_account,
+        &accounts.fee_collector_token_account,
+        &accounts.token_program,
+        accounts.payer.to_account_info(),
+        &accounts.system_program,
+        ctx.remaining_accounts,
+    )?;
+
+    release_open_slot(&mut accounts.escrow_state, accounts.maker_profile.as_deref_mut())?;
+
+    let escrow = &mut accounts.escrow_state;
+    let tip = pay_crank_tip(escrow, accounts.keeper.as_mut(), nonce_before)?;
+    if tip > 0 {
+        emit!(KeeperTipPaid {
+            escrow: escrow.key(),
+            keeper: accounts.payer.key(),
+            amount: tip,
+            tip_jar: escrow.tip_jar,
+            nonce: escrow.nonce,
+        });
+        emit!(CrankRewarded {
+            escrow: escrow.key(),
+            keeper: accounts.payer.key(),
+            reward: tip,
+            instruction: CrankInstruction::ClaimDisputeTimeout,
+        });
+    }
+
+    Ok(())
+}
+
+/// Settle a dispute nobody ruled on before its deadline, per the config's
+/// `dispute_timeout_policy`. Every open milestone is cancelled; the maker's
+/// share is refunded per the escrow's `DisputeTimeout` routing.
+/// Returns the (maker, taker) shares of the remaining balance.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn settle_dispute_timeout<'info>(
+    escrow: &mut Account<'info, EscrowState>,
+    config: &EscrowConfig,
+    mint: &InterfaceAccount<'info, Mint>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    maker_token_account: &InterfaceAccount<'info, TokenAccount>,
+    beneficiary_token_account: &InterfaceAccount<'info, TokenAccount>,
+    fee_collector_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    payer: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<(u64, u64)> {
+    verify_remaining_accounts(remaining_accounts, MAX_BULK_SETTLEMENT_ACCOUNTS)?;
+
+    let clock = Clock::get()?;
+    require!(
+        escrow.status == EscrowStatus::Disputed
+            && escrow
+                .dispute
+                .as_ref()
+                .is_some_and(|d| clock.unix_timestamp > d.deadline),
+        EscrowError::EscrowNotExpired
+    );
+    let remaining = verify_expiry_claimable(escrow, remaining_accounts)?;
+
+    // Update state BEFORE CPI (checks-effects-interactions)
+    let cancelled = escrow.cancel_open_milestones();
+
+    // Settle per the config policy — compute once, reuse for both accounting and CPI
+    let policy = config.dispute_timeout_policy;
+    let maker_bps = match policy {
+        DisputeTimeoutPolicy::SplitOnTimeout => 5_000,
+        DisputeTimeoutPolicy::FavorNonInitiator => {
+            let maker_initiated = escrow
+                .dispute
+                .as_ref()
+                .is_some_and(|d| d.initiator == escrow.maker);
+            // Against the initiator: a maker-opened dispute pays the beneficiary,
+            // a taker/beneficiary-opened one refunds the maker
+            if maker_initiated {
+                0
+            } else {
+                10_000
+            }
+        }
+    };
+    let (maker_share, taker_amount, fee) = split_amount(
+        remaining,
+        maker_bps,
+        escrow.fee_bps_at_creation,
+        escrow.flat_fee_at_creation,
+        escrow.remainder_policy,
+    )?;
+    let taker_share = taker_amount.checked_add(fee).ok_or(EscrowError::Overflow)?;
+
+    // No arbiter ruled — record the timeout settlement time only
+    if let Some(ref mut dispute) = escrow.dispute {
+        dispute.resolved_by = Pubkey::default();
+        dispute.resolved_at = clock.unix_timestamp;
+    }
+    escrow.refunded_amount = escrow
+        .refunded_amount
+        .checked_add(maker_share)
+        .ok_or(EscrowError::Overflow)?;
+    escrow.released_amount = escrow
+        .released_amount
+        .checked_add(taker_share)
+        .ok_or(EscrowError::Overflow)?;
+    escrow.status = EscrowStatus::Expired;
+    escrow.funds_distributed = true;
+    // Invalidate receipt NFT on terminal state
+    escrow.receipt_mint = None;
+
+    // PDA signer seeds
+    let creator_key = escrow.creator;
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    let decimals = mint.decimals;
+
+    let taker_amount = escrow.route_beneficiary_payout(taker_amount)?;
+    escrow.record_fee(fee)?;
+    escrow.settlement_held_until = claw_back_deadline(escrow.claw_back_seconds, taker_amount, clock.unix_timestamp)?;
+
+    let (_, cancel_fee) = refund_maker(
+        escrow, CancelReason::DisputeTimeout, maker_share, config.fee_collector,
+        vault, mint, maker_token_account,
+        &[Some(fee_collector_token_account), Some(beneficiary_token_account)],
+        token_program, signer_seeds,
+    )?;
+
+    if escrow.settlement_held_until != 0 {
+        fund_holding(
+            escrow, SETTLEMENT_HOLDING_INDEX, taker_amount, vault, mint, payer, system_program,
+            token_program, remaining_accounts, signer_seeds,
+        )?;
+    } else {
+        transfer_from_vault(
+            vault, mint, beneficiary_token_account,
+            escrow.to_account_info(), token_program,
+            signer_seeds, taker_amount, decimals,
+        )?;
+    }
+
+    transfer_from_vault(
+        vault, mint, fee_collector_token_account,
+        escrow.to_account_info(), token_program,
+        signer_seeds, fee, decimals,
+    )?;
+
+    escrow.bump_nonce()?;
+
+    emit!(DisputeTimeoutClaimed {
+        escrow: escrow.key(),
+        policy,
+        maker_share,
+        taker_share,
+        fee,
+        resolved_at: clock.unix_timestamp,
+        nonce: escrow.nonce,
+        cancel_fee,
+    });
+
+    for (milestone_index, amount) in cancelled {
+        emit!(MilestoneCancelled {
+            escrow: escrow.key(),
+            milestone_index,
+            amount,
+            reason: CancelReason::DisputeTimeout,
+            nonce: escrow.nonce,
+        });
+    }
+
+    let (milestones_released, milestones_cancelled) = escrow.settled_milestone_counts();
+    emit!(EscrowExpired {
+        escrow: escrow.key(),
+        total_released: escrow.released_amount,
+        total_refunded: escrow.refunded_amount,
+        total_fees_paid: escrow.fees_paid,
+        milestones_released,
+        milestones_cancelled,
+        beneficiary: escrow.beneficiary,
+        nonce: escrow.nonce,
+    });
+
+    verify_milestone_sum(escrow)?;
+
+    Ok((maker_share, taker_share))
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::KeeperTipsClaimed;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ClaimKeeperTips<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [KEEPER_SEED, keeper.key().as_ref()],
+        bump = keeper_account.bump,
+    )]
+    pub keeper_account: Account<'info, Keeper>,
+}
+
+/// Withdraw accumulated tips while staying registered. Rent and bond stay put.
+pub fn handler(ctx: Context<ClaimKeeperTips>) -> Result<()> {
+    let keeper_account = &mut ctx.accounts.keeper_account;
+
+    let reserved = Rent::get()?
+        .minimum_balance(8 + Keeper::INIT_SPACE)
+        .checked_add(keeper_account.bond)
+        .ok_or(EscrowError::Overflow)?;
+    let amount = keeper_account.get_lamports().saturating_sub(reserved);
+    require!(amount > 0, EscrowError::NoTipsToClaim);
+
+    keeper_account.sub_lamports(amount)?;
+    ctx.accounts.keeper.add_lamports(amount)?;
+
+    emit!(KeeperTipsClaimed {
+        keeper: keeper_account.keeper,
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::EscrowError;
+use crate::events::{EscrowClawedBack, HeldFundsReclaimed};
+use crate::helpers::verify_remaining_accounts;
+use crate::instructions::reclaim_held::reclaim_holding;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct Clawback<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+        constraint = escrow_config.authority == authority.key() @ EscrowError::NotAuthority,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.disputes_enabled @ EscrowError::DisputesDisabled,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+        constraint = maker_token_account.owner == escrow_state.maker @ EscrowError::OwnerMismatch,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Receives the holding accounts' rent; pinned to the beneficiary.
+    #[account(mut, address = escrow_state.beneficiary @ EscrowError::OwnerMismatch)]
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Authority-confirmed fraud: reverse every release on the escrow whose
+/// claw-back window is still open. `remaining_accounts` are the holding
+/// accounts of those milestones, in milestone order, then the held dispute
+/// payout's (`SETTLEMENT_HOLDING_INDEX`) if there is one.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, Clawback<'info>>) -> Result<()> {
+    verify_remaining_accounts(ctx.remaining_accounts, MAX_MILESTONES as usize + 1)?;
+
+    let clock = Clock::get()?;
+    let escrow = &ctx.accounts.escrow_state;
+    let escrow_key = escrow.key();
+    let open: Vec<u8> = (0..escrow.milestones.len() as u8)
+        .chain([SETTLEMENT_HOLDING_INDEX])
+        .filter(|&index| escrow.held_until(index) > clock.unix_timestamp)
+        .collect();
+    require!(!open.is_empty(), EscrowError::NothingHeld);
+    require!(
+        ctx.remaining_accounts.len() == open.len(),
+        EscrowError::HoldingAccountMissing
+    );
+
+    let mut reclaimed: Vec<(u8, u64)> = Vec::with_capacity(open.len());
+    for (&index, info) in open.iter().zip(ctx.remaining_accounts.iter()) {
+        let (expected, _) = Pubkey::find_program_address(
+            &[HOLDING_SEED, escrow_key.as_ref(), &[index]],
+            &crate::ID,
+        );
+        require_keys_eq!(info.key(), expected, EscrowError::HoldingAccountMissing);
+        let holding = InterfaceAccount::<TokenAccount>::try_from(info)?;
+
+        let amount = reclaim_holding(
+            &mut ctx.accounts.escrow_state,
+            index,
+            &holding,
+            &ctx.accounts.mint,
+            &ctx.accounts.maker_token_account,
+            ctx.accounts.beneficiary.to_account_info(),
+            &ctx.accounts.token_program,
+            clock.unix_timestamp,
+        )?;
+        reclaimed.push((index, amount));
+    }
+
+    let escrow = &mut ctx.accounts.escrow_state;
+    escrow.bump_nonce()?;
+
+    let authority = ctx.accounts.authority.key();
+    let mut total: u64 = 0;
+    for (milestone_index, amount) in reclaimed {
+        total = total.checked_add(amount).ok_or(EscrowError::Overflow)?;
+        emit!(HeldFundsReclaimed {
+            escrow: escrow.key(),
+            milestone_index,
+            reclaimed_by: authority,
+            amount,
+            nonce: escrow.nonce,
+        });
+    }
+
+    emit!(EscrowClawedBack {
+        escrow: escrow.key(),
+        maker: escrow.maker,
+        amount: total,
+        milestones_reclaimed: open.len() as u8,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
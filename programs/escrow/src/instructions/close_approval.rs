@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::events::ApprovalClosed;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CloseApproval<'info> {
+    #[account(mut)]
+    pub approver: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [APPROVAL_SEED, approval.escrow.as_ref(), approver.key().as_ref()],
+        bump = approval.bump,
+        close = approver,
+    )]
+    pub approval: Account<'info, Approval>,
+}
+
+/// Revokes an approval and returns its rent. Works whether or not the approval
+/// is still usable, and after the escrow itself has been closed.
+pub fn handler(ctx: Context<CloseApproval>) -> Result<()> {
+    emit!(ApprovalClosed {
+        escrow: ctx.accounts.approval.escrow,
+        approver: ctx.accounts.approver.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::ArchiveClosed;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CloseArchive<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ARCHIVE_SEED, escrow_archive.escrow.as_ref()],
+        bump = escrow_archive.bump,
+        constraint = escrow_archive.maker == maker.key() @ EscrowError::NotMaker,
+        close = maker,
+    )]
+    pub escrow_archive: Account<'info, EscrowArchive>,
+}
+
+/// Reclaims an archive's rent once `ARCHIVE_RETENTION` has passed since the
+/// escrow was closed. Archives are otherwise immutable.
+pub fn handler(ctx: Context<CloseArchive>) -> Result<()> {
+    let archive = &ctx.accounts.escrow_archive;
+    let retained_until = archive
+        .closed_at
+        .checked_add(ARCHIVE_RETENTION)
+        .ok_or(EscrowError::Overflow)?;
+    require!(
+        Clock::get()?.unix_timestamp >= retained_until,
+        EscrowError::ArchiveRetentionActive
+    );
+
+    emit!(ArchiveClosed {
+        escrow: archive.escrow,
+        archive: archive.key(),
+        maker: archive.maker,
+    });
+
+    Ok(())
+}
@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+use anchor_spl::{
+    metadata::{update_metadata_accounts_v2, Metadata, UpdateMetadataAccountsV2},
+    token_interface::{self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface},
+};
 
 use crate::error::EscrowError;
-use crate::events::EscrowClosed;
-use crate::helpers::{escrow_seeds, transfer_from_vault};
+use crate::events::{EscrowClosed, ReceiptMetadataSealed};
+use crate::helpers::{assert_vault_clean, escrow_seeds, transfer_from_vault};
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -13,21 +16,29 @@ pub struct CloseEscrow<'info> {
 
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_state.maker.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
         bump = escrow_state.bump,
         constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
         close = maker,
     )]
     pub escrow_state: Account<'info, EscrowState>,
 
-    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch,
+    )]
     pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = escrow_state,
-        associated_token::token_program = token_program,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
@@ -39,11 +50,106 @@ pub struct CloseEscrow<'info> {
     )]
     pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Required when `escrow_state.metadata` is set; closed to the maker.
+    #[account(
+        mut,
+        seeds = [METADATA_SEED, escrow_state.key().as_ref()],
+        bump = escrow_metadata.bump,
+        close = maker,
+    )]
+    pub escrow_metadata: Option<Account<'info, EscrowMetadata>>,
+
+    /// CHECK: Required while `escrow_state.receipt_metadata_mutable`; must be the
+    /// Metaplex metadata of this escrow's receipt mint (checked in `seal_receipt_metadata`).
+    #[account(mut)]
+    pub receipt_metadata: Option<UncheckedAccount<'info>>,
+
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
 }
 
 pub fn handler(ctx: Context<CloseEscrow>) -> Result<()> {
-    let escrow = &ctx.accounts.escrow_state;
+    assert_vault_clean(&ctx.accounts.vault)?;
+
+    let accounts = ctx.accounts;
+    close_vault(
+        &accounts.escrow_state,
+        &accounts.escrow_config,
+        &accounts.mint,
+        &mut accounts.vault,
+        &accounts.maker_token_account,
+        &accounts.token_program,
+        accounts.maker.to_account_info(),
+        accounts.escrow_metadata.is_some(),
+    )?;
+    seal_receipt_metadata(
+        &accounts.escrow_state,
+        accounts.receipt_metadata.as_ref(),
+        accounts.token_metadata_program.as_ref(),
+    )
+}
+
+/// Last act of the escrow PDA as the receipt's update authority: mark the
+/// primary sale as happened (the receipt's claim is settled) and make the
+/// metadata immutable, so no key can ever update it. A no-op for escrows
+/// without a mutable receipt.
+pub(crate) fn seal_receipt_metadata<'info>(
+    escrow: &Account<'info, EscrowState>,
+    receipt_metadata: Option<&UncheckedAccount<'info>>,
+    token_metadata_program: Option<&Program<'info, Metadata>>,
+) -> Result<()> {
+    if !escrow.receipt_metadata_mutable {
+        return Ok(());
+    }
+    let (Some(receipt_metadata), Some(token_metadata_program)) = (receipt_metadata, token_metadata_program) else {
+        return err!(EscrowError::ReceiptMetadataMissing);
+    };
+
+    // Only one receipt can ever exist per escrow: the mint at its RECEIPT_SEED PDA
+    let escrow_key = escrow.key();
+    let (receipt_mint, _) = Pubkey::find_program_address(&[RECEIPT_SEED, escrow_key.as_ref()], &crate::ID);
+    let (expected, _) = Pubkey::find_program_address(
+        &[b"metadata", token_metadata_program.key().as_ref(), receipt_mint.as_ref()],
+        &token_metadata_program.key(),
+    );
+    require_keys_eq!(receipt_metadata.key(), expected, EscrowError::ReceiptMetadataMismatch);
+
+    let creator_key = escrow.creator;
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    update_metadata_accounts_v2(
+        CpiContext::new_with_signer(
+            token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: receipt_metadata.to_account_info(),
+                update_authority: escrow.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        None,
+        None,
+        Some(true),
+        Some(false),
+    )?;
+
+    emit!(ReceiptMetadataSealed {
+        escrow: escrow_key,
+        metadata: receipt_metadata.key(),
+    });
+
+    Ok(())
+}
+
+/// State preconditions for closing `escrow`: terminal, not on hold, with no
+/// claim pool, bond or held payout left, and its metadata account supplied if
+/// it has one.
+pub(crate) fn verify_closable(escrow: &EscrowState, metadata_supplied: bool) -> Result<()> {
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
 
     require!(
         escrow.status == EscrowStatus::Completed
@@ -52,26 +158,78 @@ pub fn handler(ctx: Context<CloseEscrow>) -> Result<()> {
         EscrowError::EscrowNotTerminal
     );
 
+    // Unredeemed share-holder funds must never be swept or burned with the dust
+    require!(escrow.claim_pool == 0, EscrowError::ClaimPoolNotEmpty);
+
+    // The taker's bond would otherwise be swept to the maker as dust
+    require!(escrow.bond_balance == 0, EscrowError::BondNotSettled);
+
+    // Held payouts are signed for by the escrow PDA, so it must outlive them
+    require!(!escrow.has_held_funds(), EscrowError::FundsStillHeld);
+
+    // Metadata rent must not be stranded once the escrow it describes is gone
+    require!(
+        escrow.metadata.is_none() || metadata_supplied,
+        EscrowError::MetadataAccountMissing
+    );
+    Ok(())
+}
+
+/// Everything `close_escrow` does short of closing the escrow account itself:
+/// terminal-state checks, dust sweep (or burn), `EscrowClosed`, and closing the
+/// vault to `maker`. Shared with the `auto_close` path of `release_milestone`
+/// and `resolve_dispute`, which then close `escrow_state` (and metadata) by hand.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn close_vault<'info>(
+    escrow: &Account<'info, EscrowState>,
+    config: &EscrowConfig,
+    mint: &InterfaceAccount<'info, Mint>,
+    vault: &mut InterfaceAccount<'info, TokenAccount>,
+    maker_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    maker: AccountInfo<'info>,
+    metadata_supplied: bool,
+) -> Result<()> {
+    verify_closable(escrow, metadata_supplied)?;
+
     // Sweep any dust left in the vault (e.g. griefing deposits) back to maker
     // before closing. This prevents an attacker from sending 1 token to the
-    // vault ATA to permanently block closure.
-    let maker_key = escrow.maker;
+    // vault ATA to permanently block closure. Amounts below the configured
+    // threshold are burned instead, since a transfer of that size may itself
+    // be rejected (e.g. Token-2022 transfer hooks or minimums) and the token
+    // program refuses to close a non-empty account.
+    let creator_key = escrow.creator;
     let seed_bytes = escrow.seed.to_le_bytes();
     let bump = [escrow.bump];
-    let inner = escrow_seeds(&maker_key, &seed_bytes, &bump);
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
     let signer_seeds: &[&[&[u8]]] = &[&inner];
 
-    let dust = ctx.accounts.vault.amount;
-    if dust > 0 {
+    // The same instruction may have just settled out of the vault
+    vault.reload()?;
+    let dust = vault.amount;
+    let burn_dust = dust > 0 && dust < config.close_dust_threshold;
+    if burn_dust {
+        let burn_accounts = Burn {
+            mint: mint.to_account_info(),
+            from: vault.to_account_info(),
+            authority: escrow.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            burn_accounts,
+            signer_seeds,
+        );
+        token_interface::burn(cpi_ctx, dust)?;
+    } else if dust > 0 {
         transfer_from_vault(
-            &ctx.accounts.vault,
-            &ctx.accounts.mint,
-            &ctx.accounts.maker_token_account,
-            ctx.accounts.escrow_state.to_account_info(),
-            &ctx.accounts.token_program,
+            vault,
+            mint,
+            maker_token_account,
+            escrow.to_account_info(),
+            token_program,
             signer_seeds,
             dust,
-            ctx.accounts.mint.decimals,
+            mint.decimals,
         )?;
     }
 
@@ -79,15 +237,20 @@ pub fn handler(ctx: Context<CloseEscrow>) -> Result<()> {
     emit!(EscrowClosed {
         escrow: escrow.key(),
         maker: escrow.maker,
+        dust_swept: if burn_dust { 0 } else { dust },
+        dust_burned: if burn_dust { dust } else { 0 },
+        dispute_count: escrow.dispute_count,
+        dispute: escrow.dispute.clone(),
+        nonce: escrow.nonce,
     });
 
     let close_accounts = CloseAccount {
-        account: ctx.accounts.vault.to_account_info(),
-        destination: ctx.accounts.maker.to_account_info(),
-        authority: ctx.accounts.escrow_state.to_account_info(),
+        account: vault.to_account_info(),
+        destination: maker,
+        authority: escrow.to_account_info(),
     };
     let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
+        token_program.to_account_info(),
         close_accounts,
         signer_seeds,
     );
@@ -95,3 +258,42 @@ pub fn handler(ctx: Context<CloseEscrow>) -> Result<()> {
 
     Ok(())
 }
+
+/// `auto_close` tail of a settling instruction: close the vault, the metadata
+/// (if any) and `escrow_state` itself to the maker, who must have co-signed.
+/// Best effort: when the escrow can't be closed yet (see `verify_closable`, or
+/// its receipt metadata still needs sealing) it is left open and the settlement
+/// stands; `close_escrow` can finish the job later.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn auto_close_escrow<'info>(
+    escrow: &Account<'info, EscrowState>,
+    config: &EscrowConfig,
+    mint: &InterfaceAccount<'info, Mint>,
+    vault: &mut InterfaceAccount<'info, TokenAccount>,
+    maker: Option<&Signer<'info>>,
+    maker_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    token_program: &Interface<'info, TokenInterface>,
+    escrow_metadata: Option<&Account<'info, EscrowMetadata>>,
+) -> Result<()> {
+    let (Some(maker), Some(maker_token_account)) = (maker, maker_token_account) else {
+        return err!(EscrowError::CloseAccountMissing);
+    };
+    // Sealing needs the metadata accounts, which only the explicit close paths take
+    if escrow.receipt_metadata_mutable || verify_closable(escrow, escrow_metadata.is_some()).is_err() {
+        return Ok(());
+    }
+    close_vault(
+        escrow,
+        config,
+        mint,
+        vault,
+        maker_token_account,
+        token_program,
+        maker.to_account_info(),
+        escrow_metadata.is_some(),
+    )?;
+    if let Some(escrow_metadata) = escrow_metadata {
+        escrow_metadata.close(maker.to_account_info())?;
+    }
+    escrow.close(maker.to_account_info())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::ProposalClosed;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CloseProposal<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, proposal.maker.as_ref(), proposal.seed.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        constraint = proposal.creator == creator.key() @ EscrowError::NotProposalCreator,
+        close = creator,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/// Withdraw an open or expired proposal and reclaim its rent.
+pub fn handler(ctx: Context<CloseProposal>) -> Result<()> {
+    emit!(ProposalClosed {
+        proposal: ctx.accounts.proposal.key(),
+        creator: ctx.accounts.creator.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::EmergencyWithdrawCancelled;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CloseStaleEmergencyWithdraw<'info> {
+    pub payer: Signer<'info>,
+
+    /// CHECK: Rent recipient, pinned to the authority that paid for the proposal.
+    #[account(mut, address = emergency_withdrawal.proposed_by @ EscrowError::NotAuthority)]
+    pub proposed_by: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [EMERGENCY_WITHDRAW_SEED, emergency_withdrawal.escrow.as_ref()],
+        bump = emergency_withdrawal.bump,
+        close = proposed_by,
+    )]
+    pub emergency_withdrawal: Account<'info, EmergencyWithdrawal>,
+
+    /// CHECK: The escrow the proposal targets. May already be closed, which
+    /// makes the proposal stale; otherwise deserialized in the handler.
+    #[account(address = emergency_withdrawal.escrow)]
+    pub escrow_state: UncheckedAccount<'info>,
+}
+
+/// Permissionlessly close a proposal whose escrow is closed, terminal
+/// (Completed, Cancelled or Expired) or was re-created after the proposal.
+pub fn handler(ctx: Context<CloseStaleEmergencyWithdraw>) -> Result<()> {
+    let info = ctx.accounts.escrow_state.to_account_info();
+    if info.owner == &crate::ID && !info.data_is_empty() {
+        let escrow = EscrowState::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            matches!(
+                escrow.status,
+                EscrowStatus::Completed | EscrowStatus::Cancelled | EscrowStatus::Expired
+            ) || escrow.created_at != ctx.accounts.emergency_withdrawal.escrow_created_at,
+            EscrowError::EmergencyWithdrawStillLive
+        );
+    }
+
+    emit!(EmergencyWithdrawCancelled {
+        escrow: ctx.accounts.emergency_withdrawal.escrow,
+        closed_by: ctx.accounts.payer.key(),
+    });
+
+    Ok(())
+}
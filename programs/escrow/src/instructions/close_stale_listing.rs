@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::EscrowDelisted;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CloseStaleListing<'info> {
+    pub payer: Signer<'info>,
+
+    /// CHECK: Rent recipient, pinned to the maker that paid for the listing.
+    #[account(mut, address = listing.maker @ EscrowError::ListingMakerMismatch)]
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [LISTING_SEED, listing.escrow.as_ref()],
+        bump = listing.bump,
+        close = maker,
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// CHECK: The listed escrow. May already be closed, which makes the
+    /// listing stale; otherwise deserialized in the handler.
+    #[account(address = listing.escrow)]
+    pub escrow_state: UncheckedAccount<'info>,
+}
+
+/// Permissionlessly close a listing whose escrow can no longer be accepted:
+/// closed, past its acceptance deadline, or out of `AwaitingAcceptance`
+/// (accepted without the listing, cancelled, or otherwise terminal).
+pub fn handler(ctx: Context<CloseStaleListing>) -> Result<()> {
+    let info = ctx.accounts.escrow_state.to_account_info();
+    if info.owner == &crate::ID && !info.data_is_empty() {
+        let escrow = EscrowState::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        let clock = Clock::get()?;
+        require!(
+            escrow.status != EscrowStatus::AwaitingAcceptance
+                || clock.unix_timestamp > escrow.acceptance_deadline,
+            EscrowError::ListingStillLive
+        );
+    }
+
+    emit!(EscrowDelisted {
+        escrow: ctx.accounts.listing.escrow,
+        closed_by: ctx.accounts.payer.key(),
+    });
+
+    Ok(())
+}
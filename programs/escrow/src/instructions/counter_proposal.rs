@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::ProposalCountered;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CounterProposal<'info> {
+    /// The party whose turn it is to respond.
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, proposal.maker.as_ref(), proposal.seed.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/// Replace the stored terms and hand the turn back to the other party.
+pub fn handler(ctx: Context<CounterProposal>, terms: EscrowTerms) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp <= proposal.expires_at, EscrowError::ProposalExpired);
+
+    let responder = if proposal.maker_turn { proposal.maker } else { proposal.taker };
+    require!(ctx.accounts.proposer.key() == responder, EscrowError::NotYourTurn);
+
+    proposal.terms = terms;
+    proposal.maker_turn = !proposal.maker_turn;
+    proposal.revision = proposal.revision.checked_add(1).ok_or(EscrowError::Overflow)?;
+
+    emit!(ProposalCountered {
+        proposal: proposal.key(),
+        proposer: responder,
+        revision: proposal.revision,
+    });
+
+    Ok(())
+}
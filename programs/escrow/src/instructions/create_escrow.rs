@@ -1,25 +1,32 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
     token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 use crate::error::EscrowError;
 use crate::events::EscrowCreated;
+use crate::helpers::{verify_milestone_beneficiaries, verify_milestone_inputs, verify_milestone_skew, verify_milestone_sum};
 use crate::state::*;
 
 #[derive(Accounts)]
 #[instruction(seed: u64, amount: u64)]
 pub struct CreateEscrow<'info> {
-    #[account(mut)]
+    /// Authorises the deposit; may be a program PDA signing via CPI.
     pub maker: Signer<'info>,
 
+    /// Pays rent for the escrow, its vault and the maker profile. Usually the
+    /// maker itself; a program-owned maker holding data cannot pay rent.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     /// CHECK: The taker is just stored as a pubkey reference; no signing required at creation.
     pub taker: UncheckedAccount<'info>,
 
     #[account(
         seeds = [ESCROW_CONFIG_SEED],
         bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
     )]
     pub escrow_config: Account<'info, EscrowConfig>,
 
@@ -27,16 +34,25 @@ pub struct CreateEscrow<'info> {
 
     #[account(
         init,
-        payer = maker,
+        payer = payer,
         space = 8 + EscrowState::INIT_SPACE,
         seeds = [ESCROW_SEED, maker.key().as_ref(), seed.to_le_bytes().as_ref()],
         bump,
     )]
     pub escrow_state: Account<'info, EscrowState>,
 
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + MakerProfile::INIT_SPACE,
+        seeds = [MAKER_PROFILE_SEED, maker.key().as_ref()],
+        bump,
+    )]
+    pub maker_profile: Account<'info, MakerProfile>,
+
     #[account(
         init,
-        payer = maker,
+        payer = payer,
         associated_token::mint = mint,
         associated_token::authority = escrow_state,
         associated_token::token_program = token_program,
@@ -57,16 +73,106 @@ pub struct CreateEscrow<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<CreateEscrow>,
     seed: u64,
     amount: u64,
     milestones: Vec<MilestoneInput>,
     expires_at: i64,
+    category: u8,
+    initial_beneficiary: Option<Pubkey>,
+    hash_algo: u8,
+    acceptance_deadline: Option<i64>,
+    auto_release: bool,
+    claw_back_seconds: i64,
+    disputes_enabled: bool,
+    dispute_initiators: u8,
+    fee_on_top: bool,
+    agreement_hash: Option<[u8; 32]>,
+    taker_bond: u64,
+) -> Result<()> {
+    let terms = EscrowTerms {
+        amount,
+        milestones,
+        expires_at,
+        category,
+        initial_beneficiary,
+        hash_algo,
+    };
+    let accounts = ctx.accounts;
+    init_maker_profile(&mut accounts.maker_profile, accounts.maker.key(), ctx.bumps.maker_profile);
+    open_escrow(
+        &mut accounts.escrow_state,
+        ctx.bumps.escrow_state,
+        &accounts.maker,
+        accounts.taker.key(),
+        &accounts.escrow_config,
+        &accounts.mint,
+        &accounts.vault,
+        Some(&accounts.maker_token_account),
+        &accounts.token_program,
+        Some(&mut *accounts.maker_profile),
+        seed,
+        terms,
+        acceptance_deadline,
+        auto_release,
+        claw_back_seconds,
+        disputes_enabled,
+        dispute_initiators,
+        fee_on_top,
+        agreement_hash,
+        taker_bond,
+    )
+}
+
+/// Funding path shared by `create_escrow`, `accept_proposal`, `fund_request`
+/// and `create_sub_escrow`: validates `terms`, moves `terms.amount` from the
+/// maker into the vault (unless `maker_token_account` is `None`, which leaves
+/// the escrow `AwaitingFunding`) and initializes the escrow account.
+/// Only direct creation can opt into `auto_release`, pick a claw-back window
+/// longer than the config's, opt out of disputes, restrict who may open
+/// them, fund the fee on top, attach an agreement or require a taker bond;
+/// the other paths pass `false` / 0 / `true` / `DISPUTE_INITIATORS_ALL` /
+/// `false` / `None` / 0. With `fee_on_top` each milestone is grossed up by
+/// its fee, so the vault receives more than `terms.amount`. With a `maker_profile` (direct creation only) the
+/// escrow counts against the config's `max_open_escrows_per_maker`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn open_escrow<'info>(
+    escrow: &mut Account<'info, EscrowState>,
+    escrow_bump: u8,
+    maker: &Signer<'info>,
+    taker: Pubkey,
+    config: &EscrowConfig,
+    mint: &InterfaceAccount<'info, Mint>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    maker_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    token_program: &Interface<'info, TokenInterface>,
+    maker_profile: Option<&mut MakerProfile>,
+    seed: u64,
+    terms: EscrowTerms,
+    acceptance_deadline: Option<i64>,
+    auto_release: bool,
+    claw_back_seconds: i64,
+    disputes_enabled: bool,
+    dispute_initiators: u8,
+    fee_on_top: bool,
+    agreement_hash: Option<[u8; 32]>,
+    taker_bond: u64,
 ) -> Result<()> {
+    let EscrowTerms {
+        amount,
+        milestones,
+        expires_at,
+        category,
+        initial_beneficiary,
+        hash_algo,
+    } = terms;
+    require!(!config.creation_paused, EscrowError::CreationPaused);
+
     let milestone_count = milestones.len();
     require!(
-        (1..=MAX_MILESTONES).contains(&milestone_count),
+        (1..=MAX_MILESTONES as usize).contains(&milestone_count),
         EscrowError::InvalidMilestoneCount
     );
 
@@ -75,19 +181,32 @@ pub fn handler(
 
     // Validate maker != taker
     require!(
-        ctx.accounts.maker.key() != ctx.accounts.taker.key(),
+        maker.key() != taker,
         EscrowError::SelfEscrow
     );
 
+    // A distinct initial beneficiary follows the same rules as transfer_claim
+    let beneficiary = initial_beneficiary.unwrap_or(taker);
+    require!(beneficiary != maker.key(), EscrowError::InvalidBeneficiary);
+    require!(beneficiary != Pubkey::default(), EscrowError::InvalidBeneficiary);
+
+    // An attached agreement must commit to an actual document
+    require!(agreement_hash != Some([0u8; 32]), EscrowError::InvalidAgreementHash);
+
+    // Hash convention must be one the config accepts
+    require!(
+        hash_algo < 8 && config.accepted_hash_algos & (1 << hash_algo) != 0,
+        EscrowError::UnsupportedHashAlgo
+    );
+
     // Validate milestone amounts sum to total
-    let mut milestone_sum: u64 = 0;
-    for m in &milestones {
-        require!(m.amount > 0, EscrowError::InvalidAmount);
-        milestone_sum = milestone_sum
-            .checked_add(m.amount)
-            .ok_or(EscrowError::Overflow)?;
-    }
-    require!(milestone_sum == amount, EscrowError::MilestoneAmountMismatch);
+    verify_milestone_inputs(&milestones, amount)?;
+    verify_milestone_skew(&milestones, amount, config.max_milestone_skew_bps)?;
+    verify_milestone_beneficiaries(&milestones, &maker.key())?;
+    require!(
+        milestones.iter().all(|m| !m.fee_exempt) || config.allow_fee_exempt,
+        EscrowError::FeeExemptionNotAllowed
+    );
 
     // Enforce minimum expiration duration (1 hour) — also ensures expires_at is in the future
     let clock = Clock::get()?;
@@ -98,70 +217,209 @@ pub fn handler(
         EscrowError::InvalidExpiration
     );
 
+    // Opt-in acceptance: the escrow stays AwaitingAcceptance until the
+    // taker/beneficiary accepts, at the latest by `deadline`
+    if let Some(deadline) = acceptance_deadline {
+        require!(
+            deadline > clock.unix_timestamp && deadline <= expires_at,
+            EscrowError::InvalidAcceptanceDeadline
+        );
+    }
+    // A bonded escrow stays AwaitingAcceptance until the taker posts the
+    // bond, by the acceptance deadline or, without one, by expiry
+    let acceptance_deadline = if taker_bond > 0 {
+        Some(acceptance_deadline.unwrap_or(expires_at))
+    } else {
+        acceptance_deadline
+    };
+
     // Reject Token-2022 mints to prevent transfer-fee accounting issues.
     // Classic SPL Token mints are owned by TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA.
     require!(
-        *ctx.accounts.mint.to_account_info().owner == anchor_spl::token::ID,
+        *mint.to_account_info().owner == anchor_spl::token::ID,
         EscrowError::ExtendedMintNotSupported
     );
 
     // Reject mints with a freeze authority to prevent vault freeze griefing.
     require!(
-        ctx.accounts.mint.freeze_authority.is_none(),
+        mint.freeze_authority.is_none(),
         EscrowError::MintHasFreezeAuthority
     );
 
-    // Build milestone structs
-    let milestone_structs: Vec<Milestone> = milestones
-        .iter()
-        .map(|m| Milestone {
-            amount: m.amount,
+    // Build milestone structs, grossed up by their fee when it is paid on top
+    let (flat_fee, fee_free_below) = if mint.key() == config.flat_fee_mint {
+        (config.flat_fee, config.fee_free_below)
+    } else {
+        (0, 0)
+    };
+    let mut milestone_structs: Vec<Milestone> = Vec::with_capacity(milestone_count);
+    let mut funded_amount: u64 = 0;
+    for m in &milestones {
+        let fee_reserved = if fee_on_top {
+            crate::helpers::fee_on_top(m.amount, m.fee_exempt, config.fee_bps, flat_fee, fee_free_below)?
+        } else {
+            0
+        };
+        let gross = m.amount.checked_add(fee_reserved).ok_or(EscrowError::Overflow)?;
+        funded_amount = funded_amount.checked_add(gross).ok_or(EscrowError::Overflow)?;
+        milestone_structs.push(Milestone {
+            amount: gross,
             description_hash: m.description_hash,
             status: MilestoneStatus::Pending,
-        })
-        .collect();
-
-    // Transfer tokens from maker to vault
-    let transfer_accounts = TransferChecked {
-        from: ctx.accounts.maker_token_account.to_account_info(),
-        mint: ctx.accounts.mint.to_account_info(),
-        to: ctx.accounts.vault.to_account_info(),
-        authority: ctx.accounts.maker.to_account_info(),
-    };
-    let cpi_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_accounts,
-    );
-    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+            approved_at: 0,
+            sub_escrow: None,
+            held_until: 0,
+            fee_exempt: m.fee_exempt,
+            self_certify: m.self_certify,
+            fee_reserved,
+            beneficiary: m.beneficiary,
+        });
+    }
+
+    // Transfer tokens from maker to vault (sub-escrows are funded later by their parent)
+    let funded = maker_token_account.is_some();
+    if let Some(maker_token_account) = maker_token_account {
+        require!(
+            maker_token_account.amount >= funded_amount,
+            EscrowError::InsufficientBalance
+        );
+        let transfer_accounts = TransferChecked {
+            from: maker_token_account.to_account_info(),
+            mint: mint.to_account_info(),
+            to: vault.to_account_info(),
+            authority: maker.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            token_program.to_account_info(),
+            transfer_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, funded_amount, mint.decimals)?;
+    }
 
     // Initialize escrow state
-    let escrow = &mut ctx.accounts.escrow_state;
-    escrow.maker = ctx.accounts.maker.key();
-    escrow.taker = ctx.accounts.taker.key();
-    escrow.beneficiary = ctx.accounts.taker.key();
-    escrow.mint = ctx.accounts.mint.key();
-    escrow.amount = amount;
+    escrow.maker = maker.key();
+    escrow.creator = maker.key();
+    escrow.taker = taker;
+    escrow.beneficiary = beneficiary;
+    escrow.mint = mint.key();
+    escrow.vault = vault.key();
+    escrow.token_program = token_program.key();
+    escrow.amount = funded_amount;
     escrow.released_amount = 0;
     escrow.refunded_amount = 0;
     escrow.seed = seed;
-    escrow.status = EscrowStatus::Active;
+    escrow.status = if !funded {
+        EscrowStatus::AwaitingFunding
+    } else if acceptance_deadline.is_some() {
+        EscrowStatus::AwaitingAcceptance
+    } else {
+        EscrowStatus::Active
+    };
+    escrow.category = category;
     escrow.milestones = milestone_structs;
     escrow.created_at = clock.unix_timestamp;
     escrow.expires_at = expires_at;
     escrow.dispute = None;
-    escrow.bump = ctx.bumps.escrow_state;
-    escrow.fee_bps_at_creation = ctx.accounts.escrow_config.fee_bps;
+    escrow.bump = escrow_bump;
+    escrow.fee_bps_at_creation = config.fee_bps;
+    escrow.flat_fee_at_creation = flat_fee;
+    escrow.fee_free_below_at_creation = fee_free_below;
+    escrow.taker_bond = taker_bond;
+    escrow.bond_balance = 0;
+    escrow.fee_on_top = fee_on_top;
+    escrow.cancel_fee_bps_at_creation = config.cancel_fee_bps;
+    escrow.cancel_fee_recipient = config.cancel_fee_recipient;
+    escrow.cancel_routes_at_creation = config.cancel_routes;
+    escrow.agreement_hash = agreement_hash;
+    escrow.agreement_acknowledged = false;
+    escrow.agreement_acknowledged_at = 0;
     escrow.receipt_mint = None;
+    escrow.voided_receipt_mint = None;
+    escrow.receipt_metadata_mutable = false;
+    escrow.release_delay = config.release_delay;
+    escrow.on_hold = false;
+    escrow.renewal_count = 0;
+    escrow.approval_nonce = 0;
+    escrow.dispute_count = 0;
+    escrow.hash_algo = hash_algo;
+    escrow.acceptance_deadline = acceptance_deadline.unwrap_or(0);
+    escrow.nonce = 0;
+    escrow.share_mint = None;
+    escrow.claim_pool = 0;
+    escrow.metadata = None;
+    escrow.fees_paid = 0;
+    escrow.fees_refunded = 0;
+    escrow.parent = None;
+    escrow.auto_release = auto_release;
+    escrow.crank_tip = 0;
+    escrow.tip_jar = 0;
+    // The maker may opt into a longer window than the config minimum
+    require!(
+        (0..=MAX_CLAW_BACK_SECONDS).contains(&claw_back_seconds),
+        EscrowError::InvalidClawBackWindow
+    );
+    escrow.claw_back_seconds = config.claw_back_seconds.max(claw_back_seconds);
+    escrow.approval_extension = config.approval_extension;
+    escrow.expiry_extension_per_approval = config.expiry_extension_per_approval;
+    escrow.expiry_extension_budget = config.max_expiry_extension;
+    escrow.funds_distributed = false;
+    escrow.remainder_policy = config.remainder_policy;
+    escrow.disputes_enabled = disputes_enabled;
+    require!(
+        dispute_initiators != 0 && dispute_initiators & !DISPUTE_INITIATORS_ALL == 0,
+        EscrowError::InvalidDisputeInitiators
+    );
+    escrow.dispute_initiators = dispute_initiators;
+    escrow.counted_open = match maker_profile {
+        Some(profile) => profile.open_escrow(config.max_open_escrows_per_maker)?,
+        None => false,
+    };
+    escrow.beneficiary_ata = Some(get_associated_token_address_with_program_id(
+        &escrow.beneficiary,
+        &escrow.mint,
+        &escrow.token_program,
+    ));
 
     emit!(EscrowCreated {
         maker: escrow.maker,
         taker: escrow.taker,
+        beneficiary: escrow.beneficiary,
         mint: escrow.mint,
-        amount,
+        vault: escrow.vault,
+        amount: funded_amount,
         seed,
         milestones_count: milestone_count as u8,
         expires_at,
+        category,
+        hash_algo,
+        acceptance_deadline: escrow.acceptance_deadline,
+        auto_release,
+        claw_back_seconds: escrow.claw_back_seconds,
+        disputes_enabled,
+        dispute_initiators,
+        self_certify_indices: escrow
+            .milestones
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.self_certify)
+            .map(|(i, _)| i as u8)
+            .collect(),
+        fee_on_top,
+        agreement_hash,
+        taker_bond,
+        nonce: escrow.nonce,
     });
 
+    verify_milestone_sum(escrow)?;
+
     Ok(())
 }
+
+/// Fill in a `MakerProfile` that `init_if_needed` just created.
+pub(crate) fn init_maker_profile(profile: &mut MakerProfile, maker: Pubkey, bump: u8) {
+    if profile.maker == Pubkey::default() {
+        profile.maker = maker;
+        profile.open_escrows = 0;
+        profile.bump = bump;
+    }
+}
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::Metadata,
+    token::{Mint as ReceiptMint, TokenAccount as ReceiptTokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::error::EscrowError;
+use crate::instructions::create_escrow::{init_maker_profile, open_escrow};
+use crate::instructions::mint_receipt::mint_receipt_nft;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64, amount: u64)]
+pub struct CreateEscrowWithReceipt<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: The taker is just stored as a pubkey reference; no signing required at creation.
+    pub taker: UncheckedAccount<'info>,
+
+    /// Must match the escrow's beneficiary (taker or `initial_beneficiary`); pays for the receipt.
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+        constraint = escrow_config.receipts_enabled @ EscrowError::ReceiptsDisabled,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + EscrowState::INIT_SPACE,
+        seeds = [ESCROW_SEED, maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        init_if_needed,
+        payer = maker,
+        space = 8 + MakerProfile::INIT_SPACE,
+        seeds = [MAKER_PROFILE_SEED, maker.key().as_ref()],
+        bump,
+    )]
+    pub maker_profile: Account<'info, MakerProfile>,
+
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint,
+        associated_token::authority = escrow_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = maker_token_account.amount >= amount @ EscrowError::InsufficientBalance,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = beneficiary,
+        mint::decimals = 0,
+        mint::authority = escrow_state,
+        mint::freeze_authority = escrow_state,
+        seeds = [RECEIPT_SEED, escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub receipt_mint: Account<'info, ReceiptMint>,
+
+    #[account(
+        init,
+        payer = beneficiary,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = beneficiary,
+    )]
+    pub beneficiary_receipt_ata: Account<'info, ReceiptTokenAccount>,
+
+    /// CHECK: Created by Metaplex via CPI; validated by the token metadata program.
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Created by Metaplex via CPI; validated by the token metadata program.
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Create and fund an escrow, then mint its receipt NFT to the co-signing
+/// beneficiary. Acceptance is implied by the beneficiary's signature, so the
+/// escrow opens `Active` and the receipt status gate is re-checked as usual.
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<CreateEscrowWithReceipt>,
+    seed: u64,
+    amount: u64,
+    milestones: Vec<MilestoneInput>,
+    expires_at: i64,
+    category: u8,
+    initial_beneficiary: Option<Pubkey>,
+    hash_algo: u8,
+) -> Result<()> {
+    let terms = EscrowTerms {
+        amount,
+        milestones,
+        expires_at,
+        category,
+        initial_beneficiary,
+        hash_algo,
+    };
+    let accounts = ctx.accounts;
+    init_maker_profile(&mut accounts.maker_profile, accounts.maker.key(), ctx.bumps.maker_profile);
+    open_escrow(
+        &mut accounts.escrow_state,
+        ctx.bumps.escrow_state,
+        &accounts.maker,
+        accounts.taker.key(),
+        &accounts.escrow_config,
+        &accounts.mint,
+        &accounts.vault,
+        Some(&accounts.maker_token_account),
+        &accounts.token_program,
+        Some(&mut *accounts.maker_profile),
+        seed,
+        terms,
+        None,
+        false,
+        0,
+        true,
+        DISPUTE_INITIATORS_ALL,
+        false,
+        None,
+        0,
+    )?;
+
+    mint_receipt_nft(
+        &mut accounts.escrow_state,
+        &accounts.beneficiary,
+        &accounts.beneficiary,
+        &accounts.receipt_mint,
+        &accounts.beneficiary_receipt_ata,
+        &accounts.metadata,
+        &accounts.master_edition,
+        &accounts.token_metadata_program,
+        accounts.token_program.to_account_info(),
+        &accounts.system_program,
+        &accounts.rent,
+    )
+}
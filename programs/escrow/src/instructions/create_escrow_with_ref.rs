@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::helpers::derive_escrow_seed;
+use crate::instructions::create_escrow::{self, CreateEscrow};
+use crate::state::MilestoneInput;
+
+/// `create_escrow` with the seed bound to business data: it must equal
+/// `derive_escrow_seed(maker, taker, mint, external_ref)`, so the escrow
+/// address can be recomputed without persisting a random seed.
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<CreateEscrow>,
+    seed: u64,
+    amount: u64,
+    milestones: Vec<MilestoneInput>,
+    expires_at: i64,
+    category: u8,
+    external_ref: [u8; 32],
+    initial_beneficiary: Option<Pubkey>,
+    hash_algo: u8,
+    acceptance_deadline: Option<i64>,
+    auto_release: bool,
+    claw_back_seconds: i64,
+    disputes_enabled: bool,
+    dispute_initiators: u8,
+    fee_on_top: bool,
+    agreement_hash: Option<[u8; 32]>,
+    taker_bond: u64,
+) -> Result<()> {
+    let derived = derive_escrow_seed(
+        &ctx.accounts.maker.key(),
+        &ctx.accounts.taker.key(),
+        &ctx.accounts.mint.key(),
+        &external_ref,
+    );
+    require!(seed == derived, EscrowError::SeedDerivationMismatch);
+
+    create_escrow::handler(
+        ctx,
+        seed,
+        amount,
+        milestones,
+        expires_at,
+        category,
+        initial_beneficiary,
+        hash_algo,
+        acceptance_deadline,
+        auto_release,
+        claw_back_seconds,
+        disputes_enabled,
+        dispute_initiators,
+        fee_on_top,
+        agreement_hash,
+        taker_bond,
+    )
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::error::EscrowError;
+use crate::events::SubEscrowCreated;
+use crate::helpers::milestone_fee;
+use crate::instructions::create_escrow::open_escrow;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateSubEscrow<'info> {
+    /// Beneficiary of the parent escrow, acting as maker of the child.
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: Subcontractor; stored as a pubkey reference.
+    pub taker: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, parent_escrow.creator.as_ref(), parent_escrow.seed.to_le_bytes().as_ref()],
+        bump = parent_escrow.bump,
+        constraint = parent_escrow.beneficiary == maker.key() @ EscrowError::NotBeneficiary,
+    )]
+    pub parent_escrow: Account<'info, EscrowState>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(address = parent_escrow.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + EscrowState::INIT_SPACE,
+        seeds = [ESCROW_SEED, maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint,
+        associated_token::authority = escrow_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = parent_escrow.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Open an unfunded child escrow linked to `parent_milestone_index` of the
+/// parent. Releasing that milestone moves `terms.amount` of its net payout
+/// into the child's vault and activates it.
+pub fn handler(
+    ctx: Context<CreateSubEscrow>,
+    seed: u64,
+    parent_milestone_index: u8,
+    terms: EscrowTerms,
+) -> Result<()> {
+    let parent = &ctx.accounts.parent_escrow;
+
+    require!(!parent.on_hold, EscrowError::EscrowOnHold);
+    require!(parent.status == EscrowStatus::Active, EscrowError::EscrowNotActive);
+    require!(parent.share_mint.is_none(), EscrowError::ClaimIsFractionalized);
+
+    let idx = parent_milestone_index as usize;
+    require!(idx < parent.milestones.len(), EscrowError::MilestoneIndexOutOfBounds);
+    let milestone = &parent.milestones[idx];
+    require!(
+        milestone.status == MilestoneStatus::Pending || milestone.status == MilestoneStatus::Approved,
+        EscrowError::MilestoneNotPending
+    );
+    require!(milestone.sub_escrow.is_none(), EscrowError::MilestoneAlreadyLinked);
+
+    let (_, net_payout) = milestone_fee(
+        milestone,
+        parent.fee_bps_at_creation,
+        parent.flat_fee_at_creation,
+        parent.fee_free_below_at_creation,
+    )?;
+    require!(terms.amount <= net_payout, EscrowError::SubEscrowExceedsPayout);
+
+    let amount = terms.amount;
+    let accounts = ctx.accounts;
+    open_escrow(
+        &mut accounts.escrow_state,
+        ctx.bumps.escrow_state,
+        &accounts.maker,
+        accounts.taker.key(),
+        &accounts.escrow_config,
+        &accounts.mint,
+        &accounts.vault,
+        None,
+        &accounts.token_program,
+        None,
+        seed,
+        terms,
+        None,
+        false,
+        0,
+        true,
+        DISPUTE_INITIATORS_ALL,
+        false,
+        None,
+        0,
+    )?;
+
+    let parent_key = accounts.parent_escrow.key();
+    let child = &mut accounts.escrow_state;
+    child.parent = Some(SubEscrowLink {
+        escrow: parent_key,
+        milestone_index: parent_milestone_index,
+    });
+
+    let parent = &mut accounts.parent_escrow;
+    parent.milestones[idx].sub_escrow = Some(child.key());
+    parent.bump_nonce()?;
+
+    emit!(SubEscrowCreated {
+        parent: parent_key,
+        parent_milestone_index,
+        child: child.key(),
+        maker: child.maker,
+        taker: child.taker,
+        amount,
+        nonce: parent.nonce,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::EscrowDelisted;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct DelistEscrow<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LISTING_SEED, listing.escrow.as_ref()],
+        bump = listing.bump,
+        constraint = listing.maker == maker.key() @ EscrowError::NotMaker,
+        close = maker,
+    )]
+    pub listing: Account<'info, Listing>,
+}
+
+/// Withdraw a listing and reclaim its rent. The escrow itself is untouched.
+pub fn handler(ctx: Context<DelistEscrow>) -> Result<()> {
+    emit!(EscrowDelisted {
+        escrow: ctx.accounts.listing.escrow,
+        closed_by: ctx.accounts.maker.key(),
+    });
+
+    Ok(())
+}
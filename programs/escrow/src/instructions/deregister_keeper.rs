@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::events::KeeperDeregistered;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct DeregisterKeeper<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [KEEPER_SEED, keeper.key().as_ref()],
+        bump = keeper_account.bump,
+        close = keeper,
+    )]
+    pub keeper_account: Account<'info, Keeper>,
+}
+
+/// Close the keeper entry, returning its rent, bond and any unclaimed tips.
+pub fn handler(ctx: Context<DeregisterKeeper>) -> Result<()> {
+    let keeper_account = &ctx.accounts.keeper_account;
+
+    emit!(KeeperDeregistered {
+        keeper: keeper_account.keeper,
+        bond: keeper_account.bond,
+        tips_earned: keeper_account.tips_earned,
+    });
+
+    Ok(())
+}
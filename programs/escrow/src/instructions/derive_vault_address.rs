@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+#[derive(Accounts)]
+pub struct DeriveVaultAddress<'info> {
+    /// Must be owned by `token_program`, so a classic mint paired with
+    /// Token-2022 (or the reverse) fails here rather than in `create_escrow`.
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Read-only: the vault ATA `create_escrow` will initialize for `escrow`
+/// (the escrow PDA, which need not exist yet), so clients can check their
+/// own derivation before building the transaction.
+pub fn handler(ctx: Context<DeriveVaultAddress>, escrow: Pubkey) -> Result<Pubkey> {
+    Ok(get_associated_token_address_with_program_id(
+        &escrow,
+        &ctx.accounts.mint.key(),
+        &ctx.accounts.token_program.key(),
+    ))
+}
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::EscrowError;
+use crate::events::EmergencyWithdrawExecuted;
+use crate::helpers::{escrow_seeds, release_open_slot, transfer_from_vault, verify_milestone_sum};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ExecuteEmergencyWithdraw<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+        constraint = escrow_config.authority == authority.key() @ EscrowError::NotAuthority,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.disputes_enabled @ EscrowError::DisputesDisabled,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        mut,
+        seeds = [EMERGENCY_WITHDRAW_SEED, escrow_state.key().as_ref()],
+        bump = emergency_withdrawal.bump,
+        constraint = emergency_withdrawal.escrow_created_at == escrow_state.created_at @ EscrowError::EmergencyWithdrawStale,
+        close = authority,
+    )]
+    pub emergency_withdrawal: Account<'info, EmergencyWithdrawal>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = recovery_token_account.owner == emergency_withdrawal.recovery @ EscrowError::OwnerMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub recovery_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Gets the open slot back when the drain cancels the escrow.
+    #[account(
+        mut,
+        seeds = [MAKER_PROFILE_SEED, escrow_state.creator.as_ref()],
+        bump = maker_profile.bump,
+    )]
+    pub maker_profile: Option<Account<'info, MakerProfile>>,
+}
+
+/// Once the timelock has run out, drain the whole vault (escrow funds, any
+/// taker bond, claim pool and dust) to the proposal's recovery owner. Unsettled
+/// milestones are cancelled into `emergency_withdrawn` and an open escrow
+/// becomes Cancelled, so it can afterwards be closed as usual. Refused while a
+/// claw-back holding still has funds. The vault-state checks of the settling
+/// instructions are skipped on purpose: a stuck vault is what this is for.
+pub fn handler(ctx: Context<ExecuteEmergencyWithdraw>) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp >= ctx.accounts.emergency_withdrawal.executable_at,
+        EscrowError::EmergencyWithdrawTimelocked
+    );
+
+    let amount = ctx.accounts.vault.amount;
+    let escrow = &mut ctx.accounts.escrow_state;
+    // Claw-back holdings sit outside the vault; they must be released or
+    // reclaimed first so nothing is left behind
+    require!(!escrow.has_held_funds(), EscrowError::FundsStillHeld);
+
+    let mut cancelled_amount: u64 = 0;
+    for milestone in escrow.milestones.iter_mut() {
+        if matches!(milestone.status, MilestoneStatus::Pending | MilestoneStatus::Approved) {
+            milestone.status = MilestoneStatus::Cancelled;
+            cancelled_amount = cancelled_amount
+                .checked_add(milestone.amount)
+                .ok_or(EscrowError::Overflow)?;
+        }
+    }
+    escrow.emergency_withdrawn = escrow
+        .emergency_withdrawn
+        .checked_add(cancelled_amount)
+        .ok_or(EscrowError::Overflow)?;
+    if !matches!(
+        escrow.status,
+        EscrowStatus::Completed | EscrowStatus::Cancelled | EscrowStatus::Expired
+    ) {
+        escrow.status = EscrowStatus::Cancelled;
+    }
+    escrow.receipt_mint = None;
+    escrow.claim_pool = 0;
+    escrow.bond_balance = 0;
+
+    let creator_key = escrow.creator;
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    transfer_from_vault(
+        &ctx.accounts.vault, &ctx.accounts.mint,
+        &ctx.accounts.recovery_token_account,
+        escrow.to_account_info(), &ctx.accounts.token_program,
+        signer_seeds, amount, ctx.accounts.mint.decimals,
+    )?;
+
+    escrow.bump_nonce()?;
+
+    emit!(EmergencyWithdrawExecuted {
+        escrow: escrow.key(),
+        authority: ctx.accounts.authority.key(),
+        recovery: ctx.accounts.emergency_withdrawal.recovery,
+        amount,
+        cancelled_amount,
+        nonce: escrow.nonce,
+    });
+
+    verify_milestone_sum(escrow)?;
+    release_open_slot(escrow, ctx.accounts.maker_profile.as_deref_mut())?;
+
+    Ok(())
+}
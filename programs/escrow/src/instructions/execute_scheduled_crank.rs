@@ -0,0 +1,104 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+use anchor_lang::Bumps;
+
+use crate::error::EscrowError;
+use crate::events::ScheduledCrankExecuted;
+use crate::helpers::next_crank;
+use crate::instructions::{claim_active_expired, claim_dispute_timeout, release_milestone};
+use crate::instructions::{ClaimActiveExpired, ClaimDisputeTimeout, ReleaseMilestone};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ExecuteScheduledCrank<'info> {
+    /// Anyone can execute a due schedule. Must also be the target's `payer`,
+    /// so keeper tips and the closing schedule's rent go to the same account.
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            CRANK_SCHEDULE_SEED,
+            crank_schedule.escrow.as_ref(),
+            crank_schedule.creator.as_ref(),
+            &[crank_schedule.kind.tag()],
+        ],
+        bump = crank_schedule.bump,
+    )]
+    pub crank_schedule: Account<'info, CrankSchedule>,
+}
+
+/// Fires a due schedule. `remaining_accounts` are exactly the accounts the
+/// target instruction takes, in its order, followed by its own remaining
+/// accounts (e.g. the receipt token account). One-shot schedules, and
+/// re-arming ones that have run out of milestones, close to the executor.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ExecuteScheduledCrank<'info>>) -> Result<()> {
+    let kind = ctx.accounts.crank_schedule.kind;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= ctx.accounts.crank_schedule.eta, EscrowError::CrankNotDue);
+
+    // Every target context starts with `payer, escrow_state`
+    let targets = ctx.remaining_accounts;
+    require!(targets.len() >= 2, EscrowError::CrankTargetMismatch);
+    require_keys_eq!(targets[0].key(), ctx.accounts.executor.key(), EscrowError::CrankTargetMismatch);
+    require_keys_eq!(targets[1].key(), ctx.accounts.crank_schedule.escrow, EscrowError::CrankTargetMismatch);
+
+    match kind {
+        CrankKind::ReleaseMilestone { index, .. } => {
+            dispatch::<ReleaseMilestone>(ctx.program_id, targets, &[index], |target| {
+                release_milestone::handler(target, index, None, false)
+            })?
+        }
+        CrankKind::ClaimActiveExpired => {
+            dispatch::<ClaimActiveExpired>(ctx.program_id, targets, &[], claim_active_expired::handler)?
+        }
+        CrankKind::ClaimDisputeTimeout => {
+            dispatch::<ClaimDisputeTimeout>(ctx.program_id, targets, &[], claim_dispute_timeout::handler)?
+        }
+    }
+
+    let escrow = Account::<EscrowState>::try_from(&targets[1])?;
+    let next = next_crank(kind, escrow.status.clone(), escrow.milestones.len(), now)?;
+
+    let schedule = &mut ctx.accounts.crank_schedule;
+    schedule.executions = schedule.executions.checked_add(1).ok_or(EscrowError::Overflow)?;
+    if let Some((next_kind, next_eta)) = next {
+        schedule.kind = next_kind;
+        schedule.eta = next_eta;
+    }
+
+    emit!(ScheduledCrankExecuted {
+        schedule: schedule.key(),
+        escrow: schedule.escrow,
+        executor: ctx.accounts.executor.key(),
+        kind,
+        next_eta: next.map(|(_, eta)| eta),
+    });
+
+    if next.is_none() {
+        schedule.close(ctx.accounts.executor.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+/// Runs a target instruction in-process: validates its accounts exactly as the
+/// program entrypoint would (`ix_data` carries its `#[instruction]` args), calls
+/// the handler, then persists the accounts.
+fn dispatch<'info, T>(
+    program_id: &Pubkey,
+    mut accounts: &'info [AccountInfo<'info>],
+    ix_data: &[u8],
+    run: impl FnOnce(Context<'_, '_, 'info, 'info, T>) -> Result<()>,
+) -> Result<()>
+where
+    T: Accounts<'info, T::Bumps> + AccountsExit<'info> + Bumps,
+    T::Bumps: Default,
+{
+    let mut bumps = T::Bumps::default();
+    let mut target = T::try_accounts(program_id, &mut accounts, ix_data, &mut bumps, &mut BTreeSet::new())?;
+    run(Context::new(program_id, &mut target, accounts, bumps))?;
+    target.exit(program_id)
+}
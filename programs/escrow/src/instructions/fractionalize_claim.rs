@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, MintTo, Token, TokenAccount},
+};
+
+use crate::error::EscrowError;
+use crate::events::ClaimFractionalized;
+use crate::helpers::escrow_seeds;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct FractionalizeClaim<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.beneficiary == beneficiary.key() @ EscrowError::NotBeneficiary,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        init,
+        payer = beneficiary,
+        mint::decimals = 0,
+        mint::authority = escrow_state,
+        seeds = [SHARE_SEED, escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = beneficiary,
+        associated_token::mint = share_mint,
+        associated_token::authority = beneficiary,
+    )]
+    pub beneficiary_share_ata: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Split the beneficiary's claim into `total_shares` fungible shares and switch
+/// the escrow to pull-payment mode. Dispute standing stays with the taker.
+pub fn handler(ctx: Context<FractionalizeClaim>, total_shares: u64) -> Result<()> {
+    let escrow = &ctx.accounts.escrow_state;
+
+    // A receipt NFT already represents the claim; the two are mutually exclusive
+    require!(escrow.receipt_mint.is_none(), EscrowError::ReceiptExists);
+    require!(escrow.share_mint.is_none(), EscrowError::ClaimIsFractionalized);
+
+    require!(escrow.status == EscrowStatus::Active, EscrowError::EscrowNotActive);
+
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp <= escrow.expires_at, EscrowError::EscrowExpired);
+
+    require!(total_shares > 0, EscrowError::InvalidAmount);
+
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let creator_key = escrow.creator;
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                to: ctx.accounts.beneficiary_share_ata.to_account_info(),
+                authority: ctx.accounts.escrow_state.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        total_shares,
+    )?;
+
+    let escrow = &mut ctx.accounts.escrow_state;
+    escrow.share_mint = Some(ctx.accounts.share_mint.key());
+
+    escrow.bump_nonce()?;
+
+    emit!(ClaimFractionalized {
+        escrow: escrow.key(),
+        share_mint: ctx.accounts.share_mint.key(),
+        beneficiary: ctx.accounts.beneficiary.key(),
+        total_shares,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::error::EscrowError;
+use crate::events::RequestFunded;
+use crate::instructions::create_escrow::open_escrow;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct FundRequest<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: Requester, refunded the request rent.
+    #[account(mut, address = escrow_request.taker @ EscrowError::NotRequester)]
+    pub taker: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [REQUEST_SEED, maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump = escrow_request.bump,
+        close = taker,
+    )]
+    pub escrow_request: Account<'info, EscrowRequest>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(address = escrow_request.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + EscrowState::INIT_SPACE,
+        seeds = [ESCROW_SEED, maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint,
+        associated_token::authority = escrow_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = maker_token_account.amount >= escrow_request.terms.amount @ EscrowError::InsufficientBalance,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Fund a taker-issued request as-is, with maker = funder and taker = requester.
+pub fn handler(ctx: Context<FundRequest>, seed: u64) -> Result<()> {
+    let request = &ctx.accounts.escrow_request;
+
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp <= request.expires_at, EscrowError::RequestExpired);
+
+    let terms = request.terms.clone();
+    let accounts = ctx.accounts;
+    open_escrow(
+        &mut accounts.escrow_state,
+        ctx.bumps.escrow_state,
+        &accounts.maker,
+        accounts.taker.key(),
+        &accounts.escrow_config,
+        &accounts.mint,
+        &accounts.vault,
+        Some(&accounts.maker_token_account),
+        &accounts.token_program,
+        None,
+        seed,
+        terms,
+        None,
+        false,
+        0,
+        true,
+        DISPUTE_INITIATORS_ALL,
+        false,
+        None,
+        0,
+    )?;
+
+    emit!(RequestFunded {
+        request: accounts.escrow_request.key(),
+        escrow: accounts.escrow_state.key(),
+        maker: accounts.maker.key(),
+        taker: accounts.taker.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::error::EscrowError;
+use crate::events::TipJarFunded;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct FundTipJar<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Set the per-crank keeper tip and deposit `amount` lamports into the jar.
+/// Usually bundled with escrow creation; `amount` may be 0 to only change the tip.
+pub fn handler(ctx: Context<FundTipJar>, crank_tip: u64, amount: u64) -> Result<()> {
+    let escrow = &ctx.accounts.escrow_state;
+
+    require!(
+        !matches!(
+            escrow.status,
+            EscrowStatus::Completed | EscrowStatus::Cancelled | EscrowStatus::Expired
+        ),
+        EscrowError::EscrowNotActive
+    );
+    require!(crank_tip > 0, EscrowError::InvalidCrankTip);
+
+    if amount > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.maker.to_account_info(),
+                    to: ctx.accounts.escrow_state.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+    }
+
+    let escrow = &mut ctx.accounts.escrow_state;
+    escrow.crank_tip = crank_tip;
+    escrow.tip_jar = escrow.tip_jar.checked_add(amount).ok_or(EscrowError::Overflow)?;
+
+    escrow.bump_nonce()?;
+
+    emit!(TipJarFunded {
+        escrow: escrow.key(),
+        crank_tip,
+        amount,
+        tip_jar: escrow.tip_jar,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
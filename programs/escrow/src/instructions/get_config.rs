@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::helpers::effective_config;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct GetConfig<'info> {
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+}
+
+/// Read-only: the whole effective config in one call, decoupled from the
+/// account layout. See `EffectiveConfig` for the versioning rules.
+pub fn handler(ctx: Context<GetConfig>) -> Result<EffectiveConfig> {
+    Ok(effective_config(&ctx.accounts.escrow_config))
+}
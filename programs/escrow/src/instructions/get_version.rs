@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+use crate::version::ProgramVersion;
+
+#[derive(Accounts)]
+pub struct GetVersion<'info> {
+    /// Unused by the handler; gives the context a lifetime so the generated
+    /// CPI client compiles. Unlike `escrow_config`, it exists on every deployment.
+    pub system_program: Program<'info, System>,
+}
+
+/// Read-only: the program version and its compiled-in subsystems, so clients
+/// can tell deployments apart before sending instructions they may not support.
+pub fn handler(_ctx: Context<GetVersion>) -> Result<ProgramVersion> {
+    Ok(ProgramVersion::CURRENT)
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::EscrowHeld;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct HoldEscrow<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+        constraint = escrow_config.authority == authority.key() @ EscrowError::NotAuthority,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.disputes_enabled @ EscrowError::DisputesDisabled,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+}
+
+pub fn handler(ctx: Context<HoldEscrow>) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+
+    escrow.on_hold = true;
+
+    escrow.bump_nonce()?;
+
+    emit!(EscrowHeld {
+        escrow: escrow.key(),
+        authority: ctx.accounts.authority.key(),
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
@@ -37,12 +37,35 @@ pub fn handler(ctx: Context<InitializeConfig>, fee_bps: u16, dispute_timeout: i6
     config.fee_collector = ctx.accounts.fee_collector.key();
     config.dispute_timeout = dispute_timeout;
     config.bump = ctx.bumps.escrow_config;
+    config.set_post_baseline_defaults();
 
     emit!(ConfigInitialized {
         authority: config.authority,
         fee_bps: config.fee_bps,
         fee_collector: config.fee_collector,
         dispute_timeout: config.dispute_timeout,
+        release_delay: config.release_delay,
+        max_disputes_per_escrow: config.max_disputes_per_escrow,
+        accepted_hash_algos: config.accepted_hash_algos,
+        close_dust_threshold: config.close_dust_threshold,
+        dispute_timeout_policy: config.dispute_timeout_policy,
+        request_ttl: config.request_ttl,
+        claw_back_seconds: config.claw_back_seconds,
+        approval_extension: config.approval_extension,
+        max_expiry_extension: config.max_expiry_extension,
+        remainder_policy: config.remainder_policy,
+        allow_fee_exempt: config.allow_fee_exempt,
+        max_open_escrows_per_maker: config.max_open_escrows_per_maker,
+        expiry_extension_per_approval: config.expiry_extension_per_approval,
+        flat_fee: config.flat_fee,
+        flat_fee_mint: config.flat_fee_mint,
+        cancel_fee_bps: config.cancel_fee_bps,
+        cancel_fee_recipient: config.cancel_fee_recipient,
+        receipts_enabled: config.receipts_enabled,
+        fee_free_below: config.fee_free_below,
+        max_milestone_skew_bps: config.max_milestone_skew_bps,
+        creation_paused: config.creation_paused,
+        cancel_routes: config.cancel_routes,
     });
 
     Ok(())
@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::error::EscrowError;
 use crate::events::DisputeInitiated;
+use crate::helpers::{verify_disputable_balance, verify_dispute_cap, verify_remaining_accounts};
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -10,40 +11,82 @@ pub struct InitiateDispute<'info> {
 
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_state.maker.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
         bump = escrow_state.bump,
-        constraint = (escrow_state.maker == initiator.key() || escrow_state.taker == initiator.key() || escrow_state.beneficiary == initiator.key()) @ EscrowError::NotEscrowParty,
+        constraint = (escrow_state.maker == initiator.key() || escrow_state.taker == initiator.key() || (escrow_state.share_mint.is_none() && escrow_state.beneficiary == initiator.key())) @ EscrowError::NotEscrowParty,
     )]
     pub escrow_state: Account<'info, EscrowState>,
 
     #[account(
         seeds = [ESCROW_CONFIG_SEED],
         bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
     )]
     pub escrow_config: Account<'info, EscrowConfig>,
 }
 
+/// When a receipt NFT exists, `remaining_accounts[0]` must be the receipt
+/// token account, so a beneficiary who sold the receipt without a
+/// `sync_beneficiary` cannot open a dispute on the new holder's claim.
 pub fn handler(ctx: Context<InitiateDispute>, reason_hash: [u8; 32]) -> Result<()> {
+    verify_remaining_accounts(ctx.remaining_accounts, MAX_RECEIPT_SYNC_ACCOUNTS)?;
+
     let escrow = &mut ctx.accounts.escrow_state;
 
+    require!(escrow.disputes_enabled, EscrowError::DisputesDisabled);
+    require!(
+        escrow.may_initiate_dispute(&ctx.accounts.initiator.key()),
+        EscrowError::DisputeInitiatorNotAllowed
+    );
+    // Checked ahead of the status: a drained escrow has nothing to dispute,
+    // whether or not its status caught up
+    verify_disputable_balance(escrow)?;
     require!(escrow.status == EscrowStatus::Active, EscrowError::EscrowNotActive);
     require!(escrow.dispute.is_none(), EscrowError::DisputeAlreadyActive);
+    require!(
+        !escrow.has_open_milestone_beneficiary(),
+        EscrowError::DisputeWithMilestoneBeneficiary
+    );
+
+    // If a receipt NFT exists, verify beneficiary is synced with current NFT holder.
+    if escrow.receipt_mint.is_some() {
+        crate::helpers::verify_receipt_sync(escrow, ctx.remaining_accounts)?;
+    }
+
+    let max_disputes = ctx.accounts.escrow_config.max_disputes_per_escrow;
+    verify_dispute_cap(escrow, max_disputes)?;
 
     let clock = Clock::get()?;
     require!(clock.unix_timestamp <= escrow.expires_at, EscrowError::EscrowExpired);
 
+    let timeout = ctx.accounts.escrow_config.dispute_timeout;
+    let deadline = clock
+        .unix_timestamp
+        .checked_add(timeout)
+        .ok_or(EscrowError::Overflow)?;
+
+    escrow.dispute_count = escrow.dispute_count.checked_add(1).ok_or(EscrowError::Overflow)?;
     escrow.status = EscrowStatus::Disputed;
     escrow.dispute = Some(Dispute {
         initiator: ctx.accounts.initiator.key(),
         reason_hash,
         initiated_at: clock.unix_timestamp,
-        timeout: ctx.accounts.escrow_config.dispute_timeout,
+        timeout,
+        deadline,
         resolution: None,
+        resolved_by: Pubkey::default(),
+        resolved_at: 0,
     });
 
+    escrow.bump_nonce()?;
+
     emit!(DisputeInitiated {
         escrow: escrow.key(),
         initiator: ctx.accounts.initiator.key(),
+        dispute_count: escrow.dispute_count,
+        max_disputes,
+        hash_algo: escrow.hash_algo,
+        nonce: escrow.nonce,
     });
 
     Ok(())
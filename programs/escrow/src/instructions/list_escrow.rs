@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::{EscrowDelisted, EscrowListed};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ListEscrow<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Listing::INIT_SPACE,
+        seeds = [LISTING_SEED, escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub listing: Account<'info, Listing>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Publish an escrow that is still awaiting acceptance so takers can find it
+/// by enumerating `Listing` accounts instead of every `EscrowState`.
+pub fn handler(ctx: Context<ListEscrow>, category: u8, title_hash: [u8; 32]) -> Result<()> {
+    let escrow = &ctx.accounts.escrow_state;
+
+    require!(
+        escrow.status == EscrowStatus::AwaitingAcceptance,
+        EscrowError::NotAwaitingAcceptance
+    );
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp <= escrow.acceptance_deadline,
+        EscrowError::AcceptanceDeadlinePassed
+    );
+
+    let listing = &mut ctx.accounts.listing;
+    listing.escrow = escrow.key();
+    listing.maker = ctx.accounts.maker.key();
+    listing.mint = escrow.mint;
+    listing.category = category;
+    listing.amount = escrow.amount;
+    listing.title_hash = title_hash;
+    listing.listed_at = clock.unix_timestamp;
+    listing.bump = ctx.bumps.listing;
+
+    emit!(EscrowListed {
+        escrow: listing.escrow,
+        maker: listing.maker,
+        category,
+        amount: listing.amount,
+        title_hash,
+    });
+
+    Ok(())
+}
+
+/// Close the escrow's listing, if one was passed, refunding its rent to the
+/// maker that paid it. Shared by the acceptance paths.
+pub(crate) fn close_listing<'info>(
+    listing: Option<&Account<'info, Listing>>,
+    maker: Option<&UncheckedAccount<'info>>,
+    closed_by: Pubkey,
+) -> Result<()> {
+    let Some(listing) = listing else {
+        return Ok(());
+    };
+    let maker = maker
+        .filter(|maker| maker.key() == listing.maker)
+        .ok_or(EscrowError::ListingMakerMismatch)?;
+
+    emit!(EscrowDelisted {
+        escrow: listing.escrow,
+        closed_by,
+    });
+
+    listing.close(maker.to_account_info())
+}
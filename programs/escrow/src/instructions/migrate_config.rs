@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+
+use crate::error::EscrowError;
+use crate::events::ConfigMigrated;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// Pays the rent for the added bytes.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Loaded by hand: an older config is too short to deserialize as
+    /// `EscrowConfig`. Owner, discriminator and authority are checked in the handler.
+    #[account(
+        mut,
+        seeds = [ESCROW_CONFIG_SEED],
+        bump,
+        owner = crate::ID,
+    )]
+    pub escrow_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Bring a baseline config (`CONFIG_V1_SPACE`) up to `CONFIG_VERSION`: realloc
+/// to the current size (the authority pays the rent delta), write the
+/// `initialize_config` defaults into every appended field and stamp the version. A config that is already current is rejected, so running
+/// the migration twice cannot clobber settings.
+pub fn handler(ctx: Context<MigrateConfig>) -> Result<()> {
+    let info = ctx.accounts.escrow_config.to_account_info();
+    const V1_LEN: usize = 8 + CONFIG_V1_SPACE;
+    const CURRENT_LEN: usize = 8 + EscrowConfig::INIT_SPACE;
+
+    {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() >= V1_LEN && data[..8] == *EscrowConfig::DISCRIMINATOR,
+            EscrowError::ConfigVersionMismatch
+        );
+        // `authority` is the first field after the discriminator in every version
+        let authority = Pubkey::try_from(&data[8..40]).map_err(|_| EscrowError::ConfigVersionMismatch)?;
+        require!(authority == ctx.accounts.authority.key(), EscrowError::NotAuthority);
+        require!(data.len() == V1_LEN, EscrowError::ConfigAlreadyMigrated);
+    }
+
+    let rent_delta = Rent::get()?.minimum_balance(CURRENT_LEN).saturating_sub(info.lamports());
+    if rent_delta > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            rent_delta,
+        )?;
+    }
+    info.resize(CURRENT_LEN)?;
+
+    let mut data = info.try_borrow_mut_data()?;
+    // Zero the appended bytes so the current layout deserializes; every field
+    // they hold is set by `set_post_baseline_defaults`
+    data[V1_LEN..].fill(0);
+    let mut config = EscrowConfig::try_deserialize(&mut &data[..])?;
+    config.set_post_baseline_defaults();
+    config.try_serialize(&mut &mut data[..])?;
+
+    emit!(ConfigMigrated {
+        authority: ctx.accounts.authority.key(),
+        from_version: 1,
+        to_version: CONFIG_VERSION,
+        size: CURRENT_LEN as u64,
+    });
+
+    Ok(())
+}
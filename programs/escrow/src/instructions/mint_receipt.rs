@@ -16,20 +16,32 @@ use crate::state::*;
 
 #[derive(Accounts)]
 pub struct MintReceipt<'info> {
-    #[account(mut)]
+    /// May be a program PDA signing via CPI.
     pub beneficiary: Signer<'info>,
 
+    /// Pays rent for the receipt mint, token account, metadata and edition.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_state.maker.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
         bump = escrow_state.bump,
         constraint = escrow_state.beneficiary == beneficiary.key() @ EscrowError::NotBeneficiary,
     )]
     pub escrow_state: Account<'info, EscrowState>,
 
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+        constraint = escrow_config.receipts_enabled @ EscrowError::ReceiptsDisabled,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
     #[account(
         init,
-        payer = beneficiary,
+        payer = payer,
         mint::decimals = 0,
         mint::authority = escrow_state,
         mint::freeze_authority = escrow_state,
@@ -40,7 +52,7 @@ pub struct MintReceipt<'info> {
 
     #[account(
         init,
-        payer = beneficiary,
+        payer = payer,
         associated_token::mint = receipt_mint,
         associated_token::authority = beneficiary,
     )]
@@ -62,13 +74,51 @@ pub struct MintReceipt<'info> {
 }
 
 pub fn handler(ctx: Context<MintReceipt>) -> Result<()> {
-    let escrow = &ctx.accounts.escrow_state;
+    let accounts = ctx.accounts;
+    mint_receipt_nft(
+        &mut accounts.escrow_state,
+        &accounts.beneficiary,
+        &accounts.payer,
+        &accounts.receipt_mint,
+        &accounts.beneficiary_receipt_ata,
+        &accounts.metadata,
+        &accounts.master_edition,
+        &accounts.token_metadata_program,
+        accounts.token_program.to_account_info(),
+        &accounts.system_program,
+        &accounts.rent,
+    )
+}
+
+/// Receipt path shared by `mint_receipt` and `create_escrow_with_receipt`:
+/// enforces the minting gate, mints the single receipt token to the
+/// beneficiary and creates its Metaplex metadata and master edition. The
+/// metadata stays mutable only until close, which seals it (see
+/// `close_escrow::seal_receipt_metadata`); nothing else ever updates it.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn mint_receipt_nft<'info>(
+    escrow_state: &mut Account<'info, EscrowState>,
+    beneficiary: &Signer<'info>,
+    payer: &Signer<'info>,
+    receipt_mint: &Account<'info, Mint>,
+    beneficiary_receipt_ata: &Account<'info, TokenAccount>,
+    metadata: &UncheckedAccount<'info>,
+    master_edition: &UncheckedAccount<'info>,
+    token_metadata_program: &Program<'info, Metadata>,
+    token_program: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    rent: &Sysvar<'info, Rent>,
+) -> Result<()> {
+    let escrow = &*escrow_state;
+
+    require!(escrow.beneficiary == beneficiary.key(), EscrowError::NotBeneficiary);
 
     // Verify receipt hasn't been minted yet
     require!(
         escrow.receipt_mint.is_none(),
         EscrowError::ReceiptAlreadyMinted
     );
+    require!(escrow.share_mint.is_none(), EscrowError::ClaimIsFractionalized);
 
     // Status gate: only Active state allowed (receipt = right to receive funds)
     require!(
@@ -83,18 +133,26 @@ pub fn handler(ctx: Context<MintReceipt>) -> Result<()> {
     // Build escrow PDA signer seeds
     let seed_bytes = escrow.seed.to_le_bytes();
     let bump = [escrow.bump];
-    let maker_key = escrow.maker;
-    let inner = escrow_seeds(&maker_key, &seed_bytes, &bump);
+    let creator_key = escrow.creator;
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
     let signer_seeds: &[&[&[u8]]] = &[&inner];
 
+    // Checks-effects-interactions: record the receipt and persist it before any
+    // CPI, so a re-entrant mint_receipt would already fail the `receipt_mint`
+    // guard above. The CPIs only need the escrow PDA as mint/update authority.
+    escrow_state.receipt_mint = Some(receipt_mint.key());
+    escrow_state.receipt_metadata_mutable = true;
+    escrow_state.bump_nonce()?;
+    escrow_state.exit(&crate::ID)?;
+
     // Mint exactly 1 NFT token to beneficiary
     token::mint_to(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            token_program.clone(),
             MintTo {
-                mint: ctx.accounts.receipt_mint.to_account_info(),
-                to: ctx.accounts.beneficiary_receipt_ata.to_account_info(),
-                authority: ctx.accounts.escrow_state.to_account_info(),
+                mint: receipt_mint.to_account_info(),
+                to: beneficiary_receipt_ata.to_account_info(),
+                authority: escrow_state.to_account_info(),
             },
             signer_seeds,
         ),
@@ -102,7 +160,7 @@ pub fn handler(ctx: Context<MintReceipt>) -> Result<()> {
     )?;
 
     // Create metadata account
-    let escrow_key = ctx.accounts.escrow_state.key();
+    let escrow_key = escrow_state.key();
     let name = format!("Escrow Receipt #{}", &escrow_key.to_string()[..8]);
 
     let data = DataV2 {
@@ -117,20 +175,20 @@ pub fn handler(ctx: Context<MintReceipt>) -> Result<()> {
 
     create_metadata_accounts_v3(
         CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
+            token_metadata_program.to_account_info(),
             CreateMetadataAccountsV3 {
-                metadata: ctx.accounts.metadata.to_account_info(),
-                mint: ctx.accounts.receipt_mint.to_account_info(),
-                mint_authority: ctx.accounts.escrow_state.to_account_info(),
-                payer: ctx.accounts.beneficiary.to_account_info(),
-                update_authority: ctx.accounts.escrow_state.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                rent: ctx.accounts.rent.to_account_info(),
+                metadata: metadata.to_account_info(),
+                mint: receipt_mint.to_account_info(),
+                mint_authority: escrow_state.to_account_info(),
+                payer: payer.to_account_info(),
+                update_authority: escrow_state.to_account_info(),
+                system_program: system_program.to_account_info(),
+                rent: rent.to_account_info(),
             },
             signer_seeds,
         ),
         data,
-        false,  // is_mutable: false — metadata is immutable after creation
+        true,  // is_mutable: sealed by close_escrow, never updated before that
         true,
         None,
     )?;
@@ -138,31 +196,28 @@ pub fn handler(ctx: Context<MintReceipt>) -> Result<()> {
     // Create master edition (max_supply = 0 → true NFT)
     create_master_edition_v3(
         CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
+            token_metadata_program.to_account_info(),
             CreateMasterEditionV3 {
-                edition: ctx.accounts.master_edition.to_account_info(),
-                mint: ctx.accounts.receipt_mint.to_account_info(),
-                update_authority: ctx.accounts.escrow_state.to_account_info(),
-                mint_authority: ctx.accounts.escrow_state.to_account_info(),
-                payer: ctx.accounts.beneficiary.to_account_info(),
-                metadata: ctx.accounts.metadata.to_account_info(),
-                token_program: ctx.accounts.token_program.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                rent: ctx.accounts.rent.to_account_info(),
+                edition: master_edition.to_account_info(),
+                mint: receipt_mint.to_account_info(),
+                update_authority: escrow_state.to_account_info(),
+                mint_authority: escrow_state.to_account_info(),
+                payer: payer.to_account_info(),
+                metadata: metadata.to_account_info(),
+                token_program,
+                system_program: system_program.to_account_info(),
+                rent: rent.to_account_info(),
             },
             signer_seeds,
         ),
         Some(0),
     )?;
 
-    // Update escrow state with receipt mint
-    let escrow = &mut ctx.accounts.escrow_state;
-    escrow.receipt_mint = Some(ctx.accounts.receipt_mint.key());
-
     emit!(ReceiptMinted {
-        escrow: ctx.accounts.escrow_state.key(),
-        mint: ctx.accounts.receipt_mint.key(),
-        beneficiary: ctx.accounts.beneficiary.key(),
+        escrow: escrow_key,
+        mint: receipt_mint.key(),
+        beneficiary: beneficiary.key(),
+        nonce: escrow_state.nonce,
     });
 
     Ok(())
@@ -1,32 +1,152 @@
 pub mod initialize_config;
 pub mod create_escrow;
+pub mod create_escrow_with_ref;
+pub mod accept_escrow;
+pub mod renew_escrow;
 pub mod approve_milestone;
+pub mod approve_milestone_signed;
+pub mod unapprove_milestone;
 pub mod release_milestone;
+pub mod approve_and_release;
 pub mod initiate_dispute;
 pub mod resolve_dispute;
+pub mod simulate_resolution;
 pub mod cancel_escrow;
 pub mod claim_expired;
 pub mod update_config;
 pub mod close_escrow;
+pub mod hold_escrow;
+pub mod release_hold;
 pub mod transfer_claim;
+pub mod transfer_maker;
 pub mod mint_receipt;
 pub mod sync_beneficiary;
 pub mod revoke_receipt;
+pub mod fractionalize_claim;
+pub mod redeem_shares;
+pub mod propose_escrow;
+pub mod counter_proposal;
+pub mod accept_proposal;
+pub mod close_proposal;
+pub mod attach_metadata;
+pub mod request_escrow;
+pub mod fund_request;
+pub mod cancel_request;
+pub mod create_sub_escrow;
+pub mod cancel_sub_escrow;
+pub mod cancel_and_release;
+pub mod create_escrow_with_receipt;
+pub mod claim_active_expired;
+pub mod claim_dispute_timeout;
+pub mod approve_milestone_auto;
+pub mod register_keeper;
+pub mod deregister_keeper;
+pub mod claim_keeper_tips;
+pub mod fund_tip_jar;
+pub mod withdraw_held;
+pub mod reclaim_held;
+pub mod reset_beneficiary;
+pub mod refund_fee;
+pub mod clawback;
+pub mod certify_milestone;
+pub mod record_approval;
+pub mod close_approval;
+pub mod recover_receipt;
+pub mod batch_escrow_progress;
+pub mod archive_and_close;
+pub mod close_archive;
+pub mod get_config;
+pub mod acknowledge_agreement;
+pub mod schedule_crank;
+pub mod execute_scheduled_crank;
+pub mod cancel_crank_schedule;
+pub mod get_version;
+pub mod verify_receipt;
+pub mod post_bond;
+pub mod settle_bond;
+pub mod propose_emergency_withdraw;
+pub mod execute_emergency_withdraw;
+pub mod list_escrow;
+pub mod delist_escrow;
+pub mod close_stale_listing;
+pub mod migrate_config;
+pub mod derive_vault_address;
+pub mod cancel_emergency_withdraw;
+pub mod close_stale_emergency_withdraw;
 
 // Each module exports a `handler` fn — glob re-export causes name collision.
 // Anchor's #[program] macro requires glob re-exports for generated account types.
 #[allow(ambiguous_glob_reexports)]
 pub use initialize_config::*;
 pub use create_escrow::*;
+pub use accept_escrow::*;
+pub use renew_escrow::*;
 pub use approve_milestone::*;
+pub use approve_milestone_signed::*;
+pub use unapprove_milestone::*;
 pub use release_milestone::*;
+pub use approve_and_release::*;
 pub use initiate_dispute::*;
 pub use resolve_dispute::*;
+pub use simulate_resolution::*;
 pub use cancel_escrow::*;
 pub use claim_expired::*;
 pub use update_config::*;
 pub use close_escrow::*;
+pub use hold_escrow::*;
+pub use release_hold::*;
 pub use transfer_claim::*;
+pub use transfer_maker::*;
 pub use mint_receipt::*;
 pub use sync_beneficiary::*;
 pub use revoke_receipt::*;
+pub use fractionalize_claim::*;
+pub use redeem_shares::*;
+pub use propose_escrow::*;
+pub use counter_proposal::*;
+pub use accept_proposal::*;
+pub use close_proposal::*;
+pub use attach_metadata::*;
+pub use request_escrow::*;
+pub use fund_request::*;
+pub use cancel_request::*;
+pub use create_sub_escrow::*;
+pub use cancel_sub_escrow::*;
+pub use cancel_and_release::*;
+pub use create_escrow_with_receipt::*;
+pub use claim_active_expired::*;
+pub use claim_dispute_timeout::*;
+pub use register_keeper::*;
+pub use deregister_keeper::*;
+pub use claim_keeper_tips::*;
+pub use fund_tip_jar::*;
+pub use withdraw_held::*;
+pub use reclaim_held::*;
+pub use reset_beneficiary::*;
+pub use refund_fee::*;
+pub use clawback::*;
+pub use certify_milestone::*;
+pub use record_approval::*;
+pub use close_approval::*;
+pub use recover_receipt::*;
+pub use batch_escrow_progress::*;
+pub use archive_and_close::*;
+pub use close_archive::*;
+pub use get_config::*;
+pub use acknowledge_agreement::*;
+pub use schedule_crank::*;
+pub use execute_scheduled_crank::*;
+pub use cancel_crank_schedule::*;
+pub use get_version::*;
+pub use verify_receipt::*;
+pub use post_bond::*;
+pub use settle_bond::*;
+pub use propose_emergency_withdraw::*;
+pub use execute_emergency_withdraw::*;
+pub use list_escrow::*;
+pub use delist_escrow::*;
+pub use close_stale_listing::*;
+pub use migrate_config::*;
+pub use derive_vault_address::*;
+pub use cancel_emergency_withdraw::*;
+pub use close_stale_emergency_withdraw::*;
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::EscrowError;
+use crate::events::{BondPosted, EscrowAccepted};
+use crate::instructions::list_escrow::close_listing;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct PostBond<'info> {
+    pub taker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.taker == taker.key() @ EscrowError::NotTaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = taker_token_account.amount >= escrow_state.taker_bond @ EscrowError::InsufficientBalance,
+        token::mint = mint,
+        token::authority = taker,
+        token::token_program = token_program,
+    )]
+    pub taker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Closed on acceptance when the escrow was listed; rent goes to `listing_maker`.
+    #[account(
+        mut,
+        seeds = [LISTING_SEED, escrow_state.key().as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Option<Account<'info, Listing>>,
+
+    /// CHECK: Required with `listing`; must be the maker that paid for it.
+    #[account(mut)]
+    pub listing_maker: Option<UncheckedAccount<'info>>,
+}
+
+/// Deposit the taker bond into the vault, which also accepts the escrow.
+/// The bond is tracked in `bond_balance`, never in `amount`, so no milestone
+/// payout can draw on it.
+pub fn handler(ctx: Context<PostBond>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow_state;
+
+    require!(
+        escrow.status == EscrowStatus::AwaitingAcceptance,
+        EscrowError::NotAwaitingAcceptance
+    );
+    require!(escrow.taker_bond > 0, EscrowError::NoBondRequired);
+
+    // Past the deadline the maker reclaims via cancel_escrow instead
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp <= escrow.acceptance_deadline,
+        EscrowError::AcceptanceDeadlinePassed
+    );
+
+    let bond = escrow.taker_bond;
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.taker_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.taker.to_account_info(),
+            },
+        ),
+        bond,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let escrow = &mut ctx.accounts.escrow_state;
+    escrow.bond_balance = bond;
+    escrow.status = EscrowStatus::Active;
+
+    escrow.bump_nonce()?;
+
+    emit!(BondPosted {
+        escrow: escrow.key(),
+        taker: escrow.taker,
+        amount: bond,
+        nonce: escrow.nonce,
+    });
+    emit!(EscrowAccepted {
+        escrow: escrow.key(),
+        accepted_by: escrow.taker,
+        nonce: escrow.nonce,
+    });
+
+    close_listing(
+        ctx.accounts.listing.as_ref(),
+        ctx.accounts.listing_maker.as_ref(),
+        ctx.accounts.taker.key(),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::EmergencyWithdrawProposed;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ProposeEmergencyWithdraw<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+        constraint = escrow_config.authority == authority.key() @ EscrowError::NotAuthority,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.disputes_enabled @ EscrowError::DisputesDisabled,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + EmergencyWithdrawal::INIT_SPACE,
+        seeds = [EMERGENCY_WITHDRAW_SEED, escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub emergency_withdrawal: Account<'info, EmergencyWithdrawal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// First half of the authority's last-resort recovery: records where the
+/// vault will go and starts the `EMERGENCY_WITHDRAW_DELAY` timelock. The
+/// escrow itself is untouched, so the parties can still settle or cancel it
+/// in the meantime.
+pub fn handler(ctx: Context<ProposeEmergencyWithdraw>, recovery: Pubkey) -> Result<()> {
+    require!(recovery != Pubkey::default(), EscrowError::InvalidRecoveryAddress);
+
+    let clock = Clock::get()?;
+    let executable_at = clock
+        .unix_timestamp
+        .checked_add(EMERGENCY_WITHDRAW_DELAY)
+        .ok_or(EscrowError::Overflow)?;
+
+    let proposal = &mut ctx.accounts.emergency_withdrawal;
+    proposal.escrow = ctx.accounts.escrow_state.key();
+    proposal.proposed_by = ctx.accounts.authority.key();
+    proposal.recovery = recovery;
+    proposal.proposed_at = clock.unix_timestamp;
+    proposal.executable_at = executable_at;
+    proposal.bump = ctx.bumps.emergency_withdrawal;
+    proposal.escrow_created_at = ctx.accounts.escrow_state.created_at;
+
+    emit!(EmergencyWithdrawProposed {
+        escrow: proposal.escrow,
+        authority: proposal.proposed_by,
+        recovery,
+        executable_at,
+    });
+
+    Ok(())
+}
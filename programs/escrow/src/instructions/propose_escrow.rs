@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::error::EscrowError;
+use crate::events::ProposalCreated;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct ProposeEscrow<'info> {
+    /// Either party may open the negotiation.
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    /// CHECK: Funding party of the eventual escrow; stored as a pubkey reference.
+    pub maker: UncheckedAccount<'info>,
+
+    /// CHECK: Receiving party of the eventual escrow; stored as a pubkey reference.
+    pub taker: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [PROPOSAL_SEED, maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<ProposeEscrow>,
+    seed: u64,
+    terms: EscrowTerms,
+    expires_at: i64,
+) -> Result<()> {
+    let proposer = ctx.accounts.proposer.key();
+    let maker = ctx.accounts.maker.key();
+    let taker = ctx.accounts.taker.key();
+
+    require!(maker != taker, EscrowError::SelfEscrow);
+    require!(proposer == maker || proposer == taker, EscrowError::NotEscrowParty);
+
+    let clock = Clock::get()?;
+    require!(expires_at > clock.unix_timestamp, EscrowError::InvalidProposalExpiry);
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.maker = maker;
+    proposal.taker = taker;
+    proposal.mint = ctx.accounts.mint.key();
+    proposal.seed = seed;
+    proposal.creator = proposer;
+    proposal.terms = terms;
+    proposal.maker_turn = proposer == taker;
+    proposal.revision = 0;
+    proposal.expires_at = expires_at;
+    proposal.bump = ctx.bumps.proposal;
+
+    emit!(ProposalCreated {
+        proposal: proposal.key(),
+        maker,
+        taker,
+        proposer,
+        seed,
+        expires_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+use crate::error::EscrowError;
+use crate::events::HeldFundsReclaimed;
+use crate::helpers::{escrow_seeds, transfer_from_vault};
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct ReclaimHeld<'info> {
+    /// The beneficiary (consenting to the reversal) or the config authority (ruling
+    /// on it, unless the escrow opted out of arbitration).
+    #[account(
+        constraint = authority.key() == escrow_state.beneficiary
+            || authority.key() == escrow_config.authority @ EscrowError::NotReclaimAuthority,
+        constraint = authority.key() == escrow_state.beneficiary
+            || escrow_state.disputes_enabled @ EscrowError::DisputesDisabled,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [HOLDING_SEED, escrow_state.key().as_ref(), &[milestone_index]],
+        bump,
+    )]
+    pub holding_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+        constraint = maker_token_account.owner == escrow_state.maker @ EscrowError::OwnerMismatch,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Receives the holding account's rent; pinned to the beneficiary.
+    #[account(mut, address = escrow_state.beneficiary @ EscrowError::OwnerMismatch)]
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Reverse a release while its claw-back window is open: the held payout goes
+/// back to the maker. The milestone stays `Released`; the fee is not refunded.
+/// `SETTLEMENT_HOLDING_INDEX` reverses a held dispute payout the same way.
+pub fn handler(ctx: Context<ReclaimHeld>, milestone_index: u8) -> Result<()> {
+    require!(
+        milestone_index == SETTLEMENT_HOLDING_INDEX
+            || (milestone_index as usize) < ctx.accounts.escrow_state.milestones.len(),
+        EscrowError::MilestoneIndexOutOfBounds
+    );
+
+    let clock = Clock::get()?;
+    let amount = reclaim_holding(
+        &mut ctx.accounts.escrow_state,
+        milestone_index,
+        &ctx.accounts.holding_account,
+        &ctx.accounts.mint,
+        &ctx.accounts.maker_token_account,
+        ctx.accounts.beneficiary.to_account_info(),
+        &ctx.accounts.token_program,
+        clock.unix_timestamp,
+    )?;
+
+    let escrow = &mut ctx.accounts.escrow_state;
+    escrow.bump_nonce()?;
+
+    emit!(HeldFundsReclaimed {
+        escrow: escrow.key(),
+        milestone_index,
+        reclaimed_by: ctx.accounts.authority.key(),
+        amount,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
+
+/// Return holding slot `index`'s held payout to the maker and close its holding
+/// account to the beneficiary. Shared by `reclaim_held` and `clawback`; the
+/// caller bumps the nonce. Returns the amount reclaimed.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn reclaim_holding<'info>(
+    escrow: &mut Account<'info, EscrowState>,
+    index: u8,
+    holding_account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    maker_token_account: &InterfaceAccount<'info, TokenAccount>,
+    beneficiary: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    now: i64,
+) -> Result<u64> {
+    let held_until = escrow.held_until(index);
+    require!(held_until != 0, EscrowError::NothingHeld);
+    require!(now < held_until, EscrowError::ClawBackWindowClosed);
+
+    escrow.set_held_until(index, 0)?;
+
+    let creator_key = escrow.creator;
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    let amount = holding_account.amount;
+    transfer_from_vault(
+        holding_account, mint,
+        maker_token_account,
+        escrow.to_account_info(), token_program,
+        signer_seeds, amount, mint.decimals,
+    )?;
+
+    let close_accounts = CloseAccount {
+        account: holding_account.to_account_info(),
+        destination: beneficiary,
+        authority: escrow.to_account_info(),
+    };
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        close_accounts,
+        signer_seeds,
+    ))?;
+
+    Ok(amount)
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::ApprovalRecorded;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RecordApproval<'info> {
+    #[account(mut)]
+    pub approver: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == approver.key()
+            || escrow_state.beneficiary == approver.key() @ EscrowError::NotEscrowParty,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        init,
+        payer = approver,
+        space = 8 + Approval::INIT_SPACE,
+        seeds = [APPROVAL_SEED, escrow_state.key().as_ref(), approver.key().as_ref()],
+        bump,
+    )]
+    pub approval: Account<'info, Approval>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Records the signer's consent to one mutual action so the counterparty can
+/// execute it alone. The approval is pinned to the escrow's current nonce, so
+/// any state change in between (including executing the action itself) voids it.
+pub fn handler(
+    ctx: Context<RecordApproval>,
+    action: MutualAction,
+    action_hash: [u8; 32],
+    expires_at: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        expires_at > clock.unix_timestamp
+            && expires_at
+                <= clock.unix_timestamp
+                    .checked_add(MAX_APPROVAL_TTL)
+                    .ok_or(EscrowError::Overflow)?,
+        EscrowError::InvalidApprovalExpiry
+    );
+
+    let escrow = &ctx.accounts.escrow_state;
+    let approval = &mut ctx.accounts.approval;
+    approval.escrow = escrow.key();
+    approval.approver = ctx.accounts.approver.key();
+    approval.action_hash = action_hash;
+    approval.nonce = escrow.nonce;
+    approval.expires_at = expires_at;
+    approval.bump = ctx.bumps.approval;
+
+    emit!(ApprovalRecorded {
+        escrow: approval.escrow,
+        approver: approval.approver,
+        action,
+        action_hash,
+        nonce: approval.nonce,
+        expires_at,
+    });
+
+    Ok(())
+}
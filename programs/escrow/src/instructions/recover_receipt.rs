@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+use crate::error::EscrowError;
+use crate::events::ReceiptRecovered;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RecoverReceipt<'info> {
+    pub maker: Signer<'info>,
+
+    /// The last synced beneficiary. Optional so a lost wallet can be replaced
+    /// by the config authority once the recovery delay has passed.
+    #[account(address = escrow_state.beneficiary @ EscrowError::NotBeneficiary)]
+    pub beneficiary: Option<Signer<'info>>,
+
+    #[account(address = escrow_config.authority @ EscrowError::NotAuthority)]
+    pub authority: Option<Signer<'info>>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+}
+
+/// Detaches a receipt NFT that can no longer be moved or burned (burned wallet,
+/// uncooperative exchange) and hands the claim to `new_beneficiary`. The old
+/// mint is recorded as voided so a dangling NFT can never sync back in; the
+/// escrow then behaves as if it never had a receipt.
+pub fn handler(ctx: Context<RecoverReceipt>, new_beneficiary: Pubkey) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+    let voided_receipt_mint = escrow.receipt_mint.ok_or(EscrowError::NoReceipt)?;
+
+    let co_signer = if let Some(beneficiary) = &ctx.accounts.beneficiary {
+        beneficiary.key()
+    } else {
+        let authority = ctx.accounts.authority.as_ref().ok_or(EscrowError::RecoveryCoSignerMissing)?;
+        // Authority fallback only once the escrow has sat expired for the full delay
+        require!(escrow.disputes_enabled, EscrowError::DisputesDisabled);
+        let opens_at = escrow
+            .expires_at
+            .checked_add(RECEIPT_RECOVERY_DELAY)
+            .ok_or(EscrowError::Overflow)?;
+        require!(Clock::get()?.unix_timestamp >= opens_at, EscrowError::RecoveryCoSignerMissing);
+        authority.key()
+    };
+
+    require!(
+        new_beneficiary != escrow.maker && new_beneficiary != Pubkey::default(),
+        EscrowError::InvalidBeneficiary
+    );
+
+    let old_beneficiary = escrow.beneficiary;
+    escrow.receipt_mint = None;
+    escrow.voided_receipt_mint = Some(voided_receipt_mint);
+    escrow.beneficiary = new_beneficiary;
+    if escrow.beneficiary_ata.is_some() {
+        escrow.beneficiary_ata = Some(get_associated_token_address_with_program_id(
+            &new_beneficiary,
+            &escrow.mint,
+            &escrow.token_program,
+        ));
+    }
+
+    escrow.bump_nonce()?;
+
+    emit!(ReceiptRecovered {
+        escrow: escrow.key(),
+        voided_receipt_mint,
+        old_beneficiary,
+        new_beneficiary,
+        co_signer,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
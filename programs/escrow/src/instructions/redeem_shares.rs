@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint as ShareMint, Token, TokenAccount as ShareAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::EscrowError;
+use crate::events::SharesRedeemed;
+use crate::helpers::{escrow_seeds, pro_rata_redemption, transfer_from_vault};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RedeemShares<'info> {
+    pub holder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.share_mint == Some(share_mint.key()) @ EscrowError::ClaimNotFractionalized,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(mut)]
+    pub share_mint: Account<'info, ShareMint>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = holder,
+    )]
+    pub holder_share_account: Account<'info, ShareAccount>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = holder_token_account.owner == holder.key() @ EscrowError::OwnerMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub holder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub share_token_program: Program<'info, Token>,
+}
+
+/// Burn `shares` and withdraw their pro-rata portion of the funds accrued so far.
+/// Burned shares forfeit any later accruals.
+pub fn handler(ctx: Context<RedeemShares>, shares: u64) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+
+    // Someone must remain to receive later payouts: the final shares are only
+    // redeemable once nothing more can accrue
+    let supply = ctx.accounts.share_mint.supply;
+    require!(
+        shares < supply
+            || escrow.status == EscrowStatus::Completed
+            || escrow.status == EscrowStatus::Cancelled
+            || escrow.status == EscrowStatus::Expired,
+        EscrowError::EscrowNotTerminal
+    );
+
+    let payout = pro_rata_redemption(escrow.claim_pool, shares, supply)?;
+    require!(payout > 0, EscrowError::NothingToRedeem);
+
+    // Update state BEFORE CPI (checks-effects-interactions)
+    escrow.claim_pool = escrow.claim_pool.checked_sub(payout).ok_or(EscrowError::Overflow)?;
+
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.share_token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                from: ctx.accounts.holder_share_account.to_account_info(),
+                authority: ctx.accounts.holder.to_account_info(),
+            },
+        ),
+        shares,
+    )?;
+
+    let creator_key = escrow.creator;
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    transfer_from_vault(
+        &ctx.accounts.vault, &ctx.accounts.mint,
+        &ctx.accounts.holder_token_account,
+        escrow.to_account_info(), &ctx.accounts.token_program,
+        signer_seeds, payout, ctx.accounts.mint.decimals,
+    )?;
+
+    escrow.bump_nonce()?;
+
+    emit!(SharesRedeemed {
+        escrow: escrow.key(),
+        holder: ctx.accounts.holder.key(),
+        shares,
+        amount: payout,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::EscrowError;
+use crate::events::FeeRefunded;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RefundFee<'info> {
+    pub authority: Signer<'info>,
+
+    /// Owner of the token account the rebate is paid from.
+    #[account(address = escrow_config.fee_collector @ EscrowError::FeeCollectorMismatch)]
+    pub fee_collector: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+        constraint = escrow_config.authority == authority.key() @ EscrowError::NotAuthority,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = fee_collector,
+        token::token_program = token_program,
+    )]
+    pub fee_collector_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Owned by the escrow's beneficiary or maker, per `recipient`.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Rebate `amount` of the fees collected on this escrow, paid from the fee
+/// collector's tokens to the beneficiary or the maker.
+pub fn handler(ctx: Context<RefundFee>, amount: u64, recipient: FeeRefundRecipient) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    let recipient_key = match recipient {
+        FeeRefundRecipient::Beneficiary => escrow.beneficiary,
+        FeeRefundRecipient::Maker => escrow.maker,
+    };
+    require!(
+        ctx.accounts.recipient_token_account.owner == recipient_key,
+        EscrowError::OwnerMismatch
+    );
+
+    escrow.record_fee_refund(amount)?;
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.fee_collector_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
+        authority: ctx.accounts.fee_collector.to_account_info(),
+    };
+    token_interface::transfer_checked(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_accounts),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    escrow.bump_nonce()?;
+
+    emit!(FeeRefunded {
+        escrow: escrow.key(),
+        recipient: recipient_key,
+        amount,
+        fees_refunded: escrow.fees_refunded,
+        fees_paid: escrow.fees_paid,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::events::KeeperRegistered;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RegisterKeeper<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + Keeper::INIT_SPACE,
+        seeds = [KEEPER_SEED, keeper.key().as_ref()],
+        bump,
+    )]
+    pub keeper_account: Account<'info, Keeper>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Register the signer as a keeper, optionally bonding `bond` lamports.
+/// Cranks that pass this account earn the escrow's tip when they change state.
+pub fn handler(ctx: Context<RegisterKeeper>, bond: u64) -> Result<()> {
+    if bond > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.keeper.to_account_info(),
+                    to: ctx.accounts.keeper_account.to_account_info(),
+                },
+            ),
+            bond,
+        )?;
+    }
+
+    let keeper_account = &mut ctx.accounts.keeper_account;
+    keeper_account.keeper = ctx.accounts.keeper.key();
+    keeper_account.bond = bond;
+    keeper_account.registered_at = Clock::get()?.unix_timestamp;
+    keeper_account.cranks = 0;
+    keeper_account.tips_earned = 0;
+    keeper_account.bump = ctx.bumps.keeper_account;
+
+    emit!(KeeperRegistered {
+        keeper: keeper_account.keeper,
+        bond,
+    });
+
+    Ok(())
+}
@@ -1,20 +1,27 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
 
 use crate::error::EscrowError;
-use crate::events::{EscrowCompleted, MilestoneReleased};
-use crate::helpers::{calculate_fee, escrow_seeds, transfer_from_vault};
+use crate::events::{CrankRewarded, EscrowCompleted, KeeperTipPaid, MilestoneReleased, SubEscrowFunded};
+use crate::helpers::{
+    assert_vault_clean, claw_back_deadline, escrow_seeds, milestone_fee, pay_crank_tip, percent_complete_bps, release_open_slot,
+    take_sub_escrow_funding, transfer_from_vault, verify_agreement_acknowledged, verify_expected_nonce,
+    verify_milestone_sum, verify_remaining_accounts,
+};
+use crate::instructions::close_escrow::auto_close_escrow;
 use crate::state::*;
 
 #[derive(Accounts)]
 #[instruction(milestone_index: u8)]
 pub struct ReleaseMilestone<'info> {
     /// Anyone can crank this instruction after milestone is approved.
+    #[account(mut)]
     pub payer: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_state.maker.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
         bump = escrow_state.bump,
     )]
     pub escrow_state: Account<'info, EscrowState>,
@@ -22,17 +29,20 @@ pub struct ReleaseMilestone<'info> {
     #[account(
         seeds = [ESCROW_CONFIG_SEED],
         bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
     )]
     pub escrow_config: Account<'info, EscrowConfig>,
 
-    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    /// Writable so `auto_close` can burn sub-threshold vault dust.
+    #[account(
+        mut,
+        constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch,
+    )]
     pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = escrow_state,
-        associated_token::token_program = token_program,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
@@ -40,7 +50,10 @@ pub struct ReleaseMilestone<'info> {
         mut,
         token::mint = mint,
         token::token_program = token_program,
-        constraint = beneficiary_token_account.owner == escrow_state.beneficiary @ EscrowError::OwnerMismatch,
+        constraint = beneficiary_token_account.key() != escrow_state.vault @ EscrowError::DestinationIsVault,
+        constraint = beneficiary_token_account.owner == escrow_state.milestone_payee(milestone_index) @ EscrowError::OwnerMismatch,
+        constraint = escrow_state.milestone_beneficiary(milestone_index).is_some()
+            || escrow_state.beneficiary_ata.is_none_or(|ata| ata == beneficiary_token_account.key()) @ EscrowError::BeneficiaryAtaMismatch,
     )]
     pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
 
@@ -48,15 +61,111 @@ pub struct ReleaseMilestone<'info> {
         mut,
         token::mint = mint,
         token::token_program = token_program,
+        constraint = fee_collector_token_account.key() != escrow_state.vault @ EscrowError::DestinationIsVault,
         constraint = fee_collector_token_account.owner == escrow_config.fee_collector @ EscrowError::FeeCollectorMismatch,
     )]
     pub fee_collector_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Optional wSOL unwrap: scratch account that receives the net payout and is
+    /// closed to `beneficiary` in the same instruction. Rent is fronted by `payer`
+    /// and delivered to the beneficiary along with the unwrapped SOL.
+    #[account(
+        init,
+        payer = payer,
+        seeds = [UNWRAP_SEED, escrow_state.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow_state,
+        token::token_program = token_program,
+    )]
+    pub unwrap_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Native SOL destination for the unwrap path; pinned to the milestone's payee.
+    #[account(mut, address = escrow_state.milestone_payee(milestone_index) @ EscrowError::OwnerMismatch)]
+    pub beneficiary: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Option<Program<'info, System>>,
+
+    /// Sub-escrow linked to the released milestone, funded from its net payout.
+    #[account(mut)]
+    pub sub_escrow: Option<Account<'info, EscrowState>>,
+
+    #[account(mut)]
+    pub sub_escrow_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// `payer`'s keeper entry; when present, a state-changing crank pays it the escrow's tip.
+    #[account(
+        mut,
+        seeds = [KEEPER_SEED, payer.key().as_ref()],
+        bump = keeper.bump,
+    )]
+    pub keeper: Option<Account<'info, Keeper>>,
+
+    /// Claw-back holding for this milestone; required while the escrow has a
+    /// claw-back window. Rent is fronted by `payer` and returned to the beneficiary.
+    #[account(
+        init,
+        payer = payer,
+        seeds = [HOLDING_SEED, escrow_state.key().as_ref(), &[milestone_index]],
+        bump,
+        token::mint = mint,
+        token::authority = escrow_state,
+        token::token_program = token_program,
+    )]
+    pub holding_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// `auto_close` only: the maker co-signs the close and receives the rent.
+    #[account(mut, address = escrow_state.maker @ EscrowError::NotMaker)]
+    pub maker: Option<Signer<'info>>,
+
+    /// `auto_close` only: receives any dust swept from the vault.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+        constraint = maker_token_account.key() != escrow_state.vault @ EscrowError::DestinationIsVault,
+        constraint = maker_token_account.owner == escrow_state.maker @ EscrowError::OwnerMismatch,
+    )]
+    pub maker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// `auto_close` only: required when `escrow_state.metadata` is set; closed to the maker.
+    #[account(
+        mut,
+        seeds = [METADATA_SEED, escrow_state.key().as_ref()],
+        bump = escrow_metadata.bump,
+    )]
+    pub escrow_metadata: Option<Account<'info, EscrowMetadata>>,
+
+    /// Gets the open slot back when this release completes the escrow.
+    #[account(
+        mut,
+        seeds = [MAKER_PROFILE_SEED, escrow_state.creator.as_ref()],
+        bump = maker_profile.bump,
+    )]
+    pub maker_profile: Option<Account<'info, MakerProfile>>,
 }
 
-pub fn handler(ctx: Context<ReleaseMilestone>, milestone_index: u8) -> Result<()> {
+/// With `auto_close`, a release that completes the escrow also closes it to the
+/// maker (as `close_escrow` would) when nothing blocks the close; the maker
+/// must co-sign. Ignored otherwise.
+pub fn handler(
+    ctx: Context<ReleaseMilestone>,
+    milestone_index: u8,
+    expected_nonce: Option<u64>,
+    auto_close: bool,
+) -> Result<()> {
+    verify_remaining_accounts(ctx.remaining_accounts, MAX_RECEIPT_SYNC_ACCOUNTS)?;
+    assert_vault_clean(&ctx.accounts.vault)?;
+
     let escrow = &mut ctx.accounts.escrow_state;
+    let nonce_before = escrow.nonce;
+
+    verify_expected_nonce(escrow, expected_nonce)?;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
 
     require!(
         escrow.status == EscrowStatus::Active,
@@ -73,13 +182,28 @@ pub fn handler(ctx: Context<ReleaseMilestone>, milestone_index: u8) -> Result<()
         EscrowError::MilestoneNotApproved
     );
 
+    // Approval can only be cranked once the challenge window has elapsed
+    let releasable_at = escrow.milestones[idx]
+        .approved_at
+        .checked_add(escrow.release_delay)
+        .ok_or(EscrowError::Overflow)?;
+    require!(clock.unix_timestamp >= releasable_at, EscrowError::ReleaseDelayNotElapsed);
+
     // If a receipt NFT exists, verify beneficiary is synced with current NFT holder.
     if escrow.receipt_mint.is_some() {
         crate::helpers::verify_receipt_sync(escrow, ctx.remaining_accounts)?;
     }
 
+    verify_agreement_acknowledged(escrow)?;
+
     let milestone_amount = escrow.milestones[idx].amount;
-    let (fee, taker_amount) = calculate_fee(milestone_amount, escrow.fee_bps_at_creation as u64)?;
+    let fee_exempt = escrow.milestones[idx].fee_exempt;
+    let (fee, taker_amount) = milestone_fee(
+        &escrow.milestones[idx],
+        escrow.fee_bps_at_creation,
+        escrow.flat_fee_at_creation,
+        escrow.fee_free_below_at_creation,
+    )?;
 
     // Update state BEFORE CPI (checks-effects-interactions)
     escrow.milestones[idx].status = MilestoneStatus::Released;
@@ -87,22 +211,80 @@ pub fn handler(ctx: Context<ReleaseMilestone>, milestone_index: u8) -> Result<()
         .released_amount
         .checked_add(milestone_amount)
         .ok_or(EscrowError::Overflow)?;
+    escrow.record_fee(fee)?;
 
     // PDA signer seeds
-    let maker_key = escrow.maker;
+    let creator_key = escrow.creator;
     let seed_bytes = escrow.seed.to_le_bytes();
     let bump = [escrow.bump];
-    let inner = escrow_seeds(&maker_key, &seed_bytes, &bump);
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
     let signer_seeds: &[&[&[u8]]] = &[&inner];
 
     let decimals = ctx.accounts.mint.decimals;
-
-    transfer_from_vault(
-        &ctx.accounts.vault, &ctx.accounts.mint,
-        &ctx.accounts.beneficiary_token_account,
-        escrow.to_account_info(), &ctx.accounts.token_program,
-        signer_seeds, taker_amount, decimals,
+    // A linked sub-escrow is funded first; the beneficiary receives the rest
+    let sub_amount = take_sub_escrow_funding(
+        escrow,
+        idx,
+        ctx.accounts.sub_escrow.as_mut(),
+        ctx.accounts.sub_escrow_vault.as_ref(),
     )?;
+    let taker_amount = taker_amount.checked_sub(sub_amount).ok_or(EscrowError::Overflow)?;
+    let taker_amount = escrow.route_milestone_payout(milestone_index, taker_amount)?;
+
+    let held_until = claw_back_deadline(escrow.claw_back_seconds, taker_amount, clock.unix_timestamp)?;
+    escrow.milestones[idx].held_until = held_until;
+
+    match (&ctx.accounts.unwrap_account, &ctx.accounts.beneficiary) {
+        (None, None) if held_until != 0 => {
+            let holding = ctx
+                .accounts
+                .holding_account
+                .as_ref()
+                .ok_or(EscrowError::HoldingAccountMissing)?;
+            transfer_from_vault(
+                &ctx.accounts.vault, &ctx.accounts.mint,
+                holding,
+                escrow.to_account_info(), &ctx.accounts.token_program,
+                signer_seeds, taker_amount, decimals,
+            )?;
+        }
+        (None, None) => {
+            transfer_from_vault(
+                &ctx.accounts.vault, &ctx.accounts.mint,
+                &ctx.accounts.beneficiary_token_account,
+                escrow.to_account_info(), &ctx.accounts.token_program,
+                signer_seeds, taker_amount, decimals,
+            )?;
+        }
+        (Some(unwrap_account), Some(beneficiary)) => {
+            require!(escrow.share_mint.is_none(), EscrowError::ClaimIsFractionalized);
+            require!(held_until == 0, EscrowError::ClawBackUnwrapUnsupported);
+            require!(
+                ctx.accounts.mint.key() == native_mint::ID,
+                EscrowError::UnwrapRequiresNativeMint
+            );
+
+            transfer_from_vault(
+                &ctx.accounts.vault, &ctx.accounts.mint,
+                unwrap_account,
+                escrow.to_account_info(), &ctx.accounts.token_program,
+                signer_seeds, taker_amount, decimals,
+            )?;
+
+            // Closing a native account releases its wrapped balance as lamports
+            let close_accounts = CloseAccount {
+                account: unwrap_account.to_account_info(),
+                destination: beneficiary.to_account_info(),
+                authority: escrow.to_account_info(),
+            };
+            token_interface::close_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                close_accounts,
+                signer_seeds,
+            ))?;
+        }
+        _ => return err!(EscrowError::IncompleteUnwrapAccounts),
+    }
 
     transfer_from_vault(
         &ctx.accounts.vault, &ctx.accounts.mint,
@@ -111,21 +293,85 @@ pub fn handler(ctx: Context<ReleaseMilestone>, milestone_index: u8) -> Result<()
         signer_seeds, fee, decimals,
     )?;
 
+    if sub_amount > 0 {
+        if let (Some(child), Some(child_vault)) = (&ctx.accounts.sub_escrow, &ctx.accounts.sub_escrow_vault) {
+            transfer_from_vault(
+                &ctx.accounts.vault, &ctx.accounts.mint,
+                child_vault,
+                escrow.to_account_info(), &ctx.accounts.token_program,
+                signer_seeds, sub_amount, decimals,
+            )?;
+            emit!(SubEscrowFunded {
+                parent: escrow.key(),
+                parent_milestone_index: milestone_index,
+                child: child.key(),
+                amount: sub_amount,
+                nonce: child.nonce,
+            });
+        }
+    }
+
+    escrow.bump_nonce()?;
+
     emit!(MilestoneReleased {
         escrow: escrow.key(),
         milestone_index,
         amount: milestone_amount,
         fee,
+        fee_exempt,
+        percent_complete_bps: percent_complete_bps(escrow.released_amount, escrow.amount)?,
+        nonce: escrow.nonce,
     });
 
     let all_settled = escrow.all_milestones_settled();
 
     if all_settled {
         escrow.status = EscrowStatus::Completed;
+        let (milestones_released, milestones_cancelled) = escrow.settled_milestone_counts();
         emit!(EscrowCompleted {
             escrow: escrow.key(),
             total_released: escrow.released_amount,
+            total_refunded: escrow.refunded_amount,
+            total_fees_paid: escrow.fees_paid,
+            milestones_released,
+            milestones_cancelled,
+            beneficiary: escrow.beneficiary,
+            nonce: escrow.nonce,
+        });
+    }
+
+    verify_milestone_sum(escrow)?;
+    release_open_slot(escrow, ctx.accounts.maker_profile.as_deref_mut())?;
+
+    let tip = pay_crank_tip(escrow, ctx.accounts.keeper.as_mut(), nonce_before)?;
+    if tip > 0 {
+        emit!(KeeperTipPaid {
+            escrow: escrow.key(),
+            keeper: ctx.accounts.payer.key(),
+            amount: tip,
+            tip_jar: escrow.tip_jar,
+            nonce: escrow.nonce,
         });
+        emit!(CrankRewarded {
+            escrow: escrow.key(),
+            keeper: ctx.accounts.payer.key(),
+            reward: tip,
+            instruction: CrankInstruction::ReleaseMilestone,
+        });
+    }
+
+    if auto_close && all_settled {
+        let accounts = ctx.accounts;
+        auto_close_escrow(
+            &accounts.escrow_state,
+            &accounts.escrow_config,
+            &accounts.mint,
+            &mut accounts.vault,
+            accounts.maker.as_ref(),
+            accounts.maker_token_account.as_ref(),
+            &accounts.token_program,
+            accounts.escrow_metadata.as_ref(),
+        )?;
     }
 
     Ok(())
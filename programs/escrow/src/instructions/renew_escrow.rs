@@ -0,0 +1,265 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::EscrowError;
+use crate::events::EscrowRenewed;
+use crate::helpers::{
+    fee_on_top, mutual_action_hash, reserve_open_slot, verify_approval, verify_milestone_beneficiaries, verify_milestone_inputs, verify_milestone_sum, verify_remaining_accounts,
+};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RenewEscrow<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// Renewal is mutual — the current beneficiary must co-sign the new terms,
+    /// or have recorded an `Approval` for exactly these terms beforehand.
+    #[account(address = escrow_state.beneficiary @ EscrowError::NotBeneficiary)]
+    pub beneficiary: Option<Signer<'info>>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == escrow_state.maker @ EscrowError::OwnerMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The beneficiary's pre-recorded consent; required when `beneficiary` is absent.
+    #[account(
+        seeds = [APPROVAL_SEED, escrow_state.key().as_ref(), escrow_state.beneficiary.as_ref()],
+        bump = approval.bump,
+    )]
+    pub approval: Option<Account<'info, Approval>>,
+
+    /// Charged an open slot again when a Completed escrow is renewed.
+    #[account(
+        mut,
+        seeds = [MAKER_PROFILE_SEED, escrow_state.creator.as_ref()],
+        bump = maker_profile.bump,
+    )]
+    pub maker_profile: Option<Account<'info, MakerProfile>>,
+}
+
+/// Starts a new cycle on the same PDA. Settled milestones (Released/Cancelled)
+/// are pruned, unsettled ones carry over, and the new milestones are appended.
+/// `amount` becomes carried-over + top-up and the per-cycle released/refunded
+/// counters reset to zero; the previous cycle's totals are emitted in `EscrowRenewed`.
+/// On a `fee_on_top` escrow the new milestones are grossed up by their fee at
+/// the creation-time rate, and the maker funds `top_up` plus those fees.
+/// A Completed escrow gave its open slot back, so renewing it reserves one
+/// again under the current `max_open_escrows_per_maker`.
+pub fn handler(
+    ctx: Context<RenewEscrow>,
+    top_up: u64,
+    milestones: Vec<MilestoneInput>,
+    expires_at: i64,
+) -> Result<()> {
+    verify_remaining_accounts(ctx.remaining_accounts, MAX_RECEIPT_SYNC_ACCOUNTS)?;
+
+    let clock = Clock::get()?;
+
+    if ctx.accounts.beneficiary.is_none() {
+        let approval = ctx.accounts.approval.as_ref().ok_or(EscrowError::CoSignerMissing)?;
+        let action_hash = renew_action_hash(&ctx.accounts.escrow_state, top_up, &milestones, expires_at)?;
+        verify_approval(
+            approval,
+            &ctx.accounts.escrow_state.key(),
+            ctx.accounts.escrow_state.nonce,
+            &action_hash,
+            clock.unix_timestamp,
+        )?;
+    }
+
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+    // Share holders bought a fixed receivable; the beneficiary alone can't re-terms it
+    require!(escrow.share_mint.is_none(), EscrowError::ClaimIsFractionalized);
+    require!(top_up > 0, EscrowError::InvalidAmount);
+    // Settled milestones are dropped and re-indexed, which would orphan their holdings
+    require!(!escrow.has_held_funds(), EscrowError::FundsStillHeld);
+    // A finished cycle's bond is settled against that cycle's outcome
+    require!(
+        escrow.status != EscrowStatus::Completed || escrow.bond_balance == 0,
+        EscrowError::BondNotSettled
+    );
+
+    let renewal_opens_at = escrow
+        .expires_at
+        .checked_sub(RENEWAL_WINDOW)
+        .ok_or(EscrowError::Overflow)?;
+    let is_near_expiry = escrow.status == EscrowStatus::Active
+        && clock.unix_timestamp >= renewal_opens_at
+        && clock.unix_timestamp <= escrow.expires_at;
+    require!(
+        escrow.status == EscrowStatus::Completed || is_near_expiry,
+        EscrowError::RenewalNotAllowed
+    );
+
+    // If a receipt NFT exists, verify beneficiary is synced with current NFT holder.
+    if escrow.receipt_mint.is_some() {
+        crate::helpers::verify_receipt_sync(escrow, ctx.remaining_accounts)?;
+    }
+
+    // New expiry must extend the current one and respect the minimum duration
+    require!(expires_at > escrow.expires_at, EscrowError::InvalidExpiration);
+    require!(
+        expires_at >= clock.unix_timestamp
+            .checked_add(MIN_EXPIRATION_DURATION)
+            .ok_or(EscrowError::Overflow)?,
+        EscrowError::InvalidExpiration
+    );
+
+    verify_milestone_inputs(&milestones, top_up)?;
+    verify_milestone_beneficiaries(&milestones, &escrow.maker)?;
+    require!(
+        milestones.iter().all(|m| !m.fee_exempt) || ctx.accounts.escrow_config.allow_fee_exempt,
+        EscrowError::FeeExemptionNotAllowed
+    );
+
+    // Carry over unsettled milestones, drop settled ones
+    let mut next_milestones: Vec<Milestone> = escrow
+        .milestones
+        .iter()
+        .filter(|m| m.status == MilestoneStatus::Pending || m.status == MilestoneStatus::Approved)
+        .cloned()
+        .collect();
+    let mut carried_over: u64 = 0;
+    for m in &next_milestones {
+        carried_over = carried_over
+            .checked_add(m.amount)
+            .ok_or(EscrowError::Overflow)?;
+    }
+    let mut funded_top_up: u64 = 0;
+    for m in &milestones {
+        let fee_reserved = if escrow.fee_on_top {
+            fee_on_top(
+                m.amount,
+                m.fee_exempt,
+                escrow.fee_bps_at_creation,
+                escrow.flat_fee_at_creation,
+                escrow.fee_free_below_at_creation,
+            )?
+        } else {
+            0
+        };
+        let gross = m.amount.checked_add(fee_reserved).ok_or(EscrowError::Overflow)?;
+        funded_top_up = funded_top_up.checked_add(gross).ok_or(EscrowError::Overflow)?;
+        next_milestones.push(Milestone {
+            amount: gross,
+            description_hash: m.description_hash,
+            status: MilestoneStatus::Pending,
+            approved_at: 0,
+            sub_escrow: None,
+            held_until: 0,
+            fee_exempt: m.fee_exempt,
+            self_certify: m.self_certify,
+            fee_reserved,
+            beneficiary: m.beneficiary,
+        });
+    }
+    require!(
+        (1..=MAX_MILESTONES as usize).contains(&next_milestones.len()),
+        EscrowError::InvalidMilestoneCount
+    );
+
+    // Fund the new cycle
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.maker_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.maker.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+    );
+    token_interface::transfer_checked(cpi_ctx, funded_top_up, ctx.accounts.mint.decimals)?;
+
+    let previous_released = escrow.released_amount;
+    let previous_refunded = escrow.refunded_amount;
+    let previous_fees_paid = escrow.fees_paid;
+
+    escrow.milestones = next_milestones;
+    escrow.amount = carried_over.checked_add(funded_top_up).ok_or(EscrowError::Overflow)?;
+    escrow.released_amount = 0;
+    escrow.refunded_amount = 0;
+    escrow.fees_paid = 0;
+    escrow.fees_refunded = 0;
+    escrow.expires_at = expires_at;
+    if escrow.status == EscrowStatus::Completed {
+        reserve_open_slot(
+            escrow,
+            ctx.accounts.maker_profile.as_deref_mut(),
+            ctx.accounts.escrow_config.max_open_escrows_per_maker,
+        )?;
+    }
+    escrow.status = EscrowStatus::Active;
+    escrow.dispute = None;
+    escrow.renewal_count = escrow
+        .renewal_count
+        .checked_add(1)
+        .ok_or(EscrowError::Overflow)?;
+
+    escrow.bump_nonce()?;
+
+    emit!(EscrowRenewed {
+        escrow: escrow.key(),
+        renewal_count: escrow.renewal_count,
+        top_up: funded_top_up,
+        carried_over,
+        previous_released,
+        previous_refunded,
+        previous_fees_paid,
+        expires_at,
+        nonce: escrow.nonce,
+    });
+
+    verify_milestone_sum(escrow)?;
+
+    Ok(())
+}
+
+/// Hash the beneficiary signs off on via `record_approval` to pre-approve
+/// renewing `escrow` with these exact arguments at its current nonce.
+pub fn renew_action_hash(
+    escrow: &Account<EscrowState>,
+    top_up: u64,
+    milestones: &[MilestoneInput],
+    expires_at: i64,
+) -> Result<[u8; 32]> {
+    let mut params = Vec::new();
+    top_up.serialize(&mut params)?;
+    milestones.serialize(&mut params)?;
+    expires_at.serialize(&mut params)?;
+    mutual_action_hash(MutualAction::RenewEscrow, &escrow.key(), escrow.nonce, &params)
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::error::EscrowError;
+use crate::events::EscrowRequested;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct RequestEscrow<'info> {
+    /// Prospective taker asking to be paid.
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    /// CHECK: Party asked to fund the escrow; stored as a pubkey reference.
+    pub maker: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [ESCROW_CONFIG_SEED],
+        bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
+    )]
+    pub escrow_config: Account<'info, EscrowConfig>,
+
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + EscrowRequest::INIT_SPACE,
+        seeds = [REQUEST_SEED, maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub escrow_request: Account<'info, EscrowRequest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Open an unfunded payment request. The terms are validated in full when
+/// the maker funds it via `fund_request`.
+pub fn handler(ctx: Context<RequestEscrow>, seed: u64, terms: EscrowTerms) -> Result<()> {
+    let taker = ctx.accounts.requester.key();
+    let maker = ctx.accounts.maker.key();
+    require!(maker != taker, EscrowError::SelfEscrow);
+    require!(terms.amount > 0, EscrowError::InvalidAmount);
+
+    let clock = Clock::get()?;
+    let expires_at = clock
+        .unix_timestamp
+        .checked_add(ctx.accounts.escrow_config.request_ttl)
+        .ok_or(EscrowError::Overflow)?;
+
+    let request = &mut ctx.accounts.escrow_request;
+    request.taker = taker;
+    request.maker = maker;
+    request.mint = ctx.accounts.mint.key();
+    request.seed = seed;
+    request.terms = terms;
+    request.expires_at = expires_at;
+    request.bump = ctx.bumps.escrow_request;
+
+    emit!(EscrowRequested {
+        request: request.key(),
+        maker,
+        taker,
+        seed,
+        amount: request.terms.amount,
+        expires_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+use crate::error::EscrowError;
+use crate::events::BeneficiaryReset;
+use crate::helpers::verify_expected_nonce;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ResetBeneficiary<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+}
+
+/// Recovery for a mis-assigned claim: point the beneficiary back at the taker.
+/// Only before any work is approved and while no receipt or shares represent
+/// the claim, so a claim that was legitimately sold cannot be taken back.
+pub fn handler(ctx: Context<ResetBeneficiary>, expected_nonce: Option<u64>) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    verify_expected_nonce(escrow, expected_nonce)?;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+    require!(escrow.status == EscrowStatus::Active, EscrowError::EscrowNotActive);
+    require!(escrow.receipt_mint.is_none(), EscrowError::ReceiptExists);
+    require!(escrow.share_mint.is_none(), EscrowError::ClaimIsFractionalized);
+    require!(
+        escrow.milestones.iter().all(|m| m.status == MilestoneStatus::Pending),
+        EscrowError::WorkAlreadyApproved
+    );
+    require!(escrow.beneficiary != escrow.taker, EscrowError::BeneficiaryNotReassigned);
+
+    let old_beneficiary = escrow.beneficiary;
+    escrow.beneficiary = escrow.taker;
+    if escrow.beneficiary_ata.is_some() {
+        escrow.beneficiary_ata = Some(get_associated_token_address_with_program_id(
+            &escrow.beneficiary,
+            &escrow.mint,
+            &escrow.token_program,
+        ));
+    }
+
+    escrow.bump_nonce()?;
+
+    emit!(BeneficiaryReset {
+        escrow: escrow.key(),
+        from: old_beneficiary,
+        to: escrow.beneficiary,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
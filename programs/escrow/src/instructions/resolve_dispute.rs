@@ -2,36 +2,46 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::error::EscrowError;
-use crate::events::DisputeResolved;
-use crate::helpers::{calculate_fee, escrow_seeds, transfer_from_vault};
+use crate::events::{DisputeResolved, EscrowCancelled, EscrowCompleted, MilestoneCancelled};
+use crate::helpers::{
+    assert_vault_clean, claw_back_deadline, dispute_loser, escrow_seeds, fund_holding, refund_maker, release_open_slot, resolution_breakdown,
+    transfer_from_vault, verify_expected_nonce, verify_milestone_sum, verify_remaining_accounts,
+};
+use crate::instructions::close_escrow::auto_close_escrow;
 use crate::state::*;
 
 #[derive(Accounts)]
 pub struct ResolveDispute<'info> {
+    /// Pays the rent of the claw-back holding account, which is returned to the beneficiary.
+    #[account(mut)]
     pub authority: Signer<'info>,
 
     #[account(
         seeds = [ESCROW_CONFIG_SEED],
         bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
         constraint = escrow_config.authority == authority.key() @ EscrowError::NotAuthority,
     )]
     pub escrow_config: Account<'info, EscrowConfig>,
 
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_state.maker.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
         bump = escrow_state.bump,
+        constraint = escrow_state.disputes_enabled @ EscrowError::DisputesDisabled,
     )]
     pub escrow_state: Account<'info, EscrowState>,
 
-    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    /// Writable so `auto_close` can burn sub-threshold vault dust.
+    #[account(
+        mut,
+        constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch,
+    )]
     pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = escrow_state,
-        associated_token::token_program = token_program,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
@@ -39,6 +49,7 @@ pub struct ResolveDispute<'info> {
         mut,
         token::mint = mint,
         token::token_program = token_program,
+        constraint = maker_token_account.key() != escrow_state.vault @ EscrowError::DestinationIsVault,
         constraint = maker_token_account.owner == escrow_state.maker @ EscrowError::OwnerMismatch,
     )]
     pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
@@ -47,7 +58,9 @@ pub struct ResolveDispute<'info> {
         mut,
         token::mint = mint,
         token::token_program = token_program,
+        constraint = beneficiary_token_account.key() != escrow_state.vault @ EscrowError::DestinationIsVault,
         constraint = beneficiary_token_account.owner == escrow_state.beneficiary @ EscrowError::OwnerMismatch,
+        constraint = escrow_state.beneficiary_ata.is_none_or(|ata| ata == beneficiary_token_account.key()) @ EscrowError::BeneficiaryAtaMismatch,
     )]
     pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
 
@@ -55,16 +68,72 @@ pub struct ResolveDispute<'info> {
         mut,
         token::mint = mint,
         token::token_program = token_program,
+        constraint = fee_collector_token_account.key() != escrow_state.vault @ EscrowError::DestinationIsVault,
         constraint = fee_collector_token_account.owner == escrow_config.fee_collector @ EscrowError::FeeCollectorMismatch,
     )]
     pub fee_collector_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
     pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+
+    /// `auto_close` only: the maker co-signs the close and receives the rent.
+    #[account(mut, address = escrow_state.maker @ EscrowError::NotMaker)]
+    pub maker: Option<Signer<'info>>,
+
+    /// `auto_close` only: required when `escrow_state.metadata` is set; closed to the maker.
+    #[account(
+        mut,
+        seeds = [METADATA_SEED, escrow_state.key().as_ref()],
+        bump = escrow_metadata.bump,
+    )]
+    pub escrow_metadata: Option<Account<'info, EscrowMetadata>>,
+
+    /// Gets the open slot back; every ruling is terminal.
+    #[account(
+        mut,
+        seeds = [MAKER_PROFILE_SEED, escrow_state.creator.as_ref()],
+        bump = maker_profile.bump,
+    )]
+    pub maker_profile: Option<Account<'info, MakerProfile>>,
+
+    /// `loser_pays_fee` only: the losing party's account, which must have
+    /// delegated at least the fee to `escrow_state`.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub loser_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 }
 
-pub fn handler(ctx: Context<ResolveDispute>, resolution: DisputeResolution) -> Result<()> {
+/// Every ruling is terminal, so with `auto_close` the escrow is also closed to
+/// the maker (as `close_escrow` would) when nothing blocks the close; the maker
+/// must co-sign.
+/// With `loser_pays_fee` the fee is charged to the losing party's pre-authorized
+/// `loser_token_account` and the beneficiary's share is paid out without it.
+/// The maker's share is refunded per the escrow's `DisputeRuling` routing.
+/// While the escrow has a claw-back window that share is held in the
+/// `SETTLEMENT_HOLDING_INDEX` holding instead (so `auto_close` leaves the
+/// escrow open); `remaining_accounts` then carry its PDA after the receipt
+/// token account, if any.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>,
+    resolution: DisputeResolution,
+    expected_nonce: Option<u64>,
+    auto_close: bool,
+    loser_pays_fee: bool,
+) -> Result<()> {
+    verify_remaining_accounts(ctx.remaining_accounts, MAX_BULK_SETTLEMENT_ACCOUNTS)?;
+    assert_vault_clean(&ctx.accounts.vault)?;
+
     let escrow = &mut ctx.accounts.escrow_state;
 
+    verify_expected_nonce(escrow, expected_nonce)?;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+
     require!(
         escrow.status == EscrowStatus::Disputed,
         EscrowError::DisputeNotActive
@@ -73,11 +142,7 @@ pub fn handler(ctx: Context<ResolveDispute>, resolution: DisputeResolution) -> R
     // Authority must resolve before dispute timeout elapses
     let clock = Clock::get()?;
     if let Some(ref dispute) = escrow.dispute {
-        let deadline = dispute
-            .initiated_at
-            .checked_add(dispute.timeout)
-            .ok_or(EscrowError::Overflow)?;
-        require!(clock.unix_timestamp <= deadline, EscrowError::EscrowExpired);
+        require!(clock.unix_timestamp <= dispute.deadline, EscrowError::EscrowExpired);
     }
 
     // If a receipt NFT exists, verify beneficiary is synced with current NFT holder.
@@ -85,49 +150,56 @@ pub fn handler(ctx: Context<ResolveDispute>, resolution: DisputeResolution) -> R
         crate::helpers::verify_receipt_sync(escrow, ctx.remaining_accounts)?;
     }
 
-    if let DisputeResolution::Split { maker_bps } = &resolution {
-        require!(*maker_bps <= 10_000, EscrowError::InvalidDisputeResolution);
-    }
+    // Payout amounts (shared with simulate_resolution)
+    let payout = resolution_breakdown(escrow, &resolution)?;
 
-    let remaining = escrow
-        .amount
-        .checked_sub(escrow.released_amount)
-        .ok_or(EscrowError::Overflow)?
-        .checked_sub(escrow.refunded_amount)
+    // Loser-pays: the fee leg moves from the vault to the loser's delegated account.
+    // A ruling that charges no fee (e.g. `MakerWins`) needs no loser account.
+    let fee_paid_by = if loser_pays_fee {
+        let loser = dispute_loser(escrow, &resolution)?;
+        if payout.fee > 0 {
+            let account = ctx
+                .accounts
+                .loser_token_account
+                .as_ref()
+                .ok_or(EscrowError::LoserFeeAccountMissing)?;
+            require!(account.owner == loser, EscrowError::OwnerMismatch);
+            require!(
+                account.delegate == Some(escrow.key()).into() && account.delegated_amount >= payout.fee,
+                EscrowError::FeeNotPreAuthorized
+            );
+            Some(loser)
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+    let vault_fee = if fee_paid_by.is_some() { 0 } else { payout.fee };
+    let beneficiary_gross = payout
+        .beneficiary_amount
+        .checked_add(payout.fee - vault_fee)
         .ok_or(EscrowError::Overflow)?;
 
     // PDA signer seeds
-    let maker_key = escrow.maker;
+    let creator_key = escrow.creator;
     let seed_bytes = escrow.seed.to_le_bytes();
     let bump = [escrow.bump];
-    let inner = escrow_seeds(&maker_key, &seed_bytes, &bump);
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
     let signer_seeds: &[&[&[u8]]] = &[&inner];
 
     let decimals = ctx.accounts.mint.decimals;
-    let fee_bps = escrow.fee_bps_at_creation as u64;
-
-    // Pre-compute split amounts (used for both accounting and CPI)
-    let (split_maker_share, split_taker_total) = if let DisputeResolution::Split { maker_bps } = &resolution {
-        let maker_bps_val = *maker_bps as u64;
-        let ms = remaining
-            .checked_mul(maker_bps_val)
-            .ok_or(EscrowError::Overflow)?
-            .checked_div(10_000)
-            .ok_or(EscrowError::Overflow)?;
-        let ts = remaining.checked_sub(ms).ok_or(EscrowError::Overflow)?;
-        (ms, ts)
-    } else {
-        (0, 0)
-    };
 
     // Update milestones based on resolution (checks-effects-interactions)
-    for milestone in escrow.milestones.iter_mut() {
+    let mut cancelled: Vec<(u8, u64)> = Vec::new();
+    for (i, milestone) in escrow.milestones.iter_mut().enumerate() {
         if milestone.status == MilestoneStatus::Pending
             || milestone.status == MilestoneStatus::Approved
         {
             match &resolution {
                 DisputeResolution::MakerWins => {
                     milestone.status = MilestoneStatus::Cancelled;
+                    cancelled.push((i as u8, milestone.amount));
                 }
                 DisputeResolution::TakerWins | DisputeResolution::Split { .. } => {
                     milestone.status = MilestoneStatus::Released;
@@ -147,13 +219,17 @@ pub fn handler(ctx: Context<ResolveDispute>, resolution: DisputeResolution) -> R
             escrow.status = EscrowStatus::Completed;
         }
         DisputeResolution::Split { .. } => {
+            let taker_total = payout
+                .beneficiary_amount
+                .checked_add(payout.fee)
+                .ok_or(EscrowError::Overflow)?;
             escrow.released_amount = escrow
                 .released_amount
-                .checked_add(split_taker_total)
+                .checked_add(taker_total)
                 .ok_or(EscrowError::Overflow)?;
             escrow.refunded_amount = escrow
                 .refunded_amount
-                .checked_add(split_maker_share)
+                .checked_add(payout.maker_amount)
                 .ok_or(EscrowError::Overflow)?;
             escrow.status = EscrowStatus::Completed;
         }
@@ -164,66 +240,123 @@ pub fn handler(ctx: Context<ResolveDispute>, resolution: DisputeResolution) -> R
         escrow.receipt_mint = None;
     }
 
-    // Store resolution in dispute
+    // Store resolution, resolver and timestamp in dispute
+    let resolved_by = ctx.accounts.authority.key();
     if let Some(ref mut dispute) = escrow.dispute {
         dispute.resolution = Some(resolution.clone());
+        dispute.resolved_by = resolved_by;
+        dispute.resolved_at = clock.unix_timestamp;
     }
 
-    // CPI transfers
-    match &resolution {
-        DisputeResolution::MakerWins => {
-            transfer_from_vault(
-                &ctx.accounts.vault, &ctx.accounts.mint,
-                &ctx.accounts.maker_token_account,
-                escrow.to_account_info(), &ctx.accounts.token_program,
-                signer_seeds, remaining, decimals,
-            )?;
-        }
-        DisputeResolution::TakerWins => {
-            if remaining > 0 {
-                let (fee, taker_amount) = calculate_fee(remaining, fee_bps)?;
-                transfer_from_vault(
-                    &ctx.accounts.vault, &ctx.accounts.mint,
-                    &ctx.accounts.beneficiary_token_account,
-                    escrow.to_account_info(), &ctx.accounts.token_program,
-                    signer_seeds, taker_amount, decimals,
-                )?;
-                transfer_from_vault(
-                    &ctx.accounts.vault, &ctx.accounts.mint,
-                    &ctx.accounts.fee_collector_token_account,
-                    escrow.to_account_info(), &ctx.accounts.token_program,
-                    signer_seeds, fee, decimals,
-                )?;
-            }
-        }
-        DisputeResolution::Split { .. } => {
-            let (fee, taker_amount) = calculate_fee(split_taker_total, fee_bps)?;
-
-            transfer_from_vault(
-                &ctx.accounts.vault, &ctx.accounts.mint,
-                &ctx.accounts.maker_token_account,
-                escrow.to_account_info(), &ctx.accounts.token_program,
-                signer_seeds, split_maker_share, decimals,
-            )?;
-            transfer_from_vault(
-                &ctx.accounts.vault, &ctx.accounts.mint,
-                &ctx.accounts.beneficiary_token_account,
-                escrow.to_account_info(), &ctx.accounts.token_program,
-                signer_seeds, taker_amount, decimals,
-            )?;
-            transfer_from_vault(
-                &ctx.accounts.vault, &ctx.accounts.mint,
-                &ctx.accounts.fee_collector_token_account,
-                escrow.to_account_info(), &ctx.accounts.token_program,
-                signer_seeds, fee, decimals,
-            )?;
-        }
+    let beneficiary_amount = escrow.route_beneficiary_payout(beneficiary_gross)?;
+    escrow.record_fee(payout.fee)?;
+    escrow.settlement_held_until =
+        claw_back_deadline(escrow.claw_back_seconds, beneficiary_amount, clock.unix_timestamp)?;
+
+    // CPI transfers (zero-amount legs are skipped by transfer_from_vault)
+    let (routing, cancel_fee) = refund_maker(
+        escrow, CancelReason::DisputeRuling, payout.maker_amount, ctx.accounts.escrow_config.fee_collector,
+        &ctx.accounts.vault, &ctx.accounts.mint, &ctx.accounts.maker_token_account,
+        &[Some(&ctx.accounts.fee_collector_token_account), Some(&ctx.accounts.beneficiary_token_account)],
+        &ctx.accounts.token_program, signer_seeds,
+    )?;
+    if escrow.settlement_held_until != 0 {
+        fund_holding(
+            escrow, SETTLEMENT_HOLDING_INDEX, beneficiary_amount, &ctx.accounts.vault, &ctx.accounts.mint,
+            ctx.accounts.authority.to_account_info(), &ctx.accounts.system_program,
+            &ctx.accounts.token_program, ctx.remaining_accounts, signer_seeds,
+        )?;
+    } else {
+        transfer_from_vault(
+            &ctx.accounts.vault, &ctx.accounts.mint,
+            &ctx.accounts.beneficiary_token_account,
+            escrow.to_account_info(), &ctx.accounts.token_program,
+            signer_seeds, beneficiary_amount, decimals,
+        )?;
+    }
+    transfer_from_vault(
+        &ctx.accounts.vault, &ctx.accounts.mint,
+        &ctx.accounts.fee_collector_token_account,
+        escrow.to_account_info(), &ctx.accounts.token_program,
+        signer_seeds, vault_fee, decimals,
+    )?;
+    if let (Some(_), Some(loser_account)) = (fee_paid_by, ctx.accounts.loser_token_account.as_ref()) {
+        // The escrow PDA signs as the loser's delegate
+        transfer_from_vault(
+            loser_account, &ctx.accounts.mint,
+            &ctx.accounts.fee_collector_token_account,
+            escrow.to_account_info(), &ctx.accounts.token_program,
+            signer_seeds, payout.fee, decimals,
+        )?;
     }
 
+    escrow.bump_nonce()?;
+
     emit!(DisputeResolved {
         escrow: escrow.key(),
         resolution,
+        resolved_by,
+        resolved_at: clock.unix_timestamp,
+        fee_paid_by,
+        nonce: escrow.nonce,
     });
 
+    for (milestone_index, amount) in cancelled {
+        emit!(MilestoneCancelled {
+            escrow: escrow.key(),
+            milestone_index,
+            amount,
+            reason: CancelReason::DisputeRuling,
+            nonce: escrow.nonce,
+        });
+    }
+
+    // Every ruling is terminal: report the final totals like the other terminal paths
+    let (milestones_released, milestones_cancelled) = escrow.settled_milestone_counts();
+    if escrow.status == EscrowStatus::Cancelled {
+        emit!(EscrowCancelled {
+            escrow: escrow.key(),
+            refunded_amount: payout.maker_amount.checked_sub(cancel_fee).ok_or(EscrowError::Overflow)?,
+            cancel_fee,
+            total_released: escrow.released_amount,
+            total_refunded: escrow.refunded_amount,
+            total_fees_paid: escrow.fees_paid,
+            milestones_released,
+            milestones_cancelled,
+            beneficiary: escrow.beneficiary,
+            nonce: escrow.nonce,
+            reason: CancelReason::DisputeRuling,
+            routing,
+        });
+    } else {
+        emit!(EscrowCompleted {
+            escrow: escrow.key(),
+            total_released: escrow.released_amount,
+            total_refunded: escrow.refunded_amount,
+            total_fees_paid: escrow.fees_paid,
+            milestones_released,
+            milestones_cancelled,
+            beneficiary: escrow.beneficiary,
+            nonce: escrow.nonce,
+        });
+    }
+
+    verify_milestone_sum(escrow)?;
+    release_open_slot(escrow, ctx.accounts.maker_profile.as_deref_mut())?;
+
+    if auto_close {
+        let accounts = ctx.accounts;
+        auto_close_escrow(
+            &accounts.escrow_state,
+            &accounts.escrow_config,
+            &accounts.mint,
+            &mut accounts.vault,
+            accounts.maker.as_ref(),
+            Some(&accounts.maker_token_account),
+            &accounts.token_program,
+            accounts.escrow_metadata.as_ref(),
+        )?;
+    }
+
     Ok(())
 }
@@ -12,7 +12,7 @@ pub struct RevokeReceipt<'info> {
 
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_state.maker.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
         bump = escrow_state.bump,
     )]
     pub escrow_state: Account<'info, EscrowState>,
@@ -21,6 +21,7 @@ pub struct RevokeReceipt<'info> {
     #[account(
         seeds = [RECEIPT_SEED, escrow_state.key().as_ref()],
         bump,
+        constraint = Some(receipt_mint.key()) != escrow_state.voided_receipt_mint @ EscrowError::ReceiptVoided,
         constraint = Some(receipt_mint.key()) == escrow_state.receipt_mint @ EscrowError::MintMismatch,
         constraint = receipt_mint.supply == 0 @ EscrowError::ReceiptNotBurned,
     )]
@@ -34,9 +35,12 @@ pub fn handler(ctx: Context<RevokeReceipt>) -> Result<()> {
     // Clear the receipt_mint — this re-enables transfer_claim
     escrow.receipt_mint = None;
 
+    escrow.bump_nonce()?;
+
     emit!(ReceiptRevoked {
         escrow: escrow.key(),
         receipt_mint: receipt_mint_key,
+        nonce: escrow.nonce,
     });
 
     Ok(())
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::CrankScheduled;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(kind: CrankKind)]
+pub struct ScheduleCrank<'info> {
+    /// Anyone can queue a crank; they fund the schedule's rent.
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + CrankSchedule::INIT_SPACE,
+        seeds = [CRANK_SCHEDULE_SEED, escrow_state.key().as_ref(), creator.key().as_ref(), &[kind.tag()]],
+        bump,
+    )]
+    pub crank_schedule: Account<'info, CrankSchedule>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Queues `kind` to fire on this escrow at or after `eta`. Only the shape of
+/// the schedule is checked here; the target instruction's own checks run when
+/// `execute_scheduled_crank` fires it.
+pub fn handler(ctx: Context<ScheduleCrank>, kind: CrankKind, eta: i64) -> Result<()> {
+    let escrow = &ctx.accounts.escrow_state;
+
+    require!(
+        !matches!(
+            escrow.status,
+            EscrowStatus::Completed | EscrowStatus::Cancelled | EscrowStatus::Expired
+        ),
+        EscrowError::EscrowNotActive
+    );
+    if let CrankKind::ReleaseMilestone { index, interval } = kind {
+        require!(
+            (index as usize) < escrow.milestones.len() && interval >= 0,
+            EscrowError::InvalidCrankSchedule
+        );
+    }
+
+    let schedule = &mut ctx.accounts.crank_schedule;
+    schedule.escrow = escrow.key();
+    schedule.creator = ctx.accounts.creator.key();
+    schedule.kind = kind;
+    schedule.eta = eta;
+    schedule.executions = 0;
+    schedule.bump = ctx.bumps.crank_schedule;
+
+    emit!(CrankScheduled {
+        schedule: schedule.key(),
+        escrow: schedule.escrow,
+        creator: schedule.creator,
+        kind,
+        eta,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::EscrowError;
+use crate::events::BondSettled;
+use crate::helpers::{assert_vault_clean, bond_split, escrow_seeds, transfer_from_vault};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct SettleBond<'info> {
+    /// Anyone may settle; the bond only ever goes to the maker and the taker.
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = escrow_state.vault @ EscrowError::VaultMismatch,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == escrow_state.maker @ EscrowError::OwnerMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = taker_token_account.owner == escrow_state.taker @ EscrowError::OwnerMismatch,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub taker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Pay out the taker bond of a terminal escrow as `bond_split` decides.
+/// Must run before the escrow can be closed.
+pub fn handler(ctx: Context<SettleBond>) -> Result<()> {
+    assert_vault_clean(&ctx.accounts.vault)?;
+
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+    require!(escrow.bond_balance > 0, EscrowError::NoBondHeld);
+
+    let (to_maker, to_taker) = bond_split(escrow)?;
+    escrow.bond_balance = 0;
+
+    let creator_key = escrow.creator;
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    let decimals = ctx.accounts.mint.decimals;
+    transfer_from_vault(
+        &ctx.accounts.vault, &ctx.accounts.mint,
+        &ctx.accounts.maker_token_account,
+        escrow.to_account_info(), &ctx.accounts.token_program,
+        signer_seeds, to_maker, decimals,
+    )?;
+    transfer_from_vault(
+        &ctx.accounts.vault, &ctx.accounts.mint,
+        &ctx.accounts.taker_token_account,
+        escrow.to_account_info(), &ctx.accounts.token_program,
+        signer_seeds, to_taker, decimals,
+    )?;
+
+    escrow.bump_nonce()?;
+
+    emit!(BondSettled {
+        escrow: escrow.key(),
+        to_maker,
+        to_taker,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
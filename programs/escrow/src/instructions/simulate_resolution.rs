@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::helpers::resolution_breakdown;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct SimulateResolution<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+}
+
+/// Read-only: no status requirement, no state changes, no CPIs.
+pub fn handler(
+    ctx: Context<SimulateResolution>,
+    resolution: DisputeResolution,
+) -> Result<ResolutionBreakdown> {
+    resolution_breakdown(&ctx.accounts.escrow_state, &resolution)
+}
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
 use anchor_spl::token::{Mint, TokenAccount};
 
 use crate::error::EscrowError;
-use crate::events::BeneficiarySynced;
+use crate::events::{BeneficiarySynced, CrankRewarded, KeeperTipPaid};
+use crate::helpers::pay_crank_tip;
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -13,7 +15,7 @@ pub struct SyncBeneficiary<'info> {
 
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_state.maker.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
         bump = escrow_state.bump,
     )]
     pub escrow_state: Account<'info, EscrowState>,
@@ -22,6 +24,7 @@ pub struct SyncBeneficiary<'info> {
     #[account(
         seeds = [RECEIPT_SEED, escrow_state.key().as_ref()],
         bump,
+        constraint = Some(receipt_mint.key()) != escrow_state.voided_receipt_mint @ EscrowError::ReceiptVoided,
         constraint = Some(receipt_mint.key()) == escrow_state.receipt_mint @ EscrowError::MintMismatch,
     )]
     pub receipt_mint: Account<'info, Mint>,
@@ -32,10 +35,19 @@ pub struct SyncBeneficiary<'info> {
         constraint = receipt_token_account.amount == 1 @ EscrowError::InvalidReceiptHolder,
     )]
     pub receipt_token_account: Account<'info, TokenAccount>,
+
+    /// `payer`'s keeper entry; when present, a state-changing crank pays it the escrow's tip.
+    #[account(
+        mut,
+        seeds = [KEEPER_SEED, payer.key().as_ref()],
+        bump = keeper.bump,
+    )]
+    pub keeper: Option<Account<'info, Keeper>>,
 }
 
 pub fn handler(ctx: Context<SyncBeneficiary>) -> Result<()> {
     let escrow = &mut ctx.accounts.escrow_state;
+    let nonce_before = escrow.nonce;
 
     // Allow sync in Active or Disputed states (needed to unblock resolve_dispute/claim_expired
     // when receipt NFT changes hands during a dispute)
@@ -66,12 +78,39 @@ pub fn handler(ctx: Context<SyncBeneficiary>) -> Result<()> {
 
     let old_beneficiary = escrow.beneficiary;
     escrow.beneficiary = new_beneficiary;
+    if escrow.beneficiary_ata.is_some() {
+        escrow.beneficiary_ata = Some(get_associated_token_address_with_program_id(
+            &new_beneficiary,
+            &escrow.mint,
+            &escrow.token_program,
+        ));
+    }
+
+    escrow.bump_nonce()?;
 
     emit!(BeneficiarySynced {
         escrow: escrow.key(),
         old_beneficiary,
         new_beneficiary,
+        nonce: escrow.nonce,
     });
 
+    let tip = pay_crank_tip(escrow, ctx.accounts.keeper.as_mut(), nonce_before)?;
+    if tip > 0 {
+        emit!(KeeperTipPaid {
+            escrow: escrow.key(),
+            keeper: ctx.accounts.payer.key(),
+            amount: tip,
+            tip_jar: escrow.tip_jar,
+            nonce: escrow.nonce,
+        });
+        emit!(CrankRewarded {
+            escrow: escrow.key(),
+            keeper: ctx.accounts.payer.key(),
+            reward: tip,
+            instruction: CrankInstruction::SyncBeneficiary,
+        });
+    }
+
     Ok(())
 }
@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
 
 use crate::error::EscrowError;
 use crate::events::ClaimTransferred;
+use crate::helpers::verify_expected_nonce;
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -11,7 +13,7 @@ pub struct TransferClaim<'info> {
 
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_state.maker.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
         bump = escrow_state.bump,
         constraint = escrow_state.beneficiary == beneficiary.key() @ EscrowError::NotBeneficiary,
     )]
@@ -21,11 +23,15 @@ pub struct TransferClaim<'info> {
     pub new_beneficiary: UncheckedAccount<'info>,
 }
 
-pub fn handler(ctx: Context<TransferClaim>) -> Result<()> {
+pub fn handler(ctx: Context<TransferClaim>, expected_nonce: Option<u64>) -> Result<()> {
     let escrow = &mut ctx.accounts.escrow_state;
 
+    verify_expected_nonce(escrow, expected_nonce)?;
+
     // Block transfer_claim when receipt NFT exists — use NFT transfer + sync_beneficiary instead
     require!(escrow.receipt_mint.is_none(), EscrowError::ReceiptExists);
+    // Shares are the claim once fractionalized — they trade as SPL tokens instead
+    require!(escrow.share_mint.is_none(), EscrowError::ClaimIsFractionalized);
 
     require!(escrow.status == EscrowStatus::Active, EscrowError::EscrowNotActive);
 
@@ -43,11 +49,21 @@ pub fn handler(ctx: Context<TransferClaim>) -> Result<()> {
 
     let old_beneficiary = escrow.beneficiary;
     escrow.beneficiary = ctx.accounts.new_beneficiary.key();
+    if escrow.beneficiary_ata.is_some() {
+        escrow.beneficiary_ata = Some(get_associated_token_address_with_program_id(
+            &escrow.beneficiary,
+            &escrow.mint,
+            &escrow.token_program,
+        ));
+    }
+
+    escrow.bump_nonce()?;
 
     emit!(ClaimTransferred {
         escrow: escrow.key(),
         from: old_beneficiary,
         to: escrow.beneficiary,
+        nonce: escrow.nonce,
     });
 
     Ok(())
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::MakerTransferred;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct TransferMaker<'info> {
+    /// Current maker must sign to hand over the maker role.
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// CHECK: The new maker receiving refund rights and approval authority.
+    pub new_maker: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<TransferMaker>) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+    let new_maker = ctx.accounts.new_maker.key();
+
+    require!(new_maker != Pubkey::default(), EscrowError::InvalidMaker);
+    require!(new_maker != escrow.maker, EscrowError::InvalidMaker);
+    require!(new_maker != escrow.taker, EscrowError::InvalidMaker);
+    require!(new_maker != escrow.beneficiary, EscrowError::InvalidMaker);
+
+    // PDA address stays derived from `creator`, so only the role moves
+    let old_maker = escrow.maker;
+    escrow.maker = new_maker;
+
+    escrow.bump_nonce()?;
+
+    emit!(MakerTransferred {
+        escrow: escrow.key(),
+        from: old_maker,
+        to: new_maker,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+use crate::events::MilestoneUnapproved;
+use crate::helpers::verify_milestone_sum;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct UnapproveMilestone<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+        constraint = escrow_state.maker == maker.key() @ EscrowError::NotMaker,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+}
+
+pub fn handler(ctx: Context<UnapproveMilestone>, milestone_index: u8) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    require!(escrow.status == EscrowStatus::Active, EscrowError::EscrowNotActive);
+
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp <= escrow.expires_at, EscrowError::EscrowExpired);
+
+    let idx = milestone_index as usize;
+    require!(idx < escrow.milestones.len(), EscrowError::MilestoneIndexOutOfBounds);
+    require!(
+        escrow.milestones[idx].status == MilestoneStatus::Approved,
+        EscrowError::MilestoneNotApproved
+    );
+
+    // Only revertible while the challenge window is still open
+    let releasable_at = escrow.milestones[idx]
+        .approved_at
+        .checked_add(escrow.release_delay)
+        .ok_or(EscrowError::Overflow)?;
+    require!(clock.unix_timestamp < releasable_at, EscrowError::ChallengeWindowClosed);
+
+    escrow.milestones[idx].status = MilestoneStatus::Pending;
+    escrow.milestones[idx].approved_at = 0;
+
+    escrow.bump_nonce()?;
+
+    emit!(MilestoneUnapproved {
+        escrow: escrow.key(),
+        milestone_index,
+        nonce: escrow.nonce,
+    });
+
+    verify_milestone_sum(escrow)?;
+
+    Ok(())
+}
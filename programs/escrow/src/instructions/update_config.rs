@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
 use crate::error::EscrowError;
-use crate::events::ConfigUpdated;
-use crate::state::{EscrowConfig, ESCROW_CONFIG_SEED, MAX_DISPUTE_TIMEOUT};
+use crate::events::{ConfigUpdated, ReceiptMintingToggled};
+use crate::state::{
+    CancelFeeRecipient, CancelReason, CancelRouteUpdate, CancelRouting, DisputeTimeoutPolicy, EscrowConfig, RemainderPolicy, CONFIG_VERSION, ESCROW_CONFIG_SEED, MAX_APPROVAL_EXTENSION,
+    MAX_CLAW_BACK_SECONDS, MAX_DISPUTE_TIMEOUT, MAX_RELEASE_DELAY, MAX_REQUEST_TTL,
+};
 
 #[derive(Accounts)]
 pub struct UpdateConfig<'info> {
@@ -11,6 +14,7 @@ pub struct UpdateConfig<'info> {
         mut,
         seeds = [ESCROW_CONFIG_SEED],
         bump = escrow_config.bump,
+        constraint = escrow_config.config_version == CONFIG_VERSION @ EscrowError::ConfigVersionMismatch,
         constraint = escrow_config.authority == authority.key() @ EscrowError::NotAuthority,
     )]
     pub escrow_config: Account<'info, EscrowConfig>,
@@ -19,11 +23,34 @@ pub struct UpdateConfig<'info> {
     pub fee_collector: UncheckedAccount<'info>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<UpdateConfig>,
     new_authority: Option<Pubkey>,
     fee_bps: Option<u16>,
     dispute_timeout: Option<i64>,
+    release_delay: Option<i64>,
+    max_disputes_per_escrow: Option<u8>,
+    accepted_hash_algos: Option<u8>,
+    close_dust_threshold: Option<u64>,
+    dispute_timeout_policy: Option<DisputeTimeoutPolicy>,
+    request_ttl: Option<i64>,
+    claw_back_seconds: Option<i64>,
+    approval_extension: Option<i64>,
+    max_expiry_extension: Option<i64>,
+    remainder_policy: Option<RemainderPolicy>,
+    allow_fee_exempt: Option<bool>,
+    max_open_escrows_per_maker: Option<u16>,
+    expiry_extension_per_approval: Option<i64>,
+    flat_fee: Option<u64>,
+    flat_fee_mint: Option<Pubkey>,
+    cancel_fee_bps: Option<u16>,
+    cancel_fee_recipient: Option<CancelFeeRecipient>,
+    receipts_enabled: Option<bool>,
+    fee_free_below: Option<u64>,
+    max_milestone_skew_bps: Option<u16>,
+    creation_paused: Option<bool>,
+    cancel_route: Option<CancelRouteUpdate>,
 ) -> Result<()> {
     let config = &mut ctx.accounts.escrow_config;
 
@@ -37,6 +64,118 @@ pub fn handler(
         config.dispute_timeout = timeout;
     }
 
+    if let Some(delay) = release_delay {
+        require!((0..=MAX_RELEASE_DELAY).contains(&delay), EscrowError::InvalidReleaseDelay);
+        config.release_delay = delay;
+    }
+
+    if let Some(cap) = max_disputes_per_escrow {
+        config.max_disputes_per_escrow = cap;
+    }
+
+    if let Some(mask) = accepted_hash_algos {
+        require!(mask != 0, EscrowError::UnsupportedHashAlgo);
+        config.accepted_hash_algos = mask;
+    }
+
+    if let Some(threshold) = close_dust_threshold {
+        config.close_dust_threshold = threshold;
+    }
+
+    if let Some(policy) = dispute_timeout_policy {
+        config.dispute_timeout_policy = policy;
+    }
+
+    if let Some(ttl) = request_ttl {
+        require!(ttl > 0 && ttl <= MAX_REQUEST_TTL, EscrowError::InvalidRequestTtl);
+        config.request_ttl = ttl;
+    }
+
+    if let Some(window) = claw_back_seconds {
+        require!((0..=MAX_CLAW_BACK_SECONDS).contains(&window), EscrowError::InvalidClawBackWindow);
+        config.claw_back_seconds = window;
+    }
+
+    if let Some(window) = approval_extension {
+        require!((0..=MAX_APPROVAL_EXTENSION).contains(&window), EscrowError::InvalidApprovalExtension);
+        config.approval_extension = window;
+    }
+
+    if let Some(cap) = max_expiry_extension {
+        require!((0..=MAX_APPROVAL_EXTENSION).contains(&cap), EscrowError::InvalidApprovalExtension);
+        config.max_expiry_extension = cap;
+    }
+
+    if let Some(policy) = remainder_policy {
+        config.remainder_policy = policy;
+    }
+
+    if let Some(allow) = allow_fee_exempt {
+        config.allow_fee_exempt = allow;
+    }
+
+    if let Some(cap) = max_open_escrows_per_maker {
+        config.max_open_escrows_per_maker = cap;
+    }
+
+    if let Some(extension) = expiry_extension_per_approval {
+        require!((0..=MAX_APPROVAL_EXTENSION).contains(&extension), EscrowError::InvalidApprovalExtension);
+        config.expiry_extension_per_approval = extension;
+    }
+
+    if let Some(fee) = flat_fee {
+        config.flat_fee = fee;
+    }
+
+    if let Some(mint) = flat_fee_mint {
+        config.flat_fee_mint = mint;
+    }
+
+    if let Some(bps) = cancel_fee_bps {
+        require!(bps <= 10_000, EscrowError::InvalidFeeRate);
+        config.cancel_fee_bps = bps;
+    }
+
+    if let Some(recipient) = cancel_fee_recipient {
+        config.cancel_fee_recipient = recipient;
+    }
+
+    if let Some(CancelRouteUpdate { reason, routing }) = cancel_route {
+        require!(routing.fee_bps <= 10_000, EscrowError::InvalidFeeRate);
+        config.cancel_routes[reason as usize] = routing;
+        if reason == CancelReason::MakerCancel {
+            config.cancel_fee_bps = routing.fee_bps;
+            config.cancel_fee_recipient = routing.fee_recipient;
+        }
+    }
+    // The legacy cancel-fee fields are the MakerCancel route
+    config.cancel_routes[CancelReason::MakerCancel as usize] = CancelRouting {
+        fee_bps: config.cancel_fee_bps,
+        fee_recipient: config.cancel_fee_recipient,
+    };
+
+    if let Some(enabled) = receipts_enabled {
+        if enabled != config.receipts_enabled {
+            config.receipts_enabled = enabled;
+            emit!(ReceiptMintingToggled {
+                authority: ctx.accounts.authority.key(),
+                enabled,
+            });
+        }
+    }
+
+    if let Some(threshold) = fee_free_below {
+        config.fee_free_below = threshold;
+    }
+
+    if let Some(bps) = max_milestone_skew_bps {
+        config.max_milestone_skew_bps = bps;
+    }
+
+    if let Some(paused) = creation_paused {
+        config.creation_paused = paused;
+    }
+
     let new_fee_collector = ctx.accounts.fee_collector.key();
     require!(new_fee_collector != Pubkey::default(), EscrowError::InvalidFeeCollector);
     if new_fee_collector != config.fee_collector {
@@ -53,6 +192,28 @@ pub fn handler(
         fee_bps: config.fee_bps,
         fee_collector: config.fee_collector,
         dispute_timeout: config.dispute_timeout,
+        release_delay: config.release_delay,
+        max_disputes_per_escrow: config.max_disputes_per_escrow,
+        accepted_hash_algos: config.accepted_hash_algos,
+        close_dust_threshold: config.close_dust_threshold,
+        dispute_timeout_policy: config.dispute_timeout_policy,
+        request_ttl: config.request_ttl,
+        claw_back_seconds: config.claw_back_seconds,
+        approval_extension: config.approval_extension,
+        max_expiry_extension: config.max_expiry_extension,
+        remainder_policy: config.remainder_policy,
+        allow_fee_exempt: config.allow_fee_exempt,
+        max_open_escrows_per_maker: config.max_open_escrows_per_maker,
+        expiry_extension_per_approval: config.expiry_extension_per_approval,
+        flat_fee: config.flat_fee,
+        flat_fee_mint: config.flat_fee_mint,
+        cancel_fee_bps: config.cancel_fee_bps,
+        cancel_fee_recipient: config.cancel_fee_recipient,
+        receipts_enabled: config.receipts_enabled,
+        fee_free_below: config.fee_free_below,
+        max_milestone_skew_bps: config.max_milestone_skew_bps,
+        creation_paused: config.creation_paused,
+        cancel_routes: config.cancel_routes,
     });
 
     Ok(())
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::EscrowError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct VerifyReceipt<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// Must be the escrow's PDA-derived receipt, still current and not burned.
+    #[account(
+        seeds = [RECEIPT_SEED, escrow_state.key().as_ref()],
+        bump,
+        constraint = Some(receipt_mint.key()) != escrow_state.voided_receipt_mint @ EscrowError::ReceiptVoided,
+        constraint = Some(receipt_mint.key()) == escrow_state.receipt_mint @ EscrowError::MintMismatch,
+        constraint = receipt_mint.supply == 1 @ EscrowError::ReceiptSupplyInvalid,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+}
+
+/// Read-only: confirms a receipt NFT is backed by a live escrow, for buyers
+/// and marketplaces checking it before a purchase. Fails unless the escrow is
+/// Active; otherwise returns who the claim currently pays and what is left.
+pub fn handler(ctx: Context<VerifyReceipt>) -> Result<ReceiptVerification> {
+    let escrow = &ctx.accounts.escrow_state;
+    require!(escrow.status == EscrowStatus::Active, EscrowError::EscrowNotActive);
+
+    Ok(ReceiptVerification {
+        escrow: escrow.key(),
+        receipt_mint: ctx.accounts.receipt_mint.key(),
+        beneficiary: escrow.beneficiary,
+        remaining_amount: escrow.remaining_amount()?,
+        nonce: escrow.nonce,
+    })
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+use crate::error::EscrowError;
+use crate::events::HeldFundsWithdrawn;
+use crate::helpers::{escrow_seeds, transfer_from_vault};
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct WithdrawHeld<'info> {
+    #[account(
+        mut,
+        constraint = beneficiary.key() == escrow_state.milestone_payee(milestone_index) @ EscrowError::NotBeneficiary,
+    )]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_state.creator.as_ref(), escrow_state.seed.to_le_bytes().as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(constraint = mint.key() == escrow_state.mint @ EscrowError::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [HOLDING_SEED, escrow_state.key().as_ref(), &[milestone_index]],
+        bump,
+    )]
+    pub holding_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+        constraint = beneficiary_token_account.owner == escrow_state.milestone_payee(milestone_index) @ EscrowError::OwnerMismatch,
+        constraint = escrow_state.milestone_beneficiary(milestone_index).is_some()
+            || escrow_state.beneficiary_ata.is_none_or(|ata| ata == beneficiary_token_account.key()) @ EscrowError::BeneficiaryAtaMismatch,
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.token_program @ EscrowError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Pay out a milestone's held payout once its claw-back window has passed,
+/// closing the holding account to the milestone's payee. `SETTLEMENT_HOLDING_INDEX`
+/// withdraws a held dispute payout to the escrow beneficiary.
+pub fn handler(ctx: Context<WithdrawHeld>, milestone_index: u8) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_state;
+
+    require!(!escrow.on_hold, EscrowError::EscrowOnHold);
+
+    require!(
+        milestone_index == SETTLEMENT_HOLDING_INDEX || (milestone_index as usize) < escrow.milestones.len(),
+        EscrowError::MilestoneIndexOutOfBounds
+    );
+    let held_until = escrow.held_until(milestone_index);
+    require!(held_until != 0, EscrowError::NothingHeld);
+
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp >= held_until, EscrowError::ClawBackWindowOpen);
+
+    escrow.set_held_until(milestone_index, 0)?;
+
+    let creator_key = escrow.creator;
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let bump = [escrow.bump];
+    let inner = escrow_seeds(&creator_key, &seed_bytes, &bump);
+    let signer_seeds: &[&[&[u8]]] = &[&inner];
+
+    let amount = ctx.accounts.holding_account.amount;
+    transfer_from_vault(
+        &ctx.accounts.holding_account, &ctx.accounts.mint,
+        &ctx.accounts.beneficiary_token_account,
+        escrow.to_account_info(), &ctx.accounts.token_program,
+        signer_seeds, amount, ctx.accounts.mint.decimals,
+    )?;
+
+    let close_accounts = CloseAccount {
+        account: ctx.accounts.holding_account.to_account_info(),
+        destination: ctx.accounts.beneficiary.to_account_info(),
+        authority: escrow.to_account_info(),
+    };
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        close_accounts,
+        signer_seeds,
+    ))?;
+
+    escrow.bump_nonce()?;
+
+    emit!(HeldFundsWithdrawn {
+        escrow: escrow.key(),
+        milestone_index,
+        beneficiary: ctx.accounts.beneficiary.key(),
+        amount,
+        nonce: escrow.nonce,
+    });
+
+    Ok(())
+}
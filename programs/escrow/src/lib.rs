@@ -1,4 +1,6 @@
 #![allow(unexpected_cfgs)]
+// The generated CPI client mirrors the handlers' argument lists.
+#![cfg_attr(feature = "cpi", allow(clippy::too_many_arguments))]
 
 use anchor_lang::prelude::*;
 
@@ -9,9 +11,14 @@ pub mod events;
 pub mod helpers;
 pub mod instructions;
 pub mod state;
+pub mod version;
 
 use instructions::*;
-use state::{DisputeResolution, MilestoneInput};
+use state::{
+    CancelFeeRecipient, CancelRouteUpdate, CrankKind, DisputeResolution, DisputeTimeoutPolicy, EffectiveConfig, EscrowProgress, EscrowTerms,
+    FeeRefundRecipient, MilestoneInput, MutualAction, ReceiptVerification, RemainderPolicy, ResolutionBreakdown,
+};
+use version::ProgramVersion;
 
 #[program]
 pub mod escrow {
@@ -25,28 +32,144 @@ pub mod escrow {
         instructions::initialize_config::handler(ctx, fee_bps, dispute_timeout)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_escrow(
         ctx: Context<CreateEscrow>,
         seed: u64,
         amount: u64,
         milestones: Vec<MilestoneInput>,
         expires_at: i64,
+        category: u8,
+        initial_beneficiary: Option<Pubkey>,
+        hash_algo: u8,
+        acceptance_deadline: Option<i64>,
+        auto_release: bool,
+        claw_back_seconds: i64,
+        disputes_enabled: bool,
+        dispute_initiators: u8,
+        fee_on_top: bool,
+        agreement_hash: Option<[u8; 32]>,
+        taker_bond: u64,
     ) -> Result<()> {
-        instructions::create_escrow::handler(ctx, seed, amount, milestones, expires_at)
+        instructions::create_escrow::handler(
+            ctx,
+            seed,
+            amount,
+            milestones,
+            expires_at,
+            category,
+            initial_beneficiary,
+            hash_algo,
+            acceptance_deadline,
+            auto_release,
+            claw_back_seconds,
+            disputes_enabled,
+            dispute_initiators,
+            fee_on_top,
+            agreement_hash,
+            taker_bond,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_escrow_with_ref(
+        ctx: Context<CreateEscrow>,
+        seed: u64,
+        amount: u64,
+        milestones: Vec<MilestoneInput>,
+        expires_at: i64,
+        category: u8,
+        external_ref: [u8; 32],
+        initial_beneficiary: Option<Pubkey>,
+        hash_algo: u8,
+        acceptance_deadline: Option<i64>,
+        auto_release: bool,
+        claw_back_seconds: i64,
+        disputes_enabled: bool,
+        dispute_initiators: u8,
+        fee_on_top: bool,
+        agreement_hash: Option<[u8; 32]>,
+        taker_bond: u64,
+    ) -> Result<()> {
+        instructions::create_escrow_with_ref::handler(
+            ctx,
+            seed,
+            amount,
+            milestones,
+            expires_at,
+            category,
+            external_ref,
+            initial_beneficiary,
+            hash_algo,
+            acceptance_deadline,
+            auto_release,
+            claw_back_seconds,
+            disputes_enabled,
+            dispute_initiators,
+            fee_on_top,
+            agreement_hash,
+            taker_bond,
+        )
+    }
+
+    pub fn renew_escrow(
+        ctx: Context<RenewEscrow>,
+        top_up: u64,
+        milestones: Vec<MilestoneInput>,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::renew_escrow::handler(ctx, top_up, milestones, expires_at)
+    }
+
+    pub fn accept_escrow(ctx: Context<AcceptEscrow>) -> Result<()> {
+        instructions::accept_escrow::handler(ctx)
     }
 
     pub fn approve_milestone(
         ctx: Context<ApproveMilestone>,
         milestone_index: u8,
+        expected_amount: u64,
+        expected_description_hash: [u8; 32],
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        instructions::approve_milestone::handler(
+            ctx,
+            milestone_index,
+            expected_amount,
+            expected_description_hash,
+            expected_nonce,
+        )
+    }
+
+    pub fn approve_milestone_signed(
+        ctx: Context<ApproveMilestoneSigned>,
+        milestone_index: u8,
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::approve_milestone_signed::handler(ctx, milestone_index, nonce)
+    }
+
+    pub fn unapprove_milestone(
+        ctx: Context<UnapproveMilestone>,
+        milestone_index: u8,
     ) -> Result<()> {
-        instructions::approve_milestone::handler(ctx, milestone_index)
+        instructions::unapprove_milestone::handler(ctx, milestone_index)
     }
 
     pub fn release_milestone(
         ctx: Context<ReleaseMilestone>,
         milestone_index: u8,
+        expected_nonce: Option<u64>,
+        auto_close: bool,
+    ) -> Result<()> {
+        instructions::release_milestone::handler(ctx, milestone_index, expected_nonce, auto_close)
+    }
+
+    pub fn approve_and_release(
+        ctx: Context<ApproveAndRelease>,
+        milestone_index: u8,
     ) -> Result<()> {
-        instructions::release_milestone::handler(ctx, milestone_index)
+        instructions::approve_and_release::handler(ctx, milestone_index)
     }
 
     pub fn initiate_dispute(
@@ -56,36 +179,115 @@ pub mod escrow {
         instructions::initiate_dispute::handler(ctx, reason_hash)
     }
 
-    pub fn resolve_dispute(
-        ctx: Context<ResolveDispute>,
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>,
         resolution: DisputeResolution,
+        expected_nonce: Option<u64>,
+        auto_close: bool,
+        loser_pays_fee: bool,
     ) -> Result<()> {
-        instructions::resolve_dispute::handler(ctx, resolution)
+        instructions::resolve_dispute::handler(ctx, resolution, expected_nonce, auto_close, loser_pays_fee)
+    }
+
+    pub fn simulate_resolution(
+        ctx: Context<SimulateResolution>,
+        resolution: DisputeResolution,
+    ) -> Result<ResolutionBreakdown> {
+        instructions::simulate_resolution::handler(ctx, resolution)
     }
 
-    pub fn cancel_escrow(ctx: Context<CancelEscrow>) -> Result<()> {
-        instructions::cancel_escrow::handler(ctx)
+    pub fn cancel_escrow(
+        ctx: Context<CancelEscrow>,
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        instructions::cancel_escrow::handler(ctx, expected_nonce)
     }
 
-    pub fn claim_expired(ctx: Context<ClaimExpired>) -> Result<()> {
+    /// Deprecated: use `claim_active_expired` or `claim_dispute_timeout`.
+    pub fn claim_expired<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimExpired<'info>>) -> Result<()> {
         instructions::claim_expired::handler(ctx)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update_config(
         ctx: Context<UpdateConfig>,
         new_authority: Option<Pubkey>,
         fee_bps: Option<u16>,
         dispute_timeout: Option<i64>,
+        release_delay: Option<i64>,
+        max_disputes_per_escrow: Option<u8>,
+        accepted_hash_algos: Option<u8>,
+        close_dust_threshold: Option<u64>,
+        dispute_timeout_policy: Option<DisputeTimeoutPolicy>,
+        request_ttl: Option<i64>,
+        claw_back_seconds: Option<i64>,
+        approval_extension: Option<i64>,
+        max_expiry_extension: Option<i64>,
+        remainder_policy: Option<RemainderPolicy>,
+        allow_fee_exempt: Option<bool>,
+        max_open_escrows_per_maker: Option<u16>,
+        expiry_extension_per_approval: Option<i64>,
+        flat_fee: Option<u64>,
+        flat_fee_mint: Option<Pubkey>,
+        cancel_fee_bps: Option<u16>,
+        cancel_fee_recipient: Option<CancelFeeRecipient>,
+        receipts_enabled: Option<bool>,
+        fee_free_below: Option<u64>,
+        max_milestone_skew_bps: Option<u16>,
+        creation_paused: Option<bool>,
+        cancel_route: Option<CancelRouteUpdate>,
     ) -> Result<()> {
-        instructions::update_config::handler(ctx, new_authority, fee_bps, dispute_timeout)
+        instructions::update_config::handler(
+            ctx,
+            new_authority,
+            fee_bps,
+            dispute_timeout,
+            release_delay,
+            max_disputes_per_escrow,
+            accepted_hash_algos,
+            close_dust_threshold,
+            dispute_timeout_policy,
+            request_ttl,
+            claw_back_seconds,
+            approval_extension,
+            max_expiry_extension,
+            remainder_policy,
+            allow_fee_exempt,
+            max_open_escrows_per_maker,
+            expiry_extension_per_approval,
+            flat_fee,
+            flat_fee_mint,
+            cancel_fee_bps,
+            cancel_fee_recipient,
+            receipts_enabled,
+            fee_free_below,
+            max_milestone_skew_bps,
+            creation_paused,
+            cancel_route,
+        )
+    }
+
+    pub fn hold_escrow(ctx: Context<HoldEscrow>) -> Result<()> {
+        instructions::hold_escrow::handler(ctx)
+    }
+
+    pub fn release_hold(ctx: Context<ReleaseHold>) -> Result<()> {
+        instructions::release_hold::handler(ctx)
     }
 
     pub fn close_escrow(ctx: Context<CloseEscrow>) -> Result<()> {
         instructions::close_escrow::handler(ctx)
     }
 
-    pub fn transfer_claim(ctx: Context<TransferClaim>) -> Result<()> {
-        instructions::transfer_claim::handler(ctx)
+    pub fn transfer_claim(
+        ctx: Context<TransferClaim>,
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        instructions::transfer_claim::handler(ctx, expected_nonce)
+    }
+
+    pub fn transfer_maker(ctx: Context<TransferMaker>) -> Result<()> {
+        instructions::transfer_maker::handler(ctx)
     }
 
     pub fn mint_receipt(ctx: Context<MintReceipt>) -> Result<()> {
@@ -99,4 +301,264 @@ pub mod escrow {
     pub fn revoke_receipt(ctx: Context<RevokeReceipt>) -> Result<()> {
         instructions::revoke_receipt::handler(ctx)
     }
+
+    pub fn fractionalize_claim(
+        ctx: Context<FractionalizeClaim>,
+        total_shares: u64,
+    ) -> Result<()> {
+        instructions::fractionalize_claim::handler(ctx, total_shares)
+    }
+
+    pub fn redeem_shares(ctx: Context<RedeemShares>, shares: u64) -> Result<()> {
+        instructions::redeem_shares::handler(ctx, shares)
+    }
+
+    pub fn propose_escrow(
+        ctx: Context<ProposeEscrow>,
+        seed: u64,
+        terms: EscrowTerms,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::propose_escrow::handler(ctx, seed, terms, expires_at)
+    }
+
+    pub fn counter_proposal(ctx: Context<CounterProposal>, terms: EscrowTerms) -> Result<()> {
+        instructions::counter_proposal::handler(ctx, terms)
+    }
+
+    pub fn accept_proposal(ctx: Context<AcceptProposal>, terms_hash: [u8; 32]) -> Result<()> {
+        instructions::accept_proposal::handler(ctx, terms_hash)
+    }
+
+    pub fn close_proposal(ctx: Context<CloseProposal>) -> Result<()> {
+        instructions::close_proposal::handler(ctx)
+    }
+
+    pub fn attach_metadata(ctx: Context<AttachMetadata>, data: Vec<u8>) -> Result<()> {
+        instructions::attach_metadata::handler(ctx, data)
+    }
+
+    pub fn request_escrow(ctx: Context<RequestEscrow>, seed: u64, terms: EscrowTerms) -> Result<()> {
+        instructions::request_escrow::handler(ctx, seed, terms)
+    }
+
+    pub fn fund_request(ctx: Context<FundRequest>, seed: u64) -> Result<()> {
+        instructions::fund_request::handler(ctx, seed)
+    }
+
+    pub fn cancel_request(ctx: Context<CancelRequest>) -> Result<()> {
+        instructions::cancel_request::handler(ctx)
+    }
+
+    pub fn create_sub_escrow(
+        ctx: Context<CreateSubEscrow>,
+        seed: u64,
+        parent_milestone_index: u8,
+        terms: EscrowTerms,
+    ) -> Result<()> {
+        instructions::create_sub_escrow::handler(ctx, seed, parent_milestone_index, terms)
+    }
+
+    pub fn cancel_sub_escrow(ctx: Context<CancelSubEscrow>) -> Result<()> {
+        instructions::cancel_sub_escrow::handler(ctx)
+    }
+
+    pub fn cancel_and_release<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelAndRelease<'info>>,
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        instructions::cancel_and_release::handler(ctx, expected_nonce)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_escrow_with_receipt(
+        ctx: Context<CreateEscrowWithReceipt>,
+        seed: u64,
+        amount: u64,
+        milestones: Vec<MilestoneInput>,
+        expires_at: i64,
+        category: u8,
+        initial_beneficiary: Option<Pubkey>,
+        hash_algo: u8,
+    ) -> Result<()> {
+        instructions::create_escrow_with_receipt::handler(
+            ctx,
+            seed,
+            amount,
+            milestones,
+            expires_at,
+            category,
+            initial_beneficiary,
+            hash_algo,
+        )
+    }
+
+    pub fn claim_active_expired<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimActiveExpired<'info>>) -> Result<()> {
+        instructions::claim_active_expired::handler(ctx)
+    }
+
+    pub fn claim_dispute_timeout<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimDisputeTimeout<'info>>) -> Result<()> {
+        instructions::claim_dispute_timeout::handler(ctx)
+    }
+
+    pub fn approve_milestone_auto(
+        ctx: Context<ApproveAndRelease>,
+        milestone_index: u8,
+        expected_amount: u64,
+        expected_description_hash: [u8; 32],
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        instructions::approve_milestone_auto::handler(
+            ctx,
+            milestone_index,
+            expected_amount,
+            expected_description_hash,
+            expected_nonce,
+        )
+    }
+
+    pub fn register_keeper(ctx: Context<RegisterKeeper>, bond: u64) -> Result<()> {
+        instructions::register_keeper::handler(ctx, bond)
+    }
+
+    pub fn deregister_keeper(ctx: Context<DeregisterKeeper>) -> Result<()> {
+        instructions::deregister_keeper::handler(ctx)
+    }
+
+    pub fn claim_keeper_tips(ctx: Context<ClaimKeeperTips>) -> Result<()> {
+        instructions::claim_keeper_tips::handler(ctx)
+    }
+
+    pub fn fund_tip_jar(ctx: Context<FundTipJar>, crank_tip: u64, amount: u64) -> Result<()> {
+        instructions::fund_tip_jar::handler(ctx, crank_tip, amount)
+    }
+
+    pub fn withdraw_held(ctx: Context<WithdrawHeld>, milestone_index: u8) -> Result<()> {
+        instructions::withdraw_held::handler(ctx, milestone_index)
+    }
+
+    pub fn reclaim_held(ctx: Context<ReclaimHeld>, milestone_index: u8) -> Result<()> {
+        instructions::reclaim_held::handler(ctx, milestone_index)
+    }
+
+    pub fn reset_beneficiary(ctx: Context<ResetBeneficiary>, expected_nonce: Option<u64>) -> Result<()> {
+        instructions::reset_beneficiary::handler(ctx, expected_nonce)
+    }
+
+    pub fn refund_fee(ctx: Context<RefundFee>, amount: u64, recipient: FeeRefundRecipient) -> Result<()> {
+        instructions::refund_fee::handler(ctx, amount, recipient)
+    }
+
+    pub fn clawback<'info>(ctx: Context<'_, '_, 'info, 'info, Clawback<'info>>) -> Result<()> {
+        instructions::clawback::handler(ctx)
+    }
+
+    pub fn certify_milestone(ctx: Context<CertifyMilestone>, milestone_index: u8) -> Result<()> {
+        instructions::certify_milestone::handler(ctx, milestone_index)
+    }
+
+    pub fn record_approval(
+        ctx: Context<RecordApproval>,
+        action: MutualAction,
+        action_hash: [u8; 32],
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::record_approval::handler(ctx, action, action_hash, expires_at)
+    }
+
+    pub fn close_approval(ctx: Context<CloseApproval>) -> Result<()> {
+        instructions::close_approval::handler(ctx)
+    }
+
+    pub fn recover_receipt(ctx: Context<RecoverReceipt>, new_beneficiary: Pubkey) -> Result<()> {
+        instructions::recover_receipt::handler(ctx, new_beneficiary)
+    }
+
+    pub fn batch_escrow_progress<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchEscrowProgress<'info>>,
+    ) -> Result<Vec<EscrowProgress>> {
+        instructions::batch_escrow_progress::handler(ctx)
+    }
+
+    pub fn archive_and_close(ctx: Context<ArchiveAndClose>) -> Result<()> {
+        instructions::archive_and_close::handler(ctx)
+    }
+
+    pub fn close_archive(ctx: Context<CloseArchive>) -> Result<()> {
+        instructions::close_archive::handler(ctx)
+    }
+
+    pub fn get_config(ctx: Context<GetConfig>) -> Result<EffectiveConfig> {
+        instructions::get_config::handler(ctx)
+    }
+
+    pub fn acknowledge_agreement(ctx: Context<AcknowledgeAgreement>, agreement_hash: [u8; 32]) -> Result<()> {
+        instructions::acknowledge_agreement::handler(ctx, agreement_hash)
+    }
+
+    pub fn schedule_crank(ctx: Context<ScheduleCrank>, kind: CrankKind, eta: i64) -> Result<()> {
+        instructions::schedule_crank::handler(ctx, kind, eta)
+    }
+
+    pub fn execute_scheduled_crank<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteScheduledCrank<'info>>,
+    ) -> Result<()> {
+        instructions::execute_scheduled_crank::handler(ctx)
+    }
+
+    pub fn cancel_crank_schedule(ctx: Context<CancelCrankSchedule>) -> Result<()> {
+        instructions::cancel_crank_schedule::handler(ctx)
+    }
+
+    pub fn get_version(ctx: Context<GetVersion>) -> Result<ProgramVersion> {
+        instructions::get_version::handler(ctx)
+    }
+
+    pub fn verify_receipt(ctx: Context<VerifyReceipt>) -> Result<ReceiptVerification> {
+        instructions::verify_receipt::handler(ctx)
+    }
+
+    pub fn post_bond(ctx: Context<PostBond>) -> Result<()> {
+        instructions::post_bond::handler(ctx)
+    }
+
+    pub fn settle_bond(ctx: Context<SettleBond>) -> Result<()> {
+        instructions::settle_bond::handler(ctx)
+    }
+
+    pub fn propose_emergency_withdraw(ctx: Context<ProposeEmergencyWithdraw>, recovery: Pubkey) -> Result<()> {
+        instructions::propose_emergency_withdraw::handler(ctx, recovery)
+    }
+
+    pub fn execute_emergency_withdraw(ctx: Context<ExecuteEmergencyWithdraw>) -> Result<()> {
+        instructions::execute_emergency_withdraw::handler(ctx)
+    }
+
+    pub fn list_escrow(ctx: Context<ListEscrow>, category: u8, title_hash: [u8; 32]) -> Result<()> {
+        instructions::list_escrow::handler(ctx, category, title_hash)
+    }
+
+    pub fn delist_escrow(ctx: Context<DelistEscrow>) -> Result<()> {
+        instructions::delist_escrow::handler(ctx)
+    }
+
+    pub fn close_stale_listing(ctx: Context<CloseStaleListing>) -> Result<()> {
+        instructions::close_stale_listing::handler(ctx)
+    }
+
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate_config::handler(ctx)
+    }
+
+    pub fn derive_vault_address(ctx: Context<DeriveVaultAddress>, escrow: Pubkey) -> Result<Pubkey> {
+        instructions::derive_vault_address::handler(ctx, escrow)
+    }
+
+    pub fn cancel_emergency_withdraw(ctx: Context<CancelEmergencyWithdraw>) -> Result<()> {
+        instructions::cancel_emergency_withdraw::handler(ctx)
+    }
+
+    pub fn close_stale_emergency_withdraw(ctx: Context<CloseStaleEmergencyWithdraw>) -> Result<()> {
+        instructions::close_stale_emergency_withdraw::handler(ctx)
+    }
 }
@@ -1,11 +1,87 @@
 use anchor_lang::prelude::*;
 
-pub const MAX_MILESTONES: usize = 5;
+use crate::error::EscrowError;
+
+#[constant]
+pub const MAX_MILESTONES: u8 = 5;
+#[constant]
 pub const ESCROW_CONFIG_SEED: &[u8] = b"escrow_config";
+#[constant]
 pub const ESCROW_SEED: &[u8] = b"escrow";
+#[constant]
 pub const RECEIPT_SEED: &[u8] = b"receipt";
+pub const UNWRAP_SEED: &[u8] = b"unwrap";
+pub const SHARE_SEED: &[u8] = b"shares";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const METADATA_SEED: &[u8] = b"escrow_metadata";
+pub const REQUEST_SEED: &[u8] = b"escrow_request";
+pub const KEEPER_SEED: &[u8] = b"keeper";
+pub const HOLDING_SEED: &[u8] = b"holding";
+pub const MAKER_PROFILE_SEED: &[u8] = b"maker_profile";
+pub const APPROVAL_SEED: &[u8] = b"approval";
+pub const ARCHIVE_SEED: &[u8] = b"archive";
+pub const CRANK_SCHEDULE_SEED: &[u8] = b"crank_schedule";
+pub const EMERGENCY_WITHDRAW_SEED: &[u8] = b"emergency_withdraw";
+pub const LISTING_SEED: &[u8] = b"listing";
+pub const MAX_METADATA_LEN: usize = 1024;
+#[constant]
 pub const MIN_EXPIRATION_DURATION: i64 = 3600; // 1 hour minimum
+#[constant]
 pub const MAX_DISPUTE_TIMEOUT: i64 = 365 * 24 * 3600; // 1 year maximum
+pub const MAX_RELEASE_DELAY: i64 = 30 * 24 * 3600; // 30 days maximum
+pub const MAX_CLAW_BACK_SECONDS: i64 = 30 * 24 * 3600; // 30 days maximum
+pub const MAX_APPROVAL_EXTENSION: i64 = 30 * 24 * 3600; // 30 days maximum
+pub const DEFAULT_MAX_DISPUTES_PER_ESCROW: u8 = 3;
+pub const RENEWAL_WINDOW: i64 = 7 * 24 * 3600; // renewable within 7 days of expiry
+pub const DEFAULT_REQUEST_TTL: i64 = 7 * 24 * 3600; // 7 days
+pub const MAX_REQUEST_TTL: i64 = 90 * 24 * 3600; // 90 days maximum
+pub const MAX_APPROVAL_TTL: i64 = 30 * 24 * 3600; // 30 days maximum
+pub const RECEIPT_RECOVERY_DELAY: i64 = 30 * 24 * 3600; // authority fallback opens 30 days past expiry
+pub const ARCHIVE_RETENTION: i64 = 365 * 24 * 3600; // archives are kept at least 1 year
+#[constant]
+pub const EMERGENCY_WITHDRAW_DELAY: i64 = 7 * 24 * 3600; // parties get 7 days to object or exit
+/// Byte offset of `EscrowState::category` for `getProgramAccounts` memcmp filters:
+/// discriminator + 6 pubkeys + 4 u64s + status (unit enum, 1 byte).
+#[constant]
+pub const ESCROW_CATEGORY_OFFSET: u32 = 8 + 6 * 32 + 4 * 8 + 1;
+/// Byte offset of `Listing::category` for `getProgramAccounts` memcmp filters:
+/// discriminator + 3 pubkeys.
+pub const LISTING_CATEGORY_OFFSET: usize = 8 + 3 * 32;
+pub const HASH_ALGO_SHA256: u8 = 0;
+pub const HASH_ALGO_KECCAK256: u8 = 1;
+/// Default `accepted_hash_algos` bitmask: sha256 and keccak256.
+pub const DEFAULT_ACCEPTED_HASH_ALGOS: u8 = (1 << HASH_ALGO_SHA256) | (1 << HASH_ALGO_KECCAK256);
+/// `dispute_initiators` bits: which escrow roles may call `initiate_dispute`.
+pub const DISPUTE_INITIATOR_MAKER: u8 = 1 << 0;
+pub const DISPUTE_INITIATOR_TAKER: u8 = 1 << 1;
+pub const DISPUTE_INITIATOR_BENEFICIARY: u8 = 1 << 2;
+pub const DISPUTE_INITIATORS_ALL: u8 =
+    DISPUTE_INITIATOR_MAKER | DISPUTE_INITIATOR_TAKER | DISPUTE_INITIATOR_BENEFICIARY;
+pub const APPROVAL_MESSAGE_LEN: usize = 32 + 32 + 1 + 8; // program id, escrow, index, nonce
+/// `remaining_accounts` bound for handlers that only read the receipt token account.
+pub const MAX_RECEIPT_SYNC_ACCOUNTS: usize = 1;
+/// `remaining_accounts` bound for bulk settlements: the receipt token account
+/// plus one claw-back holding or milestone payee token account per milestone.
+pub const MAX_BULK_SETTLEMENT_ACCOUNTS: usize = MAX_RECEIPT_SYNC_ACCOUNTS + MAX_MILESTONES as usize;
+/// Holding slot for an aggregate dispute payout (`resolve_dispute`,
+/// `claim_dispute_timeout`); never a milestone index.
+pub const SETTLEMENT_HOLDING_INDEX: u8 = u8::MAX;
+/// Escrows per `batch_escrow_progress` call: the summaries plus the Vec length
+/// prefix must fit the 1024-byte return-data limit.
+pub const MAX_BATCH_PROGRESS_ACCOUNTS: usize = (1024 - 4) / EscrowProgress::INIT_SPACE;
+/// Layout version of `EffectiveConfig`; bumped whenever fields are appended.
+#[constant]
+pub const CONFIG_VIEW_VERSION: u8 = 7;
+/// Layout version of the stored `EscrowConfig`. Every instruction that loads
+/// the config requires exactly this version; older accounts are brought up to
+/// date by `migrate_config`.
+#[constant]
+pub const CONFIG_VERSION: u8 = 3;
+/// The deployed baseline layout: `authority`, `fee_bps`, `fee_collector`,
+/// `dispute_timeout` and `bump`. Every later field is appended by `migrate_config`.
+pub const CONFIG_V1_SPACE: usize = 32 + 2 + 32 + 8 + 1;
+/// Number of `CancelReason` variants; `cancel_routes` tables are indexed by reason.
+pub const CANCEL_REASON_COUNT: usize = 4;
 
 #[account]
 #[derive(InitSpace)]
@@ -15,6 +91,175 @@ pub struct EscrowConfig {
     pub fee_collector: Pubkey,
     pub dispute_timeout: i64,
     pub bump: u8,
+    // Fields below were appended after the baseline layout; `migrate_config`
+    // fills them in with `set_post_baseline_defaults`
+    /// Challenge window (seconds) between approval and release, snapshotted per escrow.
+    pub release_delay: i64,
+    /// Lifetime cap on `initiate_dispute` calls per escrow. 0 = unlimited.
+    pub max_disputes_per_escrow: u8,
+    /// Bitmask of `hash_algo` values accepted at creation (bit n = algo n).
+    pub accepted_hash_algos: u8,
+    /// Vault dust strictly below this amount is burned instead of swept on close.
+    pub close_dust_threshold: u64,
+    /// How `claim_dispute_timeout` settles a dispute nobody ruled on.
+    pub dispute_timeout_policy: DisputeTimeoutPolicy,
+    /// Lifetime (seconds) of a taker-issued `EscrowRequest` before it can no longer be funded.
+    pub request_ttl: i64,
+    /// Window (seconds) after a release during which the net payout sits in a
+    /// holding account and can still be reclaimed; snapshotted per escrow. 0 = off.
+    pub claw_back_seconds: i64,
+    /// An approval landing closer than this (seconds) to `expires_at` pushes the
+    /// expiry out to `now + approval_extension`; snapshotted per escrow. 0 = off.
+    pub approval_extension: i64,
+    /// Lifetime cap (seconds) on how far approvals may push an escrow's expiry.
+    pub max_expiry_extension: i64,
+    /// Who receives the rounding remainder of fee and split math; snapshotted per escrow.
+    pub remainder_policy: RemainderPolicy,
+    /// Whether makers may mark milestones `fee_exempt` at creation or renewal.
+    pub allow_fee_exempt: bool,
+    /// Cap on escrows a maker may have open at once, counted on their
+    /// `MakerProfile`. 0 = unlimited (and nothing is counted).
+    pub max_open_escrows_per_maker: u16,
+    /// Keep-alive: every approval pushes `expires_at` out by this many seconds,
+    /// drawn from the same budget as `approval_extension`; snapshotted per escrow. 0 = off.
+    pub expiry_extension_per_approval: i64,
+    /// Minimum fee per payout, in `flat_fee_mint` units: releases charge
+    /// max(bps fee, flat fee), capped at the amount. 0 = off.
+    pub flat_fee: u64,
+    /// The only mint `flat_fee` applies to; escrows in other mints snapshot 0.
+    pub flat_fee_mint: Pubkey,
+    /// Share of the refund `cancel_escrow` withholds from the maker once the
+    /// escrow is accepted; snapshotted per escrow. 0 = full refund.
+    pub cancel_fee_bps: u16,
+    /// Who receives the cancellation fee; snapshotted per escrow.
+    pub cancel_fee_recipient: CancelFeeRecipient,
+    /// Kill switch for new receipt NFTs. Receipts already minted keep syncing,
+    /// revoking and gating payouts as usual.
+    pub receipts_enabled: bool,
+    /// Milestone releases below this amount, in `flat_fee_mint` units, pay no
+    /// fee at all; snapshotted per escrow (other mints snapshot 0). 0 = off.
+    pub fee_free_below: u64,
+    /// Max deviation of any milestone from an even split of the escrow amount,
+    /// in bps of the even share, enforced at creation. 0 = off.
+    pub max_milestone_skew_bps: u16,
+    /// Layout version of this account (`CONFIG_VERSION`); absent (v1) before migration.
+    pub config_version: u8,
+    /// Kill switch for new escrows. Existing escrows settle as usual.
+    pub creation_paused: bool,
+    /// Refund routing per `CancelReason` (indexed by the reason), snapshotted
+    /// per escrow. The `MakerCancel` entry mirrors `cancel_fee_bps` and
+    /// `cancel_fee_recipient`.
+    pub cancel_routes: [CancelRouting; CANCEL_REASON_COUNT],
+}
+
+impl EscrowConfig {
+    /// Reset every field appended after the baseline layout (`CONFIG_V1_SPACE`)
+    /// to its default and stamp `CONFIG_VERSION`.
+    pub fn set_post_baseline_defaults(&mut self) {
+        self.release_delay = 0;
+        self.claw_back_seconds = 0;
+        self.approval_extension = 0;
+        self.max_expiry_extension = 0;
+        self.max_disputes_per_escrow = DEFAULT_MAX_DISPUTES_PER_ESCROW;
+        self.accepted_hash_algos = DEFAULT_ACCEPTED_HASH_ALGOS;
+        self.close_dust_threshold = 0;
+        self.dispute_timeout_policy = DisputeTimeoutPolicy::SplitOnTimeout;
+        self.request_ttl = DEFAULT_REQUEST_TTL;
+        self.remainder_policy = RemainderPolicy::ToBeneficiary;
+        self.allow_fee_exempt = false;
+        self.max_open_escrows_per_maker = 0;
+        self.expiry_extension_per_approval = 0;
+        self.flat_fee = 0;
+        self.flat_fee_mint = Pubkey::default();
+        self.cancel_fee_bps = 0;
+        self.cancel_fee_recipient = CancelFeeRecipient::FeeCollector;
+        self.receipts_enabled = true;
+        self.fee_free_below = 0;
+        self.max_milestone_skew_bps = 0;
+        self.config_version = CONFIG_VERSION;
+        self.creation_paused = false;
+        self.cancel_routes = [CancelRouting::FULL_MAKER_REFUND; CANCEL_REASON_COUNT];
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum DisputeTimeoutPolicy {
+    /// Remaining funds are split 50/50 between maker and beneficiary; an odd
+    /// unit goes to the escrow's `remainder_policy` party.
+    SplitOnTimeout,
+    /// Remaining funds go to the party that did not open the dispute.
+    FavorNonInitiator,
+}
+
+/// Destination of the units integer division leaves over when a settlement is
+/// split between maker, beneficiary and fee collector (see `helpers::split_amount`).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum RemainderPolicy {
+    ToMaker,
+    /// Default; matches the historical floor-the-fee behaviour.
+    ToBeneficiary,
+    ToFeeCollector,
+}
+
+/// Permissionless crank that paid a keeper, reported in `CrankRewarded`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CrankInstruction {
+    ReleaseMilestone,
+    ClaimActiveExpired,
+    ClaimDisputeTimeout,
+    SyncBeneficiary,
+}
+
+/// Why a milestone was cancelled, reported per milestone in `MilestoneCancelled`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CancelReason {
+    /// `cancel_escrow` / `cancel_and_release` refunded it to the maker.
+    MakerCancel,
+    /// A `MakerWins` ruling in `resolve_dispute`.
+    DisputeRuling,
+    /// Still pending when an active escrow expired.
+    Expiry,
+    /// Still open when an unresolved dispute timed out.
+    DisputeTimeout,
+}
+
+/// How a cancellation refund is split, configured per `CancelReason` in
+/// `EscrowConfig::cancel_routes`, looked up by `EscrowState::cancel_routing`
+/// and reported in `EscrowCancelled`. The maker
+/// receives the refund less `fee_bps`, which goes to `fee_recipient`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub struct CancelRouting {
+    pub fee_bps: u16,
+    pub fee_recipient: CancelFeeRecipient,
+}
+
+impl CancelRouting {
+    /// Default for every reason without a policy: the whole refund to the maker.
+    pub const FULL_MAKER_REFUND: Self = Self {
+        fee_bps: 0,
+        fee_recipient: CancelFeeRecipient::FeeCollector,
+    };
+}
+
+/// `update_config` argument replacing the routing of one `CancelReason`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CancelRouteUpdate {
+    pub reason: CancelReason,
+    pub routing: CancelRouting,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeeRefundRecipient {
+    Beneficiary,
+    Maker,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum CancelFeeRecipient {
+    /// Default; the platform keeps the cancellation fee.
+    FeeCollector,
+    /// Compensates the beneficiary for the time the escrow was reserved.
+    Beneficiary,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
@@ -24,6 +269,10 @@ pub enum EscrowStatus {
     Disputed,
     Cancelled,
     Expired,
+    /// Funded but not yet accepted by the taker/beneficiary (opt-in at creation).
+    AwaitingAcceptance,
+    /// Sub-escrow created but not yet funded by the release of its parent milestone.
+    AwaitingFunding,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
@@ -39,6 +288,29 @@ pub struct Milestone {
     pub amount: u64,
     pub description_hash: [u8; 32],
     pub status: MilestoneStatus,
+    /// Timestamp of the latest approval (0 while Pending).
+    pub approved_at: i64,
+    /// Child escrow funded from this milestone's net payout on release.
+    pub sub_escrow: Option<Pubkey>,
+    /// While non-zero, the released net payout sits in this milestone's holding
+    /// account and can be reclaimed until this time; withdrawable after it.
+    pub held_until: i64,
+    /// Released without the platform fee; only accepted when the config allows it.
+    pub fee_exempt: bool,
+    /// Fixed at creation: the beneficiary may approve this milestone via `certify_milestone`.
+    pub self_certify: bool,
+    /// Fee funded on top of the face amount (already included in `amount`);
+    /// 0 unless the escrow was created with `fee_on_top`.
+    pub fee_reserved: u64,
+    /// Fixed payee of this milestone's single releases; `None` pays the escrow beneficiary.
+    pub beneficiary: Option<Pubkey>,
+}
+
+/// Link from a sub-escrow to the parent milestone that funds it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub struct SubEscrowLink {
+    pub escrow: Pubkey,
+    pub milestone_index: u8,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
@@ -54,7 +326,12 @@ pub struct Dispute {
     pub reason_hash: [u8; 32],
     pub initiated_at: i64,
     pub timeout: i64,
+    /// `initiated_at + timeout`, computed once (overflow-checked) at initiation.
+    pub deadline: i64,
     pub resolution: Option<DisputeResolution>,
+    /// Signer of the ruling; `Pubkey::default()` when settled by timeout.
+    pub resolved_by: Pubkey,
+    pub resolved_at: i64,
 }
 
 #[account]
@@ -64,11 +341,16 @@ pub struct EscrowState {
     pub taker: Pubkey,
     pub beneficiary: Pubkey,
     pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub token_program: Pubkey,
     pub amount: u64,
     pub released_amount: u64,
     pub refunded_amount: u64,
     pub seed: u64,
     pub status: EscrowStatus,
+    /// Caller-defined classification (freelance, deposit, OTC, ...). No logic impact.
+    /// Kept ahead of all variable-length fields so it sits at `ESCROW_CATEGORY_OFFSET`.
+    pub category: u8,
     #[max_len(MAX_MILESTONES)]
     pub milestones: Vec<Milestone>,
     pub created_at: i64,
@@ -77,6 +359,101 @@ pub struct EscrowState {
     pub fee_bps_at_creation: u16,
     pub bump: u8,
     pub receipt_mint: Option<Pubkey>,
+    pub release_delay: i64,
+    /// Authority-imposed legal hold: blocks payouts, refunds and closure.
+    pub on_hold: bool,
+    /// Canonical ATA of the current beneficiary. When set, payouts must land here.
+    pub beneficiary_ata: Option<Pubkey>,
+    /// Number of completed renewal cycles on this PDA.
+    pub renewal_count: u32,
+    /// Next nonce accepted by `approve_milestone_signed` (replay protection).
+    pub approval_nonce: u64,
+    /// Disputes ever opened on this escrow. Never decremented.
+    pub dispute_count: u8,
+    /// Hash convention for all description/reason hashes on this escrow
+    /// (`HASH_ALGO_*`). Informational only: hashes stay opaque on-chain.
+    pub hash_algo: u8,
+    /// Maker at creation, used for PDA derivation. Immutable, unlike `maker`,
+    /// which `transfer_maker` can reassign.
+    pub creator: Pubkey,
+    /// Latest time `accept_escrow` may be called (0 when acceptance isn't required).
+    pub acceptance_deadline: i64,
+    /// Optimistic-concurrency counter, bumped by every mutating instruction.
+    pub nonce: u64,
+    /// Fungible claim shares minted by `fractionalize_claim`. When set, the
+    /// escrow is in pull-payment mode: beneficiary payouts accrue to `claim_pool`.
+    pub share_mint: Option<Pubkey>,
+    /// Net payouts held in the vault for share holders, drained by `redeem_shares`.
+    pub claim_pool: u64,
+    /// `EscrowMetadata` PDA attached by the maker, closed with the escrow.
+    pub metadata: Option<Pubkey>,
+    /// Cumulative fees sent to the fee collector this cycle (reset on renewal).
+    pub fees_paid: u64,
+    /// Set on sub-escrows created by `create_sub_escrow`.
+    pub parent: Option<SubEscrowLink>,
+    /// Fixed at creation: approvals go through `approve_milestone_auto`, which
+    /// pays the milestone out in the same instruction.
+    pub auto_release: bool,
+    /// Lamports paid from the tip jar to a registered keeper per state-changing crank.
+    pub crank_tip: u64,
+    /// Lamports deposited by the maker for crank tips and not yet paid out.
+    /// They sit on this account, so whatever is left goes back to the maker on close.
+    pub tip_jar: u64,
+    /// Claw-back window snapshotted from the config at creation.
+    pub claw_back_seconds: i64,
+    /// Fees rebated by `refund_fee` this cycle; never exceeds `fees_paid`.
+    pub fees_refunded: u64,
+    /// Approval extension window snapshotted from the config at creation.
+    pub approval_extension: i64,
+    /// Seconds of approval-driven expiry extension still available to this escrow.
+    pub expiry_extension_budget: i64,
+    /// Set once an expiry claim has paid out the vault, so clients can tell a
+    /// settled `Expired` escrow from an `Active` one that is merely past `expires_at`.
+    pub funds_distributed: bool,
+    /// Rounding remainder policy snapshotted from the config at creation.
+    pub remainder_policy: RemainderPolicy,
+    /// Fixed at creation. When false the config authority has no power over the
+    /// escrow: no disputes, holds, claw-backs or authority-side reclaims.
+    pub disputes_enabled: bool,
+    /// Counted in the creator's `MakerProfile.open_escrows`; cleared when the
+    /// escrow reaches a terminal status and the count is given back.
+    pub counted_open: bool,
+    /// Bitmask of `DISPUTE_INITIATOR_*` roles allowed to open a dispute; fixed at creation.
+    pub dispute_initiators: u8,
+    pub expiry_extension_per_approval: i64,
+    /// Receipt mint detached by `recover_receipt`. Its NFT can never sync or revoke again.
+    pub voided_receipt_mint: Option<Pubkey>,
+    /// Flat fee floor snapshotted from the config at creation (0 unless the
+    /// escrow's mint is the config's `flat_fee_mint`).
+    pub flat_fee_at_creation: u64,
+    /// The maker funded each milestone's fee on top of its face amount, so
+    /// releases pay the beneficiary the full face amount.
+    pub fee_on_top: bool,
+    /// The receipt NFT's metadata is still mutable under the escrow PDA;
+    /// closing the escrow must seal it while the PDA can still sign.
+    pub receipt_metadata_mutable: bool,
+    pub cancel_fee_bps_at_creation: u16,
+    pub cancel_fee_recipient: CancelFeeRecipient,
+    /// Hash of an off-chain agreement attached at creation. Until the taker
+    /// acknowledges it, milestones cannot be released.
+    pub agreement_hash: Option<[u8; 32]>,
+    pub agreement_acknowledged: bool,
+    pub agreement_acknowledged_at: i64,
+    /// Config `fee_free_below` if the escrow's mint is `flat_fee_mint`, else 0.
+    pub fee_free_below_at_creation: u64,
+    /// Performance bond the taker must post before the escrow goes Active;
+    /// 0 when none was required at creation.
+    pub taker_bond: u64,
+    /// Bond held in the vault outside `amount`, until `settle_bond` pays it out.
+    pub bond_balance: u64,
+    /// `held_until` of the `SETTLEMENT_HOLDING_INDEX` holding, which carries a
+    /// dispute payout made while a claw-back window applied.
+    pub settlement_held_until: i64,
+    /// Config `cancel_routes` at creation, indexed by `CancelReason`.
+    pub cancel_routes_at_creation: [CancelRouting; CANCEL_REASON_COUNT],
+    /// Open milestone amounts `execute_emergency_withdraw` cancelled and sent
+    /// to the recovery account rather than refunding them to the maker.
+    pub emergency_withdrawn: u64,
 }
 
 impl EscrowState {
@@ -86,11 +463,518 @@ impl EscrowState {
             m.status == MilestoneStatus::Released || m.status == MilestoneStatus::Cancelled
         })
     }
+
+    /// Advance the optimistic-concurrency nonce after a state change.
+    pub fn bump_nonce(&mut self) -> Result<()> {
+        self.nonce = self.nonce.checked_add(1).ok_or(EscrowError::Overflow)?;
+        Ok(())
+    }
+
+    /// Add a fee transfer to the running `fees_paid` total.
+    pub fn record_fee(&mut self, fee: u64) -> Result<()> {
+        self.fees_paid = self.fees_paid.checked_add(fee).ok_or(EscrowError::Overflow)?;
+        Ok(())
+    }
+
+    /// Count a fee rebate against this cycle's collected fees.
+    pub fn record_fee_refund(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, EscrowError::InvalidAmount);
+        let refunded = self.fees_refunded.checked_add(amount).ok_or(EscrowError::Overflow)?;
+        require!(refunded <= self.fees_paid, EscrowError::FeeRefundExceedsCollected);
+        self.fees_refunded = refunded;
+        Ok(())
+    }
+
+    /// Push `expires_at` out to `now + approval_extension` when an approval lands
+    /// inside that window, then by the flat `expiry_extension_per_approval`, both
+    /// limited by the remaining budget. Returns the seconds added.
+    pub fn extend_expiry_for_approval(&mut self, now: i64) -> Result<i64> {
+        let mut wanted = 0;
+        if self.approval_extension > 0 {
+            let target = now.checked_add(self.approval_extension).ok_or(EscrowError::Overflow)?;
+            wanted = (target - self.expires_at).max(0);
+        }
+        wanted = wanted
+            .checked_add(self.expiry_extension_per_approval)
+            .ok_or(EscrowError::Overflow)?;
+        let extension = wanted.min(self.expiry_extension_budget);
+        self.expires_at = self.expires_at.checked_add(extension).ok_or(EscrowError::Overflow)?;
+        self.expiry_extension_budget -= extension;
+        Ok(extension)
+    }
+
+    /// Whether `signer` holds a role in `dispute_initiators`. A signer with
+    /// several roles (e.g. taker and beneficiary) needs only one of them allowed.
+    pub fn may_initiate_dispute(&self, signer: &Pubkey) -> bool {
+        let allowed = |bit: u8| self.dispute_initiators & bit != 0;
+        (allowed(DISPUTE_INITIATOR_MAKER) && self.maker == *signer)
+            || (allowed(DISPUTE_INITIATOR_TAKER) && self.taker == *signer)
+            || (allowed(DISPUTE_INITIATOR_BENEFICIARY)
+                && self.share_mint.is_none()
+                && self.beneficiary == *signer)
+    }
+
+    /// True while any released payout is still in a claw-back holding account.
+    pub fn has_held_funds(&self) -> bool {
+        self.settlement_held_until != 0 || self.milestones.iter().any(|m| m.held_until != 0)
+    }
+
+    /// `held_until` of holding slot `index`: a milestone, or the dispute
+    /// settlement at `SETTLEMENT_HOLDING_INDEX`. 0 for an unknown slot.
+    pub fn held_until(&self, index: u8) -> i64 {
+        if index == SETTLEMENT_HOLDING_INDEX {
+            return self.settlement_held_until;
+        }
+        self.milestones.get(index as usize).map_or(0, |m| m.held_until)
+    }
+
+    pub fn set_held_until(&mut self, index: u8, held_until: i64) -> Result<()> {
+        if index == SETTLEMENT_HOLDING_INDEX {
+            self.settlement_held_until = held_until;
+            return Ok(());
+        }
+        let milestone = self
+            .milestones
+            .get_mut(index as usize)
+            .ok_or(EscrowError::MilestoneIndexOutOfBounds)?;
+        milestone.held_until = held_until;
+        Ok(())
+    }
+
+    /// Number of (released, cancelled) milestones, reported in terminal events.
+    pub fn settled_milestone_counts(&self) -> (u8, u8) {
+        self.milestones.iter().fold((0, 0), |(released, cancelled), m| match m.status {
+            MilestoneStatus::Released => (released + 1, cancelled),
+            MilestoneStatus::Cancelled => (released, cancelled + 1),
+            _ => (released, cancelled),
+        })
+    }
+
+    /// Expiry bookkeeping: Approved milestones are Released (earned), Pending
+    /// ones Cancelled. Returns the (approved, pending) amounts settled.
+    pub fn settle_milestones_on_expiry(&mut self) -> Result<(u64, u64)> {
+        let mut approved: u64 = 0;
+        let mut pending: u64 = 0;
+        for milestone in self.milestones.iter_mut() {
+            match milestone.status {
+                MilestoneStatus::Approved => {
+                    approved = approved.checked_add(milestone.amount).ok_or(EscrowError::Overflow)?;
+                    milestone.status = MilestoneStatus::Released;
+                }
+                MilestoneStatus::Pending => {
+                    pending = pending.checked_add(milestone.amount).ok_or(EscrowError::Overflow)?;
+                    milestone.status = MilestoneStatus::Cancelled;
+                }
+                _ => {}
+            }
+        }
+        self.released_amount = self.released_amount.checked_add(approved).ok_or(EscrowError::Overflow)?;
+        self.refunded_amount = self.refunded_amount.checked_add(pending).ok_or(EscrowError::Overflow)?;
+        Ok((approved, pending))
+    }
+
+    /// Cancel every milestone still Pending or Approved (dispute timeout).
+    /// Returns `(index, amount)` of each milestone cancelled.
+    pub fn cancel_open_milestones(&mut self) -> Vec<(u8, u64)> {
+        let mut cancelled = Vec::new();
+        for (i, milestone) in self.milestones.iter_mut().enumerate() {
+            if milestone.status == MilestoneStatus::Pending
+                || milestone.status == MilestoneStatus::Approved
+            {
+                milestone.status = MilestoneStatus::Cancelled;
+                cancelled.push((i as u8, milestone.amount));
+            }
+        }
+        cancelled
+    }
+
+    /// Route a net beneficiary payout. Returns the amount to transfer to the
+    /// beneficiary now; once the claim is fractionalized the payout is credited
+    /// to `claim_pool` instead and nothing is transferred.
+    pub fn route_beneficiary_payout(&mut self, amount: u64) -> Result<u64> {
+        if self.share_mint.is_none() {
+            return Ok(amount);
+        }
+        self.claim_pool = self.claim_pool.checked_add(amount).ok_or(EscrowError::Overflow)?;
+        Ok(0)
+    }
+
+    /// Recipient of milestone `index`'s release: its own beneficiary when set,
+    /// otherwise the escrow beneficiary (also for an out-of-range index, which
+    /// the handlers reject separately).
+    pub fn milestone_payee(&self, index: u8) -> Pubkey {
+        self.milestone_beneficiary(index).unwrap_or(self.beneficiary)
+    }
+
+    pub fn milestone_beneficiary(&self, index: u8) -> Option<Pubkey> {
+        self.milestones.get(index as usize).and_then(|m| m.beneficiary)
+    }
+
+    /// True while an unsettled milestone names its own beneficiary. Dispute
+    /// rulings split the open balance as a whole, so they cannot pay it.
+    pub fn has_open_milestone_beneficiary(&self) -> bool {
+        self.milestones.iter().any(|m| {
+            m.beneficiary.is_some()
+                && (m.status == MilestoneStatus::Pending || m.status == MilestoneStatus::Approved)
+        })
+    }
+
+    /// `route_beneficiary_payout` for a single milestone release. A milestone
+    /// with its own beneficiary is paid directly: it is not part of the escrow
+    /// beneficiary's claim, so it never accrues to the share pool.
+    pub fn route_milestone_payout(&mut self, index: u8, amount: u64) -> Result<u64> {
+        if self.milestone_beneficiary(index).is_some() {
+            return Ok(amount);
+        }
+        self.route_beneficiary_payout(amount)
+    }
+
+    /// Refund routing for a cancellation with `reason`, from the table
+    /// snapshotted at creation. Withdrawing before acceptance always refunds
+    /// the maker in full.
+    pub fn cancel_routing(&self, reason: CancelReason) -> CancelRouting {
+        if self.status == EscrowStatus::AwaitingAcceptance {
+            return CancelRouting::FULL_MAKER_REFUND;
+        }
+        self.cancel_routes_at_creation[reason as usize]
+    }
+
+    /// Funds still held in the vault: `amount - released - refunded - emergency_withdrawn`.
+    pub fn remaining_amount(&self) -> Result<u64> {
+        let remaining = self
+            .amount
+            .checked_sub(self.released_amount)
+            .ok_or(EscrowError::Overflow)?
+            .checked_sub(self.refunded_amount)
+            .ok_or(EscrowError::Overflow)?
+            .checked_sub(self.emergency_withdrawn)
+            .ok_or(EscrowError::Overflow)?;
+        Ok(remaining)
+    }
+}
+
+/// Immutable summary of a closed escrow, written by `archive_and_close`.
+/// PDA: `[ARCHIVE_SEED, escrow]`; closable by `maker` after `ARCHIVE_RETENTION`.
+#[account]
+#[derive(InitSpace)]
+pub struct EscrowArchive {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    /// Beneficiary at close time.
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub released_amount: u64,
+    pub refunded_amount: u64,
+    pub fees_paid: u64,
+    pub status: EscrowStatus,
+    pub created_at: i64,
+    pub closed_at: i64,
+    pub bump: u8,
+}
+
+/// Free-form on-chain details (descriptions, line items) for one escrow.
+/// PDA: `[METADATA_SEED, escrow_state]`, sized to the blob at attach time.
+#[account]
+pub struct EscrowMetadata {
+    pub escrow: Pubkey,
+    pub data: Vec<u8>,
+    pub bump: u8,
+}
+
+impl EscrowMetadata {
+    /// Account size (including discriminator) for a blob of `data_len` bytes.
+    pub fn space(data_len: usize) -> usize {
+        8 + 32 + 4 + data_len + 1
+    }
+}
+
+/// Payout breakdown of a dispute ruling, as computed by `resolve_dispute`.
+/// Returned by `simulate_resolution` via return data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ResolutionBreakdown {
+    /// Unsettled vault balance the ruling distributes.
+    pub remaining: u64,
+    pub maker_amount: u64,
+    pub beneficiary_amount: u64,
+    pub fee: u64,
+}
+
+/// Backing of a receipt NFT, returned by `verify_receipt` via return data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReceiptVerification {
+    pub escrow: Pubkey,
+    pub receipt_mint: Pubkey,
+    /// Current payee of the claim; a buyer becomes it after `sync_beneficiary`.
+    pub beneficiary: Pubkey,
+    /// Vault balance not yet released or refunded.
+    pub remaining_amount: u64,
+    /// Lets a buyer detect state changes between verifying and settling.
+    pub nonce: u64,
+}
+
+/// Per-escrow summary returned by `batch_escrow_progress` via return data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
+pub struct EscrowProgress {
+    pub escrow: Pubkey,
+    pub status: EscrowStatus,
+    /// `released_amount / amount` in basis points for the current cycle.
+    pub released_bps: u16,
+    /// Milestones that are Released or Cancelled.
+    pub settled_milestones: u8,
+    pub milestone_count: u8,
+}
+
+/// Effective protocol configuration returned by `get_config` via return data.
+/// Fields are only ever appended, so a client decoding an older `version`
+/// keeps working; the program-wide limits follow the stored settings.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
+pub struct EffectiveConfig {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub fee_bps: u16,
+    pub fee_collector: Pubkey,
+    pub dispute_timeout: i64,
+    pub release_delay: i64,
+    pub max_disputes_per_escrow: u8,
+    pub accepted_hash_algos: u8,
+    pub close_dust_threshold: u64,
+    pub dispute_timeout_policy: DisputeTimeoutPolicy,
+    pub request_ttl: i64,
+    pub claw_back_seconds: i64,
+    pub approval_extension: i64,
+    pub max_expiry_extension: i64,
+    pub remainder_policy: RemainderPolicy,
+    pub allow_fee_exempt: bool,
+    pub max_open_escrows_per_maker: u16,
+    pub expiry_extension_per_approval: i64,
+    pub flat_fee: u64,
+    pub flat_fee_mint: Pubkey,
+    /// Shortest lifetime `create_escrow` and `renew_escrow` accept (seconds).
+    pub min_expiration_duration: i64,
+    /// Longest claw-back window a maker may opt into (seconds).
+    pub max_claw_back_seconds: i64,
+    /// How long before `expires_at` an Active escrow becomes renewable (seconds).
+    pub renewal_window: i64,
+    pub max_milestones: u8,
+    // Version 2
+    pub cancel_fee_bps: u16,
+    pub cancel_fee_recipient: CancelFeeRecipient,
+    // Version 3
+    pub receipts_enabled: bool,
+    // Version 4
+    pub fee_free_below: u64,
+    // Version 5
+    pub max_milestone_skew_bps: u16,
+    // Version 6
+    pub config_version: u8,
+    pub creation_paused: bool,
+    // Version 7
+    pub cancel_routes: [CancelRouting; CANCEL_REASON_COUNT],
 }
 
 /// Input struct for creating milestones (used as instruction argument).
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 pub struct MilestoneInput {
     pub amount: u64,
     pub description_hash: [u8; 32],
+    pub fee_exempt: bool,
+    pub self_certify: bool,
+    /// Overrides the escrow beneficiary for this milestone; never the maker.
+    pub beneficiary: Option<Pubkey>,
+}
+
+/// Negotiable escrow terms: exactly what `create_escrow` funds, minus the
+/// parties, mint and seed that identify the deal.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct EscrowTerms {
+    pub amount: u64,
+    #[max_len(MAX_MILESTONES)]
+    pub milestones: Vec<MilestoneInput>,
+    pub expires_at: i64,
+    pub category: u8,
+    pub initial_beneficiary: Option<Pubkey>,
+    pub hash_algo: u8,
+}
+
+/// Pre-funding negotiation between maker and taker for the escrow at
+/// `[ESCROW_SEED, maker, seed]`. PDA: `[PROPOSAL_SEED, maker, seed]`.
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub mint: Pubkey,
+    pub seed: u64,
+    /// Party that opened the proposal; receives the rent when it closes.
+    pub creator: Pubkey,
+    pub terms: EscrowTerms,
+    /// True when the maker must respond next (the taker proposed last).
+    pub maker_turn: bool,
+    /// Number of counter-proposals so far.
+    pub revision: u32,
+    /// After this time the proposal can no longer be countered or accepted.
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+/// Unfunded payment request issued by the taker for the escrow at
+/// `[ESCROW_SEED, maker, seed]`. PDA: `[REQUEST_SEED, maker, seed]`.
+#[account]
+#[derive(InitSpace)]
+pub struct EscrowRequest {
+    /// Requester; becomes the escrow taker and receives the rent when it closes.
+    pub taker: Pubkey,
+    /// Party expected to fund the request.
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    pub seed: u64,
+    pub terms: EscrowTerms,
+    /// After this time the request can no longer be funded.
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+/// Registered crank operator. PDA: `[KEEPER_SEED, keeper]`. Holds the optional
+/// SOL bond plus unclaimed tips; both go back to the keeper on deregistration.
+#[account]
+#[derive(InitSpace)]
+pub struct Keeper {
+    pub keeper: Pubkey,
+    /// Lamports bonded at registration (may be 0).
+    pub bond: u64,
+    pub registered_at: i64,
+    /// State-changing cranks that paid a tip.
+    pub cranks: u64,
+    /// Cumulative tips received (claimed or not).
+    pub tips_earned: u64,
+    pub bump: u8,
+}
+
+/// Per-maker bookkeeping. PDA: `[MAKER_PROFILE_SEED, maker]`, created on the
+/// maker's first direct escrow creation.
+#[account]
+#[derive(InitSpace)]
+pub struct MakerProfile {
+    pub maker: Pubkey,
+    /// Escrows created while `max_open_escrows_per_maker` was set that have not
+    /// yet reached a terminal status.
+    pub open_escrows: u16,
+    pub bump: u8,
+}
+
+impl MakerProfile {
+    /// Count a new escrow against `cap`. Returns whether it was counted
+    /// (nothing is tracked while the cap is 0).
+    pub fn open_escrow(&mut self, cap: u16) -> Result<bool> {
+        if cap == 0 {
+            return Ok(false);
+        }
+        require!(self.open_escrows < cap, EscrowError::TooManyOpenEscrows);
+        self.open_escrows = self.open_escrows.checked_add(1).ok_or(EscrowError::Overflow)?;
+        Ok(true)
+    }
+}
+
+/// Mutual-consent instructions an absent party can pre-approve with an `Approval`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum MutualAction {
+    RenewEscrow,
+}
+
+/// One party's recorded consent to a specific mutual action. PDA:
+/// `[APPROVAL_SEED, escrow, approver]`. Only valid while the escrow's nonce is
+/// still `nonce` and before `expires_at`; closable by the approver at any time.
+#[account]
+#[derive(InitSpace)]
+pub struct Approval {
+    pub escrow: Pubkey,
+    pub approver: Pubkey,
+    /// `mutual_action_hash` of the approved action and its exact parameters.
+    pub action_hash: [u8; 32],
+    /// Escrow nonce the approval was recorded at; any state change voids it.
+    pub nonce: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+/// Permissionless instruction a `CrankSchedule` fires.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum CrankKind {
+    /// `release_milestone(index)`. With a non-zero `interval` the schedule
+    /// re-arms for the next milestone `interval` seconds after each release.
+    ReleaseMilestone { index: u8, interval: i64 },
+    ClaimActiveExpired,
+    ClaimDisputeTimeout,
+}
+
+impl CrankKind {
+    /// Seed byte: one schedule per kind, escrow and creator.
+    pub fn tag(&self) -> u8 {
+        match self {
+            CrankKind::ReleaseMilestone { .. } => 0,
+            CrankKind::ClaimActiveExpired => 1,
+            CrankKind::ClaimDisputeTimeout => 2,
+        }
+    }
+}
+
+/// Queued crank for automation providers. PDA:
+/// `[CRANK_SCHEDULE_SEED, escrow, creator, kind.tag()]`. Whoever executes it
+/// once due receives the rent when it closes; the creator can close it earlier.
+#[account]
+#[derive(InitSpace)]
+pub struct CrankSchedule {
+    pub escrow: Pubkey,
+    /// Paid the rent; the only signer that can cancel.
+    pub creator: Pubkey,
+    pub kind: CrankKind,
+    /// Earliest unix timestamp `execute_scheduled_crank` accepts.
+    pub eta: i64,
+    /// Successful executions so far (re-armed schedules only ever exceed 1).
+    pub executions: u32,
+    pub bump: u8,
+}
+
+/// Pending authority recovery of an escrow's vault. PDA:
+/// `[EMERGENCY_WITHDRAW_SEED, escrow]`, so one proposal per escrow; closed to
+/// the authority when executed or cancelled, or permissionlessly to
+/// `proposed_by` once its escrow is closed, terminal or re-created.
+#[account]
+#[derive(InitSpace)]
+pub struct EmergencyWithdrawal {
+    pub escrow: Pubkey,
+    pub proposed_by: Pubkey,
+    /// Owner of the token account the vault is drained to.
+    pub recovery: Pubkey,
+    pub proposed_at: i64,
+    /// `proposed_at + EMERGENCY_WITHDRAW_DELAY`; execution is rejected before it.
+    pub executable_at: i64,
+    pub bump: u8,
+    /// `created_at` of the escrow it was proposed for. An escrow re-created at
+    /// the same address has a different one, so a leftover proposal can't skip
+    /// the timelock against it.
+    pub escrow_created_at: i64,
+}
+
+/// Public discovery entry for an escrow still awaiting acceptance. PDA:
+/// `[LISTING_SEED, escrow]`, so at most one per escrow; closed to `maker` on
+/// delisting, acceptance, or permissionlessly once the escrow is no longer
+/// acceptable.
+#[account]
+#[derive(InitSpace)]
+pub struct Listing {
+    pub escrow: Pubkey,
+    /// Paid the rent; receives it back when the listing closes.
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    /// Caller-defined tag, kept at `LISTING_CATEGORY_OFFSET` for memcmp filters.
+    pub category: u8,
+    /// Escrow amount at listing time.
+    pub amount: u64,
+    /// Hash of the off-chain title/description.
+    pub title_hash: [u8; 32],
+    pub listed_at: i64,
+    pub bump: u8,
 }
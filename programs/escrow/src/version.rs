@@ -0,0 +1,40 @@
+//! Program version and optional-subsystem flags reported by `get_version`.
+//! Bump the version and set a feature bit in the same commit that ships the
+//! change, so clients can gate on them instead of hitting opaque errors.
+
+use anchor_lang::prelude::*;
+
+/// Kept equal to the crate version in Cargo.toml.
+pub const VERSION_MAJOR: u16 = 0;
+pub const VERSION_MINOR: u16 = 15;
+pub const VERSION_PATCH: u16 = 0;
+
+/// Receipt NFTs (`mint_receipt`, `create_escrow_with_receipt`, sync / revoke / recover).
+pub const FEATURE_RECEIPTS: u64 = 1 << 0;
+/// Token-2022 mints accepted as escrow mints. Unset: `create_escrow` rejects them.
+pub const FEATURE_TOKEN_2022: u64 = 1 << 1;
+/// Pull-style payouts: releases into a fractionalized claim's pool that share
+/// holders redeem (`fractionalize_claim`, `redeem_shares`), and claw-back
+/// holdings the payee withdraws (`withdraw_held`).
+pub const FEATURE_PULL_PAYMENTS: u64 = 1 << 2;
+
+/// Subsystems compiled into this build.
+pub const FEATURE_BITS: u64 = FEATURE_RECEIPTS | FEATURE_PULL_PAYMENTS;
+
+/// Returned by `get_version` via return data. Fields are only ever appended.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ProgramVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+    pub feature_bits: u64,
+}
+
+impl ProgramVersion {
+    pub const CURRENT: ProgramVersion = ProgramVersion {
+        major: VERSION_MAJOR,
+        minor: VERSION_MINOR,
+        patch: VERSION_PATCH,
+        feature_bits: FEATURE_BITS,
+    };
+}
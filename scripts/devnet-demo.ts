@@ -199,6 +199,7 @@ async function main() {
     .createEscrow(seed, escrowAmount, milestones, expiresAt)
     .accounts({
       maker: maker.publicKey,
+      payer: maker.publicKey,
       taker: taker.publicKey,
       mint,
       escrowState: escrowPDA,
@@ -23,6 +23,18 @@ import { assert } from "chai";
 import {
   findEscrowConfigPDA,
   findEscrowPDA,
+  findEscrowRequestPDA,
+  findHoldingPDA,
+  findMakerProfilePDA,
+  findApprovalPDA,
+  findEscrowArchivePDA,
+  findEmergencyWithdrawalPDA,
+  renewActionHash,
+  DISPUTE_INITIATORS_ALL,
+  CANCEL_REASON_COUNT,
+  CONFIG_VERSION,
+  HASH_ALGO_SHA256,
+  HASH_ALGO_KECCAK256,
   createDescriptionHash,
   makeMilestones,
 } from "../client/pda";
@@ -38,6 +50,10 @@ const IDL = require("../target/idl/escrow.json");
 const FEE_BPS = 250; // 2.5 %
 const DISPUTE_TIMEOUT = new BN(86400); // 1 day
 const TOTAL_AMOUNT = new BN(1_000_000);
+const ANY_AMOUNT = new BN(0); // approve_milestone: skip expected-amount check
+const ANY_HASH = new Array(32).fill(0); // approve_milestone: skip expected-hash check
+// Deployed baseline EscrowConfig: discriminator, authority, fee_bps, fee_collector, dispute_timeout, bump
+const BASELINE_CONFIG_LEN = 8 + 32 + 2 + 32 + 8 + 1;
 
 // ---------------------------------------------------------------------------
 // Helpers
@@ -189,6 +205,11 @@ describe("escrow-bankrun (time-dependent tests)", () => {
       seed?: BN;
       milestoneAmounts?: BN[];
       expiresAt?: BN;
+      acceptanceDeadline?: BN;
+      clawBackSeconds?: BN;
+      takerBond?: BN;
+      feeExempt?: boolean[];
+      milestoneBeneficiaries?: (PublicKey | null)[];
     } = {}
   ): Promise<{
     seed: BN;
@@ -219,13 +240,17 @@ describe("escrow-bankrun (time-dependent tests)", () => {
 
     const milestones = makeMilestones(
       milestoneAmounts,
-      milestoneAmounts.map((_, i) => `bankrun-task-${i}`)
+      milestoneAmounts.map((_, i) => `bankrun-task-${i}`),
+      overrides.feeExempt ?? [],
+      [],
+      overrides.milestoneBeneficiaries ?? []
     );
 
     await program.methods
-      .createEscrow(seed, totalAmount, milestones, expiresAt)
+      .createEscrow(seed, totalAmount, milestones, expiresAt, 0, null, 0, overrides.acceptanceDeadline ?? null, false, overrides.clawBackSeconds ?? new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, overrides.takerBond ?? new BN(0))
       .accounts({
         maker: maker.publicKey,
+        payer: maker.publicKey,
         taker: taker.publicKey,
         escrowConfig: configPDA,
         mint,
@@ -274,7 +299,7 @@ describe("escrow-bankrun (time-dependent tests)", () => {
 
     // Approve milestone 0 only
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
@@ -393,10 +418,9 @@ describe("escrow-bankrun (time-dependent tests)", () => {
       .signers([taker])
       .rpc();
 
-    // Read the dispute's initiated_at from on-chain state
+    // Read the stored dispute deadline from on-chain state
     const escrowBefore = await program.account.escrowState.fetch(escrowPDA);
-    const initiatedAt = (escrowBefore.dispute as any).initiatedAt as BN;
-    const disputeTimeout = (escrowBefore.dispute as any).timeout as BN;
+    const deadline = (escrowBefore.dispute as any).deadline as BN;
 
     // Record balances before
     const makerBefore = await getAccount(provider.connection, makerATA);
@@ -404,7 +428,7 @@ describe("escrow-bankrun (time-dependent tests)", () => {
     const feeBefore = await getAccount(provider.connection, feeCollectorATA);
 
     // Warp past dispute timeout AND past expires_at (whichever is later)
-    const disputeDeadline = initiatedAt.toNumber() + disputeTimeout.toNumber();
+    const disputeDeadline = deadline.toNumber();
     const warpTarget = Math.max(disputeDeadline, expiresAt.toNumber()) + 1;
     await warpTo(warpTarget);
 
@@ -495,19 +519,23 @@ describe("escrow-bankrun (time-dependent tests)", () => {
       .signers([maker])
       .rpc();
 
-    // Read initiated_at
+    // Read the stored dispute deadline
     const escrowData = await program.account.escrowState.fetch(escrowPDA);
     const initiatedAt = (escrowData.dispute as any).initiatedAt as BN;
-    const disputeTimeout = (escrowData.dispute as any).timeout as BN;
+    const disputeDeadline = ((escrowData.dispute as any).deadline as BN).toNumber();
+    assert.equal(
+      disputeDeadline,
+      initiatedAt.toNumber() + DISPUTE_TIMEOUT.toNumber(),
+      "Stored deadline should equal initiated_at + timeout"
+    );
 
     // Warp past dispute timeout
-    const disputeDeadline = initiatedAt.toNumber() + disputeTimeout.toNumber();
     await warpTo(disputeDeadline + 1);
 
     // Try resolve_dispute — should fail with EscrowExpired
     try {
       await program.methods
-        .resolveDispute({ makerWins: {} })
+        .resolveDispute({ makerWins: {} }, null, false, false)
         .accounts({
           authority: authority.publicKey,
           escrowConfig: configPDA,
@@ -688,7 +716,7 @@ describe("escrow-bankrun (time-dependent tests)", () => {
 
     // Approve BOTH milestones
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
@@ -697,7 +725,7 @@ describe("escrow-bankrun (time-dependent tests)", () => {
       .rpc();
 
     await program.methods
-      .approveMilestone(1)
+      .approveMilestone(1, ANY_AMOUNT, ANY_HASH, null)
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
@@ -782,4 +810,2057 @@ describe("escrow-bankrun (time-dependent tests)", () => {
       "Beneficiary should receive 975_000 (1_000_000 minus 25_000 fee)"
     );
   });
+
+  // =========================================================================
+  // Test 85: release_delay challenge window — unapprove inside, release at boundary
+  // =========================================================================
+  it("85. release_delay: release blocked inside window, unapprove allowed, release at boundary", async () => {
+    const RELEASE_DELAY = 600; // 10 minutes
+
+    await program.methods
+      .updateConfig(null, null, null, new BN(RELEASE_DELAY), null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        feeCollector: feeCollector.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(500_000), new BN(500_000)],
+    });
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.releaseDelay.toNumber(), RELEASE_DELAY, "release_delay snapshotted at creation");
+
+    const releaseAccounts = {
+      payer: stranger.publicKey,
+      escrowState: escrowPDA,
+      escrowConfig: configPDA,
+      mint,
+      vault,
+      beneficiaryTokenAccount: takerATA,
+      feeCollectorTokenAccount: feeCollectorATA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // Approve milestone 0 — release is blocked inside the window
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    try {
+      await program.methods
+        .releaseMilestone(0, null, false)
+        .accounts(releaseAccounts)
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown ReleaseDelayNotElapsed");
+    } catch (err: any) {
+      assert.include(err.message, "ReleaseDelayNotElapsed");
+    }
+
+    // Maker can revert milestone 0 while the window is open
+    await program.methods
+      .unapproveMilestone(0)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[0].status.pending !== undefined, "Milestone 0 back to Pending");
+    assert.equal(escrow.milestones[0].approvedAt.toNumber(), 0);
+
+    // Approve milestone 1 and warp exactly to approved_at + release_delay
+    await program.methods
+      .approveMilestone(1, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    const approvedAt = escrow.milestones[1].approvedAt.toNumber();
+    await warpTo(approvedAt + RELEASE_DELAY);
+
+    try {
+      await program.methods
+        .unapproveMilestone(1)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown ChallengeWindowClosed");
+    } catch (err: any) {
+      assert.include(err.message, "ChallengeWindowClosed");
+    }
+
+    await program.methods
+      .releaseMilestone(1, null, false)
+      .accounts(releaseAccounts)
+      .signers([stranger])
+      .rpc();
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[1].status.released !== undefined, "Milestone 1 released at boundary");
+
+    // Restore zero delay for subsequent tests
+    await program.methods
+      .updateConfig(null, null, null, new BN(0), null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        feeCollector: feeCollector.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+  });
+
+  // ===========================================================================
+  // 106. accept_escrow - lapsed acceptance deadline, maker reclaims in full
+  // ===========================================================================
+  it("106. accept_escrow: rejected after the deadline; maker cancels for a full refund", async () => {
+    const clock = await context.banksClient.getClock();
+    const now = Number(clock.unixTimestamp);
+    const acceptanceDeadline = now + 600;
+
+    const { escrowPDA, vault } = await setupEscrow({ acceptanceDeadline: new BN(acceptanceDeadline) });
+
+    await warpTo(acceptanceDeadline + 1);
+
+    try {
+      await program.methods
+        .acceptEscrow()
+        .accounts({ acceptor: taker.publicKey, escrowState: escrowPDA })
+        .signers([taker])
+        .rpc();
+      assert.fail("Should have thrown AcceptanceDeadlinePassed");
+    } catch (err: any) {
+      assert.include(err.message, "AcceptanceDeadlinePassed");
+    }
+
+    await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.cancelled !== undefined);
+    assert.ok(escrow.refundedAmount.eq(escrow.amount), "Full refund before acceptance");
+  });
+
+  // ===========================================================================
+  // 112. claim_expired - FavorNonInitiator timeout policy, both initiator sides
+  // ===========================================================================
+  it("112. claim_expired: FavorNonInitiator pays the party that did not open the dispute", async () => {
+    const setPolicy = (policy: object) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, policy, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    const disputeAndTimeOut = async (initiator: Keypair) => {
+      const clock = await context.banksClient.getClock();
+      const expiresAt = new BN(Number(clock.unixTimestamp) + 7200);
+      const { escrowPDA, vault } = await setupEscrow({
+        milestoneAmounts: [TOTAL_AMOUNT],
+        expiresAt,
+      });
+
+      await program.methods
+        .initiateDispute(createDescriptionHash("frivolous?"))
+        .accounts({ initiator: initiator.publicKey, escrowState: escrowPDA, escrowConfig: configPDA })
+        .signers([initiator])
+        .rpc();
+      const escrow = await program.account.escrowState.fetch(escrowPDA);
+      const deadline = (escrow.dispute as any).deadline.toNumber();
+      await warpTo(Math.max(deadline, expiresAt.toNumber()) + 1);
+
+      const makerBefore = await getAccount(provider.connection, makerATA);
+      const takerBefore = await getAccount(provider.connection, takerATA);
+      await program.methods
+        .claimExpired()
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+      const makerAfter = await getAccount(provider.connection, makerATA);
+      const takerAfter = await getAccount(provider.connection, takerATA);
+      return {
+        maker: (BigInt(makerAfter.amount.toString()) - BigInt(makerBefore.amount.toString())).toString(),
+        taker: (BigInt(takerAfter.amount.toString()) - BigInt(takerBefore.amount.toString())).toString(),
+      };
+    };
+
+    await setPolicy({ favorNonInitiator: {} });
+    const config = await program.account.escrowConfig.fetch(configPDA);
+    assert.ok(config.disputeTimeoutPolicy.favorNonInitiator !== undefined);
+
+    // Taker stalls its own dispute: maker is refunded in full
+    const takerOpened = await disputeAndTimeOut(taker);
+    assert.equal(takerOpened.maker, "1000000");
+    assert.equal(takerOpened.taker, "0");
+
+    // Maker stalls its own dispute: beneficiary is paid (minus the 2.5% fee)
+    const makerOpened = await disputeAndTimeOut(maker);
+    assert.equal(makerOpened.maker, "0");
+    assert.equal(makerOpened.taker, "975000");
+
+    // Restore the default split for subsequent tests
+    await setPolicy({ splitOnTimeout: {} });
+  });
+
+  // ===========================================================================
+  // 114. fund_request - expired request cannot be funded, only canceled
+  // ===========================================================================
+  it("114. fund_request: rejects a request past its TTL; requester can still cancel", async () => {
+    const seed = nextSeed();
+    const [requestPDA] = findEscrowRequestPDA(maker.publicKey, seed);
+    const [escrowPDA] = findEscrowPDA(maker.publicKey, seed);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
+    const clock = await context.banksClient.getClock();
+    const now = Number(clock.unixTimestamp);
+
+    await program.methods
+      .requestEscrow(seed, {
+        amount: TOTAL_AMOUNT,
+        milestones: makeMilestones([TOTAL_AMOUNT], ["bankrun-request"]),
+        // Escrow expiry well beyond the request TTL so only the request lapses
+        expiresAt: new BN(now + 60 * 24 * 3600),
+        category: 0,
+        initialBeneficiary: null,
+        hashAlgo: 0,
+      })
+      .accounts({
+        requester: taker.publicKey,
+        maker: maker.publicKey,
+        mint,
+        escrowConfig: configPDA,
+        escrowRequest: requestPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([taker])
+      .rpc();
+
+    const request = await program.account.escrowRequest.fetch(requestPDA);
+    await warpTo(request.expiresAt.toNumber() + 1);
+
+    try {
+      await program.methods
+        .fundRequest(seed)
+        .accounts({
+          maker: maker.publicKey,
+          taker: taker.publicKey,
+          escrowRequest: requestPDA,
+          escrowConfig: configPDA,
+          mint,
+          escrowState: escrowPDA,
+          vault,
+          makerTokenAccount: makerATA,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown RequestExpired");
+    } catch (err: any) {
+      assert.include(err.message, "RequestExpired");
+    }
+
+    await program.methods
+      .cancelRequest()
+      .accounts({ requester: taker.publicKey, escrowRequest: requestPDA })
+      .signers([taker])
+      .rpc();
+    assert.isNull(
+      await context.banksClient.getAccount(requestPDA),
+      "Request closed on cancel"
+    );
+  });
+
+  // =========================================================================
+  // 119. claim_active_expired - payout accounts only needed for approved work
+  // =========================================================================
+  it("119. claim_active_expired: refunds pending without payout accounts, requires them for approved", async () => {
+    const clock = await context.banksClient.getClock();
+    const expiresAt = new BN(Number(clock.unixTimestamp) + 7200);
+
+    const pendingOnly = await setupEscrow({
+      milestoneAmounts: [new BN(600_000), new BN(400_000)],
+      expiresAt,
+    });
+    const withApproved = await setupEscrow({
+      milestoneAmounts: [new BN(600_000), new BN(400_000)],
+      expiresAt,
+    });
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: withApproved.escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    await warpTo(expiresAt.toNumber() + 1);
+
+    const makerBefore = await getAccount(provider.connection, makerATA);
+    await program.methods
+      .claimActiveExpired()
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: pendingOnly.escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault: pendingOnly.vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([stranger])
+      .rpc();
+    const makerAfter = await getAccount(provider.connection, makerATA);
+    assert.equal(
+      (BigInt(makerAfter.amount.toString()) - BigInt(makerBefore.amount.toString())).toString(),
+      TOTAL_AMOUNT.toString()
+    );
+    const expired = await program.account.escrowState.fetch(pendingOnly.escrowPDA);
+    assert.ok(expired.status.expired !== undefined);
+
+    try {
+      await program.methods
+        .claimActiveExpired()
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: withApproved.escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault: withApproved.vault,
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown PayoutAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "PayoutAccountMissing");
+    }
+  });
+
+  // =========================================================================
+  // 120. claim_dispute_timeout / claim_active_expired - each only takes its branch
+  // =========================================================================
+  it("120. claim_dispute_timeout: rejects active escrows; claim_active_expired rejects disputed ones", async () => {
+    const clock = await context.banksClient.getClock();
+    const expiresAt = new BN(Number(clock.unixTimestamp) + 7200);
+
+    const active = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT], expiresAt });
+    const disputed = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT], expiresAt });
+    await program.methods
+      .initiateDispute(createDescriptionHash("split claim test"))
+      .accounts({
+        initiator: taker.publicKey,
+        escrowState: disputed.escrowPDA,
+        escrowConfig: configPDA,
+      })
+      .signers([taker])
+      .rpc();
+    const deadline = ((await program.account.escrowState.fetch(disputed.escrowPDA)).dispute as any)
+      .deadline as BN;
+
+    await warpTo(Math.max(deadline.toNumber(), expiresAt.toNumber()) + 1);
+
+    try {
+      await program.methods
+        .claimDisputeTimeout()
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: active.escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault: active.vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown EscrowNotExpired");
+    } catch (err: any) {
+      assert.include(err.message, "EscrowNotExpired");
+    }
+
+    try {
+      await program.methods
+        .claimActiveExpired()
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: disputed.escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault: disputed.vault,
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown EscrowNotExpired");
+    } catch (err: any) {
+      assert.include(err.message, "EscrowNotExpired");
+    }
+
+    await program.methods
+      .claimDisputeTimeout()
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: disputed.escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault: disputed.vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([stranger])
+      .rpc();
+    const settled = await program.account.escrowState.fetch(disputed.escrowPDA);
+    assert.ok(settled.status.expired !== undefined);
+    assert.ok(settled.milestones[0].status.cancelled !== undefined);
+  });
+
+  // ===========================================================================
+  // 124. claw-back window — released payouts held, reclaimed inside, withdrawn after
+  // ===========================================================================
+  it("124. withdraw_held / reclaim_held: claw-back window holds released payouts", async () => {
+    const CLAW_BACK = 3600;
+    const setWindow = (window: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, new BN(window), null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await setWindow(CLAW_BACK);
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(400_000), new BN(600_000)],
+    });
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.clawBackSeconds.toNumber(), CLAW_BACK, "window snapshotted at creation");
+
+    const releaseAccounts = (index: number) => ({
+      payer: stranger.publicKey,
+      escrowState: escrowPDA,
+      escrowConfig: configPDA,
+      mint,
+      vault,
+      beneficiaryTokenAccount: takerATA,
+      feeCollectorTokenAccount: feeCollectorATA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      holdingAccount: findHoldingPDA(escrowPDA, index, program.programId)[0],
+    });
+    for (const index of [0, 1]) {
+      await program.methods
+        .approveMilestone(index, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+    }
+
+    // Without a holding account the release is refused
+    try {
+      await program.methods
+        .releaseMilestone(0, null, false)
+        .accounts({ ...releaseAccounts(0), holdingAccount: null })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown HoldingAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "HoldingAccountMissing");
+    }
+
+    const takerBefore = await getAccount(provider.connection, takerATA);
+    const makerBefore = await getAccount(provider.connection, makerATA);
+    for (const index of [0, 1]) {
+      await program.methods
+        .releaseMilestone(index, null, false)
+        .accounts(releaseAccounts(index))
+        .signers([stranger])
+        .rpc();
+    }
+    const [holding0] = findHoldingPDA(escrowPDA, 0, program.programId);
+    const [holding1] = findHoldingPDA(escrowPDA, 1, program.programId);
+    assert.equal((await getAccount(provider.connection, holding0)).amount.toString(), "390000");
+    assert.equal((await getAccount(provider.connection, holding1)).amount.toString(), "585000");
+    let takerAfter = await getAccount(provider.connection, takerATA);
+    assert.equal(takerAfter.amount.toString(), takerBefore.amount.toString(), "Nothing paid out yet");
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    const heldUntil = escrow.milestones[1].heldUntil.toNumber();
+    assert.ok(escrow.status.completed !== undefined);
+
+    // Inside the window: withdrawal is blocked, the arbiter reverses milestone 0
+    try {
+      await program.methods
+        .withdrawHeld(1)
+        .accounts({
+          beneficiary: taker.publicKey,
+          escrowState: escrowPDA,
+          mint,
+          holdingAccount: holding1,
+          beneficiaryTokenAccount: takerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([taker])
+        .rpc();
+      assert.fail("Should have thrown ClawBackWindowOpen");
+    } catch (err: any) {
+      assert.include(err.message, "ClawBackWindowOpen");
+    }
+
+    const reclaimAccounts = (index: number) => ({
+      authority: authority.publicKey,
+      escrowState: escrowPDA,
+      escrowConfig: configPDA,
+      mint,
+      holdingAccount: findHoldingPDA(escrowPDA, index, program.programId)[0],
+      makerTokenAccount: makerATA,
+      beneficiary: taker.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+    await program.methods
+      .reclaimHeld(0)
+      .accounts(reclaimAccounts(0))
+      .signers([authority])
+      .rpc();
+    const makerAfter = await getAccount(provider.connection, makerATA);
+    assert.equal(
+      (BigInt(makerAfter.amount.toString()) - BigInt(makerBefore.amount.toString())).toString(),
+      "390000",
+      "Reclaimed payout returned to the maker"
+    );
+    assert.isNull(await context.banksClient.getAccount(holding0), "Holding closed");
+
+    // After the window: reclaim is refused, the beneficiary withdraws
+    await warpTo(heldUntil);
+    try {
+      await program.methods
+        .reclaimHeld(1)
+        .accounts(reclaimAccounts(1))
+        .signers([authority])
+        .rpc();
+      assert.fail("Should have thrown ClawBackWindowClosed");
+    } catch (err: any) {
+      assert.include(err.message, "ClawBackWindowClosed");
+    }
+
+    await program.methods
+      .withdrawHeld(1)
+      .accounts({
+        beneficiary: taker.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        holdingAccount: holding1,
+        beneficiaryTokenAccount: takerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([taker])
+      .rpc();
+    takerAfter = await getAccount(provider.connection, takerATA);
+    assert.equal(
+      (BigInt(takerAfter.amount.toString()) - BigInt(takerBefore.amount.toString())).toString(),
+      "585000"
+    );
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.milestones[1].heldUntil.toNumber(), 0);
+
+    await setWindow(0);
+  });
+
+  // ===========================================================================
+  // 127. approval_extension — late approvals push expires_at out, within budget
+  // ===========================================================================
+  it("127. approve_milestone: late approval extends expiry up to the configured cap", async () => {
+    const setExtension = (window: number, cap: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, new BN(window), new BN(cap), null, null, null, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    const approve = (index: number) =>
+      program.methods
+        .approveMilestone(index, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+
+    await setExtension(600, 900);
+    const { escrowPDA, vault } = await setupEscrow();
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    const originalExpiry = escrow.expiresAt.toNumber();
+    assert.equal(escrow.expiryExtensionBudget.toNumber(), 900);
+
+    // Exactly approval_extension before expiry: unchanged
+    await warpTo(originalExpiry - 600);
+    await approve(0);
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.expiresAt.toNumber(), originalExpiry);
+
+    // Inside the window: pushed to now + 600
+    await warpTo(originalExpiry - 100);
+    await approve(1);
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.expiresAt.toNumber(), originalExpiry + 500);
+    assert.equal(escrow.expiryExtensionBudget.toNumber(), 400);
+
+    // Budget exhausted: capped at original expiry + 900
+    await warpTo(originalExpiry + 499);
+    await approve(2);
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.expiresAt.toNumber(), originalExpiry + 900);
+    assert.equal(escrow.expiryExtensionBudget.toNumber(), 0);
+
+    // The late approval can still be released after the original expiry
+    await program.methods
+      .releaseMilestone(2, null, false)
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([stranger])
+      .rpc();
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[2].status.released !== undefined);
+
+    await setExtension(0, 0);
+  });
+
+  // ===========================================================================
+  // 129. clawback — maker-chosen window, authority reverses every open hold
+  // ===========================================================================
+  it("129. clawback: authority reverses held releases on a completed escrow", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(400_000), new BN(600_000)],
+      clawBackSeconds: new BN(7200),
+    });
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.clawBackSeconds.toNumber(), 7200, "maker-chosen window recorded");
+
+    for (const index of [0, 1]) {
+      await program.methods
+        .approveMilestone(index, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+      await program.methods
+        .releaseMilestone(index, null, false)
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          holdingAccount: findHoldingPDA(escrowPDA, index, program.programId)[0],
+        })
+        .signers([stranger])
+        .rpc();
+    }
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.completed !== undefined);
+
+    const holdings = [0, 1].map((index) => ({
+      pubkey: findHoldingPDA(escrowPDA, index, program.programId)[0],
+      isWritable: true,
+      isSigner: false,
+    }));
+    const clawbackAccounts = {
+      authority: authority.publicKey,
+      escrowConfig: configPDA,
+      escrowState: escrowPDA,
+      mint,
+      makerTokenAccount: makerATA,
+      beneficiary: taker.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    try {
+      await program.methods
+        .clawback()
+        .accounts({ ...clawbackAccounts, authority: stranger.publicKey })
+        .remainingAccounts(holdings)
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown NotAuthority");
+    } catch (err: any) {
+      assert.include(err.message, "NotAuthority");
+    }
+
+    try {
+      await program.methods
+        .clawback()
+        .accounts(clawbackAccounts)
+        .remainingAccounts(holdings.slice(0, 1))
+        .signers([authority])
+        .rpc();
+      assert.fail("Should have thrown HoldingAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "HoldingAccountMissing");
+    }
+
+    const makerBefore = await getAccount(provider.connection, makerATA);
+    await program.methods
+      .clawback()
+      .accounts(clawbackAccounts)
+      .remainingAccounts(holdings)
+      .signers([authority])
+      .rpc();
+    const makerAfter = await getAccount(provider.connection, makerATA);
+    // Net payouts (2.5% fee kept): 390_000 + 585_000
+    assert.equal((makerAfter.amount - makerBefore.amount).toString(), "975000");
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones.every((m: any) => m.heldUntil.toNumber() === 0));
+
+    try {
+      await program.methods
+        .clawback()
+        .accounts(clawbackAccounts)
+        .signers([authority])
+        .rpc();
+      assert.fail("Should have thrown NothingHeld");
+    } catch (err: any) {
+      assert.include(err.message, "NothingHeld");
+    }
+  });
+
+  // =========================================================================
+  // 130. funds_distributed - claimable vs already-claimed expiry
+  // =========================================================================
+  it("130. funds_distributed: false while expired-but-Active, true after claim_active_expired", async () => {
+    const clock = await context.banksClient.getClock();
+    const expiresAt = new BN(Number(clock.unixTimestamp) + 7200);
+    const { escrowPDA, vault } = await setupEscrow({ expiresAt });
+
+    await warpTo(expiresAt.toNumber() + 1);
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok("active" in escrow.status);
+    assert.equal(escrow.fundsDistributed, false);
+
+    await program.methods
+      .claimActiveExpired()
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([stranger])
+      .rpc();
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok("expired" in escrow.status);
+    assert.equal(escrow.fundsDistributed, true);
+  });
+
+  // =========================================================================
+  // 131. remainder_policy - rounding dust routed per the snapshotted policy
+  // =========================================================================
+  it("131. remainder_policy: split dust goes to the fee collector under ToFeeCollector", async () => {
+    const setRemainderPolicy = (policy: object) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, policy, null, null, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await setRemainderPolicy({ toFeeCollector: {} });
+    const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [new BN(1_000_001)] });
+    // Restore the default right away: the escrow keeps its creation-time snapshot
+    await setRemainderPolicy({ toBeneficiary: {} });
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.remainderPolicy.toFeeCollector !== undefined);
+
+    await program.methods
+      .initiateDispute(createDescriptionHash("half done"))
+      .accounts({ initiator: taker.publicKey, escrowState: escrowPDA, escrowConfig: configPDA })
+      .signers([taker])
+      .rpc();
+
+    const balances = async () =>
+      Promise.all(
+        [makerATA, takerATA, feeCollectorATA].map(async (ata) =>
+          BigInt((await getAccount(provider.connection, ata)).amount.toString())
+        )
+      );
+    const before = await balances();
+    await program.methods
+      .resolveDispute({ split: { makerBps: 5_000 } }, null, false, false)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+    const after = await balances();
+
+    // 500_000.5 each side; the beneficiary's half pays 12_500.0125 in fees.
+    // Every party is floored and the one leftover unit goes to the fee collector.
+    const deltas = after.map((b, i) => (b - before[i]).toString());
+    assert.deepEqual(deltas, ["500000", "487500", "12501"]);
+  });
+
+  // =========================================================================
+  // 137. max_open_escrows_per_maker - claim_expired gives the slot back
+  // =========================================================================
+  it("137. max_open_escrows_per_maker: claim_expired frees the expired escrow's slot", async () => {
+    const setCap = (cap: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, cap, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    const [profilePDA] = findMakerProfilePDA(maker.publicKey);
+
+    await setCap(1);
+    const clock = await context.banksClient.getClock();
+    const expiresAt = new BN(Number(clock.unixTimestamp) + 7200);
+    const { escrowPDA, vault } = await setupEscrow({ expiresAt });
+    const baseline = (await program.account.makerProfile.fetch(profilePDA)).openEscrows;
+    assert.isTrue((await program.account.escrowState.fetch(escrowPDA)).countedOpen);
+
+    await warpTo(expiresAt.toNumber() + 1);
+    await program.methods
+      .claimExpired()
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        makerProfile: profilePDA,
+      })
+      .signers([stranger])
+      .rpc();
+
+    const profile = await program.account.makerProfile.fetch(profilePDA);
+    assert.equal(profile.openEscrows, baseline - 1);
+    assert.isFalse((await program.account.escrowState.fetch(escrowPDA)).countedOpen);
+    await setCap(0);
+  });
+
+  // =========================================================================
+  // 141. expiry_extension_per_approval - approvals keep progressing escrows alive
+  // =========================================================================
+  it("141. approve_milestone: expiry_extension_per_approval pushes expiry out on every approval", async () => {
+    const setKeepAlive = (perApproval: number, cap: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, new BN(cap), null, null, null, new BN(perApproval), null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await setKeepAlive(3600, 5000);
+    const { escrowPDA } = await setupEscrow();
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    const originalExpiry = escrow.expiresAt.toNumber();
+    assert.equal(escrow.expiryExtensionPerApproval.toNumber(), 3600);
+
+    const expected = [originalExpiry + 3600, originalExpiry + 5000, originalExpiry + 5000];
+    for (const [index, expiresAt] of expected.entries()) {
+      await program.methods
+        .approveMilestone(index, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+      escrow = await program.account.escrowState.fetch(escrowPDA);
+      assert.equal(escrow.expiresAt.toNumber(), expiresAt, `after approval ${index}`);
+    }
+    assert.equal(escrow.expiryExtensionBudget.toNumber(), 0);
+
+    await setKeepAlive(0, 0);
+  });
+
+  // =========================================================================
+  // Test 143: record_approval — an expired approval no longer stands in for
+  //           the beneficiary's signature
+  // =========================================================================
+  it("143. renew_escrow: expired approvals are rejected", async () => {
+    const clock = await context.banksClient.getClock();
+    const now = Number(clock.unixTimestamp);
+    const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [new BN(1_000_000)] });
+    const [approvalPDA] = findApprovalPDA(escrowPDA, taker.publicKey);
+
+    const topUp = new BN(100_000);
+    const newMilestones = makeMilestones([topUp], ["bankrun-renewal"]);
+    const newExpiresAt = new BN(now + 30 * 86400);
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+
+    const recordApproval = (expiresAt: number) =>
+      program.methods
+        .recordApproval(
+          { renewEscrow: {} },
+          renewActionHash(escrowPDA, escrow.nonce, topUp, newMilestones, newExpiresAt),
+          new BN(expiresAt)
+        )
+        .accounts({
+          approver: taker.publicKey,
+          escrowState: escrowPDA,
+          approval: approvalPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([taker])
+        .rpc();
+
+    for (const expiresAt of [now, now + 31 * 86400]) {
+      try {
+        await recordApproval(expiresAt);
+        assert.fail("Should have thrown InvalidApprovalExpiry");
+      } catch (err: any) {
+        assert.include(err.message, "InvalidApprovalExpiry");
+      }
+    }
+    await recordApproval(now + 600);
+
+    // Escrow is still inside its renewal window, but the approval has lapsed
+    await warpTo(now + 601);
+    try {
+      await program.methods
+        .renewEscrow(topUp, newMilestones, newExpiresAt)
+        .accounts({
+          maker: maker.publicKey,
+          beneficiary: null,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          approval: approvalPDA,
+        })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown ApprovalExpired");
+    } catch (err: any) {
+      assert.include(err.message, "ApprovalExpired");
+    }
+  });
+
+  // =========================================================================
+  // Test 145: recover_receipt — config authority fallback once the escrow has
+  //           been expired for RECEIPT_RECOVERY_DELAY
+  // =========================================================================
+  it("145. recover_receipt: authority stands in for a lost beneficiary only after the recovery delay", async () => {
+    const { escrowPDA } = await setupEscrow({ milestoneAmounts: [new BN(1_000_000)] });
+
+    // Metaplex isn't loaded here, so plant a receipt mint directly on the escrow
+    const lostReceipt = Keypair.generate().publicKey;
+    const escrowInfo = await context.banksClient.getAccount(escrowPDA);
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    const encoded = await program.coder.accounts.encode("escrowState", { ...escrow, receiptMint: lostReceipt });
+    const data = Buffer.alloc(escrowInfo.data.length);
+    encoded.copy(data);
+    context.setAccount(escrowPDA, { ...escrowInfo, data });
+
+    const recoverViaAuthority = () =>
+      program.methods
+        .recoverReceipt(stranger.publicKey)
+        .accounts({
+          maker: maker.publicKey,
+          beneficiary: null,
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+        })
+        .signers([maker, authority])
+        .rpc();
+
+    const recoveryOpensAt = escrow.expiresAt.toNumber() + 30 * 86400;
+    await warpTo(recoveryOpensAt - 1);
+    try {
+      await recoverViaAuthority();
+      assert.fail("Should have thrown RecoveryCoSignerMissing");
+    } catch (err: any) {
+      assert.include(err.message, "RecoveryCoSignerMissing");
+    }
+
+    await warpTo(recoveryOpensAt);
+    await recoverViaAuthority();
+    const recovered = await program.account.escrowState.fetch(escrowPDA);
+    assert.isNull(recovered.receiptMint);
+    assert.ok(recovered.voidedReceiptMint.equals(lostReceipt));
+    assert.ok(recovered.beneficiary.equals(stranger.publicKey));
+  });
+
+  // =========================================================================
+  // Test 149: close_archive — the maker reclaims the archive's rent only
+  //           once ARCHIVE_RETENTION has passed
+  // =========================================================================
+  it("149. close_archive: closable by the maker after the retention period", async () => {
+    const { escrowPDA, vault } = await setupEscrow();
+    const [archivePDA] = findEscrowArchivePDA(escrowPDA);
+
+    await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .archiveAndClose()
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowArchive: archivePDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([maker])
+      .rpc();
+
+    const record = await program.account.escrowArchive.fetch(archivePDA);
+    assert.ok(record.status.cancelled !== undefined);
+    assert.ok(record.refundedAmount.eqn(1_000_000));
+
+    const closeArchive = () =>
+      program.methods
+        .closeArchive()
+        .accounts({ maker: maker.publicKey, escrowArchive: archivePDA })
+        .signers([maker])
+        .rpc();
+
+    const retainedUntil = record.closedAt.toNumber() + 365 * 86400;
+    await warpTo(retainedUntil - 1);
+    try {
+      await closeArchive();
+      assert.fail("Should have thrown ArchiveRetentionActive");
+    } catch (err: any) {
+      assert.include(err.message, "ArchiveRetentionActive");
+    }
+
+    await warpTo(retainedUntil);
+    await closeArchive();
+    assert.isNull(await context.banksClient.getAccount(archivePDA));
+  });
+
+  // =========================================================================
+  // Test 165: settle_bond — expiry claims pay out only the escrow amount;
+  //           the held taker bond goes back to the taker
+  // =========================================================================
+  it("165. settle_bond: expiry routes leave the bond in the vault and return it to the taker", async () => {
+    const BOND = new BN(50_000);
+    const mintToTx = new Transaction().add(
+      createMintToInstruction(mint, takerATA, context.payer.publicKey, BigInt(BOND.muln(2).toString()))
+    );
+    mintToTx.recentBlockhash = context.lastBlockhash;
+    mintToTx.feePayer = context.payer.publicKey;
+    mintToTx.sign(context.payer);
+    await context.banksClient.processTransaction(mintToTx);
+
+    const balance = async (account: PublicKey) =>
+      new BN((await getAccount(provider.connection, account)).amount.toString());
+
+    const bondedEscrow = async () => {
+      const created = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT], takerBond: BOND });
+      await program.methods
+        .postBond()
+        .accounts({
+          taker: taker.publicKey,
+          escrowState: created.escrowPDA,
+          mint,
+          vault: created.vault,
+          takerTokenAccount: takerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([taker])
+        .rpc();
+      return created;
+    };
+
+    const activeExpired = await bondedEscrow();
+    const disputed = await bondedEscrow();
+    await program.methods
+      .initiateDispute(createDescriptionHash("bonded timeout"))
+      .accounts({ initiator: taker.publicKey, escrowState: disputed.escrowPDA, escrowConfig: configPDA })
+      .signers([taker])
+      .rpc();
+
+    const escrow = await program.account.escrowState.fetch(disputed.escrowPDA);
+    const deadline = (escrow.dispute as any).deadline.toNumber();
+    await warpTo(Math.max(deadline, escrow.expiresAt.toNumber()) + 1);
+
+    for (const { escrowPDA, vault } of [activeExpired, disputed]) {
+      await program.methods
+        .claimExpired()
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+
+      const expired = await program.account.escrowState.fetch(escrowPDA);
+      assert.ok(expired.status.expired !== undefined);
+      assert.equal((await balance(vault)).toString(), BOND.toString(), "Only the bond is left");
+
+      const makerBefore = await balance(makerATA);
+      const takerBefore = await balance(takerATA);
+      await program.methods
+        .settleBond()
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: escrowPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          takerTokenAccount: takerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.ok((await balance(makerATA)).eq(makerBefore));
+      assert.ok((await balance(takerATA)).sub(takerBefore).eq(BOND));
+      assert.equal((await balance(vault)).toString(), "0");
+    }
+  });
+
+  // =========================================================================
+  // Test 167: emergency withdraw — authority-only, timelocked, drains the
+  //           vault to the proposed recovery address and cancels the escrow
+  // =========================================================================
+  it("167. emergency withdraw: executable only after the timelock, pays the recovery owner", async () => {
+    const { escrowPDA, vault } = await setupEscrow();
+    const [proposalPDA] = findEmergencyWithdrawalPDA(escrowPDA);
+
+    const recoveryATA = getAssociatedTokenAddressSync(mint, stranger.publicKey);
+    const createAtaTx = new Transaction().add(
+      createAssociatedTokenAccountInstruction(context.payer.publicKey, recoveryATA, stranger.publicKey, mint)
+    );
+    createAtaTx.recentBlockhash = context.lastBlockhash;
+    createAtaTx.feePayer = context.payer.publicKey;
+    createAtaTx.sign(context.payer);
+    await context.banksClient.processTransaction(createAtaTx);
+
+    const propose = (signer: Keypair) =>
+      program.methods
+        .proposeEmergencyWithdraw(stranger.publicKey)
+        .accounts({
+          authority: signer.publicKey,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+          emergencyWithdrawal: proposalPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc();
+
+    try {
+      await propose(maker);
+      assert.fail("Should have thrown NotAuthority");
+    } catch (err: any) {
+      assert.include(err.message, "NotAuthority");
+    }
+
+    // Escrows that opted out of authority intervention can't be drained either
+    const { escrowPDA: optedOutPDA } = await setupEscrow();
+    const optedOutInfo = await context.banksClient.getAccount(optedOutPDA);
+    const optedOut = await program.account.escrowState.fetch(optedOutPDA);
+    const encoded = await program.coder.accounts.encode("escrowState", { ...optedOut, disputesEnabled: false });
+    const data = Buffer.alloc(optedOutInfo.data.length);
+    encoded.copy(data);
+    context.setAccount(optedOutPDA, { ...optedOutInfo, data });
+    try {
+      await program.methods
+        .proposeEmergencyWithdraw(stranger.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          escrowState: optedOutPDA,
+          emergencyWithdrawal: findEmergencyWithdrawalPDA(optedOutPDA)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+      assert.fail("Should have thrown DisputesDisabled");
+    } catch (err: any) {
+      assert.include(err.message, "DisputesDisabled");
+    }
+
+    await propose(authority);
+    const proposal = await program.account.emergencyWithdrawal.fetch(proposalPDA);
+    assert.ok(proposal.recovery.equals(stranger.publicKey));
+    assert.equal(proposal.executableAt.toNumber(), proposal.proposedAt.toNumber() + 7 * 86400);
+
+    const execute = () =>
+      program.methods
+        .executeEmergencyWithdraw()
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+          emergencyWithdrawal: proposalPDA,
+          mint,
+          vault,
+          recoveryTokenAccount: recoveryATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          makerProfile: null,
+        })
+        .signers([authority])
+        .rpc();
+
+    try {
+      await execute();
+      assert.fail("Should have thrown EmergencyWithdrawTimelocked");
+    } catch (err: any) {
+      assert.include(err.message, "EmergencyWithdrawTimelocked");
+    }
+
+    await warpTo(proposal.executableAt.toNumber());
+
+    // A payout still sitting in a claw-back holding would be left behind
+    const setSettlementHeldUntil = async (heldUntil: number) => {
+      const info = await context.banksClient.getAccount(escrowPDA);
+      const state = await program.account.escrowState.fetch(escrowPDA);
+      const encodedState = await program.coder.accounts.encode("escrowState", {
+        ...state,
+        settlementHeldUntil: new BN(heldUntil),
+      });
+      const stateData = Buffer.alloc(info.data.length);
+      encodedState.copy(stateData);
+      context.setAccount(escrowPDA, { ...info, data: stateData });
+    };
+    await setSettlementHeldUntil(proposal.executableAt.toNumber() + 3600);
+    try {
+      await execute();
+      assert.fail("Should have thrown FundsStillHeld");
+    } catch (err: any) {
+      assert.include(err.message, "FundsStillHeld");
+    }
+    await setSettlementHeldUntil(0);
+
+    await execute();
+
+    assert.equal((await getAccount(provider.connection, vault)).amount.toString(), "0");
+    assert.equal((await getAccount(provider.connection, recoveryATA)).amount.toString(), "1000000");
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.cancelled !== undefined);
+    assert.ok(escrow.emergencyWithdrawn.eqn(1_000_000), "Drained milestones are not a maker refund");
+    assert.ok(escrow.refundedAmount.eqn(0));
+    assert.isNull(await context.banksClient.getAccount(proposalPDA));
+  });
+
+  // =========================================================================
+  // Test 171: migrate_config — a baseline-sized config is unusable until
+  //           migrated, migration runs once and fills in defaults, and
+  //           creation_paused gates new escrows
+  // =========================================================================
+  it("171. migrate_config: upgrades a baseline config once; creation_paused blocks new escrows", async () => {
+    const configInfo = await context.banksClient.getAccount(configPDA);
+    const currentData = Buffer.from(configInfo.data);
+    const before = await program.account.escrowConfig.fetch(configPDA);
+    context.setAccount(configPDA, { ...configInfo, data: currentData.subarray(0, BASELINE_CONFIG_LEN) });
+
+    const setCreationPaused = (paused: boolean) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, paused, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    try {
+      await setCreationPaused(false);
+      assert.fail("Should not load a baseline config");
+    } catch (err: any) {
+      assert.notInclude(err.message, "Should not load a baseline config");
+    }
+
+    const migrate = (signer: Keypair) =>
+      program.methods
+        .migrateConfig()
+        .accounts({
+          authority: signer.publicKey,
+          escrowConfig: configPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc();
+
+    try {
+      await migrate(maker);
+      assert.fail("Should have thrown NotAuthority");
+    } catch (err: any) {
+      assert.include(err.message, "NotAuthority");
+    }
+
+    await migrate(authority);
+    const migrated = await context.banksClient.getAccount(configPDA);
+    assert.equal(migrated.data.length, currentData.length);
+    let config = await program.account.escrowConfig.fetch(configPDA);
+    // Baseline fields survive; everything appended since gets the initialize_config defaults
+    assert.ok(config.authority.equals(before.authority));
+    assert.equal(config.feeBps, before.feeBps);
+    assert.ok(config.feeCollector.equals(before.feeCollector));
+    assert.ok(config.disputeTimeout.eq(before.disputeTimeout));
+    assert.equal(config.bump, before.bump);
+    assert.equal(config.configVersion, CONFIG_VERSION);
+    assert.isFalse(config.creationPaused);
+    assert.isTrue(config.receiptsEnabled);
+    assert.ok(config.remainderPolicy.toBeneficiary !== undefined);
+    assert.ok(config.disputeTimeoutPolicy.splitOnTimeout !== undefined);
+    assert.ok(config.requestTtl.eqn(7 * 24 * 3600));
+    assert.equal(config.maxDisputesPerEscrow, 3);
+    assert.equal(config.acceptedHashAlgos, (1 << HASH_ALGO_SHA256) | (1 << HASH_ALGO_KECCAK256));
+    assert.equal(config.cancelRoutes.length, CANCEL_REASON_COUNT);
+    assert.isTrue(config.cancelRoutes.every((route: any) => route.feeBps === 0));
+
+    try {
+      await migrate(authority);
+      assert.fail("Should have thrown ConfigAlreadyMigrated");
+    } catch (err: any) {
+      assert.include(err.message, "ConfigAlreadyMigrated");
+    }
+
+    await setCreationPaused(true);
+    try {
+      await setupEscrow();
+      assert.fail("Should have thrown CreationPaused");
+    } catch (err: any) {
+      assert.include(err.message, "CreationPaused");
+    }
+
+    await setCreationPaused(false);
+    config = await program.account.escrowConfig.fetch(configPDA);
+    assert.isFalse(config.creationPaused);
+    await setupEscrow();
+  });
+
+  // =========================================================================
+  // Test 174: emergency withdraw proposals — cancellable by the authority,
+  //           closable by anyone once stale, and never reusable against an
+  //           escrow re-created at the same address
+  // =========================================================================
+  it("174. emergency withdraw: cancel, stale close, and a re-created escrow rejects the old proposal", async () => {
+    const seed = nextSeed();
+    const { escrowPDA, vault } = await setupEscrow({ seed });
+    const [proposalPDA] = findEmergencyWithdrawalPDA(escrowPDA);
+
+    const propose = () =>
+      program.methods
+        .proposeEmergencyWithdraw(stranger.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+          emergencyWithdrawal: proposalPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+    const cancelProposal = (signer: Keypair) =>
+      program.methods
+        .cancelEmergencyWithdraw()
+        .accounts({
+          authority: signer.publicKey,
+          escrowConfig: configPDA,
+          emergencyWithdrawal: proposalPDA,
+        })
+        .signers([signer])
+        .rpc();
+    const closeStale = () =>
+      program.methods
+        .closeStaleEmergencyWithdraw()
+        .accounts({
+          payer: stranger.publicKey,
+          proposedBy: authority.publicKey,
+          emergencyWithdrawal: proposalPDA,
+          escrowState: escrowPDA,
+        })
+        .signers([stranger])
+        .rpc();
+
+    await propose();
+    try {
+      await closeStale();
+      assert.fail("Should have thrown EmergencyWithdrawStillLive");
+    } catch (err: any) {
+      assert.include(err.message, "EmergencyWithdrawStillLive");
+    }
+    try {
+      await cancelProposal(maker);
+      assert.fail("Should have thrown NotAuthority");
+    } catch (err: any) {
+      assert.include(err.message, "NotAuthority");
+    }
+    await cancelProposal(authority);
+    assert.isNull(await context.banksClient.getAccount(proposalPDA));
+
+    // Propose again, then cancel, close and re-create the escrow at the same address
+    await propose();
+    const proposal = await program.account.emergencyWithdrawal.fetch(proposalPDA);
+    await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .closeEscrow()
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    await warpTo(proposal.executableAt.toNumber());
+    await setupEscrow({ seed });
+    const recreated = await program.account.escrowState.fetch(escrowPDA);
+    assert.notEqual(recreated.createdAt.toNumber(), proposal.escrowCreatedAt.toNumber());
+
+    const recoveryATA = getAssociatedTokenAddressSync(mint, stranger.publicKey);
+    if (!(await context.banksClient.getAccount(recoveryATA))) {
+      const createAtaTx = new Transaction().add(
+        createAssociatedTokenAccountInstruction(context.payer.publicKey, recoveryATA, stranger.publicKey, mint)
+      );
+      createAtaTx.recentBlockhash = context.lastBlockhash;
+      createAtaTx.feePayer = context.payer.publicKey;
+      createAtaTx.sign(context.payer);
+      await context.banksClient.processTransaction(createAtaTx);
+    }
+    try {
+      await program.methods
+        .executeEmergencyWithdraw()
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+          emergencyWithdrawal: proposalPDA,
+          mint,
+          vault,
+          recoveryTokenAccount: recoveryATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          makerProfile: null,
+        })
+        .signers([authority])
+        .rpc();
+      assert.fail("Should have thrown EmergencyWithdrawStale");
+    } catch (err: any) {
+      assert.include(err.message, "EmergencyWithdrawStale");
+    }
+    assert.equal((await getAccount(provider.connection, vault)).amount.toString(), "1000000");
+
+    // The leftover proposal is stale for the new escrow: anyone may close it
+    await closeStale();
+    assert.isNull(await context.banksClient.getAccount(proposalPDA));
+  });
+
+  // =========================================================================
+  // Test 175: claim_active_expired - approved milestones charged per milestone
+  // =========================================================================
+  it("175. claim_active_expired: a fee-exempt approved milestone is paid in full at expiry", async () => {
+    const clock = await context.banksClient.getClock();
+    const expiresAt = new BN(Number(clock.unixTimestamp) + 7200);
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(600_000), new BN(400_000)],
+      feeExempt: [true, false],
+      expiresAt,
+    });
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    await warpTo(expiresAt.toNumber() + 1);
+
+    const takerBefore = await getAccount(provider.connection, takerATA);
+    const feeBefore = await getAccount(provider.connection, feeCollectorATA);
+    const makerBefore = await getAccount(provider.connection, makerATA);
+    await program.methods
+      .claimActiveExpired()
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([stranger])
+      .rpc();
+
+    const delta = async (ata: PublicKey, before: { amount: bigint }) =>
+      (BigInt((await getAccount(provider.connection, ata)).amount.toString()) - BigInt(before.amount.toString())).toString();
+    assert.equal(await delta(takerATA, takerBefore), "600000", "Exempt milestone paid without a fee");
+    assert.equal(await delta(feeCollectorATA, feeBefore), "0");
+    assert.equal(await delta(makerATA, makerBefore), "400000");
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.feesPaid.toString(), "0");
+  });
+
+  // =========================================================================
+  // Test 176: cancel_and_release - approved payouts held under a claw-back window
+  // =========================================================================
+  it("176. cancel_and_release: holds each approved payout while a claw-back window applies", async () => {
+    const WINDOW = 3600;
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(600_000), new BN(400_000)],
+      clawBackSeconds: new BN(WINDOW),
+    });
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    const [holding0] = findHoldingPDA(escrowPDA, 0, program.programId);
+    const cancelAndRelease = (holdings: PublicKey[]) =>
+      program.methods
+        .cancelAndRelease(null)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(holdings.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+        .signers([maker])
+        .rpc();
+
+    try {
+      await cancelAndRelease([]);
+      assert.fail("Should have thrown HoldingAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "HoldingAccountMissing");
+    }
+
+    const takerBefore = await getAccount(provider.connection, takerATA);
+    await cancelAndRelease([holding0]);
+    assert.equal((await getAccount(provider.connection, holding0)).amount.toString(), "585000");
+    const takerAfter = await getAccount(provider.connection, takerATA);
+    assert.equal(takerAfter.amount.toString(), takerBefore.amount.toString(), "Nothing paid out directly");
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok("completed" in escrow.status);
+    assert.isAbove(escrow.milestones[0].heldUntil.toNumber(), 0);
+    assert.equal(escrow.milestones[1].heldUntil.toNumber(), 0);
+
+    // Reversible inside the window, like a single release
+    await program.methods
+      .reclaimHeld(0)
+      .accounts({
+        authority: authority.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        holdingAccount: holding0,
+        makerTokenAccount: makerATA,
+        beneficiary: taker.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+    assert.isNull(await context.banksClient.getAccount(holding0));
+  });
+
+  // =========================================================================
+  // Test 177: claim_active_expired - approved payouts held under a claw-back window
+  // =========================================================================
+  it("177. claim_active_expired: holds each approved payout while a claw-back window applies", async () => {
+    const WINDOW = 3600;
+    const clock = await context.banksClient.getClock();
+    const expiresAt = new BN(Number(clock.unixTimestamp) + 7200);
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(600_000), new BN(400_000)],
+      clawBackSeconds: new BN(WINDOW),
+      expiresAt,
+    });
+    await program.methods
+      .approveMilestone(1, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    await warpTo(expiresAt.toNumber() + 1);
+
+    const [holding1] = findHoldingPDA(escrowPDA, 1, program.programId);
+    const takerBefore = await getAccount(provider.connection, takerATA);
+    await program.methods
+      .claimActiveExpired()
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([{ pubkey: holding1, isWritable: true, isSigner: false }])
+      .signers([stranger])
+      .rpc();
+    assert.equal((await getAccount(provider.connection, holding1)).amount.toString(), "390000");
+    assert.equal(
+      (await getAccount(provider.connection, takerATA)).amount.toString(),
+      takerBefore.amount.toString(),
+      "Nothing paid out directly"
+    );
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok("expired" in escrow.status);
+    const heldUntil = escrow.milestones[1].heldUntil.toNumber();
+    assert.isAbove(heldUntil, 0);
+
+    await warpTo(heldUntil);
+    await program.methods
+      .withdrawHeld(1)
+      .accounts({
+        beneficiary: taker.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        holdingAccount: holding1,
+        beneficiaryTokenAccount: takerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([taker])
+      .rpc();
+    assert.equal(
+      (
+        BigInt((await getAccount(provider.connection, takerATA)).amount.toString()) -
+        BigInt(takerBefore.amount.toString())
+      ).toString(),
+      "390000"
+    );
+  });
+
+  // =========================================================================
+  // Test 178: resolve_dispute - beneficiary share held under a claw-back window
+  // =========================================================================
+  it("178. resolve_dispute: holds the beneficiary's share in the settlement holding", async () => {
+    const SETTLEMENT_HOLDING_INDEX = 255;
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      clawBackSeconds: new BN(3600),
+    });
+    await program.methods
+      .initiateDispute(createDescriptionHash("held ruling"))
+      .accounts({ initiator: taker.publicKey, escrowState: escrowPDA, escrowConfig: configPDA })
+      .signers([taker])
+      .rpc();
+
+    const [settlementHolding] = findHoldingPDA(escrowPDA, SETTLEMENT_HOLDING_INDEX, program.programId);
+    const takerBefore = await getAccount(provider.connection, takerATA);
+    await program.methods
+      .resolveDispute({ takerWins: {} }, null, false, false)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([{ pubkey: settlementHolding, isWritable: true, isSigner: false }])
+      .signers([authority])
+      .rpc();
+    assert.equal((await getAccount(provider.connection, settlementHolding)).amount.toString(), "975000");
+    assert.equal(
+      (await getAccount(provider.connection, takerATA)).amount.toString(),
+      takerBefore.amount.toString(),
+      "Nothing paid out directly"
+    );
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.isAbove(escrow.settlementHeldUntil.toNumber(), 0);
+
+    // Confirmed fraud: clawback reverses the held ruling too
+    const makerBefore = await getAccount(provider.connection, makerATA);
+    await program.methods
+      .clawback()
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        mint,
+        makerTokenAccount: makerATA,
+        beneficiary: taker.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([{ pubkey: settlementHolding, isWritable: true, isSigner: false }])
+      .signers([authority])
+      .rpc();
+    assert.equal(
+      (
+        BigInt((await getAccount(provider.connection, makerATA)).amount.toString()) -
+        BigInt(makerBefore.amount.toString())
+      ).toString(),
+      "975000"
+    );
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.settlementHeldUntil.toNumber(), 0);
+    assert.isNull(await context.banksClient.getAccount(settlementHolding));
+  });
+
+  // =========================================================================
+  // Test 179: claim_dispute_timeout - beneficiary share held under a claw-back window
+  // =========================================================================
+  it("179. claim_dispute_timeout: holds the beneficiary's share in the settlement holding", async () => {
+    const SETTLEMENT_HOLDING_INDEX = 255;
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      clawBackSeconds: new BN(3600),
+    });
+    // Maker-opened: FavorNonInitiator pays the beneficiary on timeout
+    await program.methods
+      .initiateDispute(createDescriptionHash("unanswered"))
+      .accounts({ initiator: maker.publicKey, escrowState: escrowPDA, escrowConfig: configPDA })
+      .signers([maker])
+      .rpc();
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    await warpTo((escrow.dispute as any).deadline.toNumber() + 1);
+
+    const [settlementHolding] = findHoldingPDA(escrowPDA, SETTLEMENT_HOLDING_INDEX, program.programId);
+    const takerBefore = await getAccount(provider.connection, takerATA);
+    await program.methods
+      .claimDisputeTimeout()
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([{ pubkey: settlementHolding, isWritable: true, isSigner: false }])
+      .signers([stranger])
+      .rpc();
+    assert.equal((await getAccount(provider.connection, settlementHolding)).amount.toString(), "975000");
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok("expired" in escrow.status);
+    const heldUntil = escrow.settlementHeldUntil.toNumber();
+    assert.isAbove(heldUntil, 0);
+
+    await warpTo(heldUntil);
+    await program.methods
+      .withdrawHeld(SETTLEMENT_HOLDING_INDEX)
+      .accounts({
+        beneficiary: taker.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        holdingAccount: settlementHolding,
+        beneficiaryTokenAccount: takerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([taker])
+      .rpc();
+    assert.equal(
+      (
+        BigInt((await getAccount(provider.connection, takerATA)).amount.toString()) -
+        BigInt(takerBefore.amount.toString())
+      ).toString(),
+      "975000"
+    );
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.settlementHeldUntil.toNumber(), 0);
+  });
+
+  // =========================================================================
+  // Test 181: claim_active_expired - approved milestone paid to its own beneficiary
+  // =========================================================================
+  it("181. claim_active_expired: pays an approved milestone's own beneficiary", async () => {
+    const clock = await context.banksClient.getClock();
+    const expiresAt = new BN(Number(clock.unixTimestamp) + 7200);
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(600_000), new BN(400_000)],
+      milestoneBeneficiaries: [stranger.publicKey, null],
+      expiresAt,
+    });
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    await warpTo(expiresAt.toNumber() + 1);
+
+    const strangerATA = getAssociatedTokenAddressSync(mint, stranger.publicKey);
+    if (!(await context.banksClient.getAccount(strangerATA))) {
+      const createAtaTx = new Transaction().add(
+        createAssociatedTokenAccountInstruction(context.payer.publicKey, strangerATA, stranger.publicKey, mint)
+      );
+      createAtaTx.recentBlockhash = context.lastBlockhash;
+      createAtaTx.feePayer = context.payer.publicKey;
+      createAtaTx.sign(context.payer);
+      await context.banksClient.processTransaction(createAtaTx);
+    }
+
+    const claim = (payees: PublicKey[]) =>
+      program.methods
+        .claimActiveExpired()
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(payees.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+        .signers([stranger])
+        .rpc();
+
+    try {
+      await claim([]);
+      assert.fail("Should have thrown PayeeAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "PayeeAccountMissing");
+    }
+
+    const takerBefore = await getAccount(provider.connection, takerATA);
+    const payeeBefore = await getAccount(provider.connection, strangerATA);
+    await claim([strangerATA]);
+    const gain = async (ata: PublicKey, before: { amount: bigint }) =>
+      (BigInt((await getAccount(provider.connection, ata)).amount.toString()) - BigInt(before.amount.toString())).toString();
+    assert.equal(await gain(strangerATA, payeeBefore), "585000");
+    assert.equal(await gain(takerATA, takerBefore), "0", "The escrow beneficiary gets nothing for milestone 0");
+  });
+
+  // =========================================================================
+  // Test 182: cancel routes — a migrated baseline config starts with full
+  //           refunds for every reason, and an escrow's snapshotted Expiry
+  //           route splits the pending refund in claim_active_expired
+  // =========================================================================
+  it("182. cancel routes: baseline migration defaults to full refunds; claim_active_expired applies the Expiry route", async () => {
+    const setCancelRoute = (reason: object, feeBps: number, feeRecipient: object) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, { reason, routing: { feeBps, feeRecipient } })
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    // Bytes past the baseline layout from an earlier configuration must not leak
+    // into the migrated routes
+    await setCancelRoute({ makerCancel: {} }, 300, { feeCollector: {} });
+    const configInfo = await context.banksClient.getAccount(configPDA);
+    const currentData = Buffer.from(configInfo.data);
+    context.setAccount(configPDA, { ...configInfo, data: currentData.subarray(0, BASELINE_CONFIG_LEN) });
+    await program.methods
+      .migrateConfig()
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+    let config = await program.account.escrowConfig.fetch(configPDA);
+    assert.equal(config.configVersion, CONFIG_VERSION);
+    assert.equal(config.cancelFeeBps, 0);
+    assert.isTrue(config.cancelRoutes.every((route: any) => route.feeBps === 0), "Every reason refunds in full");
+
+    await setCancelRoute({ expiry: {} }, 1_000, { beneficiary: {} });
+    const clock = await context.banksClient.getClock();
+    const expiresAt = new BN(Number(clock.unixTimestamp) + 7200);
+    const { escrowPDA, vault } = await setupEscrow({ expiresAt });
+    // Later config changes don't reach existing escrows
+    await setCancelRoute({ expiry: {} }, 0, { feeCollector: {} });
+    config = await program.account.escrowConfig.fetch(configPDA);
+    assert.equal(config.cancelRoutes[2].feeBps, 0);
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.cancelRoutesAtCreation[2].feeBps, 1_000);
+    assert.equal(escrow.cancelFeeBpsAtCreation, 0);
+    await warpTo(expiresAt.toNumber() + 1);
+
+    const claim = (withPayoutAccounts: boolean) =>
+      program.methods
+        .claimActiveExpired()
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: withPayoutAccounts ? takerATA : null,
+          feeCollectorTokenAccount: withPayoutAccounts ? feeCollectorATA : null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+
+    try {
+      await claim(false);
+      assert.fail("Should have thrown CancelFeeAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "CancelFeeAccountMissing");
+    }
+
+    const makerBefore = await getAccount(provider.connection, makerATA);
+    const takerBefore = await getAccount(provider.connection, takerATA);
+    await claim(true);
+    const gain = async (ata: PublicKey, before: { amount: bigint }) =>
+      (BigInt((await getAccount(provider.connection, ata)).amount.toString()) - BigInt(before.amount.toString())).toString();
+    // 10% of the 1_000_000 pending refund goes to the beneficiary
+    assert.equal(await gain(makerATA, makerBefore), "900000");
+    assert.equal(await gain(takerATA, takerBefore), "100000");
+    const settled = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(settled.refundedAmount.eq(TOTAL_AMOUNT));
+  });
+
+  // =========================================================================
+  // Test 186: verify_milestone_sum — a handler that mutates milestones
+  //           refuses to commit state breaking the accounting invariant
+  // =========================================================================
+  it("186. approve_milestone: reverts with MilestoneAmountMismatch on corrupted escrow totals", async () => {
+    const { escrowPDA } = await setupEscrow({
+      milestoneAmounts: [new BN(600_000), new BN(400_000)],
+    });
+    const original = await program.account.escrowState.fetch(escrowPDA);
+    const patchEscrow = async (fields: object) => {
+      const info = await context.banksClient.getAccount(escrowPDA);
+      const encoded = await program.coder.accounts.encode("escrowState", { ...original, ...fields });
+      const data = Buffer.alloc(info.data.length);
+      encoded.copy(data);
+      context.setAccount(escrowPDA, { ...info, data });
+    };
+    const approve = () =>
+      program.methods
+        .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+
+    // Milestones no longer sum to the escrow amount
+    await patchEscrow({ amount: original.amount.subn(1) });
+    try {
+      await approve();
+      assert.fail("Should have thrown MilestoneAmountMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "MilestoneAmountMismatch");
+    }
+
+    // Settled totals exceed the escrow amount
+    await patchEscrow({ releasedAmount: original.amount.addn(1) });
+    try {
+      await approve();
+      assert.fail("Should have thrown MilestoneAmountMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "MilestoneAmountMismatch");
+    }
+
+    await patchEscrow({});
+    await approve();
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[0].status.approved !== undefined);
+  });
 });
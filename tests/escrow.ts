@@ -5,11 +5,17 @@ import {
   createMint,
   mintTo,
   getAccount,
+  getMint,
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
   getAssociatedTokenAddressSync,
   createAssociatedTokenAccount,
   createTransferInstruction,
   createBurnInstruction,
+  createAccount,
+  createWrappedNativeAccount,
+  NATIVE_MINT,
+  approve,
 } from "@solana/spl-token";
 import {
   PublicKey,
@@ -17,6 +23,8 @@ import {
   SystemProgram,
   LAMPORTS_PER_SOL,
   SYSVAR_RENT_PUBKEY,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  Ed25519Program,
 } from "@solana/web3.js";
 import { assert } from "chai";
 import {
@@ -24,10 +32,38 @@ import {
   findEscrowConfigPDA,
   findEscrowPDA,
   findReceiptMintPDA,
+  findUnwrapPDA,
+  findShareMintPDA,
+  findProposalPDA,
+  findEscrowMetadataPDA,
+  findEscrowRequestPDA,
+  findKeeperPDA,
+  findMakerProfilePDA,
+  findApprovalPDA,
+  findEscrowArchivePDA,
+  findListingPDA,
+  findCrankSchedulePDA,
+  renewActionHash,
+  DISPUTE_INITIATORS_ALL,
+  DISPUTE_INITIATOR_MAKER,
+  DISPUTE_INITIATOR_TAKER,
+  DISPUTE_INITIATOR_BENEFICIARY,
+  proposalTermsHash,
   findMetadataPDA,
   findMasterEditionPDA,
   createDescriptionHash,
   makeMilestones,
+  buildApprovalMessage,
+  deriveEscrowSeed,
+  ESCROW_CATEGORY_OFFSET,
+  LISTING_CATEGORY_OFFSET,
+  HASH_ALGO_SHA256,
+  HASH_ALGO_KECCAK256,
+  MAX_BATCH_PROGRESS_ACCOUNTS,
+  CONFIG_VIEW_VERSION,
+  FEATURE_RECEIPTS,
+  FEATURE_TOKEN_2022,
+  FEATURE_PULL_PAYMENTS,
 } from "../client/pda";
 
 // ---------------------------------------------------------------------------
@@ -36,6 +72,8 @@ import {
 const FEE_BPS = 250; // 2.5%
 const DISPUTE_TIMEOUT = new BN(86400); // 1 day in seconds
 const TOTAL_AMOUNT = new BN(1_000_000); // 1 token with 6 decimals
+const ANY_AMOUNT = new BN(0); // approve_milestone: skip expected-amount check
+const ANY_HASH = new Array(32).fill(0); // approve_milestone: skip expected-hash check
 
 // ---------------------------------------------------------------------------
 // Helper functions
@@ -131,12 +169,25 @@ describe("escrow", () => {
       takerKp?: Keypair;
       mintPk?: PublicKey;
       makerAtaPk?: PublicKey;
+      category?: number;
+      initialBeneficiary?: PublicKey | null;
+      hashAlgo?: number;
+      acceptanceDeadline?: BN | null;
+      autoRelease?: boolean;
+      feeExempt?: boolean[];
+      selfCertify?: boolean[];
+      disputesEnabled?: boolean;
+      disputeInitiators?: number;
+      feeOnTop?: boolean;
+      agreementHash?: number[] | null;
+      takerBond?: BN;
+      milestoneBeneficiaries?: (PublicKey | null)[];
     } = {}
   ): Promise<{
     seed: BN;
     escrowPDA: PublicKey;
     vault: PublicKey;
-    milestones: { amount: BN; descriptionHash: number[] }[];
+    milestones: { amount: BN; descriptionHash: number[]; feeExempt: boolean; selfCertify: boolean; beneficiary: PublicKey | null }[];
   }> {
     const {
       seed = nextSeed(),
@@ -151,6 +202,19 @@ describe("escrow", () => {
       takerKp = taker,
       mintPk = mint,
       makerAtaPk = makerATA,
+      category = 0,
+      initialBeneficiary = null,
+      hashAlgo = HASH_ALGO_SHA256,
+      acceptanceDeadline = null,
+      autoRelease = false,
+      feeExempt = [],
+      selfCertify = [],
+      disputesEnabled = true,
+      disputeInitiators = DISPUTE_INITIATORS_ALL,
+      feeOnTop = false,
+      agreementHash = null,
+      takerBond = new BN(0),
+      milestoneBeneficiaries = [],
     } = overrides;
 
     const [escrowPDA] = findEscrowPDA(makerKp.publicKey, seed);
@@ -158,13 +222,17 @@ describe("escrow", () => {
 
     const milestones = makeMilestones(
       milestoneAmounts,
-      milestoneAmounts.map((_, i) => `task-${i}`)
+      milestoneAmounts.map((_, i) => `task-${i}`),
+      feeExempt,
+      selfCertify,
+      milestoneBeneficiaries
     );
 
     await program.methods
-      .createEscrow(seed, amount, milestones, expiresAt)
+      .createEscrow(seed, amount, milestones, expiresAt, category, initialBeneficiary, hashAlgo, acceptanceDeadline, autoRelease, new BN(0), disputesEnabled, disputeInitiators, feeOnTop, agreementHash, takerBond)
       .accounts({
         maker: makerKp.publicKey,
+        payer: makerKp.publicKey,
         taker: takerKp.publicKey,
         mint: mintPk,
         escrowState: escrowPDA,
@@ -290,9 +358,10 @@ describe("escrow", () => {
     const makerBefore = await getAccount(connection, makerATA);
 
     await program.methods
-      .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt)
+      .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
       .accounts({
         maker: maker.publicKey,
+        payer: maker.publicKey,
         taker: taker.publicKey,
         mint,
         escrowState: escrowPDA,
@@ -341,9 +410,10 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt)
+        .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
         .accounts({
           maker: maker.publicKey,
+          payer: maker.publicKey,
           taker: taker.publicKey,
           mint,
           escrowState: escrowPDA,
@@ -375,9 +445,10 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt)
+        .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
         .accounts({
           maker: maker.publicKey,
+          payer: maker.publicKey,
           taker: taker.publicKey,
           mint,
           escrowState: escrowPDA,
@@ -406,9 +477,10 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .createEscrow(seed, TOTAL_AMOUNT, [], expiresAt)
+        .createEscrow(seed, TOTAL_AMOUNT, [], expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
         .accounts({
           maker: maker.publicKey,
+          payer: maker.publicKey,
           taker: taker.publicKey,
           mint,
           escrowState: escrowPDA,
@@ -433,7 +505,7 @@ describe("escrow", () => {
     const { escrowPDA } = await setupEscrow();
 
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
@@ -456,7 +528,7 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .approveMilestone(0)
+        .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
         .accounts({
           maker: stranger.publicKey,
           escrowState: escrowPDA,
@@ -484,7 +556,7 @@ describe("escrow", () => {
 
     // Approve milestone 0
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
@@ -494,7 +566,7 @@ describe("escrow", () => {
     const vaultBefore = await getAccount(connection, vault);
 
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: maker.publicKey,
         escrowState: escrowPDA,
@@ -541,7 +613,7 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .releaseMilestone(0)
+        .releaseMilestone(0, null, false)
         .accounts({
           payer: maker.publicKey,
           escrowState: escrowPDA,
@@ -571,13 +643,13 @@ describe("escrow", () => {
 
     // Approve and release milestone 0
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
 
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: maker.publicKey,
         escrowState: escrowPDA,
@@ -596,7 +668,7 @@ describe("escrow", () => {
     const vaultBefore = await getAccount(connection, vault);
 
     await program.methods
-      .cancelEscrow()
+      .cancelEscrow(null)
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
@@ -633,7 +705,7 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .cancelEscrow()
+        .cancelEscrow(null)
         .accounts({
           maker: stranger.publicKey,
           escrowState: escrowPDA,
@@ -700,7 +772,7 @@ describe("escrow", () => {
 
     // Resolve as MakerWins
     await program.methods
-      .resolveDispute({ makerWins: {} })
+      .resolveDispute({ makerWins: {} }, null, false, false)
       .accounts({
         authority: authority.publicKey,
         escrowConfig: configPDA,
@@ -751,7 +823,7 @@ describe("escrow", () => {
     const feeBefore = await getAccount(connection, feeCollectorATA);
 
     await program.methods
-      .resolveDispute({ takerWins: {} })
+      .resolveDispute({ takerWins: {} }, null, false, false)
       .accounts({
         authority: authority.publicKey,
         escrowConfig: configPDA,
@@ -815,7 +887,7 @@ describe("escrow", () => {
     const makerBps = 5000;
 
     await program.methods
-      .resolveDispute({ split: { makerBps } })
+      .resolveDispute({ split: { makerBps } }, null, false, false)
       .accounts({
         authority: authority.publicKey,
         escrowConfig: configPDA,
@@ -951,7 +1023,7 @@ describe("escrow", () => {
     // Approve all milestones sequentially
     for (let i = 0; i < milestoneAmounts.length; i++) {
       await program.methods
-        .approveMilestone(i)
+        .approveMilestone(i, ANY_AMOUNT, ANY_HASH, null)
         .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
         .signers([maker])
         .rpc();
@@ -966,7 +1038,7 @@ describe("escrow", () => {
     // Release all milestones (permissionless — use stranger as payer/crank)
     for (let i = 0; i < milestoneAmounts.length; i++) {
       await program.methods
-        .releaseMilestone(i)
+        .releaseMilestone(i, null, false)
         .accounts({
           payer: stranger.publicKey,
           escrowState: escrowPDA,
@@ -1023,7 +1095,7 @@ describe("escrow", () => {
     const newTimeout = new BN(172800); // 2 days
 
     await program.methods
-      .updateConfig(null, newFeeBps, newTimeout)
+      .updateConfig(null, newFeeBps, newTimeout, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
       .accounts({
         authority: authority.publicKey,
         escrowConfig: configPDA,
@@ -1039,7 +1111,7 @@ describe("escrow", () => {
 
     // Restore original values for subsequent tests
     await program.methods
-      .updateConfig(null, FEE_BPS, DISPUTE_TIMEOUT)
+      .updateConfig(null, FEE_BPS, DISPUTE_TIMEOUT, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
       .accounts({
         authority: authority.publicKey,
         escrowConfig: configPDA,
@@ -1055,7 +1127,7 @@ describe("escrow", () => {
   it("21. update_config: fails when called by non-authority", async () => {
     try {
       await program.methods
-        .updateConfig(null, 100, null)
+        .updateConfig(null, 100, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
         .accounts({
           authority: stranger.publicKey,
           escrowConfig: configPDA,
@@ -1078,7 +1150,7 @@ describe("escrow", () => {
   it("22. update_config: fails when fee_bps > 10000", async () => {
     try {
       await program.methods
-        .updateConfig(null, 15000, null)
+        .updateConfig(null, 15000, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
         .accounts({
           authority: authority.publicKey,
           escrowConfig: configPDA,
@@ -1101,7 +1173,7 @@ describe("escrow", () => {
 
     // Transfer authority
     await program.methods
-      .updateConfig(newAuthority.publicKey, null, null)
+      .updateConfig(newAuthority.publicKey, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
       .accounts({
         authority: authority.publicKey,
         escrowConfig: configPDA,
@@ -1116,7 +1188,7 @@ describe("escrow", () => {
     // Old authority should fail
     try {
       await program.methods
-        .updateConfig(null, 100, null)
+        .updateConfig(null, 100, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
         .accounts({
           authority: authority.publicKey,
           escrowConfig: configPDA,
@@ -1131,7 +1203,7 @@ describe("escrow", () => {
 
     // Transfer back for remaining tests
     await program.methods
-      .updateConfig(authority.publicKey, null, null)
+      .updateConfig(authority.publicKey, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
       .accounts({
         authority: newAuthority.publicKey,
         escrowConfig: configPDA,
@@ -1154,7 +1226,7 @@ describe("escrow", () => {
 
     // Cancel it first
     await program.methods
-      .cancelEscrow()
+      .cancelEscrow(null)
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
@@ -1174,6 +1246,7 @@ describe("escrow", () => {
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
+        escrowConfig: configPDA,
         mint,
         vault,
         makerTokenAccount: makerATA,
@@ -1216,6 +1289,7 @@ describe("escrow", () => {
         .accounts({
           maker: maker.publicKey,
           escrowState: escrowPDA,
+          escrowConfig: configPDA,
           mint,
           vault,
           makerTokenAccount: makerATA,
@@ -1239,7 +1313,7 @@ describe("escrow", () => {
 
     // Cancel first to get terminal state
     await program.methods
-      .cancelEscrow()
+      .cancelEscrow(null)
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
@@ -1257,6 +1331,7 @@ describe("escrow", () => {
         .accounts({
           maker: stranger.publicKey,
           escrowState: escrowPDA,
+          escrowConfig: configPDA,
           mint,
           vault,
           makerTokenAccount: makerATA,
@@ -1360,7 +1435,7 @@ describe("escrow", () => {
     // Cancel should fail because status is Disputed, not Active
     try {
       await program.methods
-        .cancelEscrow()
+        .cancelEscrow(null)
         .accounts({
           maker: maker.publicKey,
           escrowState: escrowPDA,
@@ -1396,7 +1471,7 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .resolveDispute({ makerWins: {} })
+        .resolveDispute({ makerWins: {} }, null, false, false)
         .accounts({
           authority: stranger.publicKey,
           escrowConfig: configPDA,
@@ -1427,7 +1502,7 @@ describe("escrow", () => {
 
     // Approve milestone 0
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
@@ -1435,7 +1510,7 @@ describe("escrow", () => {
     // Try to approve again
     try {
       await program.methods
-        .approveMilestone(0)
+        .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
         .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
         .signers([maker])
         .rpc();
@@ -1461,9 +1536,10 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .createEscrow(seed, TOTAL_AMOUNT, sixMilestones, expiresAt)
+        .createEscrow(seed, TOTAL_AMOUNT, sixMilestones, expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
         .accounts({
           maker: maker.publicKey,
+          payer: maker.publicKey,
           taker: taker.publicKey,
           mint,
           escrowState: escrowPDA,
@@ -1494,7 +1570,7 @@ describe("escrow", () => {
 
     // Approve milestone
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
@@ -1504,7 +1580,7 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .releaseMilestone(0)
+        .releaseMilestone(0, null, false)
         .accounts({
           payer: stranger.publicKey,
           escrowState: escrowPDA,
@@ -1558,13 +1634,13 @@ describe("escrow", () => {
 
     // Approve + release to reach Completed state
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
 
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: maker.publicKey,
         escrowState: escrowPDA,
@@ -1588,6 +1664,7 @@ describe("escrow", () => {
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
+        escrowConfig: configPDA,
         mint,
         vault,
         makerTokenAccount: makerATA,
@@ -1618,12 +1695,12 @@ describe("escrow", () => {
 
     // Approve milestone 0 and 1 (but don't release)
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
     await program.methods
-      .approveMilestone(1)
+      .approveMilestone(1, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
@@ -1633,7 +1710,7 @@ describe("escrow", () => {
     // Cancel should only refund PENDING milestones (milestone 2 = 300_000)
     // Approved milestones (0 and 1) remain intact to protect the taker
     await program.methods
-      .cancelEscrow()
+      .cancelEscrow(null)
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
@@ -1676,9 +1753,10 @@ describe("escrow", () => {
     const expiresAt = new BN(Math.floor(Date.now() / 1000) + 3600);
 
     await program.methods
-      .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt)
+      .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
       .accounts({
         maker: maker.publicKey,
+        payer: maker.publicKey,
         taker: taker.publicKey,
         mint,
         escrowState: escrowPDA,
@@ -1710,9 +1788,10 @@ describe("escrow", () => {
     const expiresAt = new BN(Math.floor(Date.now() / 1000) + 3600);
 
     await program.methods
-      .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt)
+      .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
       .accounts({
         maker: maker.publicKey,
+        payer: maker.publicKey,
         taker: taker.publicKey,
         mint,
         escrowState: escrowPDA,
@@ -1736,7 +1815,7 @@ describe("escrow", () => {
     const { escrowPDA } = await setupEscrow();
 
     await program.methods
-      .transferClaim()
+      .transferClaim(null)
       .accounts({
         beneficiary: taker.publicKey,
         escrowState: escrowPDA,
@@ -1760,7 +1839,7 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .transferClaim()
+        .transferClaim(null)
         .accounts({
           beneficiary: stranger.publicKey,
           escrowState: escrowPDA,
@@ -1798,7 +1877,7 @@ describe("escrow", () => {
 
     // Taker transfers claim to stranger
     await program.methods
-      .transferClaim()
+      .transferClaim(null)
       .accounts({
         beneficiary: taker.publicKey,
         escrowState: escrowPDA,
@@ -1809,7 +1888,7 @@ describe("escrow", () => {
 
     // Maker approves milestone 0
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
@@ -1819,7 +1898,7 @@ describe("escrow", () => {
 
     // Release milestone to stranger's ATA (new beneficiary)
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: maker.publicKey,
         escrowState: escrowPDA,
@@ -1872,7 +1951,7 @@ describe("escrow", () => {
     // Now try to transfer claim on a disputed escrow
     try {
       await program.methods
-        .transferClaim()
+        .transferClaim(null)
         .accounts({
           beneficiary: taker.publicKey,
           escrowState: escrowPDA,
@@ -1900,7 +1979,7 @@ describe("escrow", () => {
 
     // Taker transfers claim to stranger
     await program.methods
-      .transferClaim()
+      .transferClaim(null)
       .accounts({
         beneficiary: taker.publicKey,
         escrowState: escrowPDA,
@@ -1940,9 +2019,10 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt)
+        .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
         .accounts({
           maker: maker.publicKey,
+          payer: maker.publicKey,
           taker: maker.publicKey, // same as maker!
           mint,
           escrowState: escrowPDA,
@@ -1974,9 +2054,10 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .createEscrow(seed, new BN(0), [], expiresAt)
+        .createEscrow(seed, new BN(0), [], expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
         .accounts({
           maker: maker.publicKey,
+          payer: maker.publicKey,
           taker: taker.publicKey,
           mint,
           escrowState: escrowPDA,
@@ -2005,7 +2086,7 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .transferClaim()
+        .transferClaim(null)
         .accounts({
           beneficiary: taker.publicKey,
           escrowState: escrowPDA,
@@ -2030,7 +2111,7 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .resolveDispute({ makerWins: {} })
+        .resolveDispute({ makerWins: {} }, null, false, false)
         .accounts({
           authority: authority.publicKey,
           escrowConfig: configPDA,
@@ -2069,7 +2150,7 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .resolveDispute({ split: { makerBps: 15000 } })
+        .resolveDispute({ split: { makerBps: 15000 } }, null, false, false)
         .accounts({
           authority: authority.publicKey,
           escrowConfig: configPDA,
@@ -2102,7 +2183,7 @@ describe("escrow", () => {
     // Approve all milestones
     for (let i = 0; i < milestoneAmounts.length; i++) {
       await program.methods
-        .approveMilestone(i)
+        .approveMilestone(i, ANY_AMOUNT, ANY_HASH, null)
         .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
         .signers([maker])
         .rpc();
@@ -2110,7 +2191,7 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .cancelEscrow()
+        .cancelEscrow(null)
         .accounts({
           maker: maker.publicKey,
           escrowState: escrowPDA,
@@ -2133,7 +2214,7 @@ describe("escrow", () => {
   it("50. update_config: fails with dispute_timeout = 0 (InvalidDisputeTimeout)", async () => {
     try {
       await program.methods
-        .updateConfig(null, null, new BN(0))
+        .updateConfig(null, null, new BN(0), null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
         .accounts({
           authority: authority.publicKey,
           escrowConfig: configPDA,
@@ -2153,7 +2234,7 @@ describe("escrow", () => {
   it("51. update_config: fails when setting authority to zero address", async () => {
     try {
       await program.methods
-        .updateConfig(PublicKey.default, null, null)
+        .updateConfig(PublicKey.default, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
         .accounts({
           authority: authority.publicKey,
           escrowConfig: configPDA,
@@ -2185,6 +2266,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta,
@@ -2231,6 +2313,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta,
@@ -2251,6 +2334,7 @@ describe("escrow", () => {
         .mintReceipt()
         .accounts({
           beneficiary: taker.publicKey,
+          payer: taker.publicKey,
           escrowState: escrowPDA,
           receiptMint,
           beneficiaryReceiptAta,
@@ -2289,6 +2373,7 @@ describe("escrow", () => {
         .mintReceipt()
         .accounts({
           beneficiary: maker.publicKey,
+          payer: maker.publicKey,
           escrowState: escrowPDA,
           receiptMint,
           beneficiaryReceiptAta: makerReceiptAta,
@@ -2330,6 +2415,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta,
@@ -2346,13 +2432,13 @@ describe("escrow", () => {
 
     // Approve + release the single milestone to complete escrow
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
 
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: maker.publicKey,
         escrowState: escrowPDA,
@@ -2369,16 +2455,19 @@ describe("escrow", () => {
       .signers([maker])
       .rpc();
 
-    // Close escrow
+    // Close escrow (sealing the receipt metadata on the way out)
     await program.methods
       .closeEscrow()
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
+        escrowConfig: configPDA,
         mint,
         vault,
         makerTokenAccount: makerATA,
         tokenProgram: TOKEN_PROGRAM_ID,
+        receiptMetadata: metadata,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       })
       .signers([maker])
       .rpc();
@@ -2414,6 +2503,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta: takerReceiptAta,
@@ -2470,7 +2560,7 @@ describe("escrow", () => {
 
     // Approve + release → funds should go to stranger (new beneficiary)
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
@@ -2478,7 +2568,7 @@ describe("escrow", () => {
     const strangerBefore = await getAccount(connection, strangerTokenAta);
 
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: maker.publicKey,
         escrowState: escrowPDA,
@@ -2521,6 +2611,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta: takerReceiptAta,
@@ -2586,6 +2677,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta: takerReceiptAta,
@@ -2664,6 +2756,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta: takerReceiptAta,
@@ -2681,7 +2774,7 @@ describe("escrow", () => {
     // Now try transfer_claim — should be blocked
     try {
       await program.methods
-        .transferClaim()
+        .transferClaim(null)
         .accounts({
           beneficiary: taker.publicKey,
           escrowState: escrowPDA,
@@ -2703,7 +2796,7 @@ describe("escrow", () => {
 
     // No receipt minted — transfer_claim should work as before
     await program.methods
-      .transferClaim()
+      .transferClaim(null)
       .accounts({
         beneficiary: taker.publicKey,
         escrowState: escrowPDA,
@@ -2738,6 +2831,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta: takerReceiptAta,
@@ -2754,7 +2848,7 @@ describe("escrow", () => {
 
     // Approve milestone 0
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
@@ -2798,7 +2892,7 @@ describe("escrow", () => {
     const strangerBefore = await getAccount(connection, strangerTokenAta);
 
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: stranger.publicKey,
         escrowState: escrowPDA,
@@ -2841,6 +2935,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta: takerReceiptAta,
@@ -2857,13 +2952,13 @@ describe("escrow", () => {
 
     // Complete the escrow: approve + release milestone
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
 
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: maker.publicKey,
         escrowState: escrowPDA,
@@ -2924,6 +3019,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta: takerReceiptAta,
@@ -2969,7 +3065,7 @@ describe("escrow", () => {
 
     // Verify transfer_claim works again (was blocked by ReceiptExists)
     await program.methods
-      .transferClaim()
+      .transferClaim(null)
       .accounts({
         beneficiary: taker.publicKey,
         escrowState: escrowPDA,
@@ -2998,6 +3094,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta: takerReceiptAta,
@@ -3065,9 +3162,10 @@ describe("escrow", () => {
 
     try {
       await program.methods
-        .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt)
+        .createEscrow(seed, TOTAL_AMOUNT, milestones, expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
         .accounts({
           maker: maker.publicKey,
+          payer: maker.publicKey,
           taker: taker.publicKey,
           mint: freezeMint,
           escrowState: escrowPDA,
@@ -3095,13 +3193,13 @@ describe("escrow", () => {
 
     // Approve + release milestone 0 → escrow becomes Completed, vault empty
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
 
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: maker.publicKey,
         escrowState: escrowPDA,
@@ -3136,6 +3234,7 @@ describe("escrow", () => {
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
+        escrowConfig: configPDA,
         mint,
         vault,
         makerTokenAccount: makerATA,
@@ -3166,6 +3265,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta: takerReceiptAta,
@@ -3189,6 +3289,9 @@ describe("escrow", () => {
         escrowState: escrowPDA,
         escrowConfig: configPDA,
       })
+      .remainingAccounts([
+        { pubkey: takerReceiptAta, isWritable: false, isSigner: false },
+      ])
       .signers([maker])
       .rpc();
 
@@ -3242,7 +3345,7 @@ describe("escrow", () => {
   it("69. release_milestone: full flow with 0% fee — taker receives full amount", async () => {
     // Update config to 0% fee
     await program.methods
-      .updateConfig(null, 0, null)
+      .updateConfig(null, 0, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
       .accounts({
         authority: authority.publicKey,
         escrowConfig: configPDA,
@@ -3258,7 +3361,7 @@ describe("escrow", () => {
 
     // Approve milestone 0
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
@@ -3269,7 +3372,7 @@ describe("escrow", () => {
 
     // Release milestone 0
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: maker.publicKey,
         escrowState: escrowPDA,
@@ -3303,7 +3406,7 @@ describe("escrow", () => {
 
     // RESTORE config fee back to 250 bps to avoid breaking subsequent tests
     await program.methods
-      .updateConfig(null, FEE_BPS, null)
+      .updateConfig(null, FEE_BPS, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
       .accounts({
         authority: authority.publicKey,
         escrowConfig: configPDA,
@@ -3359,9 +3462,10 @@ describe("escrow", () => {
 
     // Should succeed without overflow
     await program.methods
-      .createEscrow(seed, largeAmount, milestones, expiresAt)
+      .createEscrow(seed, largeAmount, milestones, expiresAt, 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
       .accounts({
         maker: maker.publicKey,
+        payer: maker.publicKey,
         taker: taker.publicKey,
         mint: largeMint,
         escrowState: escrowPDA,
@@ -3416,7 +3520,7 @@ describe("escrow", () => {
     });
 
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
@@ -3425,7 +3529,7 @@ describe("escrow", () => {
     const feeBefore = await getAccount(connection, feeCollectorATA);
 
     await program.methods
-      .releaseMilestone(0)
+      .releaseMilestone(0, null, false)
       .accounts({
         payer: maker.publicKey,
         escrowState: escrowPDA,
@@ -3488,6 +3592,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta: takerReceiptAta,
@@ -3521,7 +3626,7 @@ describe("escrow", () => {
 
     // Approve milestone 0 by maker
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
       .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
       .signers([maker])
       .rpc();
@@ -3530,7 +3635,7 @@ describe("escrow", () => {
     // This should fail because escrow.beneficiary is still taker but NFT holder is stranger
     try {
       await program.methods
-        .releaseMilestone(0)
+        .releaseMilestone(0, null, false)
         .accounts({
           payer: maker.publicKey,
           escrowState: escrowPDA,
@@ -3574,6 +3679,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta,
@@ -3594,7 +3700,7 @@ describe("escrow", () => {
 
     // Cancel escrow (all milestones are Pending → all Cancelled → status = Cancelled)
     await program.methods
-      .cancelEscrow()
+      .cancelEscrow(null)
       .accounts({
         maker: maker.publicKey,
         escrowState: escrowPDA,
@@ -3638,6 +3744,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta,
@@ -3663,6 +3770,9 @@ describe("escrow", () => {
         escrowState: escrowPDA,
         escrowConfig: configPDA,
       })
+      .remainingAccounts([
+        { pubkey: beneficiaryReceiptAta, isWritable: false, isSigner: false },
+      ])
       .signers([taker])
       .rpc();
 
@@ -3672,7 +3782,7 @@ describe("escrow", () => {
 
     // Resolve dispute: MakerWins (must pass receipt ATA in remaining_accounts for sync check)
     await program.methods
-      .resolveDispute({ makerWins: {} })
+      .resolveDispute({ makerWins: {} }, null, false, false)
       .accounts({
         authority: authority.publicKey,
         escrowConfig: configPDA,
@@ -3718,6 +3828,7 @@ describe("escrow", () => {
       .mintReceipt()
       .accounts({
         beneficiary: taker.publicKey,
+        payer: taker.publicKey,
         escrowState: escrowPDA,
         receiptMint,
         beneficiaryReceiptAta,
@@ -3743,12 +3854,15 @@ describe("escrow", () => {
         escrowState: escrowPDA,
         escrowConfig: configPDA,
       })
+      .remainingAccounts([
+        { pubkey: beneficiaryReceiptAta, isWritable: false, isSigner: false },
+      ])
       .signers([maker])
       .rpc();
 
     // Resolve dispute: TakerWins
     await program.methods
-      .resolveDispute({ takerWins: {} })
+      .resolveDispute({ takerWins: {} }, null, false, false)
       .accounts({
         authority: authority.publicKey,
         escrowConfig: configPDA,
@@ -3771,4 +3885,5110 @@ describe("escrow", () => {
     assert.ok(escrow.status.completed !== undefined, "Escrow should be Completed");
     assert.isNotNull(escrow.receiptMint, "receipt_mint should be preserved after TakerWins");
   });
+
+  // ===========================================================================
+  // 84. milestone sum invariant holds across release + cancel
+  // ===========================================================================
+  it("84. milestone sum invariant: holds after partial release and cancel", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(400_000), new BN(300_000), new BN(300_000)],
+    });
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([stranger])
+      .rpc();
+    await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    const milestoneSum = escrow.milestones.reduce(
+      (sum: BN, m: any) => sum.add(m.amount),
+      new BN(0)
+    );
+    assert.ok(milestoneSum.eq(escrow.amount), "Milestone amounts must still sum to total");
+    assert.ok(
+      escrow.releasedAmount.add(escrow.refundedAmount).eq(escrow.amount),
+      "Released + refunded must equal total once fully settled"
+    );
+  });
+
+  // ===========================================================================
+  // 86. hold_escrow - blocks release until release_hold
+  // ===========================================================================
+  it("86. hold_escrow: blocks release_milestone until authority lifts the hold", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      amount: TOTAL_AMOUNT,
+    });
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    // Non-authority cannot place a hold
+    try {
+      await program.methods
+        .holdEscrow()
+        .accounts({
+          authority: stranger.publicKey,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown NotAuthority");
+    } catch (err: any) {
+      assert.include(err.message, "NotAuthority");
+    }
+
+    await program.methods
+      .holdEscrow()
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+      })
+      .signers([authority])
+      .rpc();
+
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.isTrue(escrow.onHold, "Escrow should be on hold");
+
+    const releaseAccounts = {
+      payer: stranger.publicKey,
+      escrowState: escrowPDA,
+      escrowConfig: configPDA,
+      mint,
+      vault,
+      beneficiaryTokenAccount: takerATA,
+      feeCollectorTokenAccount: feeCollectorATA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    try {
+      await program.methods
+        .releaseMilestone(0, null, false)
+        .accounts(releaseAccounts)
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown EscrowOnHold");
+    } catch (err: any) {
+      assert.include(err.message, "EscrowOnHold");
+    }
+
+    // Funds remain locked in the vault while held
+    const vaultHeld = await getAccount(connection, vault);
+    assert.equal(vaultHeld.amount.toString(), TOTAL_AMOUNT.toString());
+
+    await program.methods
+      .releaseHold()
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+      })
+      .signers([authority])
+      .rpc();
+
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts(releaseAccounts)
+      .signers([stranger])
+      .rpc();
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.isFalse(escrow.onHold, "Hold should be lifted");
+    assert.ok(escrow.status.completed !== undefined, "Escrow should be Completed");
+  });
+
+  // ===========================================================================
+  // 87. beneficiary_ata - recorded at creation, moved on transfer_claim
+  // ===========================================================================
+  it("87. beneficiary_ata: follows transfer_claim and rejects non-canonical payout accounts", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      amount: TOTAL_AMOUNT,
+    });
+
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(
+      escrow.beneficiaryAta.equals(getAssociatedTokenAddressSync(mint, taker.publicKey)),
+      "beneficiary_ata should be the taker's canonical ATA"
+    );
+
+    await program.methods
+      .transferClaim(null)
+      .accounts({
+        beneficiary: taker.publicKey,
+        escrowState: escrowPDA,
+        newBeneficiary: stranger.publicKey,
+      })
+      .signers([taker])
+      .rpc();
+
+    const strangerATA = getAssociatedTokenAddressSync(mint, stranger.publicKey);
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(
+      escrow.beneficiaryAta.equals(strangerATA),
+      "beneficiary_ata should move to the new beneficiary's canonical ATA"
+    );
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    // A non-ATA token account owned by the new beneficiary is rejected
+    const strayAccount = await createAccount(
+      connection,
+      authority,
+      mint,
+      stranger.publicKey,
+      Keypair.generate()
+    );
+    try {
+      await program.methods
+        .releaseMilestone(0, null, false)
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount: strayAccount,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown BeneficiaryAtaMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "BeneficiaryAtaMismatch");
+    }
+  });
+
+  // ===========================================================================
+  // 88. vault - stored on EscrowState, other accounts rejected
+  // ===========================================================================
+  it("88. vault: address stored at creation and mismatched vault is rejected (VaultMismatch)", async () => {
+    const { escrowPDA, vault } = await setupEscrow();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.vault.equals(vault), "Stored vault should equal the escrow ATA");
+
+    try {
+      await program.methods
+        .cancelEscrow(null)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          mint,
+          vault: takerATA, // Wrong! Not the escrow vault
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown VaultMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "VaultMismatch");
+    }
+  });
+
+  // ===========================================================================
+  // 89. approve_and_release - maker pays a pending milestone in one tx
+  // ===========================================================================
+  it("89. approve_and_release: maker approves and pays the last milestone, escrow Completed", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      amount: TOTAL_AMOUNT,
+    });
+
+    const takerBefore = await getAccount(connection, takerATA);
+    const feeBefore = await getAccount(connection, feeCollectorATA);
+
+    const accounts = {
+      maker: maker.publicKey,
+      escrowState: escrowPDA,
+      escrowConfig: configPDA,
+      mint,
+      vault,
+      beneficiaryTokenAccount: takerATA,
+      feeCollectorTokenAccount: feeCollectorATA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // Only the maker may sign
+    try {
+      await program.methods
+        .approveAndRelease(0)
+        .accounts({ ...accounts, maker: stranger.publicKey })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown NotMaker");
+    } catch (err: any) {
+      assert.include(err.message, "NotMaker");
+    }
+
+    await program.methods
+      .approveAndRelease(0)
+      .accounts(accounts)
+      .signers([maker])
+      .rpc();
+
+    const takerAfter = await getAccount(connection, takerATA);
+    const feeAfter = await getAccount(connection, feeCollectorATA);
+    const expectedFee = (BigInt(TOTAL_AMOUNT.toString()) * BigInt(FEE_BPS)) / BigInt(10_000);
+
+    assert.equal(
+      (BigInt(takerAfter.amount.toString()) - BigInt(takerBefore.amount.toString())).toString(),
+      (BigInt(TOTAL_AMOUNT.toString()) - expectedFee).toString()
+    );
+    assert.equal(
+      (BigInt(feeAfter.amount.toString()) - BigInt(feeBefore.amount.toString())).toString(),
+      expectedFee.toString()
+    );
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[0].status.released !== undefined, "Milestone should be Released");
+    assert.ok(escrow.status.completed !== undefined, "Escrow should be Completed");
+  });
+
+  // ===========================================================================
+  // 90. token_program - recorded at creation
+  // ===========================================================================
+  it("90. create_escrow: records the vault's token program on EscrowState", async () => {
+    const { escrowPDA, vault } = await setupEscrow();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.tokenProgram.equals(TOKEN_PROGRAM_ID), "token_program should be SPL Token");
+
+    const vaultInfo = await connection.getAccountInfo(vault);
+    assert.ok(vaultInfo!.owner.equals(escrow.tokenProgram), "Vault must live under the recorded token program");
+  });
+
+  // ===========================================================================
+  // 91. renew_escrow - completed escrow starts a new cycle on the same PDA
+  // ===========================================================================
+  it("91. renew_escrow: tops up a completed escrow and restarts the cycle", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      amount: TOTAL_AMOUNT,
+    });
+
+    const topUp = new BN(500_000);
+    const newMilestones = makeMilestones([new BN(200_000), new BN(300_000)], ["cycle-2a", "cycle-2b"]);
+    const newExpiresAt = new BN(Math.floor(Date.now() / 1000) + 7200);
+    const renewAccounts = {
+      maker: maker.publicKey,
+      beneficiary: taker.publicKey,
+      escrowState: escrowPDA,
+      escrowConfig: configPDA,
+      mint,
+      vault,
+      makerTokenAccount: makerATA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // Active escrow far from expiry cannot be renewed
+    try {
+      await program.methods
+        .renewEscrow(topUp, newMilestones, newExpiresAt)
+        .accounts(renewAccounts)
+        .signers([maker, taker])
+        .rpc();
+      assert.fail("Should have thrown RenewalNotAllowed");
+    } catch (err: any) {
+      assert.include(err.message, "RenewalNotAllowed");
+    }
+
+    await program.methods
+      .approveAndRelease(0)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    await program.methods
+      .renewEscrow(topUp, newMilestones, newExpiresAt)
+      .accounts(renewAccounts)
+      .signers([maker, taker])
+      .rpc();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.active !== undefined, "Renewed escrow should be Active");
+    assert.ok(escrow.amount.eq(topUp), "Amount resets to the new cycle's funding");
+    assert.ok(escrow.releasedAmount.isZero(), "released_amount resets per cycle");
+    assert.ok(escrow.refundedAmount.isZero(), "refunded_amount resets per cycle");
+    assert.equal(escrow.milestones.length, 2, "Settled milestones are pruned");
+    assert.equal(escrow.renewalCount, 1);
+    assert.ok(escrow.expiresAt.eq(newExpiresAt));
+
+    const vaultAcct = await getAccount(connection, vault);
+    assert.equal(vaultAcct.amount.toString(), topUp.toString());
+  });
+
+  // ===========================================================================
+  // 92. resolve_dispute - records resolver and resolution timestamp
+  // ===========================================================================
+  it("92. resolve_dispute: records resolved_by and resolved_at on the dispute", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      amount: TOTAL_AMOUNT,
+    });
+
+    await program.methods
+      .initiateDispute(createDescriptionHash("resolver audit trail"))
+      .accounts({
+        initiator: taker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+      })
+      .signers([taker])
+      .rpc();
+
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok((escrow.dispute as any).resolvedBy.equals(PublicKey.default));
+    assert.equal((escrow.dispute as any).resolvedAt.toNumber(), 0);
+
+    await program.methods
+      .resolveDispute({ makerWins: {} }, null, false, false)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    const dispute = escrow.dispute as any;
+    assert.ok(dispute.resolvedBy.equals(authority.publicKey), "resolved_by should be the authority");
+    assert.ok(dispute.resolvedAt.toNumber() >= dispute.initiatedAt.toNumber(), "resolved_at should be set");
+  });
+
+  // ===========================================================================
+  // 93. approve_milestone_signed - offline maker approval relayed by a crank
+  // ===========================================================================
+  it("93. approve_milestone_signed: relays an offline maker approval and rejects replay/forgery", async () => {
+    const { escrowPDA } = await setupEscrow();
+
+    const signedApproval = (signer: Keypair, milestoneIndex: number, nonce: BN) =>
+      Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: buildApprovalMessage(escrowPDA, milestoneIndex, nonce, program.programId),
+      });
+    const relay = (milestoneIndex: number, nonce: BN, verifyIx: anchor.web3.TransactionInstruction) =>
+      program.methods
+        .approveMilestoneSigned(milestoneIndex, nonce)
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: escrowPDA,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .preInstructions([verifyIx])
+        .signers([stranger])
+        .rpc();
+
+    // Maker signs offline, stranger relays
+    await relay(0, new BN(0), signedApproval(maker, 0, new BN(0)));
+
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[0].status.approved !== undefined, "Milestone 0 should be Approved");
+    assert.equal(escrow.approvalNonce.toNumber(), 1, "Nonce should advance");
+
+    // Replaying a consumed nonce is rejected
+    try {
+      await relay(1, new BN(0), signedApproval(maker, 1, new BN(0)));
+      assert.fail("Should have thrown InvalidApprovalNonce");
+    } catch (err: any) {
+      assert.include(err.message, "InvalidApprovalNonce");
+    }
+
+    // A signature from anyone other than the maker is rejected
+    try {
+      await relay(1, new BN(1), signedApproval(stranger, 1, new BN(1)));
+      assert.fail("Should have thrown ApprovalSignerMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "ApprovalSignerMismatch");
+    }
+
+    // A maker signature over a different milestone does not authorize this one
+    try {
+      await relay(1, new BN(1), signedApproval(maker, 2, new BN(1)));
+      assert.fail("Should have thrown InvalidApprovalSignature");
+    } catch (err: any) {
+      assert.include(err.message, "InvalidApprovalSignature");
+    }
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[1].status.pending !== undefined, "Milestone 1 stays Pending");
+  });
+
+  // ===========================================================================
+  // 94. create_escrow - category is stored and filterable via memcmp
+  // ===========================================================================
+  it("94. create_escrow: stores category at a fixed offset for memcmp filtering", async () => {
+    const category = 200;
+    const { escrowPDA } = await setupEscrow({ category });
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.category, category);
+
+    // The offset the program publishes in its IDL, which the client mirrors
+    const idlOffset = Number(
+      program.idl.constants!.find((c) => c.name === "ESCROW_CATEGORY_OFFSET")!.value
+    );
+    assert.equal(ESCROW_CATEGORY_OFFSET, idlOffset, "Client offset matches the IDL");
+
+    const raw = await connection.getAccountInfo(escrowPDA);
+    assert.equal(raw!.data[idlOffset], category, "Category byte at documented offset");
+
+    const matches = await program.account.escrowState.all([
+      {
+        memcmp: {
+          offset: idlOffset,
+          bytes: anchor.utils.bytes.bs58.encode([category]),
+        },
+      },
+    ]);
+    assert.deepEqual(
+      matches.map((m) => m.publicKey.toBase58()),
+      [escrowPDA.toBase58()],
+      "Only the tagged escrow should match"
+    );
+  });
+
+  // ===========================================================================
+  // 95. initiate_dispute - per-escrow dispute cap survives renewal
+  // ===========================================================================
+  it("95. initiate_dispute: rejects disputes beyond max_disputes_per_escrow (DisputeLimitReached)", async () => {
+    const setCap = (cap: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, cap, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await setCap(1);
+    try {
+      const { escrowPDA, vault } = await setupEscrow({
+        milestoneAmounts: [TOTAL_AMOUNT],
+        amount: TOTAL_AMOUNT,
+      });
+      const dispute = (label: string) =>
+        program.methods
+          .initiateDispute(createDescriptionHash(label))
+          .accounts({
+            initiator: taker.publicKey,
+            escrowState: escrowPDA,
+            escrowConfig: configPDA,
+          })
+          .signers([taker])
+          .rpc();
+
+      await dispute("first dispute");
+      let escrow = await program.account.escrowState.fetch(escrowPDA);
+      assert.equal(escrow.disputeCount, 1);
+
+      await program.methods
+        .resolveDispute({ takerWins: {} }, null, false, false)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+
+      // Renewal clears the dispute but not the lifetime counter
+      await program.methods
+        .renewEscrow(
+          new BN(100_000),
+          makeMilestones([new BN(100_000)], ["cycle-2"]),
+          new BN(Math.floor(Date.now() / 1000) + 7200)
+        )
+        .accounts({
+          maker: maker.publicKey,
+          beneficiary: taker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker, taker])
+        .rpc();
+
+      try {
+        await dispute("second dispute");
+        assert.fail("Should have thrown DisputeLimitReached");
+      } catch (err: any) {
+        assert.include(err.message, "DisputeLimitReached");
+      }
+
+      escrow = await program.account.escrowState.fetch(escrowPDA);
+      assert.equal(escrow.disputeCount, 1, "Counter unchanged by the rejected attempt");
+      assert.ok(escrow.status.active !== undefined);
+    } finally {
+      await setCap(3);
+    }
+  });
+
+  // ===========================================================================
+  // 96. release_milestone - wSOL payout unwrapped to native SOL
+  // ===========================================================================
+  it("96. release_milestone: unwraps wSOL payouts to native SOL for the beneficiary", async () => {
+    const makerWsol = await createWrappedNativeAccount(
+      connection, maker, maker.publicKey, TOTAL_AMOUNT.toNumber()
+    );
+    const takerWsol = await createAssociatedTokenAccount(connection, taker, NATIVE_MINT, taker.publicKey);
+    const feeCollectorWsol = await createAssociatedTokenAccount(
+      connection, maker, NATIVE_MINT, feeCollector.publicKey
+    );
+
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      amount: TOTAL_AMOUNT,
+      mintPk: NATIVE_MINT,
+      makerAtaPk: makerWsol,
+    });
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    const [unwrapPDA] = findUnwrapPDA(escrowPDA);
+    const takerLamportsBefore = await connection.getBalance(taker.publicKey);
+
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint: NATIVE_MINT,
+        vault,
+        beneficiaryTokenAccount: takerWsol,
+        feeCollectorTokenAccount: feeCollectorWsol,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        unwrapAccount: unwrapPDA,
+        beneficiary: taker.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([stranger])
+      .rpc();
+
+    const fee = TOTAL_AMOUNT.muln(FEE_BPS).divn(10_000);
+    const net = TOTAL_AMOUNT.sub(fee);
+    const rent = await connection.getMinimumBalanceForRentExemption(165);
+    const takerLamportsAfter = await connection.getBalance(taker.publicKey);
+    assert.equal(takerLamportsAfter - takerLamportsBefore, net.toNumber() + rent, "Net payout arrives as native SOL");
+
+    assert.equal((await getAccount(connection, takerWsol)).amount.toString(), "0", "wSOL ATA untouched");
+    assert.equal((await getAccount(connection, feeCollectorWsol)).amount.toString(), fee.toString());
+    assert.isNull(await connection.getAccountInfo(unwrapPDA), "Scratch account closed");
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.completed !== undefined);
+  });
+
+  // ===========================================================================
+  // 97. release_milestone - fail: unwrap requested on a non-wSOL escrow
+  // ===========================================================================
+  it("97. release_milestone: rejects unwrap for non-wSOL mints (UnwrapRequiresNativeMint)", async () => {
+    const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    try {
+      await program.methods
+        .releaseMilestone(0, null, false)
+        .accounts({
+          payer: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          unwrapAccount: findUnwrapPDA(escrowPDA)[0],
+          beneficiary: taker.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown UnwrapRequiresNativeMint");
+    } catch (err: any) {
+      assert.include(err.message, "UnwrapRequiresNativeMint");
+    }
+  });
+
+  // ===========================================================================
+  // 98. simulate_resolution - preview matches the actual ruling payout
+  // ===========================================================================
+  it("98. simulate_resolution: previewed breakdown matches resolve_dispute balances", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(333_333), new BN(666_667)],
+      amount: TOTAL_AMOUNT,
+    });
+    const simulate = (resolution: any) =>
+      program.methods
+        .simulateResolution(resolution)
+        .accounts({ escrowState: escrowPDA })
+        .view();
+
+    // Works before any dispute, for negotiation
+    const preview = await simulate({ makerWins: {} });
+    assert.ok(preview.makerAmount.eq(TOTAL_AMOUNT));
+    assert.ok(preview.beneficiaryAmount.isZero() && preview.fee.isZero());
+
+    await program.methods
+      .initiateDispute(createDescriptionHash("simulate before ruling"))
+      .accounts({ initiator: taker.publicKey, escrowState: escrowPDA, escrowConfig: configPDA })
+      .signers([taker])
+      .rpc();
+
+    const ruling = { split: { makerBps: 3333 } };
+    const simulated = await simulate(ruling);
+    assert.ok(simulated.remaining.eq(TOTAL_AMOUNT));
+    assert.ok(
+      simulated.makerAmount.add(simulated.beneficiaryAmount).add(simulated.fee).eq(TOTAL_AMOUNT),
+      "Breakdown accounts for every unit"
+    );
+
+    const makerBefore = await getAccount(connection, makerATA);
+    const takerBefore = await getAccount(connection, takerATA);
+    const feeBefore = await getAccount(connection, feeCollectorATA);
+
+    await program.methods
+      .resolveDispute(ruling, null, false, false)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+
+    const makerAfter = await getAccount(connection, makerATA);
+    const takerAfter = await getAccount(connection, takerATA);
+    const feeAfter = await getAccount(connection, feeCollectorATA);
+    assert.equal((makerAfter.amount - makerBefore.amount).toString(), simulated.makerAmount.toString());
+    assert.equal((takerAfter.amount - takerBefore.amount).toString(), simulated.beneficiaryAmount.toString());
+    assert.equal((feeAfter.amount - feeBefore.amount).toString(), simulated.fee.toString());
+  });
+
+  // ===========================================================================
+  // 99. create_escrow_with_ref - seed derived from business data
+  // ===========================================================================
+  it("99. create_escrow_with_ref: address is recomputable from maker/taker/mint/external_ref", async () => {
+    // Reference vector shared with the on-chain unit test
+    const vector = deriveEscrowSeed(
+      new PublicKey(Buffer.alloc(32, 1)),
+      new PublicKey(Buffer.alloc(32, 2)),
+      new PublicKey(Buffer.alloc(32, 3)),
+      Buffer.alloc(32, 4)
+    );
+    assert.equal(vector.toString(16), "ad090d76600bfefe");
+
+    const externalRef = createDescriptionHash(`invoice-${Date.now()}`);
+    const seed = deriveEscrowSeed(maker.publicKey, taker.publicKey, mint, externalRef);
+    const [escrowPDA] = findEscrowPDA(maker.publicKey, seed);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
+    const milestones = makeMilestones([TOTAL_AMOUNT], ["ref-milestone"]);
+    const expiresAt = new BN(Math.floor(Date.now() / 1000) + 3600);
+    const accounts = {
+      maker: maker.publicKey,
+      payer: maker.publicKey,
+      taker: taker.publicKey,
+      mint,
+      escrowState: escrowPDA,
+      vault,
+      makerTokenAccount: makerATA,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    // A seed that doesn't match the derivation is rejected
+    const wrongSeed = seed.addn(1);
+    const [wrongPDA] = findEscrowPDA(maker.publicKey, wrongSeed);
+    try {
+      await program.methods
+        .createEscrowWithRef(wrongSeed, TOTAL_AMOUNT, milestones, expiresAt, 0, externalRef, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
+        .accounts({
+          ...accounts,
+          escrowState: wrongPDA,
+          vault: getAssociatedTokenAddressSync(mint, wrongPDA, true),
+        })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown SeedDerivationMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "SeedDerivationMismatch");
+    }
+
+    await program.methods
+      .createEscrowWithRef(seed, TOTAL_AMOUNT, milestones, expiresAt, 0, externalRef, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
+      .accounts(accounts)
+      .signers([maker])
+      .rpc();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.seed.eq(seed), "Derived seed stored as usual");
+  });
+
+  // ===========================================================================
+  // 100. create_escrow - initial beneficiary distinct from the taker
+  // ===========================================================================
+  it("100. create_escrow: initial_beneficiary receives the first release", async () => {
+    // Maker cannot be the initial beneficiary
+    try {
+      await setupEscrow({ initialBeneficiary: maker.publicKey });
+      assert.fail("Should have thrown InvalidBeneficiary");
+    } catch (err: any) {
+      assert.include(err.message, "InvalidBeneficiary");
+    }
+
+    // Derive or create stranger's ATA (may already exist from earlier tests)
+    let strangerATA: anchor.web3.PublicKey;
+    try {
+      strangerATA = await createTokenAccount(connection, authority, mint, stranger.publicKey);
+    } catch (_) {
+      strangerATA = getAssociatedTokenAddressSync(mint, stranger.publicKey);
+    }
+
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      initialBeneficiary: stranger.publicKey,
+    });
+
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.taker.equals(taker.publicKey), "Taker unchanged");
+    assert.ok(escrow.beneficiary.equals(stranger.publicKey), "Beneficiary set at creation");
+    assert.ok(escrow.beneficiaryAta!.equals(strangerATA));
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    const strangerBefore = await getAccount(connection, strangerATA);
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({
+        payer: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: strangerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    const strangerAfter = await getAccount(connection, strangerATA);
+    const net = TOTAL_AMOUNT.sub(TOTAL_AMOUNT.muln(FEE_BPS).divn(10_000));
+    assert.equal((strangerAfter.amount - strangerBefore.amount).toString(), net.toString());
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.completed !== undefined);
+  });
+
+  // ===========================================================================
+  // 101. create_escrow - hash_algo recorded and checked against config
+  // ===========================================================================
+  it("101. create_escrow: records hash_algo and rejects algorithms the config doesn't accept", async () => {
+    const { escrowPDA } = await setupEscrow({ hashAlgo: HASH_ALGO_KECCAK256 });
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.hashAlgo, HASH_ALGO_KECCAK256);
+
+    try {
+      await setupEscrow({ hashAlgo: 9 });
+      assert.fail("Should have thrown UnsupportedHashAlgo");
+    } catch (err: any) {
+      assert.include(err.message, "UnsupportedHashAlgo");
+    }
+
+    const setAccepted = (mask: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, mask, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await setAccepted(1 << HASH_ALGO_SHA256);
+    try {
+      await setupEscrow({ hashAlgo: HASH_ALGO_KECCAK256 });
+      assert.fail("Should have thrown UnsupportedHashAlgo");
+    } catch (err: any) {
+      assert.include(err.message, "UnsupportedHashAlgo");
+    } finally {
+      await setAccepted((1 << HASH_ALGO_SHA256) | (1 << HASH_ALGO_KECCAK256));
+    }
+  });
+
+  // ===========================================================================
+  // 102. transfer_maker - maker role moves, escrow address does not
+  // ===========================================================================
+  it("102. transfer_maker: new maker inherits approval authority on the same PDA", async () => {
+    const { escrowPDA } = await setupEscrow();
+    const transfer = (signer: Keypair, newMaker: PublicKey) =>
+      program.methods
+        .transferMaker()
+        .accounts({ maker: signer.publicKey, escrowState: escrowPDA, newMaker })
+        .signers([signer])
+        .rpc();
+
+    // New maker cannot be the beneficiary
+    try {
+      await transfer(maker, taker.publicKey);
+      assert.fail("Should have thrown InvalidMaker");
+    } catch (err: any) {
+      assert.include(err.message, "InvalidMaker");
+    }
+
+    await transfer(maker, stranger.publicKey);
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.maker.equals(stranger.publicKey), "Maker role reassigned");
+    assert.ok(escrow.creator.equals(maker.publicKey), "PDA creator unchanged");
+
+    // Former maker lost approval authority
+    try {
+      await program.methods
+        .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown NotMaker");
+    } catch (err: any) {
+      assert.include(err.message, "NotMaker");
+    }
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: stranger.publicKey, escrowState: escrowPDA })
+      .signers([stranger])
+      .rpc();
+
+    const after = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(after.milestones[0].status.approved !== undefined);
+  });
+
+  // ===========================================================================
+  // 103. approve_milestone - expected amount / hash guard against index races
+  // ===========================================================================
+  it("103. approve_milestone: rejects approval when expected amount or hash differ (MilestoneMismatch)", async () => {
+    const { escrowPDA, milestones } = await setupEscrow();
+    const approve = (amount: BN, hash: number[]) =>
+      program.methods
+        .approveMilestone(1, amount, hash, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+
+    // Maker reviewed milestone 0 but signs index 1
+    try {
+      await approve(milestones[0].amount, ANY_HASH);
+      assert.fail("Should have thrown MilestoneMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "MilestoneMismatch");
+    }
+    try {
+      await approve(ANY_AMOUNT, milestones[0].descriptionHash);
+      assert.fail("Should have thrown MilestoneMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "MilestoneMismatch");
+    }
+
+    await approve(milestones[1].amount, milestones[1].descriptionHash);
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[1].status.approved !== undefined);
+  });
+
+  // ===========================================================================
+  // 105. accept_escrow - opt-in acceptance gates the escrow going live
+  // ===========================================================================
+  it("105. accept_escrow: escrow stays AwaitingAcceptance until the taker accepts", async () => {
+    const acceptanceDeadline = new BN(Math.floor(Date.now() / 1000) + 1800);
+    const { escrowPDA } = await setupEscrow({ acceptanceDeadline });
+
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.awaitingAcceptance !== undefined);
+    assert.ok(escrow.acceptanceDeadline.eq(acceptanceDeadline));
+
+    // Approvals and disputes are blocked before acceptance
+    try {
+      await program.methods
+        .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown EscrowNotActive");
+    } catch (err: any) {
+      assert.include(err.message, "EscrowNotActive");
+    }
+    try {
+      await program.methods
+        .initiateDispute(createDescriptionHash("not yet live"))
+        .accounts({ initiator: taker.publicKey, escrowState: escrowPDA, escrowConfig: configPDA })
+        .signers([taker])
+        .rpc();
+      assert.fail("Should have thrown EscrowNotActive");
+    } catch (err: any) {
+      assert.include(err.message, "EscrowNotActive");
+    }
+
+    // Only the taker/beneficiary can accept
+    try {
+      await program.methods
+        .acceptEscrow()
+        .accounts({ acceptor: stranger.publicKey, escrowState: escrowPDA })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown NotEscrowParty");
+    } catch (err: any) {
+      assert.include(err.message, "NotEscrowParty");
+    }
+
+    await program.methods
+      .acceptEscrow()
+      .accounts({ acceptor: taker.publicKey, escrowState: escrowPDA })
+      .signers([taker])
+      .rpc();
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.active !== undefined, "Accepted escrow is Active");
+
+    try {
+      await program.methods
+        .acceptEscrow()
+        .accounts({ acceptor: taker.publicKey, escrowState: escrowPDA })
+        .signers([taker])
+        .rpc();
+      assert.fail("Should have thrown NotAwaitingAcceptance");
+    } catch (err: any) {
+      assert.include(err.message, "NotAwaitingAcceptance");
+    }
+  });
+
+  // ===========================================================================
+  // 107. expected_nonce - optimistic concurrency on escrow mutations
+  // ===========================================================================
+  it("107. expected_nonce: mutations bump the nonce and stale snapshots fail with StaleState", async () => {
+    const { escrowPDA } = await setupEscrow();
+    const snapshot = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(snapshot.nonce.toNumber(), 0);
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, snapshot.nonce)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.nonce.toNumber(), 1);
+
+    // A coordinator still holding the pre-approval snapshot is rejected
+    try {
+      await program.methods
+        .approveMilestone(1, ANY_AMOUNT, ANY_HASH, snapshot.nonce)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown StaleState");
+    } catch (err: any) {
+      assert.include(err.message, "StaleState");
+    }
+    try {
+      await program.methods
+        .transferClaim(snapshot.nonce)
+        .accounts({
+          beneficiary: taker.publicKey,
+          escrowState: escrowPDA,
+          newBeneficiary: stranger.publicKey,
+        })
+        .signers([taker])
+        .rpc();
+      assert.fail("Should have thrown StaleState");
+    } catch (err: any) {
+      assert.include(err.message, "StaleState");
+    }
+
+    await program.methods
+      .transferClaim(escrow.nonce)
+      .accounts({
+        beneficiary: taker.publicKey,
+        escrowState: escrowPDA,
+        newBeneficiary: stranger.publicKey,
+      })
+      .signers([taker])
+      .rpc();
+
+    const after = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(after.nonce.toNumber(), 2);
+    assert.ok(after.beneficiary.equals(stranger.publicKey));
+  });
+
+  // ===========================================================================
+  // 108. close_escrow - dust below the config threshold is burned, not swept
+  // ===========================================================================
+  it("108. close_escrow: dust below close_dust_threshold is burned so close always succeeds", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+    });
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({
+        payer: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    await mintTokens(connection, authority, mint, makerATA, new BN(100));
+    const tx = new anchor.web3.Transaction().add(
+      createTransferInstruction(makerATA, vault, maker.publicKey, 100)
+    );
+    await anchor.web3.sendAndConfirmTransaction(connection, tx, [maker]);
+
+    await program.methods
+      .updateConfig(null, null, null, null, null, null, new BN(1_000), null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        feeCollector: feeCollector.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+
+    const config = await program.account.escrowConfig.fetch(configPDA);
+    assert.equal(config.closeDustThreshold.toNumber(), 1_000);
+
+    const makerBefore = await getAccount(connection, makerATA);
+    const supplyBefore = (await getMint(connection, mint)).supply;
+
+    await program.methods
+      .closeEscrow()
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    // Dust was burned rather than returned, and both accounts are gone
+    const makerAfter = await getAccount(connection, makerATA);
+    assert.equal(makerAfter.amount.toString(), makerBefore.amount.toString());
+    const supplyAfter = (await getMint(connection, mint)).supply;
+    assert.equal((supplyBefore - supplyAfter).toString(), "100");
+    assert.isNull(await connection.getAccountInfo(vault));
+    assert.isNull(await connection.getAccountInfo(escrowPDA));
+
+    // Restore the default (always sweep) for subsequent tests
+    await program.methods
+      .updateConfig(null, null, null, null, null, null, new BN(0), null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        feeCollector: feeCollector.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+  });
+
+  // ===========================================================================
+  // 109. fractionalize_claim / redeem_shares - pull-payment claim pool
+  // ===========================================================================
+  it("109. fractionalize_claim: releases accrue to the claim pool and holders redeem pro rata", async () => {
+    const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+    const [shareMint] = findShareMintPDA(escrowPDA);
+    const takerShareAta = getAssociatedTokenAddressSync(shareMint, taker.publicKey);
+
+    await program.methods
+      .fractionalizeClaim(new BN(100))
+      .accounts({
+        beneficiary: taker.publicKey,
+        escrowState: escrowPDA,
+        shareMint,
+        beneficiaryShareAta: takerShareAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([taker])
+      .rpc();
+
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.shareMint.equals(shareMint));
+    assert.equal((await getAccount(connection, takerShareAta)).amount.toString(), "100");
+
+    // A factoring desk buys 40% of the receivable
+    const strangerShareAta = await createAssociatedTokenAccount(
+      connection, authority, shareMint, stranger.publicKey
+    );
+    await anchor.web3.sendAndConfirmTransaction(
+      connection,
+      new anchor.web3.Transaction().add(
+        createTransferInstruction(takerShareAta, strangerShareAta, taker.publicKey, 40)
+      ),
+      [taker]
+    );
+
+    // The claim itself can no longer be reassigned
+    try {
+      await program.methods
+        .transferClaim(null)
+        .accounts({ beneficiary: taker.publicKey, escrowState: escrowPDA, newBeneficiary: stranger.publicKey })
+        .signers([taker])
+        .rpc();
+      assert.fail("Should have thrown ClaimIsFractionalized");
+    } catch (err: any) {
+      assert.include(err.message, "ClaimIsFractionalized");
+    }
+
+    // Release the lone milestone (2.5% fee) — net stays in the vault
+    const takerBefore = await getAccount(connection, takerATA);
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({
+        payer: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.claimPool.toNumber(), 975_000);
+    const takerAfterRelease = await getAccount(connection, takerATA);
+    assert.equal(takerAfterRelease.amount.toString(), takerBefore.amount.toString());
+
+    // Completed, but the pool still belongs to share holders
+    try {
+      await program.methods
+        .closeEscrow()
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown ClaimPoolNotEmpty");
+    } catch (err: any) {
+      assert.include(err.message, "ClaimPoolNotEmpty");
+    }
+
+    let strangerATA: PublicKey;
+    try {
+      strangerATA = await createTokenAccount(connection, authority, mint, stranger.publicKey);
+    } catch (_) {
+      strangerATA = getAssociatedTokenAddressSync(mint, stranger.publicKey);
+    }
+    const redeem = (holder: Keypair, shareAta: PublicKey, tokenAta: PublicKey, shares: number) =>
+      program.methods
+        .redeemShares(new BN(shares))
+        .accounts({
+          holder: holder.publicKey,
+          escrowState: escrowPDA,
+          shareMint,
+          holderShareAccount: shareAta,
+          mint,
+          vault,
+          holderTokenAccount: tokenAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          shareTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([holder])
+        .rpc();
+
+    const strangerBefore = await getAccount(connection, strangerATA);
+    await redeem(stranger, strangerShareAta, strangerATA, 40);
+    const strangerAfter = await getAccount(connection, strangerATA);
+    assert.equal(
+      (BigInt(strangerAfter.amount.toString()) - BigInt(strangerBefore.amount.toString())).toString(),
+      "390000"
+    );
+
+    await redeem(taker, takerShareAta, takerATA, 60);
+    const takerAfter = await getAccount(connection, takerATA);
+    assert.equal(
+      (BigInt(takerAfter.amount.toString()) - BigInt(takerAfterRelease.amount.toString())).toString(),
+      "585000"
+    );
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.claimPool.toNumber(), 0);
+    assert.equal((await getMint(connection, shareMint)).supply.toString(), "0");
+  });
+
+  // ===========================================================================
+  // 110. propose_escrow / counter_proposal / accept_proposal - negotiated funding
+  // ===========================================================================
+  it("110. accept_proposal: funds exactly the last counter-proposed terms", async () => {
+    const seed = nextSeed();
+    const [proposalPDA] = findProposalPDA(maker.publicKey, seed);
+    const [escrowPDA] = findEscrowPDA(maker.publicKey, seed);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
+    const expiresAt = new BN(Math.floor(Date.now() / 1000) + 7200);
+    const terms = (amounts: number[]) => ({
+      amount: new BN(amounts.reduce((a, b) => a + b, 0)),
+      milestones: makeMilestones(amounts.map((a) => new BN(a)), amounts.map((_, i) => `term-${i}`)),
+      expiresAt,
+      category: 0,
+      initialBeneficiary: null,
+      hashAlgo: HASH_ALGO_SHA256,
+    });
+    const opening = terms([600_000, 400_000]);
+    const counter = terms([500_000, 500_000]);
+
+    // Taker opens, maker counters — the turn is back with the taker
+    await program.methods
+      .proposeEscrow(seed, opening, new BN(Math.floor(Date.now() / 1000) + 3600))
+      .accounts({
+        proposer: taker.publicKey,
+        maker: maker.publicKey,
+        taker: taker.publicKey,
+        mint,
+        proposal: proposalPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([taker])
+      .rpc();
+    try {
+      await program.methods
+        .counterProposal(counter)
+        .accounts({ proposer: taker.publicKey, proposal: proposalPDA })
+        .signers([taker])
+        .rpc();
+      assert.fail("Should have thrown NotYourTurn");
+    } catch (err: any) {
+      assert.include(err.message, "NotYourTurn");
+    }
+    await program.methods
+      .counterProposal(counter)
+      .accounts({ proposer: maker.publicKey, proposal: proposalPDA })
+      .signers([maker])
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPDA);
+    assert.equal(proposal.revision, 1);
+    assert.isFalse(proposal.makerTurn);
+
+    const accept = (acceptor: Keypair, hash: number[]) =>
+      program.methods
+        .acceptProposal(hash)
+        .accounts({
+          acceptor: acceptor.publicKey,
+          maker: maker.publicKey,
+          taker: taker.publicKey,
+          creator: taker.publicKey,
+          proposal: proposalPDA,
+          escrowConfig: configPDA,
+          mint,
+          escrowState: escrowPDA,
+          vault,
+          makerTokenAccount: makerATA,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(acceptor === maker ? [maker] : [acceptor, maker])
+        .rpc();
+
+    // The maker countered last, so cannot also accept
+    try {
+      await accept(maker, proposalTermsHash(counter));
+      assert.fail("Should have thrown NotYourTurn");
+    } catch (err: any) {
+      assert.include(err.message, "NotYourTurn");
+    }
+    // Accepting the superseded opening terms is rejected
+    try {
+      await accept(taker, proposalTermsHash(opening));
+      assert.fail("Should have thrown ProposalTermsMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "ProposalTermsMismatch");
+    }
+
+    await accept(taker, proposalTermsHash(counter));
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.amount.eq(counter.amount));
+    assert.equal(escrow.milestones.length, 2);
+    assert.ok(escrow.milestones[0].amount.eq(new BN(500_000)));
+    assert.ok(escrow.expiresAt.eq(expiresAt));
+    assert.ok(escrow.taker.equals(taker.publicKey));
+    assert.equal((await getAccount(connection, vault)).amount.toString(), "1000000");
+    assert.isNull(await connection.getAccountInfo(proposalPDA), "Proposal closed on acceptance");
+  });
+
+  // ===========================================================================
+  // 111. attach_metadata - on-chain details closed alongside the escrow
+  // ===========================================================================
+  it("111. attach_metadata: stores the blob on-chain and close_escrow reclaims it", async () => {
+    const { escrowPDA, vault } = await setupEscrow();
+    const [metadataPDA] = findEscrowMetadataPDA(escrowPDA);
+    const blob = Buffer.from(
+      JSON.stringify({ title: "Landing page", items: ["design", "build", "deploy"] })
+    );
+
+    await program.methods
+      .attachMetadata(blob)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowMetadata: metadataPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([maker])
+      .rpc();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.metadata.equals(metadataPDA));
+    const metadata = await program.account.escrowMetadata.fetch(metadataPDA);
+    assert.ok(metadata.escrow.equals(escrowPDA));
+    assert.equal(Buffer.from(metadata.data).toString(), blob.toString());
+
+    await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    const closeAccounts = {
+      maker: maker.publicKey,
+      escrowState: escrowPDA,
+      escrowConfig: configPDA,
+      mint,
+      vault,
+      makerTokenAccount: makerATA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    try {
+      await program.methods.closeEscrow().accounts(closeAccounts).signers([maker]).rpc();
+      assert.fail("Should have thrown MetadataAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "MetadataAccountMissing");
+    }
+
+    await program.methods
+      .closeEscrow()
+      .accounts({ ...closeAccounts, escrowMetadata: metadataPDA })
+      .signers([maker])
+      .rpc();
+
+    assert.isNull(await connection.getAccountInfo(escrowPDA));
+    assert.isNull(await connection.getAccountInfo(metadataPDA));
+  });
+
+  // ===========================================================================
+  // 113. request_escrow / fund_request - taker-issued payment request
+  // ===========================================================================
+  it("113. fund_request: maker funds the taker's request once; request closes", async () => {
+    const seed = nextSeed();
+    const [requestPDA] = findEscrowRequestPDA(maker.publicKey, seed);
+    const [escrowPDA] = findEscrowPDA(maker.publicKey, seed);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
+    const terms = {
+      amount: new BN(1_000_000),
+      milestones: makeMilestones([new BN(700_000), new BN(300_000)], ["design", "build"]),
+      expiresAt: new BN(Math.floor(Date.now() / 1000) + 7200),
+      category: 0,
+      initialBeneficiary: null,
+      hashAlgo: HASH_ALGO_SHA256,
+    };
+
+    await program.methods
+      .requestEscrow(seed, terms)
+      .accounts({
+        requester: taker.publicKey,
+        maker: maker.publicKey,
+        mint,
+        escrowConfig: configPDA,
+        escrowRequest: requestPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([taker])
+      .rpc();
+
+    const config = await program.account.escrowConfig.fetch(configPDA);
+    const request = await program.account.escrowRequest.fetch(requestPDA);
+    assert.ok(request.taker.equals(taker.publicKey));
+    assert.ok(request.maker.equals(maker.publicKey));
+    assert.ok(request.terms.amount.eq(terms.amount));
+    assert.isAtMost(
+      Math.abs(request.expiresAt.toNumber() - Math.floor(Date.now() / 1000) - config.requestTtl.toNumber()),
+      60
+    );
+
+    // Only the requester can cancel it
+    try {
+      await program.methods
+        .cancelRequest()
+        .accounts({ requester: stranger.publicKey, escrowRequest: requestPDA })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown NotRequester");
+    } catch (err: any) {
+      assert.include(err.message, "NotRequester");
+    }
+
+    const fund = () =>
+      program.methods
+        .fundRequest(seed)
+        .accounts({
+          maker: maker.publicKey,
+          taker: taker.publicKey,
+          escrowRequest: requestPDA,
+          escrowConfig: configPDA,
+          mint,
+          escrowState: escrowPDA,
+          vault,
+          makerTokenAccount: makerATA,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+
+    await fund();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.maker.equals(maker.publicKey));
+    assert.ok(escrow.taker.equals(taker.publicKey));
+    assert.ok(escrow.amount.eq(terms.amount));
+    assert.equal(escrow.milestones.length, 2);
+    assert.equal((await getAccount(connection, vault)).amount.toString(), "1000000");
+    assert.isNull(await connection.getAccountInfo(requestPDA), "Request closed on funding");
+
+    // An already-funded request is gone and cannot be funded again
+    try {
+      await fund();
+      assert.fail("Should have thrown AccountNotInitialized");
+    } catch (err: any) {
+      assert.include(err.message, "AccountNotInitialized");
+    }
+  });
+
+  // ===========================================================================
+  // 115. EscrowCancelled - terminal event carries cumulative totals
+  // ===========================================================================
+  it("115. cancel_escrow: terminal event reports totals, fees and milestone counts", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(600_000), new BN(400_000)],
+    });
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({
+        payer: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    // 2.5% of 600_000
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.feesPaid.toString(), "15000");
+
+    const sig = await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const events = [...parser.parseLogs(tx!.meta!.logMessages!)];
+    const cancelled = events.find((e) => e.name === "escrowCancelled");
+    assert.ok(cancelled, "EscrowCancelled emitted");
+    assert.equal(cancelled!.data.refundedAmount.toString(), "400000");
+    assert.equal(cancelled!.data.totalReleased.toString(), "600000");
+    assert.equal(cancelled!.data.totalRefunded.toString(), "400000");
+    assert.equal(cancelled!.data.totalFeesPaid.toString(), "15000");
+    assert.equal(cancelled!.data.milestonesReleased, 1);
+    assert.equal(cancelled!.data.milestonesCancelled, 1);
+    assert.ok((cancelled!.data.beneficiary as PublicKey).equals(taker.publicKey));
+    assert.ok(cancelled!.data.reason.makerCancel !== undefined);
+    assert.equal(cancelled!.data.routing.feeBps, 0, "no cancellation fee configured: full maker refund");
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.cancelled !== undefined);
+  });
+
+  // ===========================================================================
+  // 116. create_sub_escrow - parent milestone release funds the child
+  // ===========================================================================
+  it("116. create_sub_escrow: releasing the parent milestone funds and activates the child", async () => {
+    const parent = await setupEscrow();
+    const subTerms = (amount: number) => ({
+      amount: new BN(amount),
+      milestones: makeMilestones([new BN(amount)], ["subcontracted"]),
+      expiresAt: new BN(Math.floor(Date.now() / 1000) + 7200),
+      category: 0,
+      initialBeneficiary: null,
+      hashAlgo: HASH_ALGO_SHA256,
+    });
+    const createSub = (index: number, amount: number) => {
+      const seed = nextSeed();
+      const [childPDA] = findEscrowPDA(taker.publicKey, seed);
+      const childVault = getAssociatedTokenAddressSync(mint, childPDA, true);
+      const send = () =>
+        program.methods
+          .createSubEscrow(seed, index, subTerms(amount))
+          .accounts({
+            maker: taker.publicKey,
+            taker: stranger.publicKey,
+            parentEscrow: parent.escrowPDA,
+            escrowConfig: configPDA,
+            mint,
+            escrowState: childPDA,
+            vault: childVault,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker])
+          .rpc();
+      return { childPDA, childVault, send };
+    };
+
+    // Milestone 0 nets 390_000 after the 2.5% fee
+    try {
+      await createSub(0, 390_001).send();
+      assert.fail("Should have thrown SubEscrowExceedsPayout");
+    } catch (err: any) {
+      assert.include(err.message, "SubEscrowExceedsPayout");
+    }
+
+    const sub = createSub(0, 300_000);
+    await sub.send();
+    let child = await program.account.escrowState.fetch(sub.childPDA);
+    assert.ok(child.status.awaitingFunding !== undefined);
+    assert.ok(child.maker.equals(taker.publicKey));
+    assert.ok(child.taker.equals(stranger.publicKey));
+    assert.ok(child.parent.escrow.equals(parent.escrowPDA));
+    assert.equal(child.parent.milestoneIndex, 0);
+    let parentState = await program.account.escrowState.fetch(parent.escrowPDA);
+    assert.ok(parentState.milestones[0].subEscrow.equals(sub.childPDA));
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: parent.escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    const releaseAccounts = {
+      payer: maker.publicKey,
+      escrowState: parent.escrowPDA,
+      escrowConfig: configPDA,
+      mint,
+      vault: parent.vault,
+      beneficiaryTokenAccount: takerATA,
+      feeCollectorTokenAccount: feeCollectorATA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    try {
+      await program.methods
+        .releaseMilestone(0, null, false)
+        .accounts(releaseAccounts)
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown SubEscrowAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "SubEscrowAccountMissing");
+    }
+
+    const takerBefore = await getAccount(connection, takerATA);
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({ ...releaseAccounts, subEscrow: sub.childPDA, subEscrowVault: sub.childVault })
+      .signers([maker])
+      .rpc();
+    const takerAfter = await getAccount(connection, takerATA);
+
+    assert.equal((await getAccount(connection, sub.childVault)).amount.toString(), "300000");
+    assert.equal(
+      (BigInt(takerAfter.amount.toString()) - BigInt(takerBefore.amount.toString())).toString(),
+      "90000",
+      "Beneficiary receives the net payout minus the sub-escrow amount"
+    );
+    child = await program.account.escrowState.fetch(sub.childPDA);
+    assert.ok(child.status.active !== undefined);
+
+    // An unfunded child can be abandoned, unlinking its parent milestone
+    const abandoned = createSub(1, 100_000);
+    await abandoned.send();
+    await program.methods
+      .cancelSubEscrow()
+      .accounts({
+        maker: taker.publicKey,
+        escrowState: abandoned.childPDA,
+        parentEscrow: parent.escrowPDA,
+      })
+      .signers([taker])
+      .rpc();
+    child = await program.account.escrowState.fetch(abandoned.childPDA);
+    assert.ok(child.status.cancelled !== undefined);
+    parentState = await program.account.escrowState.fetch(parent.escrowPDA);
+    assert.isNull(parentState.milestones[1].subEscrow);
+  });
+
+  // ===========================================================================
+  // 117. cancel_and_release - one-shot terminal settlement
+  // ===========================================================================
+  it("117. cancel_and_release: refunds pending, pays approved, ends terminal by dominant bucket", async () => {
+    const settle = async (approvedIndexes: number[]) => {
+      const { escrowPDA, vault } = await setupEscrow();
+      for (const i of approvedIndexes) {
+        await program.methods
+          .approveMilestone(i, ANY_AMOUNT, ANY_HASH, null)
+          .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+          .signers([maker])
+          .rpc();
+      }
+      const makerBefore = await getAccount(connection, makerATA);
+      const takerBefore = await getAccount(connection, takerATA);
+      await program.methods
+        .cancelAndRelease(null)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc();
+      const makerAfter = await getAccount(connection, makerATA);
+      const takerAfter = await getAccount(connection, takerATA);
+      assert.equal((await getAccount(connection, vault)).amount.toString(), "0");
+      return {
+        escrow: await program.account.escrowState.fetch(escrowPDA),
+        makerDelta: (BigInt(makerAfter.amount.toString()) - BigInt(makerBefore.amount.toString())).toString(),
+        takerDelta: (BigInt(takerAfter.amount.toString()) - BigInt(takerBefore.amount.toString())).toString(),
+      };
+    };
+
+    // 700_000 approved vs 300_000 pending: Completed
+    const mostlyDone = await settle([0, 1]);
+    assert.ok(mostlyDone.escrow.status.completed !== undefined);
+    assert.equal(mostlyDone.makerDelta, "300000");
+    assert.equal(mostlyDone.takerDelta, "682500");
+    assert.equal(mostlyDone.escrow.feesPaid.toString(), "17500");
+    assert.ok(mostlyDone.escrow.milestones[2].status.cancelled !== undefined);
+
+    // 400_000 approved vs 600_000 pending: Cancelled
+    const mostlyRefunded = await settle([0]);
+    assert.ok(mostlyRefunded.escrow.status.cancelled !== undefined);
+    assert.equal(mostlyRefunded.makerDelta, "600000");
+    assert.equal(mostlyRefunded.takerDelta, "390000");
+    assert.ok(mostlyRefunded.escrow.milestones[0].status.released !== undefined);
+  });
+
+  // ===========================================================================
+  // 118. create_escrow_with_receipt - escrow and Receipt NFT in one transaction
+  // ===========================================================================
+  it("118. create_escrow_with_receipt: maker and beneficiary co-sign create + mint", async () => {
+    const createWithReceipt = async (beneficiaryKp: Keypair) => {
+      const seed = nextSeed();
+      const [escrowPDA] = findEscrowPDA(maker.publicKey, seed);
+      const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
+      const [receiptMint] = findReceiptMintPDA(escrowPDA);
+      const [metadata] = findMetadataPDA(receiptMint);
+      const [masterEdition] = findMasterEditionPDA(receiptMint);
+      const beneficiaryReceiptAta = getAssociatedTokenAddressSync(
+        receiptMint,
+        beneficiaryKp.publicKey
+      );
+      const milestones = makeMilestones([new BN(600_000), new BN(400_000)], ["a", "b"]);
+      const expiresAt = new BN(Math.floor(Date.now() / 1000) + 3600);
+
+      await program.methods
+        .createEscrowWithReceipt(seed, TOTAL_AMOUNT, milestones, expiresAt, 0, null, 0)
+        .accounts({
+          maker: maker.publicKey,
+          taker: taker.publicKey,
+          beneficiary: beneficiaryKp.publicKey,
+          escrowConfig: configPDA,
+          mint,
+          escrowState: escrowPDA,
+          vault,
+          makerTokenAccount: makerATA,
+          receiptMint,
+          beneficiaryReceiptAta,
+          metadata,
+          masterEdition,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker, beneficiaryKp])
+        .rpc();
+
+      return { escrowPDA, vault, receiptMint, beneficiaryReceiptAta };
+    };
+
+    const { escrowPDA, vault, receiptMint, beneficiaryReceiptAta } =
+      await createWithReceipt(taker);
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.active !== undefined);
+    assert.ok((escrow.receiptMint as PublicKey).equals(receiptMint));
+    assert.equal(escrow.nonce.toString(), "1");
+    assert.equal((await getAccount(connection, vault)).amount.toString(), TOTAL_AMOUNT.toString());
+    assert.equal((await getAccount(connection, beneficiaryReceiptAta)).amount.toString(), "1");
+
+    // A co-signer that is not the escrow's beneficiary cannot take the receipt
+    try {
+      await createWithReceipt(stranger);
+      assert.fail("Should have thrown NotBeneficiary");
+    } catch (err: any) {
+      assert.include(err.message, "NotBeneficiary");
+    }
+  });
+
+  // ===========================================================================
+  // 121. remaining_accounts bound - excess accounts rejected before any work
+  // ===========================================================================
+  it("121. release_milestone: rejects excess remaining accounts (TooManyAccounts)", async () => {
+    const { escrowPDA, vault } = await setupEscrow();
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    const padding = Array.from({ length: 8 }, () => ({
+      pubkey: Keypair.generate().publicKey,
+      isWritable: false,
+      isSigner: false,
+    }));
+
+    try {
+      await program.methods
+        .releaseMilestone(0, null, false)
+        .accounts({
+          payer: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(padding)
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown TooManyAccounts");
+    } catch (err: any) {
+      assert.include(err.message, "TooManyAccounts");
+    }
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[0].status.approved !== undefined);
+    assert.equal(escrow.releasedAmount.toString(), "0");
+  });
+
+  // ===========================================================================
+  // 122. approve_milestone_auto - auto_release escrows pay out on approval
+  // ===========================================================================
+  it("122. approve_milestone_auto: pays out on approval; plain approve_milestone is rejected", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      autoRelease: true,
+    });
+    const created = await program.account.escrowState.fetch(escrowPDA);
+    assert.isTrue(created.autoRelease);
+
+    try {
+      await program.methods
+        .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown AutoReleaseRequired");
+    } catch (err: any) {
+      assert.include(err.message, "AutoReleaseRequired");
+    }
+
+    const autoAccounts = (escrow: PublicKey, escrowVault: PublicKey) => ({
+      maker: maker.publicKey,
+      escrowState: escrow,
+      escrowConfig: configPDA,
+      mint,
+      vault: escrowVault,
+      beneficiaryTokenAccount: takerATA,
+      feeCollectorTokenAccount: feeCollectorATA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+    const takerBefore = await getAccount(connection, takerATA);
+    await program.methods
+      .approveMilestoneAuto(0, TOTAL_AMOUNT, ANY_HASH, null)
+      .accounts(autoAccounts(escrowPDA, vault))
+      .signers([maker])
+      .rpc();
+    const takerAfter = await getAccount(connection, takerATA);
+    assert.equal(
+      (BigInt(takerAfter.amount.toString()) - BigInt(takerBefore.amount.toString())).toString(),
+      "975000"
+    );
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[0].status.released !== undefined);
+    assert.ok(escrow.status.completed !== undefined);
+
+    // Escrows created without the flag keep the two-step flow
+    const manual = await setupEscrow();
+    try {
+      await program.methods
+        .approveMilestoneAuto(0, ANY_AMOUNT, ANY_HASH, null)
+        .accounts(autoAccounts(manual.escrowPDA, manual.vault))
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown AutoReleaseDisabled");
+    } catch (err: any) {
+      assert.include(err.message, "AutoReleaseDisabled");
+    }
+  });
+
+  // ===========================================================================
+  // 123. keeper registry - registered keepers earn tips for state-changing cranks
+  // ===========================================================================
+  it("123. keepers: tip jar pays registered keepers for cranks; tips claimable, bond returned", async () => {
+    const [keeperPDA] = findKeeperPDA(stranger.publicKey);
+    const bond = new BN(10_000_000);
+    const crankTip = new BN(5_000);
+
+    await program.methods
+      .registerKeeper(bond)
+      .accounts({
+        keeper: stranger.publicKey,
+        keeperAccount: keeperPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([stranger])
+      .rpc();
+
+    const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+
+    try {
+      await program.methods
+        .fundTipJar(new BN(0), new BN(50_000))
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA, systemProgram: SystemProgram.programId })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown InvalidCrankTip");
+    } catch (err: any) {
+      assert.include(err.message, "InvalidCrankTip");
+    }
+
+    await program.methods
+      .fundTipJar(crankTip, new BN(50_000))
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA, systemProgram: SystemProgram.programId })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    const keeperLamportsBefore = await connection.getBalance(keeperPDA);
+    const releaseSig = await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({
+        payer: stranger.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        keeper: keeperPDA,
+      })
+      .signers([stranger])
+      .rpc({ commitment: "confirmed" });
+
+    assert.equal(await connection.getBalance(keeperPDA), keeperLamportsBefore + crankTip.toNumber());
+
+    // Keepers can attribute each tip to the crank that earned it
+    const releaseTx = await connection.getTransaction(releaseSig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const rewards = [...parser.parseLogs(releaseTx!.meta!.logMessages!)].filter((e) => e.name === "crankRewarded");
+    assert.equal(rewards.length, 1);
+    assert.ok(rewards[0].data.escrow.equals(escrowPDA));
+    assert.ok(rewards[0].data.keeper.equals(stranger.publicKey));
+    assert.equal(rewards[0].data.reward.toString(), crankTip.toString());
+    assert.deepEqual(rewards[0].data.instruction, { releaseMilestone: {} });
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.tipJar.toString(), "45000");
+    const keeperState = await program.account.keeper.fetch(keeperPDA);
+    assert.equal(keeperState.cranks.toString(), "1");
+    assert.equal(keeperState.tipsEarned.toString(), crankTip.toString());
+
+    await program.methods
+      .claimKeeperTips()
+      .accounts({ keeper: stranger.publicKey, keeperAccount: keeperPDA })
+      .signers([stranger])
+      .rpc();
+    assert.equal(await connection.getBalance(keeperPDA), keeperLamportsBefore);
+
+    try {
+      await program.methods
+        .claimKeeperTips()
+        .accounts({ keeper: stranger.publicKey, keeperAccount: keeperPDA })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown NoTipsToClaim");
+    } catch (err: any) {
+      assert.include(err.message, "NoTipsToClaim");
+    }
+
+    await program.methods
+      .deregisterKeeper()
+      .accounts({ keeper: stranger.publicKey, keeperAccount: keeperPDA })
+      .signers([stranger])
+      .rpc();
+    assert.isNull(await connection.getAccountInfo(keeperPDA));
+  });
+
+  // ===========================================================================
+  // 125. reset_beneficiary - maker undoes a mis-assigned claim before any work
+  // ===========================================================================
+  it("125. reset_beneficiary: restores the taker, then refuses once work is approved", async () => {
+    const { escrowPDA } = await setupEscrow();
+
+    await program.methods
+      .transferClaim(null)
+      .accounts({
+        beneficiary: taker.publicKey,
+        escrowState: escrowPDA,
+        newBeneficiary: stranger.publicKey,
+      })
+      .signers([taker])
+      .rpc();
+
+    await program.methods
+      .resetBeneficiary(null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.beneficiary.equals(taker.publicKey), "Beneficiary restored to taker");
+
+    try {
+      await program.methods
+        .resetBeneficiary(null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown BeneficiaryNotReassigned");
+    } catch (err: any) {
+      assert.include(err.message, "BeneficiaryNotReassigned");
+    }
+
+    await program.methods
+      .transferClaim(null)
+      .accounts({
+        beneficiary: taker.publicKey,
+        escrowState: escrowPDA,
+        newBeneficiary: stranger.publicKey,
+      })
+      .signers([taker])
+      .rpc();
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    try {
+      await program.methods
+        .resetBeneficiary(null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown WorkAlreadyApproved");
+    } catch (err: any) {
+      assert.include(err.message, "WorkAlreadyApproved");
+    }
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.beneficiary.equals(stranger.publicKey), "Transferred claim kept");
+  });
+
+  // ===========================================================================
+  // 126. refund_fee - rebates bounded by the fees collected on the escrow
+  // ===========================================================================
+  it("126. refund_fee: rebates collected fees and rejects refunds beyond them", async () => {
+    const { escrowPDA, vault } = await setupEscrow();
+    await program.methods
+      .approveAndRelease(0)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    const feesPaid = escrow.feesPaid;
+    assert.ok(feesPaid.gtn(1), "release collected a fee");
+    const half = feesPaid.divn(2);
+
+    const refundAccounts = (recipientTokenAccount: PublicKey) => ({
+      authority: authority.publicKey,
+      feeCollector: feeCollector.publicKey,
+      escrowConfig: configPDA,
+      escrowState: escrowPDA,
+      mint,
+      feeCollectorTokenAccount: feeCollectorATA,
+      recipientTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+    const takerBefore = await getAccount(connection, takerATA);
+    await program.methods
+      .refundFee(half, { beneficiary: {} })
+      .accounts(refundAccounts(takerATA))
+      .signers([authority, feeCollector])
+      .rpc();
+    const takerAfter = await getAccount(connection, takerATA);
+    assert.equal((takerAfter.amount - takerBefore.amount).toString(), half.toString());
+
+    // The maker's token account does not match a beneficiary rebate
+    try {
+      await program.methods
+        .refundFee(new BN(1), { beneficiary: {} })
+        .accounts(refundAccounts(makerATA))
+        .signers([authority, feeCollector])
+        .rpc();
+      assert.fail("Should have thrown OwnerMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "OwnerMismatch");
+    }
+
+    const rest = feesPaid.sub(half);
+    try {
+      await program.methods
+        .refundFee(rest.addn(1), { maker: {} })
+        .accounts(refundAccounts(makerATA))
+        .signers([authority, feeCollector])
+        .rpc();
+      assert.fail("Should have thrown FeeRefundExceedsCollected");
+    } catch (err: any) {
+      assert.include(err.message, "FeeRefundExceedsCollected");
+    }
+
+    await program.methods
+      .refundFee(rest, { maker: {} })
+      .accounts(refundAccounts(makerATA))
+      .signers([authority, feeCollector])
+      .rpc();
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.equal(escrow.feesRefunded.toString(), feesPaid.toString());
+  });
+
+  // ===========================================================================
+  // 128. MilestoneCancelled - one event per cancelled milestone, with reason
+  // ===========================================================================
+  it("128. cancel_escrow / resolve_dispute: emit MilestoneCancelled per milestone with reason", async () => {
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const milestoneCancellations = async (sig: string) => {
+      const tx = await connection.getTransaction(sig, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      return [...parser.parseLogs(tx!.meta!.logMessages!)]
+        .filter((e) => e.name === "milestoneCancelled")
+        .map((e) => e.data);
+    };
+    const milestoneAmounts = [new BN(500_000), new BN(300_000), new BN(200_000)];
+
+    // Maker cancel: milestone 0 was approved, so only 1 and 2 are cancelled
+    const cancelled = await setupEscrow({ milestoneAmounts });
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: cancelled.escrowPDA })
+      .signers([maker])
+      .rpc();
+    const cancelSig = await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: cancelled.escrowPDA,
+        mint,
+        vault: cancelled.vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc({ commitment: "confirmed" });
+    let events = await milestoneCancellations(cancelSig);
+    assert.deepEqual(events.map((e) => e.milestoneIndex), [1, 2]);
+    assert.deepEqual(events.map((e) => e.amount.toString()), ["300000", "200000"]);
+    events.forEach((e) => assert.ok(e.reason.makerCancel !== undefined));
+
+    // MakerWins ruling: every open milestone, approved or pending, is cancelled
+    const disputed = await setupEscrow({ milestoneAmounts });
+    await program.methods
+      .approveMilestone(1, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: disputed.escrowPDA })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .initiateDispute(createDescriptionHash("late delivery"))
+      .accounts({ initiator: taker.publicKey, escrowState: disputed.escrowPDA, escrowConfig: configPDA })
+      .signers([taker])
+      .rpc();
+    const resolveSig = await program.methods
+      .resolveDispute({ makerWins: {} }, null, false, false)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        escrowState: disputed.escrowPDA,
+        mint,
+        vault: disputed.vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    events = await milestoneCancellations(resolveSig);
+    assert.deepEqual(events.map((e) => e.milestoneIndex), [0, 1, 2]);
+    events.forEach((e) => assert.ok(e.reason.disputeRuling !== undefined));
+  });
+
+  // ===========================================================================
+  // 132. fee_exempt milestones - released in full, flagged in MilestoneReleased
+  // ===========================================================================
+  it("132. approve_and_release: fee-exempt milestones skip the fee when the config allows them", async () => {
+    const setAllowFeeExempt = (allow: boolean) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, allow, null, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    const milestoneAmounts = [new BN(400_000), new BN(600_000)];
+
+    try {
+      await setupEscrow({ milestoneAmounts, feeExempt: [true, false] });
+      assert.fail("Should have thrown FeeExemptionNotAllowed");
+    } catch (err: any) {
+      assert.include(err.message, "FeeExemptionNotAllowed");
+    }
+
+    await setAllowFeeExempt(true);
+    const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts, feeExempt: [true, false] });
+    await setAllowFeeExempt(false);
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.deepEqual(escrow.milestones.map((m: any) => m.feeExempt), [true, false]);
+
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const released: any[] = [];
+    for (const index of [0, 1]) {
+      const takerBefore = await getAccount(connection, takerATA);
+      const sig = await program.methods
+        .approveAndRelease(index)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc({ commitment: "confirmed" });
+      const takerAfter = await getAccount(connection, takerATA);
+      released.push((BigInt(takerAfter.amount.toString()) - BigInt(takerBefore.amount.toString())).toString());
+
+      const tx = await connection.getTransaction(sig, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const event = [...parser.parseLogs(tx!.meta!.logMessages!)].find((e) => e.name === "milestoneReleased")!;
+      assert.equal(event.data.feeExempt, index === 0);
+    }
+
+    // Exempt milestone paid in full; the other pays the 2.5% fee
+    assert.deepEqual(released, ["400000", "585000"]);
+  });
+
+  // ===========================================================================
+  // 133. certify_milestone - beneficiary approves only self_certify milestones
+  // ===========================================================================
+  it("133. certify_milestone: beneficiary approves flagged milestones, others are rejected", async () => {
+    const { escrowPDA, vault } = await setupEscrow({ selfCertify: [false, true, false] });
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.deepEqual(escrow.milestones.map((m: any) => m.selfCertify), [false, true, false]);
+
+    const certify = (index: number, signer: Keypair) =>
+      program.methods
+        .certifyMilestone(index)
+        .accounts({ beneficiary: signer.publicKey, escrowState: escrowPDA })
+        .signers([signer])
+        .rpc();
+
+    try {
+      await certify(0, taker);
+      assert.fail("Should have thrown NotSelfCertifiable");
+    } catch (err: any) {
+      assert.include(err.message, "NotSelfCertifiable");
+    }
+
+    try {
+      await certify(1, maker);
+      assert.fail("Should have thrown NotBeneficiary");
+    } catch (err: any) {
+      assert.include(err.message, "NotBeneficiary");
+    }
+
+    await certify(1, taker);
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[1].status.approved !== undefined);
+    assert.ok(escrow.milestones[0].status.pending !== undefined);
+
+    try {
+      await certify(1, taker);
+      assert.fail("Should have thrown MilestoneNotPending");
+    } catch (err: any) {
+      assert.include(err.message, "MilestoneNotPending");
+    }
+
+    // The normal release flow applies from here
+    const takerBefore = await getAccount(connection, takerATA);
+    await program.methods
+      .releaseMilestone(1, null, false)
+      .accounts({
+        payer: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    const takerAfter = await getAccount(connection, takerATA);
+    assert.equal(
+      (BigInt(takerAfter.amount.toString()) - BigInt(takerBefore.amount.toString())).toString(),
+      "292500"
+    );
+  });
+
+  // ===========================================================================
+  // 134. release_milestone auto_close - settle and close in one transaction
+  // ===========================================================================
+  it("134. release_milestone: auto_close closes the escrow only once the release completes it", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(400_000), new BN(600_000)],
+    });
+    for (const index of [0, 1]) {
+      await program.methods
+        .approveMilestone(index, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+    }
+    const releaseAccounts = {
+      payer: maker.publicKey,
+      escrowState: escrowPDA,
+      escrowConfig: configPDA,
+      mint,
+      vault,
+      beneficiaryTokenAccount: takerATA,
+      feeCollectorTokenAccount: feeCollectorATA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      maker: maker.publicKey,
+      makerTokenAccount: makerATA,
+    };
+
+    // Not terminal yet: the release goes through and the escrow stays open
+    await program.methods
+      .releaseMilestone(0, null, true)
+      .accounts(releaseAccounts)
+      .signers([maker])
+      .rpc();
+    assert.isNotNull(await connection.getAccountInfo(escrowPDA));
+
+    // Terminal, but without the maker's close accounts
+    try {
+      await program.methods
+        .releaseMilestone(1, null, true)
+        .accounts({ ...releaseAccounts, maker: null, makerTokenAccount: null })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown CloseAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "CloseAccountMissing");
+    }
+
+    const sig = await program.methods
+      .releaseMilestone(1, null, true)
+      .accounts(releaseAccounts)
+      .signers([maker])
+      .rpc({ commitment: "confirmed" });
+    assert.isNull(await connection.getAccountInfo(escrowPDA), "escrow_state closed");
+    assert.isNull(await connection.getAccountInfo(vault), "vault closed");
+
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const tx = await connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const names = [...parser.parseLogs(tx!.meta!.logMessages!)].map((e) => e.name);
+    assert.includeMembers(names, ["milestoneReleased", "escrowCompleted", "escrowClosed"]);
+  });
+
+  // ===========================================================================
+  // 135. disputes_enabled = false - escrow opts out of arbitration
+  // ===========================================================================
+  it("135. disputes_enabled: false rejects disputes and holds but keeps releases working", async () => {
+    const { escrowPDA, vault } = await setupEscrow({ disputesEnabled: false });
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.isFalse(escrow.disputesEnabled);
+
+    for (const initiator of [maker, taker]) {
+      try {
+        await program.methods
+          .initiateDispute(createDescriptionHash("no-arbitration"))
+          .accounts({
+            initiator: initiator.publicKey,
+            escrowState: escrowPDA,
+            escrowConfig: configPDA,
+          })
+          .signers([initiator])
+          .rpc();
+        assert.fail("Should have thrown DisputesDisabled");
+      } catch (err: any) {
+        assert.include(err.message, "DisputesDisabled");
+      }
+    }
+
+    try {
+      await program.methods
+        .holdEscrow()
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+        })
+        .signers([authority])
+        .rpc();
+      assert.fail("Should have thrown DisputesDisabled");
+    } catch (err: any) {
+      assert.include(err.message, "DisputesDisabled");
+    }
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    const takerBefore = await getAccount(connection, takerATA);
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({
+        payer: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    const takerAfter = await getAccount(connection, takerATA);
+    assert.equal(
+      (BigInt(takerAfter.amount.toString()) - BigInt(takerBefore.amount.toString())).toString(),
+      "390000"
+    );
+  });
+
+  // ===========================================================================
+  // 136. max_open_escrows_per_maker - cap counted on the MakerProfile PDA
+  // ===========================================================================
+  it("136. max_open_escrows_per_maker: caps open escrows and frees slots on terminal status", async () => {
+    const setCap = (cap: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, cap, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    const [profilePDA] = findMakerProfilePDA(maker.publicKey);
+    const openEscrows = async () =>
+      (await program.account.makerProfile.fetch(profilePDA)).openEscrows;
+
+    await setCap(2);
+    try {
+      const baseline = await openEscrows();
+      const cancelled = await setupEscrow();
+      const released = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+      assert.isTrue((await program.account.escrowState.fetch(cancelled.escrowPDA)).countedOpen);
+      assert.equal(await openEscrows(), baseline + 2);
+
+      try {
+        await setupEscrow();
+        assert.fail("Should have thrown TooManyOpenEscrows");
+      } catch (err: any) {
+        assert.include(err.message, "TooManyOpenEscrows");
+      }
+
+      // Cancel: the counted escrow can't turn terminal without its profile
+      const cancelAccounts = {
+        maker: maker.publicKey,
+        escrowState: cancelled.escrowPDA,
+        mint,
+        vault: cancelled.vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      };
+      try {
+        await program.methods
+          .cancelEscrow(null)
+          .accounts({ ...cancelAccounts, makerProfile: null })
+          .signers([maker])
+          .rpc();
+        assert.fail("Should have thrown MakerProfileMissing");
+      } catch (err: any) {
+        assert.include(err.message, "MakerProfileMissing");
+      }
+      await program.methods
+        .cancelEscrow(null)
+        .accounts({ ...cancelAccounts, makerProfile: profilePDA })
+        .signers([maker])
+        .rpc();
+      assert.equal(await openEscrows(), baseline + 1);
+
+      // Release completing the escrow
+      await program.methods
+        .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: released.escrowPDA })
+        .signers([maker])
+        .rpc();
+      await program.methods
+        .releaseMilestone(0, null, false)
+        .accounts({
+          payer: maker.publicKey,
+          escrowState: released.escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault: released.vault,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          makerProfile: profilePDA,
+        })
+        .signers([maker])
+        .rpc();
+      assert.equal(await openEscrows(), baseline);
+
+      // Resolve
+      const disputed = await setupEscrow();
+      assert.equal(await openEscrows(), baseline + 1);
+      await program.methods
+        .initiateDispute(createDescriptionHash("capped escrow dispute"))
+        .accounts({
+          initiator: taker.publicKey,
+          escrowState: disputed.escrowPDA,
+          escrowConfig: configPDA,
+        })
+        .signers([taker])
+        .rpc();
+      await program.methods
+        .resolveDispute({ makerWins: {} }, null, false, false)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          escrowState: disputed.escrowPDA,
+          mint,
+          vault: disputed.vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          makerProfile: profilePDA,
+        })
+        .signers([authority])
+        .rpc();
+      assert.equal(await openEscrows(), baseline);
+      assert.isFalse((await program.account.escrowState.fetch(disputed.escrowPDA)).countedOpen);
+    } finally {
+      await setCap(0);
+    }
+  });
+
+  // ===========================================================================
+  // 138. create_escrow - adversarial milestone sums are rejected cleanly
+  // ===========================================================================
+  it("138. create_escrow: milestone sums past the amount or u64 are rejected before funding", async () => {
+    const U64_MAX = new BN("18446744073709551615");
+    for (const milestoneAmounts of [
+      [U64_MAX, new BN(1)],
+      [new BN(1), U64_MAX],
+      [TOTAL_AMOUNT, new BN(1)],
+    ]) {
+      try {
+        await setupEscrow({ milestoneAmounts });
+        assert.fail("Should have thrown MilestoneAmountMismatch");
+      } catch (err: any) {
+        assert.include(err.message, "MilestoneAmountMismatch");
+      }
+    }
+  });
+
+  // ===========================================================================
+  // 139. dispute_initiators - only the roles chosen at creation may open disputes
+  // ===========================================================================
+  it("139. dispute_initiators: each role mask admits exactly its roles", async () => {
+    for (const mask of [0, DISPUTE_INITIATORS_ALL + 1]) {
+      try {
+        await setupEscrow({ disputeInitiators: mask });
+        assert.fail("Should have thrown InvalidDisputeInitiators");
+      } catch (err: any) {
+        assert.include(err.message, "InvalidDisputeInitiators");
+      }
+    }
+
+    const roles: [Keypair, number][] = [
+      [maker, DISPUTE_INITIATOR_MAKER],
+      [taker, DISPUTE_INITIATOR_TAKER],
+      [stranger, DISPUTE_INITIATOR_BENEFICIARY],
+    ];
+    for (let mask = 1; mask <= DISPUTE_INITIATORS_ALL; mask++) {
+      const { escrowPDA } = await setupEscrow({
+        initialBeneficiary: stranger.publicKey,
+        disputeInitiators: mask,
+      });
+      const escrow = await program.account.escrowState.fetch(escrowPDA);
+      assert.equal(escrow.disputeInitiators, mask);
+
+      for (const [party, bit] of roles) {
+        const dispute = program.methods
+          .initiateDispute(createDescriptionHash(`mask-${mask}`))
+          .accounts({
+            initiator: party.publicKey,
+            escrowState: escrowPDA,
+            escrowConfig: configPDA,
+          })
+          .signers([party]);
+        if (mask & bit) {
+          // Simulate so the remaining roles can still be checked on this escrow
+          await dispute.simulate();
+        } else {
+          try {
+            await dispute.rpc();
+            assert.fail("Should have thrown DisputeInitiatorNotAllowed");
+          } catch (err: any) {
+            assert.include(err.message, "DisputeInitiatorNotAllowed");
+          }
+        }
+      }
+    }
+  });
+
+  // ===========================================================================
+  // 140. resolve_dispute loser_pays_fee - fee charged to the losing party
+  // ===========================================================================
+  it("140. resolve_dispute: loser_pays_fee charges the loser's delegated account, not the escrow", async () => {
+    const { escrowPDA, vault } = await setupEscrow();
+    await program.methods
+      .initiateDispute(createDescriptionHash("loser pays"))
+      .accounts({
+        initiator: taker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+      })
+      .signers([taker])
+      .rpc();
+
+    const resolve = (resolution: object) =>
+      program.methods
+        .resolveDispute(resolution, null, false, true)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          loserTokenAccount: makerATA,
+        })
+        .signers([authority])
+        .rpc();
+
+    try {
+      await resolve({ split: { makerBps: 5_000 } });
+      assert.fail("Should have thrown NoDisputeLoser");
+    } catch (err: any) {
+      assert.include(err.message, "NoDisputeLoser");
+    }
+    try {
+      await resolve({ takerWins: {} });
+      assert.fail("Should have thrown FeeNotPreAuthorized");
+    } catch (err: any) {
+      assert.include(err.message, "FeeNotPreAuthorized");
+    }
+
+    // 2.5% of the 1_000_000 remaining, delegated by the losing maker
+    await approve(connection, maker, makerATA, escrowPDA, maker, 25_000);
+    const makerBefore = await getAccount(connection, makerATA);
+    const takerBefore = await getAccount(connection, takerATA);
+    const feeBefore = await getAccount(connection, feeCollectorATA);
+    await resolve({ takerWins: {} });
+
+    const delta = async (ata: PublicKey, before: { amount: bigint }) =>
+      ((await getAccount(connection, ata)).amount - before.amount).toString();
+    assert.equal(await delta(takerATA, takerBefore), "1000000", "beneficiary paid in full");
+    assert.equal(await delta(makerATA, makerBefore), "-25000", "loser pays the fee");
+    assert.equal(await delta(feeCollectorATA, feeBefore), "25000");
+    assert.equal((await getAccount(connection, vault)).amount.toString(), "0");
+  });
+
+  // ===========================================================================
+  // 142. record_approval - pre-signed renewal is bound to terms and nonce
+  // ===========================================================================
+  it("142. renew_escrow: beneficiary approval replaces the co-signature once, at the recorded nonce", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      amount: TOTAL_AMOUNT,
+    });
+    const [approvalPDA] = findApprovalPDA(escrowPDA, taker.publicKey);
+    const topUp = new BN(500_000);
+    const newMilestones = makeMilestones([topUp], ["approved-cycle"]);
+    const newExpiresAt = new BN(Math.floor(Date.now() / 1000) + 7200);
+    const approvalExpiry = new BN(Math.floor(Date.now() / 1000) + 3600);
+
+    const recordApproval = async (nonce: BN) =>
+      program.methods
+        .recordApproval(
+          { renewEscrow: {} },
+          renewActionHash(escrowPDA, nonce, topUp, newMilestones, newExpiresAt),
+          approvalExpiry
+        )
+        .accounts({
+          approver: taker.publicKey,
+          escrowState: escrowPDA,
+          approval: approvalPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([taker])
+        .rpc();
+    const closeApproval = (approver: Keypair) =>
+      program.methods
+        .closeApproval()
+        .accounts({ approver: approver.publicKey, approval: approvalPDA })
+        .signers([approver])
+        .rpc();
+    const renewWithApproval = (amount: BN, approval: PublicKey | null = approvalPDA) =>
+      program.methods
+        .renewEscrow(amount, makeMilestones([amount], ["approved-cycle"]), newExpiresAt)
+        .accounts({
+          maker: maker.publicKey,
+          beneficiary: null,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          approval,
+        })
+        .signers([maker])
+        .rpc();
+
+    // Recorded while Active; completing the escrow bumps the nonce and voids it
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    await recordApproval(escrow.nonce);
+    await program.methods
+      .approveAndRelease(0)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    try {
+      await renewWithApproval(topUp);
+      assert.fail("Should have thrown ApprovalMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "ApprovalMismatch");
+    }
+
+    // Only the approver can revoke it
+    try {
+      await closeApproval(maker);
+      assert.fail("Should have rejected a non-approver close");
+    } catch (err: any) {
+      assert.include(err.message, "ConstraintSeeds");
+    }
+    await closeApproval(taker);
+    assert.isNull(await connection.getAccountInfo(approvalPDA));
+
+    try {
+      await renewWithApproval(topUp, null);
+      assert.fail("Should have thrown CoSignerMissing");
+    } catch (err: any) {
+      assert.include(err.message, "CoSignerMissing");
+    }
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    await recordApproval(escrow.nonce);
+    const approval = await program.account.approval.fetch(approvalPDA);
+    assert.ok(approval.nonce.eq(escrow.nonce));
+    assert.ok(approval.approver.equals(taker.publicKey));
+
+    // Terms other than the approved ones are rejected
+    try {
+      await renewWithApproval(topUp.addn(1));
+      assert.fail("Should have thrown ApprovalMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "ApprovalMismatch");
+    }
+
+    await renewWithApproval(topUp);
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.active !== undefined);
+    assert.equal(escrow.renewalCount, 1);
+    assert.ok(escrow.amount.eq(topUp));
+
+    // The renewal bumped the nonce, so the same approval cannot be replayed
+    try {
+      await renewWithApproval(topUp);
+      assert.fail("Should have thrown ApprovalMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "ApprovalMismatch");
+    }
+  });
+
+  // ===========================================================================
+  // 144. recover_receipt - maker + last synced beneficiary void a stuck receipt
+  // ===========================================================================
+  it("144. recover_receipt: only maker + synced beneficiary can void the receipt and reassign the claim", async () => {
+    const { escrowPDA } = await setupEscrow();
+    const [receiptMint] = findReceiptMintPDA(escrowPDA);
+    const [metadata] = findMetadataPDA(receiptMint);
+    const [masterEdition] = findMasterEditionPDA(receiptMint);
+    const takerReceiptAta = getAssociatedTokenAddressSync(receiptMint, taker.publicKey);
+
+    await program.methods
+      .mintReceipt()
+      .accounts({
+        beneficiary: taker.publicKey,
+        payer: taker.publicKey,
+        escrowState: escrowPDA,
+        receiptMint,
+        beneficiaryReceiptAta: takerReceiptAta,
+        metadata,
+        masterEdition,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([taker])
+      .rpc();
+
+    // The NFT moves to the stranger, who becomes the synced beneficiary
+    const strangerReceiptAta = await createTokenAccount(connection, authority, receiptMint, stranger.publicKey);
+    await anchor.web3.sendAndConfirmTransaction(
+      connection,
+      new anchor.web3.Transaction().add(
+        createTransferInstruction(takerReceiptAta, strangerReceiptAta, taker.publicKey, 1)
+      ),
+      [taker]
+    );
+    const sync = () =>
+      program.methods
+        .syncBeneficiary()
+        .accounts({
+          payer: authority.publicKey,
+          escrowState: escrowPDA,
+          receiptMint,
+          receiptTokenAccount: strangerReceiptAta,
+        })
+        .signers([authority])
+        .rpc();
+    await sync();
+
+    const recover = (
+      makerKp: Keypair,
+      coSigners: { beneficiary?: Keypair; authority?: Keypair },
+      newBeneficiary: PublicKey = taker.publicKey
+    ) =>
+      program.methods
+        .recoverReceipt(newBeneficiary)
+        .accounts({
+          maker: makerKp.publicKey,
+          beneficiary: coSigners.beneficiary?.publicKey ?? null,
+          authority: coSigners.authority?.publicKey ?? null,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+        })
+        .signers([makerKp, ...Object.values(coSigners)])
+        .rpc();
+    const expectFailure = async (attempt: Promise<unknown>, code: string) => {
+      try {
+        await attempt;
+        assert.fail(`Should have thrown ${code}`);
+      } catch (err: any) {
+        assert.include(err.message, code);
+      }
+    };
+
+    await expectFailure(recover(stranger, { beneficiary: stranger }), "NotMaker");
+    await expectFailure(recover(maker, { beneficiary: taker }), "NotBeneficiary");
+    await expectFailure(recover(maker, {}), "RecoveryCoSignerMissing");
+    await expectFailure(recover(maker, { authority: stranger }), "NotAuthority");
+    // Authority fallback only opens RECEIPT_RECOVERY_DELAY past expiry
+    await expectFailure(recover(maker, { authority }), "RecoveryCoSignerMissing");
+    await expectFailure(recover(maker, { beneficiary: stranger }, maker.publicKey), "InvalidBeneficiary");
+
+    await recover(maker, { beneficiary: stranger });
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.isNull(escrow.receiptMint);
+    assert.ok(escrow.voidedReceiptMint.equals(receiptMint));
+    assert.ok(escrow.beneficiary.equals(taker.publicKey));
+
+    // The dangling NFT can never sync back, and there is nothing left to recover
+    await expectFailure(sync(), "ReceiptVoided");
+    await expectFailure(recover(maker, { beneficiary: taker }), "NoReceipt");
+
+    // The claim is no longer receipt-gated
+    await program.methods
+      .transferClaim(null)
+      .accounts({
+        beneficiary: taker.publicKey,
+        escrowState: escrowPDA,
+        newBeneficiary: stranger.publicKey,
+      })
+      .signers([taker])
+      .rpc();
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.beneficiary.equals(stranger.publicKey));
+  });
+
+  // ===========================================================================
+  // 146. batch_escrow_progress - one view call summarises several escrows
+  // ===========================================================================
+  it("146. batch_escrow_progress: returns per-escrow summaries in order and bounds the batch", async () => {
+    const fresh = await setupEscrow();
+    const partial = await setupEscrow();
+    await program.methods
+      .approveAndRelease(0)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: partial.escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault: partial.vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    const view = (accounts: PublicKey[]) =>
+      program.methods
+        .batchEscrowProgress()
+        .accounts({})
+        .remainingAccounts(accounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+        .view();
+
+    const [first, second] = await view([fresh.escrowPDA, partial.escrowPDA]);
+    assert.ok(first.escrow.equals(fresh.escrowPDA));
+    assert.ok(first.status.active !== undefined);
+    assert.equal(first.releasedBps, 0);
+    assert.equal(first.settledMilestones, 0);
+    assert.equal(first.milestoneCount, 3);
+    assert.ok(second.escrow.equals(partial.escrowPDA));
+    assert.equal(second.releasedBps, 4_000, "400_000 of 1_000_000 released");
+    assert.equal(second.settledMilestones, 1);
+
+    // Only escrow accounts are accepted
+    try {
+      await view([fresh.escrowPDA, mint]);
+      assert.fail("Should have rejected a non-escrow account");
+    } catch (err: any) {
+      assert.match(err.message, /AccountOwnedByWrongProgram|AccountDiscriminatorMismatch/);
+    }
+
+    try {
+      await view(new Array(MAX_BATCH_PROGRESS_ACCOUNTS + 1).fill(fresh.escrowPDA));
+      assert.fail("Should have thrown TooManyAccounts");
+    } catch (err: any) {
+      assert.include(err.message, "TooManyAccounts");
+    }
+  });
+
+  // ===========================================================================
+  // 147. flat_fee - releases pay max(bps fee, flat fee) for the configured mint
+  // ===========================================================================
+  it("147. flat_fee: snapshotted per mint and charged when it exceeds the bps fee", async () => {
+    const setFlatFee = (flatFee: BN, flatFeeMint: PublicKey) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, flatFee, flatFeeMint, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await setFlatFee(new BN(50_000), mint);
+    const { escrowPDA, vault } = await setupEscrow();
+    // The fee floor is fixed at creation; later config changes don't touch it
+    await setFlatFee(new BN(0), PublicKey.default);
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.flatFeeAtCreation.eqn(50_000));
+
+    const takerBefore = await getAccount(connection, takerATA);
+    const feeBefore = await getAccount(connection, feeCollectorATA);
+    await program.methods
+      .approveAndRelease(0)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    // 2.5% of 400_000 is 10_000, below the 50_000 floor
+    const takerAfter = await getAccount(connection, takerATA);
+    const feeAfter = await getAccount(connection, feeCollectorATA);
+    assert.equal((feeAfter.amount - feeBefore.amount).toString(), "50000");
+    assert.equal((takerAfter.amount - takerBefore.amount).toString(), "350000");
+  });
+
+  // ===========================================================================
+  // 148. archive_and_close - compact record survives the closed escrow
+  // ===========================================================================
+  it("148. archive_and_close: archive mirrors the final escrow state and is held for the retention period", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      amount: TOTAL_AMOUNT,
+    });
+    const [archivePDA] = findEscrowArchivePDA(escrowPDA);
+    const archive = () =>
+      program.methods
+        .archiveAndClose()
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowArchive: archivePDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+
+    try {
+      await archive();
+      assert.fail("Should have thrown EscrowNotTerminal");
+    } catch (err: any) {
+      assert.include(err.message, "EscrowNotTerminal");
+    }
+
+    await program.methods
+      .approveAndRelease(0)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    const before = await program.account.escrowState.fetch(escrowPDA);
+    await archive();
+
+    assert.isNull(await connection.getAccountInfo(escrowPDA), "EscrowState is closed");
+    assert.isNull(await connection.getAccountInfo(vault), "Vault is closed");
+
+    const record = await program.account.escrowArchive.fetch(archivePDA);
+    assert.ok(record.escrow.equals(escrowPDA));
+    assert.ok(record.maker.equals(before.maker));
+    assert.ok(record.taker.equals(before.taker));
+    assert.ok(record.beneficiary.equals(before.beneficiary));
+    assert.ok(record.mint.equals(before.mint));
+    assert.ok(record.amount.eq(before.amount));
+    assert.ok(record.releasedAmount.eq(before.releasedAmount));
+    assert.ok(record.refundedAmount.eq(before.refundedAmount));
+    assert.ok(record.feesPaid.eq(before.feesPaid));
+    assert.deepEqual(record.status, before.status);
+    assert.ok(record.createdAt.eq(before.createdAt));
+    assert.ok(record.closedAt.gte(before.createdAt));
+
+    const closeArchive = (kp: Keypair) =>
+      program.methods
+        .closeArchive()
+        .accounts({ maker: kp.publicKey, escrowArchive: archivePDA })
+        .signers([kp])
+        .rpc();
+    try {
+      await closeArchive(stranger);
+      assert.fail("Should have thrown NotMaker");
+    } catch (err: any) {
+      assert.include(err.message, "NotMaker");
+    }
+    try {
+      await closeArchive(maker);
+      assert.fail("Should have thrown ArchiveRetentionActive");
+    } catch (err: any) {
+      assert.include(err.message, "ArchiveRetentionActive");
+    }
+  });
+
+  // ===========================================================================
+  // 150. settlement paths - the vault is never accepted as a payout destination
+  // ===========================================================================
+  it("150. release / resolve / claim_expired: reject the vault as any destination (DestinationIsVault)", async () => {
+    const { escrowPDA, vault } = await setupEscrow();
+    const destinations = {
+      makerTokenAccount: makerATA,
+      beneficiaryTokenAccount: takerATA,
+      feeCollectorTokenAccount: feeCollectorATA,
+    };
+    const withVaultAs = (key: keyof typeof destinations) => ({ ...destinations, [key]: vault });
+    const common = { escrowState: escrowPDA, escrowConfig: configPDA, mint, vault, tokenProgram: TOKEN_PROGRAM_ID };
+
+    const attempts: [string, () => Promise<string>][] = [];
+    for (const key of Object.keys(destinations) as (keyof typeof destinations)[]) {
+      attempts.push([
+        `release_milestone ${key}`,
+        () =>
+          program.methods
+            .releaseMilestone(0, null, false)
+            .accounts({ payer: maker.publicKey, maker: null, ...common, ...withVaultAs(key) })
+            .signers([maker])
+            .rpc(),
+      ]);
+      attempts.push([
+        `resolve_dispute ${key}`,
+        () =>
+          program.methods
+            .resolveDispute({ takerWins: {} }, null, false, false)
+            .accounts({ authority: authority.publicKey, ...common, ...withVaultAs(key) })
+            .signers([authority])
+            .rpc(),
+      ]);
+      attempts.push([
+        `claim_expired ${key}`,
+        () =>
+          program.methods
+            .claimExpired()
+            .accounts({ payer: stranger.publicKey, ...common, ...withVaultAs(key) })
+            .signers([stranger])
+            .rpc(),
+      ]);
+    }
+
+    for (const [label, attempt] of attempts) {
+      try {
+        await attempt();
+        assert.fail(`${label}: should have thrown DestinationIsVault`);
+      } catch (err: any) {
+        assert.include(err.message, "DestinationIsVault", label);
+      }
+    }
+    const vaultAcct = await getAccount(connection, vault);
+    assert.equal(vaultAcct.amount.toString(), TOTAL_AMOUNT.toString());
+  });
+
+  // ===========================================================================
+  // 151. fee_on_top - the maker funds the fee, the beneficiary gets face value
+  // ===========================================================================
+  it("151. fee_on_top: vault is funded with amount + fees and releases pay the full milestone amount", async () => {
+    const { escrowPDA, vault } = await setupEscrow({ feeOnTop: true });
+
+    // 2.5% of 400_000 / 300_000 / 300_000 = 10_000 + 7_500 + 7_500
+    const vaultAcct = await getAccount(connection, vault);
+    assert.equal(vaultAcct.amount.toString(), "1025000");
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.isTrue(escrow.feeOnTop);
+    assert.ok(escrow.amount.eqn(1_025_000));
+    assert.ok(escrow.milestones[0].amount.eqn(410_000));
+    assert.ok(escrow.milestones[0].feeReserved.eqn(10_000));
+
+    const takerBefore = await getAccount(connection, takerATA);
+    const feeBefore = await getAccount(connection, feeCollectorATA);
+    await program.methods
+      .approveAndRelease(0)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    const takerAfter = await getAccount(connection, takerATA);
+    const feeAfter = await getAccount(connection, feeCollectorATA);
+    assert.equal((takerAfter.amount - takerBefore.amount).toString(), "400000");
+    assert.equal((feeAfter.amount - feeBefore.amount).toString(), "10000");
+  });
+
+  // ===========================================================================
+  // 152. get_config - versioned view of the effective config
+  // ===========================================================================
+  it("152. get_config: returns the stored settings plus program-wide limits", async () => {
+    const view: any = await program.methods
+      .getConfig()
+      .accounts({ escrowConfig: configPDA })
+      .view();
+    const config = await program.account.escrowConfig.fetch(configPDA);
+
+    assert.equal(view.version, CONFIG_VIEW_VERSION);
+    assert.ok(view.authority.equals(config.authority));
+    assert.equal(view.feeBps, config.feeBps);
+    assert.ok(view.feeCollector.equals(config.feeCollector));
+    assert.ok(view.disputeTimeout.eq(config.disputeTimeout));
+    assert.ok(view.flatFee.eq(config.flatFee));
+    assert.equal(view.receiptsEnabled, config.receiptsEnabled);
+    assert.deepEqual(view.remainderPolicy, config.remainderPolicy);
+    // Derived limits aren't stored on the account at all
+    assert.equal(view.minExpirationDuration.toNumber(), 3600);
+    assert.equal(view.maxMilestones, 5);
+  });
+
+  // ===========================================================================
+  // 153. close_escrow - receipt metadata is sealed before the PDA stops signing
+  // ===========================================================================
+  it("153. close_escrow: seals a minted receipt's metadata and needs nothing extra without one", async () => {
+    const complete = async (escrowPDA: PublicKey, vault: PublicKey, remaining: PublicKey[] = []) =>
+      program.methods
+        .approveAndRelease(0)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(remaining.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false })))
+        .signers([maker])
+        .rpc();
+    const close = (escrowPDA: PublicKey, vault: PublicKey, receiptAccounts: object = {}) =>
+      program.methods
+        .closeEscrow()
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          ...receiptAccounts,
+        })
+        .signers([maker])
+        .rpc();
+
+    // Without a receipt the close is unchanged
+    const plain = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+    await complete(plain.escrowPDA, plain.vault);
+    await close(plain.escrowPDA, plain.vault);
+    assert.isNull(await connection.getAccountInfo(plain.escrowPDA));
+
+    const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+    const [receiptMint] = findReceiptMintPDA(escrowPDA);
+    const [metadata] = findMetadataPDA(receiptMint);
+    const beneficiaryReceiptAta = getAssociatedTokenAddressSync(receiptMint, taker.publicKey);
+    await program.methods
+      .mintReceipt()
+      .accounts({
+        beneficiary: taker.publicKey,
+        payer: taker.publicKey,
+        escrowState: escrowPDA,
+        receiptMint,
+        beneficiaryReceiptAta,
+        metadata,
+        masterEdition: findMasterEditionPDA(receiptMint)[0],
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([taker])
+      .rpc();
+    assert.isTrue((await program.account.escrowState.fetch(escrowPDA)).receiptMetadataMutable);
+    await complete(escrowPDA, vault, [beneficiaryReceiptAta]);
+
+    try {
+      await close(escrowPDA, vault);
+      assert.fail("Should have thrown ReceiptMetadataMissing");
+    } catch (err: any) {
+      assert.include(err.message, "ReceiptMetadataMissing");
+    }
+    try {
+      await close(escrowPDA, vault, {
+        receiptMetadata: findMetadataPDA(mint)[0],
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      });
+      assert.fail("Should have thrown ReceiptMetadataMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "ReceiptMetadataMismatch");
+    }
+
+    await close(escrowPDA, vault, { receiptMetadata: metadata, tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID });
+    // Fixed-width Metaplex layout: primary_sale_happened at 322, is_mutable at 323
+    const sealed = await connection.getAccountInfo(metadata);
+    assert.equal(sealed!.data[322], 1, "primary sale marks the receipt settled");
+    assert.equal(sealed!.data[323], 0, "metadata can never be updated again");
+  });
+
+  // ===========================================================================
+  // 154. cancel_fee_bps - cancelling an accepted escrow withholds a fee
+  // ===========================================================================
+  it("154. cancel_escrow: withholds the snapshotted cancellation fee for its recipient", async () => {
+    const setCancelFee = (bps: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, bps, { feeCollector: {} }, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await setCancelFee(500);
+    const { escrowPDA, vault } = await setupEscrow();
+    // Later config changes don't reach existing escrows
+    await setCancelFee(0);
+    assert.equal((await program.account.escrowState.fetch(escrowPDA)).cancelFeeBpsAtCreation, 500);
+    assert.equal((await program.account.escrowState.fetch(escrowPDA)).cancelRoutesAtCreation[0].feeBps, 500);
+
+    const cancel = (cancelFeeTokenAccount: PublicKey | null) =>
+      program.methods
+        .cancelEscrow(null)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          escrowConfig: configPDA,
+          cancelFeeTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc();
+
+    try {
+      await cancel(null);
+      assert.fail("Should have thrown CancelFeeAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "CancelFeeAccountMissing");
+    }
+    try {
+      await cancel(takerATA);
+      assert.fail("Should have thrown OwnerMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "OwnerMismatch");
+    }
+
+    const makerBefore = await getAccount(connection, makerATA);
+    const feeBefore = await getAccount(connection, feeCollectorATA);
+    await cancel(feeCollectorATA);
+    const makerAfter = await getAccount(connection, makerATA);
+    const feeAfter = await getAccount(connection, feeCollectorATA);
+
+    // 5% of the 1_000_000 refund
+    assert.equal((makerAfter.amount - makerBefore.amount).toString(), "950000");
+    assert.equal((feeAfter.amount - feeBefore.amount).toString(), "50000");
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.cancelled !== undefined);
+    assert.ok(escrow.refundedAmount.eq(TOTAL_AMOUNT));
+  });
+
+  // ===========================================================================
+  // 155. receipts_enabled - kill switch for new receipt NFTs
+  // ===========================================================================
+  it("155. mint_receipt: fails with ReceiptsDisabled while the switch is off; existing receipts keep syncing", async () => {
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const setReceiptsEnabled = async (enabled: boolean) => {
+      const sig = await program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, enabled, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+      const tx = await connection.getTransaction(sig, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      return [...parser.parseLogs(tx!.meta!.logMessages!)].filter((e) => e.name === "receiptMintingToggled");
+    };
+    const mintReceipt = (escrowPDA: PublicKey) => {
+      const [receiptMint] = findReceiptMintPDA(escrowPDA);
+      const [metadata] = findMetadataPDA(receiptMint);
+      const [masterEdition] = findMasterEditionPDA(receiptMint);
+      return program.methods
+        .mintReceipt()
+        .accounts({
+          beneficiary: taker.publicKey,
+          payer: taker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          receiptMint,
+          beneficiaryReceiptAta: getAssociatedTokenAddressSync(receiptMint, taker.publicKey),
+          metadata,
+          masterEdition,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([taker])
+        .rpc();
+    };
+
+    assert.isTrue((await program.account.escrowConfig.fetch(configPDA)).receiptsEnabled, "Enabled by default");
+    const minted = await setupEscrow();
+    await mintReceipt(minted.escrowPDA);
+
+    const disabled = await setReceiptsEnabled(false);
+    assert.equal(disabled.length, 1);
+    assert.isFalse(disabled[0].data.enabled);
+    assert.ok(disabled[0].data.authority.equals(authority.publicKey));
+    // Re-sending the current value isn't a toggle
+    assert.equal((await setReceiptsEnabled(false)).length, 0);
+
+    try {
+      const blocked = await setupEscrow();
+      try {
+        await mintReceipt(blocked.escrowPDA);
+        assert.fail("Should have thrown ReceiptsDisabled");
+      } catch (err: any) {
+        assert.include(err.message, "ReceiptsDisabled");
+      }
+
+      // The receipt minted before the switch still transfers and syncs
+      const [receiptMint] = findReceiptMintPDA(minted.escrowPDA);
+      const strangerReceiptAta = await createTokenAccount(connection, authority, receiptMint, stranger.publicKey);
+      await anchor.web3.sendAndConfirmTransaction(
+        connection,
+        new anchor.web3.Transaction().add(
+          createTransferInstruction(
+            getAssociatedTokenAddressSync(receiptMint, taker.publicKey),
+            strangerReceiptAta,
+            taker.publicKey,
+            1
+          )
+        ),
+        [taker]
+      );
+      await program.methods
+        .syncBeneficiary()
+        .accounts({
+          payer: authority.publicKey,
+          escrowState: minted.escrowPDA,
+          receiptMint,
+          receiptTokenAccount: strangerReceiptAta,
+        })
+        .signers([authority])
+        .rpc();
+      const escrow = await program.account.escrowState.fetch(minted.escrowPDA);
+      assert.ok(escrow.beneficiary.equals(stranger.publicKey));
+    } finally {
+      const enabled = await setReceiptsEnabled(true);
+      assert.equal(enabled.length, 1);
+      assert.isTrue(enabled[0].data.enabled);
+    }
+
+    const reenabled = await setupEscrow();
+    await mintReceipt(reenabled.escrowPDA);
+    assert.ok((await program.account.escrowState.fetch(reenabled.escrowPDA)).receiptMint?.equals(findReceiptMintPDA(reenabled.escrowPDA)[0]));
+  });
+
+  // ===========================================================================
+  // 156. agreement_hash - taker acknowledgment gates releases
+  // ===========================================================================
+  it("156. acknowledge_agreement: releases wait for the taker to acknowledge the attached agreement", async () => {
+    const agreementHash = createDescriptionHash("master-services-agreement-v3");
+
+    try {
+      await setupEscrow({ agreementHash: new Array(32).fill(0) });
+      assert.fail("Should have thrown InvalidAgreementHash");
+    } catch (err: any) {
+      assert.include(err.message, "InvalidAgreementHash");
+    }
+
+    const { escrowPDA, vault } = await setupEscrow({ agreementHash });
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.deepEqual(escrow.agreementHash, agreementHash);
+    assert.isFalse(escrow.agreementAcknowledged);
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    const release = () =>
+      program.methods
+        .releaseMilestone(0, null, false)
+        .accounts({
+          payer: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc();
+    try {
+      await release();
+      assert.fail("Should have thrown AgreementNotAcknowledged");
+    } catch (err: any) {
+      assert.include(err.message, "AgreementNotAcknowledged");
+    }
+
+    const acknowledge = (signer: Keypair, hash: number[]) =>
+      program.methods
+        .acknowledgeAgreement(hash)
+        .accounts({ taker: signer.publicKey, escrowState: escrowPDA })
+        .signers([signer])
+        .rpc({ commitment: "confirmed" });
+    try {
+      await acknowledge(maker, agreementHash);
+      assert.fail("Should have thrown NotEscrowParty");
+    } catch (err: any) {
+      assert.include(err.message, "NotEscrowParty");
+    }
+    try {
+      await acknowledge(taker, createDescriptionHash("some-other-document"));
+      assert.fail("Should have thrown AgreementHashMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "AgreementHashMismatch");
+    }
+
+    const sig = await acknowledge(taker, agreementHash);
+    const tx = await connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const acknowledged = [...parser.parseLogs(tx!.meta!.logMessages!)].find((e) => e.name === "agreementAcknowledged");
+    assert.ok(acknowledged, "AgreementAcknowledged emitted");
+    assert.ok(acknowledged!.data.taker.equals(taker.publicKey));
+    assert.deepEqual(acknowledged!.data.agreementHash, agreementHash);
+
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.isTrue(escrow.agreementAcknowledged);
+    assert.ok(escrow.agreementAcknowledgedAt.eq(acknowledged!.data.acknowledgedAt));
+
+    try {
+      await acknowledge(taker, agreementHash);
+      assert.fail("Should have thrown AgreementAlreadyAcknowledged");
+    } catch (err: any) {
+      assert.include(err.message, "AgreementAlreadyAcknowledged");
+    }
+
+    await release();
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.milestones[0].status.released !== undefined);
+  });
+
+  // ===========================================================================
+  // 157. fee_free_below - small milestone releases pay no fee at all
+  // ===========================================================================
+  it("157. fee_free_below: releases under the snapshotted threshold are fee-free, at the threshold they pay", async () => {
+    const setFeeFreeBelow = (threshold: BN, flatFeeMint: PublicKey) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, flatFeeMint, null, null, null, threshold, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await setFeeFreeBelow(new BN(300_000), mint);
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(299_999), new BN(300_000), new BN(400_001)],
+    });
+    // Snapshotted at creation like the other fee settings
+    await setFeeFreeBelow(new BN(0), PublicKey.default);
+    assert.ok((await program.account.escrowState.fetch(escrowPDA)).feeFreeBelowAtCreation.eqn(300_000));
+
+    const approveAndRelease = async (index: number) => {
+      const takerBefore = await getAccount(connection, takerATA);
+      const feeBefore = await getAccount(connection, feeCollectorATA);
+      await program.methods
+        .approveAndRelease(index)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc();
+      const takerAfter = await getAccount(connection, takerATA);
+      const feeAfter = await getAccount(connection, feeCollectorATA);
+      return {
+        net: (takerAfter.amount - takerBefore.amount).toString(),
+        fee: (feeAfter.amount - feeBefore.amount).toString(),
+      };
+    };
+
+    // One unit below the threshold: paid in full
+    assert.deepEqual(await approveAndRelease(0), { net: "299999", fee: "0" });
+    // Exactly at the threshold: the regular 2.5% applies
+    assert.deepEqual(await approveAndRelease(1), { net: "292500", fee: "7500" });
+  });
+
+  // ===========================================================================
+  // 158. schedule_crank - queued permissionless cranks for automation providers
+  // ===========================================================================
+  it("158. scheduled cranks: a due release fires through execute_scheduled_crank, re-arms, and is cancellable", async () => {
+    const half = TOTAL_AMOUNT.divn(2);
+    const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [half, TOTAL_AMOUNT.sub(half)] });
+    for (const index of [0, 1]) {
+      await program.methods
+        .approveMilestone(index, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+    }
+
+    // Release milestone 0 right away, then each next one an hour after the last
+    const kind = { releaseMilestone: { index: 0, interval: new BN(3600) } };
+    const [schedulePDA] = findCrankSchedulePDA(escrowPDA, stranger.publicKey, kind);
+    await program.methods
+      .scheduleCrank(kind, new BN(0))
+      .accounts({
+        creator: stranger.publicKey,
+        escrowState: escrowPDA,
+        crankSchedule: schedulePDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([stranger])
+      .rpc();
+
+    // The executor forwards the release's own accounts, with itself as payer
+    const execute = async (index: number) => {
+      const target = await program.methods
+        .releaseMilestone(index, null, false)
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .instruction();
+      return program.methods
+        .executeScheduledCrank()
+        .accounts({ executor: stranger.publicKey, crankSchedule: schedulePDA })
+        .remainingAccounts(target.keys)
+        .signers([stranger])
+        .rpc();
+    };
+
+    const takerBefore = await getAccount(connection, takerATA);
+    await execute(0);
+    const takerAfter = await getAccount(connection, takerATA);
+    const expectedNet = BigInt(half.toString()) - (BigInt(half.toString()) * BigInt(FEE_BPS)) / BigInt(10_000);
+    assert.equal((takerAfter.amount - takerBefore.amount).toString(), expectedNet.toString());
+
+    const schedule = await program.account.crankSchedule.fetch(schedulePDA);
+    assert.deepEqual(schedule.kind, { releaseMilestone: { index: 1, interval: new BN(3600) } } as any);
+    assert.equal(schedule.executions, 1);
+    assert.ok(schedule.eta.gtn(0), "Re-armed for the next milestone");
+
+    try {
+      await execute(1);
+      assert.fail("Should have thrown CrankNotDue");
+    } catch (err: any) {
+      assert.include(err.message, "CrankNotDue");
+    }
+
+    await program.methods
+      .cancelCrankSchedule()
+      .accounts({ creator: stranger.publicKey, crankSchedule: schedulePDA })
+      .signers([stranger])
+      .rpc();
+    assert.isNull(await connection.getAccountInfo(schedulePDA), "Schedule closed to its creator");
+  });
+
+  // ===========================================================================
+  // 159. get_version - program version and compiled-in subsystems
+  // ===========================================================================
+  it("159. get_version: reports the crate version and only the subsystems this build ships", async () => {
+    const version: any = await program.methods
+      .getVersion()
+      .accounts({ systemProgram: SystemProgram.programId })
+      .view();
+
+    // Pinned to Cargo.toml by the program's unit tests
+    assert.equal(`${version.major}.${version.minor}.${version.patch}`, "0.15.0");
+    assert.isFalse(version.featureBits.and(new BN(FEATURE_RECEIPTS)).isZero(), "Receipts are built in");
+    assert.isTrue(version.featureBits.and(new BN(FEATURE_TOKEN_2022)).isZero(), "Token-2022 mints are rejected");
+    assert.isFalse(version.featureBits.and(new BN(FEATURE_PULL_PAYMENTS)).isZero(), "Pull payments are built in");
+  });
+
+  // ===========================================================================
+  // 160. verify_receipt - a receipt's backing, checkable before purchase
+  // ===========================================================================
+  it("160. verify_receipt: returns the beneficiary and remaining amount while Active, fails once settled", async () => {
+    const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+    const [receiptMint] = findReceiptMintPDA(escrowPDA);
+    const takerReceiptAta = getAssociatedTokenAddressSync(receiptMint, taker.publicKey);
+    await program.methods
+      .mintReceipt()
+      .accounts({
+        beneficiary: taker.publicKey,
+        payer: taker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        receiptMint,
+        beneficiaryReceiptAta: takerReceiptAta,
+        metadata: findMetadataPDA(receiptMint)[0],
+        masterEdition: findMasterEditionPDA(receiptMint)[0],
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([taker])
+      .rpc();
+
+    const verify = () =>
+      program.methods.verifyReceipt().accounts({ escrowState: escrowPDA, receiptMint }).view();
+
+    const backing: any = await verify();
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(backing.escrow.equals(escrowPDA));
+    assert.ok(backing.receiptMint.equals(receiptMint));
+    assert.ok(backing.beneficiary.equals(taker.publicKey));
+    assert.equal(backing.remainingAmount.toString(), TOTAL_AMOUNT.toString());
+    assert.ok(backing.nonce.eq(escrow.nonce));
+
+    await program.methods
+      .approveAndRelease(0)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([{ pubkey: takerReceiptAta, isSigner: false, isWritable: false }])
+      .signers([maker])
+      .rpc();
+
+    // A fully paid-out escrow no longer backs anything
+    try {
+      await verify();
+      assert.fail("Should have thrown EscrowNotActive");
+    } catch (err: any) {
+      assert.include(err.message, "EscrowNotActive");
+    }
+  });
+
+  // ===========================================================================
+  // 161. mint_receipt - the receipt is recorded before any CPI
+  // ===========================================================================
+  it("161. mint_receipt: a second mint fails, in the same transaction or later, and nothing half-minted remains", async () => {
+    const { escrowPDA } = await setupEscrow();
+    const [receiptMint] = findReceiptMintPDA(escrowPDA);
+    const mintReceiptIx = () =>
+      program.methods
+        .mintReceipt()
+        .accounts({
+          beneficiary: taker.publicKey,
+          payer: taker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          receiptMint,
+          beneficiaryReceiptAta: getAssociatedTokenAddressSync(receiptMint, taker.publicKey),
+          metadata: findMetadataPDA(receiptMint)[0],
+          masterEdition: findMasterEditionPDA(receiptMint)[0],
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .instruction();
+    const send = async (count: number) => {
+      const tx = new anchor.web3.Transaction();
+      for (let i = 0; i < count; i++) tx.add(await mintReceiptIx());
+      return anchor.web3.sendAndConfirmTransaction(connection, tx, [taker]);
+    };
+
+    // Both mints in one transaction: the whole transaction reverts
+    try {
+      await send(2);
+      assert.fail("Should have rejected the second mint");
+    } catch (err: any) {
+      assert.notInclude(err.message, "Should have rejected");
+    }
+    const untouched = await program.account.escrowState.fetch(escrowPDA);
+    assert.isNull(untouched.receiptMint);
+    assert.isNull(await connection.getAccountInfo(receiptMint), "No receipt mint left behind");
+
+    await send(1);
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.receiptMint?.equals(receiptMint));
+    assert.ok(escrow.nonce.eq(untouched.nonce.addn(1)));
+    assert.equal((await getMint(connection, receiptMint)).supply.toString(), "1");
+
+    try {
+      await send(1);
+      assert.fail("Should have rejected the second mint");
+    } catch (err: any) {
+      assert.notInclude(err.message, "Should have rejected");
+    }
+    assert.equal((await getMint(connection, receiptMint)).supply.toString(), "1");
+  });
+
+  // ===========================================================================
+  // 162. max_milestone_skew_bps - milestone sets must stay near an even split
+  // ===========================================================================
+  it("162. max_milestone_skew_bps: balanced milestone sets open, skewed ones fail with MilestoneSkewExceeded", async () => {
+    const setSkew = (bps: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, bps, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    // Up to 50% away from the even share of TOTAL_AMOUNT / 2
+    await setSkew(5_000);
+    try {
+      const quarter = TOTAL_AMOUNT.divn(4);
+      await setupEscrow({ milestoneAmounts: [quarter, TOTAL_AMOUNT.sub(quarter)] });
+      const view: any = await program.methods.getConfig().accounts({ escrowConfig: configPDA }).view();
+      assert.equal(view.maxMilestoneSkewBps, 5_000);
+
+      // A token first milestone with everything back-loaded
+      const tiny = TOTAL_AMOUNT.divn(10);
+      try {
+        await setupEscrow({ milestoneAmounts: [tiny, TOTAL_AMOUNT.sub(tiny)] });
+        assert.fail("Should have thrown MilestoneSkewExceeded");
+      } catch (err: any) {
+        assert.include(err.message, "MilestoneSkewExceeded");
+      }
+    } finally {
+      await setSkew(0);
+    }
+
+    // Off again: the same skewed set opens
+    const tiny = TOTAL_AMOUNT.divn(10);
+    await setupEscrow({ milestoneAmounts: [tiny, TOTAL_AMOUNT.sub(tiny)] });
+  });
+
+  // ===========================================================================
+  // 163. initiate_dispute - stale beneficiary blocked until the receipt is synced
+  // ===========================================================================
+  it("163. initiate_dispute: a beneficiary who sold the Receipt NFT cannot dispute", async () => {
+    try {
+      await createTokenAccount(connection, authority, mint, stranger.publicKey);
+    } catch (_) {
+      // already created by an earlier test
+    }
+    const { escrowPDA } = await setupEscrow({ initialBeneficiary: stranger.publicKey });
+
+    const [receiptMint] = findReceiptMintPDA(escrowPDA);
+    const [metadata] = findMetadataPDA(receiptMint);
+    const [masterEdition] = findMasterEditionPDA(receiptMint);
+    const strangerReceiptAta = getAssociatedTokenAddressSync(receiptMint, stranger.publicKey);
+
+    await program.methods
+      .mintReceipt()
+      .accounts({
+        beneficiary: stranger.publicKey,
+        payer: stranger.publicKey,
+        escrowState: escrowPDA,
+        receiptMint,
+        beneficiaryReceiptAta: strangerReceiptAta,
+        metadata,
+        masterEdition,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([stranger])
+      .rpc();
+
+    // Beneficiary sells the receipt; nobody calls sync_beneficiary
+    const buyer = Keypair.generate();
+    const buyerReceiptAta = await createTokenAccount(
+      connection,
+      authority,
+      receiptMint,
+      buyer.publicKey
+    );
+    await anchor.web3.sendAndConfirmTransaction(
+      connection,
+      new anchor.web3.Transaction().add(
+        createTransferInstruction(strangerReceiptAta, buyerReceiptAta, stranger.publicKey, 1)
+      ),
+      [stranger]
+    );
+
+    const reasonHash = createDescriptionHash("stale beneficiary dispute");
+    const dispute = (initiator: Keypair, receiptAccounts: PublicKey[]) =>
+      program.methods
+        .initiateDispute(reasonHash)
+        .accounts({
+          initiator: initiator.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+        })
+        .remainingAccounts(
+          receiptAccounts.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false }))
+        )
+        .signers([initiator])
+        .rpc();
+
+    for (const receiptAccounts of [[], [buyerReceiptAta]]) {
+      try {
+        await dispute(stranger, receiptAccounts);
+        assert.fail("Should have thrown BeneficiaryNotSynced");
+      } catch (err: any) {
+        assert.include(err.message, "BeneficiaryNotSynced");
+      }
+    }
+
+    await program.methods
+      .syncBeneficiary()
+      .accounts({
+        payer: authority.publicKey,
+        escrowState: escrowPDA,
+        receiptMint,
+        receiptTokenAccount: buyerReceiptAta,
+      })
+      .signers([authority])
+      .rpc();
+
+    // The seller is no longer a party; the maker can dispute once synced
+    try {
+      await dispute(stranger, [buyerReceiptAta]);
+      assert.fail("Should have thrown NotEscrowParty");
+    } catch (err: any) {
+      assert.include(err.message, "NotEscrowParty");
+    }
+    await dispute(maker, [buyerReceiptAta]);
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.disputed !== undefined);
+    assert.ok(escrow.dispute!.initiator.equals(maker.publicKey));
+  });
+
+  // ===========================================================================
+  // 164. post_bond / settle_bond - taker bond held outside the escrow amount
+  // ===========================================================================
+  it("164. post_bond / settle_bond: the taker bond stays solvent and settles per terminal route", async () => {
+    const BOND = new BN(50_000);
+    await mintTokens(connection, authority, mint, takerATA, BOND.muln(4));
+
+    const balance = async (account: PublicKey) =>
+      new BN((await getAccount(connection, account)).amount.toString());
+
+    // The vault always holds exactly the unsettled escrow amount plus the held bond
+    const assertSolvent = async (escrowPDA: PublicKey, vault: PublicKey) => {
+      const escrow = await program.account.escrowState.fetch(escrowPDA);
+      const outstanding = escrow.amount.sub(escrow.releasedAmount).sub(escrow.refundedAmount);
+      assert.equal((await balance(vault)).toString(), outstanding.add(escrow.bondBalance).toString());
+    };
+
+    const postBond = (escrowPDA: PublicKey, vault: PublicKey, signer: Keypair) =>
+      program.methods
+        .postBond()
+        .accounts({
+          taker: signer.publicKey,
+          escrowState: escrowPDA,
+          mint,
+          vault,
+          takerTokenAccount: takerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc();
+
+    const bondedEscrow = async () => {
+      const created = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT], takerBond: BOND });
+      await postBond(created.escrowPDA, created.vault, taker);
+      await assertSolvent(created.escrowPDA, created.vault);
+      return created;
+    };
+
+    const settle = (escrowPDA: PublicKey, vault: PublicKey) =>
+      program.methods
+        .settleBond()
+        .accounts({
+          payer: stranger.publicKey,
+          escrowState: escrowPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          takerTokenAccount: takerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+
+    // Settles and returns the (maker, taker) legs; the vault is empty afterwards
+    const settleLegs = async (escrowPDA: PublicKey, vault: PublicKey) => {
+      const makerBefore = await balance(makerATA);
+      const takerBefore = await balance(takerATA);
+      await settle(escrowPDA, vault);
+      assert.equal((await balance(vault)).toString(), "0");
+      const escrow = await program.account.escrowState.fetch(escrowPDA);
+      assert.ok(escrow.bondBalance.isZero());
+      return [
+        (await balance(makerATA)).sub(makerBefore).toString(),
+        (await balance(takerATA)).sub(takerBefore).toString(),
+      ];
+    };
+
+    const dispute = async (escrowPDA: PublicKey, vault: PublicKey, resolution: any) => {
+      await program.methods
+        .initiateDispute(createDescriptionHash("bonded dispute"))
+        .accounts({ initiator: maker.publicKey, escrowState: escrowPDA, escrowConfig: configPDA })
+        .signers([maker])
+        .rpc();
+      await program.methods
+        .resolveDispute(resolution, null, false, false)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          escrowState: escrowPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+      await assertSolvent(escrowPDA, vault);
+    };
+
+    // Until the bond is posted the escrow can neither be accepted nor worked on
+    const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT], takerBond: BOND });
+    let escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.awaitingAcceptance !== undefined);
+    assert.ok(escrow.takerBond.eq(BOND));
+    assert.ok(escrow.bondBalance.isZero());
+    try {
+      await program.methods
+        .acceptEscrow()
+        .accounts({ acceptor: taker.publicKey, escrowState: escrowPDA })
+        .signers([taker])
+        .rpc();
+      assert.fail("Should have thrown BondNotPosted");
+    } catch (err: any) {
+      assert.include(err.message, "BondNotPosted");
+    }
+    try {
+      await program.methods
+        .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+      assert.fail("Should have thrown EscrowNotActive");
+    } catch (err: any) {
+      assert.include(err.message, "EscrowNotActive");
+    }
+    try {
+      await postBond(escrowPDA, vault, stranger);
+      assert.fail("Should have thrown NotTaker");
+    } catch (err: any) {
+      assert.include(err.message, "NotTaker");
+    }
+
+    await postBond(escrowPDA, vault, taker);
+    escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.active !== undefined);
+    assert.ok(escrow.bondBalance.eq(BOND));
+    assert.ok(escrow.amount.eq(TOTAL_AMOUNT), "Bond is not part of the escrow amount");
+    await assertSolvent(escrowPDA, vault);
+    try {
+      await settle(escrowPDA, vault);
+      assert.fail("Should have thrown EscrowNotTerminal");
+    } catch (err: any) {
+      assert.include(err.message, "EscrowNotTerminal");
+    }
+
+    // Completed: released in full, the bond goes back to the taker
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({
+        payer: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    await assertSolvent(escrowPDA, vault);
+    const close = () =>
+      program.methods
+        .closeEscrow()
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc();
+    try {
+      await close();
+      assert.fail("Should have thrown BondNotSettled");
+    } catch (err: any) {
+      assert.include(err.message, "BondNotSettled");
+    }
+    assert.deepEqual(await settleLegs(escrowPDA, vault), ["0", BOND.toString()]);
+    try {
+      await settle(escrowPDA, vault);
+      assert.fail("Should have thrown NoBondHeld");
+    } catch (err: any) {
+      assert.include(err.message, "NoBondHeld");
+    }
+    await close();
+
+    // MakerWins ruling: the bond is forfeited to the maker
+    const makerWins = await bondedEscrow();
+    await dispute(makerWins.escrowPDA, makerWins.vault, { makerWins: {} });
+    assert.deepEqual(await settleLegs(makerWins.escrowPDA, makerWins.vault), [BOND.toString(), "0"]);
+
+    // Split ruling: the bond is split by the same bps
+    const split = await bondedEscrow();
+    await dispute(split.escrowPDA, split.vault, { split: { makerBps: 3_000 } });
+    assert.deepEqual(await settleLegs(split.escrowPDA, split.vault), ["15000", "35000"]);
+
+    // Cancellation refunds only the escrow amount; the bond goes back to the taker
+    const cancelled = await bondedEscrow();
+    await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: cancelled.escrowPDA,
+        mint,
+        vault: cancelled.vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    await assertSolvent(cancelled.escrowPDA, cancelled.vault);
+    assert.deepEqual(await settleLegs(cancelled.escrowPDA, cancelled.vault), ["0", BOND.toString()]);
+  });
+
+  // ===========================================================================
+  // 166. close_escrow - EscrowClosed carries the final dispute outcome
+  // ===========================================================================
+  it("166. close_escrow: EscrowClosed records the last dispute and its resolution", async () => {
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const closedEvent = async (escrowPDA: PublicKey, vault: PublicKey) => {
+      const sig = await program.methods
+        .closeEscrow()
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc({ commitment: "confirmed" });
+      const tx = await connection.getTransaction(sig, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const event = [...parser.parseLogs(tx!.meta!.logMessages!)].find((e) => e.name === "escrowClosed");
+      assert.ok(event, "EscrowClosed emitted");
+      return event!.data as any;
+    };
+
+    const disputed = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+    const reasonHash = createDescriptionHash("archived dispute");
+    await program.methods
+      .initiateDispute(reasonHash)
+      .accounts({ initiator: taker.publicKey, escrowState: disputed.escrowPDA, escrowConfig: configPDA })
+      .signers([taker])
+      .rpc();
+    await program.methods
+      .resolveDispute({ split: { makerBps: 3_000 } }, null, false, false)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        escrowState: disputed.escrowPDA,
+        mint,
+        vault: disputed.vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+
+    const closed = await closedEvent(disputed.escrowPDA, disputed.vault);
+    assert.equal(closed.disputeCount, 1);
+    assert.ok(closed.dispute.initiator.equals(taker.publicKey));
+    assert.deepEqual(closed.dispute.reasonHash, reasonHash);
+    assert.equal(closed.dispute.resolution.split.makerBps, 3_000);
+    assert.ok(closed.dispute.resolvedBy.equals(authority.publicKey));
+    assert.isNull(await connection.getAccountInfo(disputed.escrowPDA));
+
+    // An escrow that was never disputed closes with no dispute record
+    const undisputed = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+    await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: undisputed.escrowPDA,
+        mint,
+        vault: undisputed.vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    const clean = await closedEvent(undisputed.escrowPDA, undisputed.vault);
+    assert.equal(clean.disputeCount, 0);
+    assert.isNull(clean.dispute);
+  });
+
+  // ===========================================================================
+  // 168. list_escrow - open escrows are discoverable; acceptance, delisting
+  //      or staleness close the listing back to the maker
+  // ===========================================================================
+  it("168. list_escrow: one listing per open escrow, closed on accept, delist or when stale", async () => {
+    const LISTING_CATEGORY = 42;
+    const titleHash = createDescriptionHash("bounty: audit the vault");
+    const acceptanceDeadline = new BN(Math.floor(Date.now() / 1000) + 1800);
+
+    const list = (escrowPDA: PublicKey) =>
+      program.methods
+        .listEscrow(LISTING_CATEGORY, titleHash)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          listing: findListingPDA(escrowPDA)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+
+    // Only escrows still awaiting acceptance can be listed
+    const active = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+    try {
+      await list(active.escrowPDA);
+      assert.fail("Should have thrown NotAwaitingAcceptance");
+    } catch (err: any) {
+      assert.include(err.message, "NotAwaitingAcceptance");
+    }
+
+    const accepted = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT], acceptanceDeadline });
+    const [acceptedListing] = findListingPDA(accepted.escrowPDA);
+    await list(accepted.escrowPDA);
+    const listing = await program.account.listing.fetch(acceptedListing);
+    assert.ok(listing.escrow.equals(accepted.escrowPDA));
+    assert.ok(listing.maker.equals(maker.publicKey));
+    assert.equal(listing.category, LISTING_CATEGORY);
+    assert.ok(listing.amount.eq(TOTAL_AMOUNT));
+    assert.deepEqual(listing.titleHash, titleHash);
+
+    // Seeded by the escrow, so a second listing cannot be created
+    try {
+      await list(accepted.escrowPDA);
+      assert.fail("Should have rejected a duplicate listing");
+    } catch (err: any) {
+      assert.ok(err);
+    }
+
+    const raw = await connection.getAccountInfo(acceptedListing);
+    assert.equal(raw!.data[LISTING_CATEGORY_OFFSET], LISTING_CATEGORY, "Category byte at documented offset");
+    const listed = await program.account.listing.all([
+      {
+        memcmp: {
+          offset: LISTING_CATEGORY_OFFSET,
+          bytes: anchor.utils.bytes.bs58.encode([LISTING_CATEGORY]),
+        },
+      },
+    ]);
+    assert.ok(listed.some((l) => l.publicKey.equals(acceptedListing)));
+
+    // A live listing is not stale
+    const closeStale = (escrowPDA: PublicKey) =>
+      program.methods
+        .closeStaleListing()
+        .accounts({
+          payer: stranger.publicKey,
+          maker: maker.publicKey,
+          listing: findListingPDA(escrowPDA)[0],
+          escrowState: escrowPDA,
+        })
+        .signers([stranger])
+        .rpc();
+    try {
+      await closeStale(accepted.escrowPDA);
+      assert.fail("Should have thrown ListingStillLive");
+    } catch (err: any) {
+      assert.include(err.message, "ListingStillLive");
+    }
+
+    // Acceptance closes the listing and refunds the maker
+    const makerLamportsBefore = await connection.getBalance(maker.publicKey);
+    await program.methods
+      .acceptEscrow()
+      .accounts({
+        acceptor: taker.publicKey,
+        escrowState: accepted.escrowPDA,
+        listing: acceptedListing,
+        listingMaker: maker.publicKey,
+      })
+      .signers([taker])
+      .rpc();
+    assert.isNull(await connection.getAccountInfo(acceptedListing));
+    assert.isAbove(await connection.getBalance(maker.publicKey), makerLamportsBefore);
+
+    // The maker can withdraw a listing at any time
+    const delisted = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT], acceptanceDeadline });
+    await list(delisted.escrowPDA);
+    try {
+      await program.methods
+        .delistEscrow()
+        .accounts({ maker: stranger.publicKey, listing: findListingPDA(delisted.escrowPDA)[0] })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown NotMaker");
+    } catch (err: any) {
+      assert.include(err.message, "NotMaker");
+    }
+    await program.methods
+      .delistEscrow()
+      .accounts({ maker: maker.publicKey, listing: findListingPDA(delisted.escrowPDA)[0] })
+      .signers([maker])
+      .rpc();
+    assert.isNull(await connection.getAccountInfo(findListingPDA(delisted.escrowPDA)[0]));
+
+    // A cancelled escrow's listing is stale and closable by anyone
+    const cancelled = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT], acceptanceDeadline });
+    await list(cancelled.escrowPDA);
+    await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: cancelled.escrowPDA,
+        mint,
+        vault: cancelled.vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    await closeStale(cancelled.escrowPDA);
+    assert.isNull(await connection.getAccountInfo(findListingPDA(cancelled.escrowPDA)[0]));
+  });
+
+  // ===========================================================================
+  // 169. Milestone beneficiaries - a milestone can pay its own recipient
+  // ===========================================================================
+  it("169. release_milestone: pays a milestone-level beneficiary instead of the escrow beneficiary", async () => {
+    const designer = Keypair.generate();
+    const designerATA = await createTokenAccount(connection, authority, mint, designer.publicKey);
+    const milestoneAmounts = [new BN(400_000), new BN(600_000)];
+
+    // The maker cannot name itself as a milestone payee
+    try {
+      await setupEscrow({ milestoneAmounts, milestoneBeneficiaries: [null, maker.publicKey] });
+      assert.fail("Should have thrown MilestoneBeneficiaryIsMaker");
+    } catch (err: any) {
+      assert.include(err.message, "MilestoneBeneficiaryIsMaker");
+    }
+
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts,
+      milestoneBeneficiaries: [null, designer.publicKey],
+    });
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.isNull(escrow.milestones[0].beneficiary);
+    assert.ok(escrow.milestones[1].beneficiary.equals(designer.publicKey));
+
+    for (const index of [0, 1]) {
+      await program.methods
+        .approveMilestone(index, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+    }
+
+    const release = (index: number, beneficiaryTokenAccount: PublicKey) =>
+      program.methods
+        .releaseMilestone(index, null, false)
+        .accounts({
+          payer: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc();
+
+    // The escrow beneficiary's account is rejected for the overridden milestone
+    try {
+      await release(1, takerATA);
+      assert.fail("Should have thrown OwnerMismatch");
+    } catch (err: any) {
+      assert.include(err.message, "OwnerMismatch");
+    }
+
+    const takerBefore = (await getAccount(connection, takerATA)).amount;
+    const feeBefore = (await getAccount(connection, feeCollectorATA)).amount;
+    await release(0, takerATA);
+    const takerGain = (await getAccount(connection, takerATA)).amount - takerBefore;
+    await release(1, designerATA);
+
+    // Milestone 0 went to the taker, milestone 1 to the designer, net of fees
+    const designerGain = (await getAccount(connection, designerATA)).amount;
+    const feeGain = (await getAccount(connection, feeCollectorATA)).amount - feeBefore;
+    assert.isTrue(takerGain > BigInt(0) && takerGain <= BigInt(400_000));
+    assert.isTrue(designerGain > BigInt(400_000) && designerGain <= BigInt(600_000));
+    assert.equal((takerGain + designerGain + feeGain).toString(), "1000000");
+    const completed = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(completed.status.completed !== undefined);
+  });
+
+  // ===========================================================================
+  // 170. initiate_dispute - a zero dispute cap means unlimited
+  // ===========================================================================
+  it("170. initiate_dispute: max_disputes_per_escrow = 0 lifts the cap", async () => {
+    const setCap = (cap: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, cap, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await setCap(0);
+    try {
+      const config = await program.account.escrowConfig.fetch(configPDA);
+      assert.equal(config.maxDisputesPerEscrow, 0);
+
+      const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+      for (let round = 1; round <= 4; round++) {
+        await program.methods
+          .initiateDispute(createDescriptionHash(`dispute ${round}`))
+          .accounts({ initiator: taker.publicKey, escrowState: escrowPDA, escrowConfig: configPDA })
+          .signers([taker])
+          .rpc();
+        const escrow = await program.account.escrowState.fetch(escrowPDA);
+        assert.equal(escrow.disputeCount, round);
+
+        // Each ruling completes the cycle; renewal makes the escrow disputable again
+        await program.methods
+          .resolveDispute({ takerWins: {} }, null, false, false)
+          .accounts({
+            authority: authority.publicKey,
+            escrowConfig: configPDA,
+            escrowState: escrowPDA,
+            mint,
+            vault,
+            makerTokenAccount: makerATA,
+            beneficiaryTokenAccount: takerATA,
+            feeCollectorTokenAccount: feeCollectorATA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([authority])
+          .rpc();
+        await program.methods
+          .renewEscrow(
+            new BN(100_000),
+            makeMilestones([new BN(100_000)], [`cycle-${round + 1}`]),
+            escrow.expiresAt.addn(3600)
+          )
+          .accounts({
+            maker: maker.publicKey,
+            beneficiary: taker.publicKey,
+            escrowState: escrowPDA,
+            escrowConfig: configPDA,
+            mint,
+            vault,
+            makerTokenAccount: makerATA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([maker, taker])
+          .rpc();
+      }
+    } finally {
+      await setCap(3);
+    }
+  });
+
+  // ===========================================================================
+  // 172. derive_vault_address - vault ATA derivation checked before create_escrow
+  // ===========================================================================
+  it("172. derive_vault_address: matches the vault create_escrow initializes and rejects a mismatched token program", async () => {
+    const deriveVault = (escrowPDA: PublicKey, tokenProgram: PublicKey) =>
+      program.methods
+        .deriveVaultAddress(escrowPDA)
+        .accounts({ mint, tokenProgram })
+        .view();
+
+    // The escrow PDA need not exist yet
+    const [futurePDA] = findEscrowPDA(maker.publicKey, new BN(987_654_321));
+    const expected = getAssociatedTokenAddressSync(mint, futurePDA, true, TOKEN_PROGRAM_ID);
+    assert.ok(((await deriveVault(futurePDA, TOKEN_PROGRAM_ID)) as PublicKey).equals(expected));
+
+    const { escrowPDA, vault } = await setupEscrow();
+    assert.ok(((await deriveVault(escrowPDA, TOKEN_PROGRAM_ID)) as PublicKey).equals(vault));
+
+    try {
+      await deriveVault(escrowPDA, TOKEN_2022_PROGRAM_ID);
+      assert.fail("Should have thrown ConstraintMintTokenProgram");
+    } catch (err: any) {
+      assert.include(err.message, "ConstraintMintTokenProgram");
+    }
+  });
+
+  // ===========================================================================
+  // 173. cancel routing - EscrowCancelled reports the reason and applied routing
+  // ===========================================================================
+  it("173. cancel_escrow: routes the fee per the maker-cancel policy and reports it in EscrowCancelled", async () => {
+    const setCancelFee = (bps: number, recipient: object) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, null, bps, recipient, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await setCancelFee(1_000, { beneficiary: {} });
+    const { escrowPDA, vault } = await setupEscrow();
+    await setCancelFee(0, { feeCollector: {} });
+
+    const takerBefore = await getAccount(connection, takerATA);
+    const sig = await program.methods
+      .cancelEscrow(null)
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        escrowConfig: configPDA,
+        cancelFeeTokenAccount: takerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc({ commitment: "confirmed" });
+    const takerAfter = await getAccount(connection, takerATA);
+
+    // 10% of the 1_000_000 refund goes to the beneficiary
+    assert.equal((takerAfter.amount - takerBefore.amount).toString(), "100000");
+
+    const tx = await connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const cancelled = [...parser.parseLogs(tx!.meta!.logMessages!)].find((e) => e.name === "escrowCancelled");
+    assert.ok(cancelled, "EscrowCancelled emitted");
+    assert.ok(cancelled!.data.reason.makerCancel !== undefined);
+    assert.equal(cancelled!.data.routing.feeBps, 1_000);
+    assert.ok(cancelled!.data.routing.feeRecipient.beneficiary !== undefined);
+    assert.equal(cancelled!.data.cancelFee.toString(), "100000");
+    assert.equal(cancelled!.data.refundedAmount.toString(), "900000");
+  });
+
+  // ===========================================================================
+  // 180. Milestone beneficiaries in bulk settlements - each payee paid, disputes refused
+  // ===========================================================================
+  it("180. cancel_and_release: pays an approved milestone's own beneficiary; disputes are refused", async () => {
+    const designer = Keypair.generate();
+    const designerATA = await createTokenAccount(connection, authority, mint, designer.publicKey);
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [new BN(400_000), new BN(600_000)],
+      milestoneBeneficiaries: [null, designer.publicKey],
+    });
+
+    // A ruling splits the open balance as a whole and could not pay the designer
+    try {
+      await program.methods
+        .initiateDispute(createDescriptionHash("multi-payee"))
+        .accounts({ initiator: taker.publicKey, escrowState: escrowPDA, escrowConfig: configPDA })
+        .signers([taker])
+        .rpc();
+      assert.fail("Should have thrown DisputeWithMilestoneBeneficiary");
+    } catch (err: any) {
+      assert.include(err.message, "DisputeWithMilestoneBeneficiary");
+    }
+
+    for (const index of [0, 1]) {
+      await program.methods
+        .approveMilestone(index, ANY_AMOUNT, ANY_HASH, null)
+        .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+        .signers([maker])
+        .rpc();
+    }
+    const cancelAndRelease = (payees: PublicKey[]) =>
+      program.methods
+        .cancelAndRelease(null)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          makerTokenAccount: makerATA,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(payees.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+        .signers([maker])
+        .rpc();
+
+    try {
+      await cancelAndRelease([]);
+      assert.fail("Should have thrown PayeeAccountMissing");
+    } catch (err: any) {
+      assert.include(err.message, "PayeeAccountMissing");
+    }
+
+    const takerBefore = (await getAccount(connection, takerATA)).amount;
+    const designerBefore = (await getAccount(connection, designerATA)).amount;
+    await cancelAndRelease([designerATA]);
+    assert.equal(((await getAccount(connection, takerATA)).amount - takerBefore).toString(), "390000");
+    assert.equal(((await getAccount(connection, designerATA)).amount - designerBefore).toString(), "585000");
+  });
+
+  // ===========================================================================
+  // 183. renew_escrow - renewing a Completed escrow takes an open slot again
+  // ===========================================================================
+  it("183. renew_escrow: a renewed Completed escrow re-reserves its slot under max_open_escrows_per_maker", async () => {
+    const setCap = (cap: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, null, null, null, null, null, null, null, null, null, null, cap, null, null, null, null, null, null, null, null, null, null)
+        .accounts({
+          authority: authority.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: feeCollector.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    const [profilePDA] = findMakerProfilePDA(maker.publicKey);
+    const openEscrows = async () =>
+      (await program.account.makerProfile.fetch(profilePDA)).openEscrows;
+
+    const baseline = await openEscrows();
+    await setCap(baseline + 1);
+    try {
+      const { escrowPDA, vault } = await setupEscrow({ milestoneAmounts: [TOTAL_AMOUNT] });
+      await program.methods
+        .approveAndRelease(0)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: escrowPDA,
+          escrowConfig: configPDA,
+          mint,
+          vault,
+          beneficiaryTokenAccount: takerATA,
+          feeCollectorTokenAccount: feeCollectorATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          makerProfile: profilePDA,
+        })
+        .signers([maker])
+        .rpc();
+      assert.equal(await openEscrows(), baseline, "Completing gives the slot back");
+
+      // Another escrow takes the only free slot
+      const blocker = await setupEscrow();
+      assert.equal(await openEscrows(), baseline + 1);
+
+      const topUp = new BN(500_000);
+      const renew = (makerProfile: PublicKey | null) =>
+        program.methods
+          .renewEscrow(topUp, makeMilestones([topUp], ["reopened-cycle"]), new BN(Math.floor(Date.now() / 1000) + 7200))
+          .accounts({
+            maker: maker.publicKey,
+            beneficiary: taker.publicKey,
+            escrowState: escrowPDA,
+            escrowConfig: configPDA,
+            mint,
+            vault,
+            makerTokenAccount: makerATA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            approval: null,
+            makerProfile,
+          })
+          .signers([maker, taker])
+          .rpc();
+
+      try {
+        await renew(null);
+        assert.fail("Should have thrown MakerProfileMissing");
+      } catch (err: any) {
+        assert.include(err.message, "MakerProfileMissing");
+      }
+      try {
+        await renew(profilePDA);
+        assert.fail("Should have thrown TooManyOpenEscrows");
+      } catch (err: any) {
+        assert.include(err.message, "TooManyOpenEscrows");
+      }
+
+      await program.methods
+        .cancelEscrow(null)
+        .accounts({
+          maker: maker.publicKey,
+          escrowState: blocker.escrowPDA,
+          mint,
+          vault: blocker.vault,
+          makerTokenAccount: makerATA,
+          escrowConfig: configPDA,
+          cancelFeeTokenAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          makerProfile: profilePDA,
+        })
+        .signers([maker])
+        .rpc();
+      assert.equal(await openEscrows(), baseline);
+
+      await renew(profilePDA);
+      const escrow = await program.account.escrowState.fetch(escrowPDA);
+      assert.ok(escrow.status.active !== undefined);
+      assert.isTrue(escrow.countedOpen);
+      assert.equal(await openEscrows(), baseline + 1);
+    } finally {
+      await setCap(0);
+    }
+  });
+
+  // ===========================================================================
+  // 184. resolve_dispute loser_pays_fee - a MakerWins ruling charges nobody
+  // ===========================================================================
+  it("184. resolve_dispute: loser_pays_fee with MakerWins needs no loser account and records no payer", async () => {
+    const { escrowPDA, vault } = await setupEscrow();
+    await program.methods
+      .initiateDispute(createDescriptionHash("maker wins, loser pays"))
+      .accounts({
+        initiator: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+      })
+      .signers([maker])
+      .rpc();
+
+    const makerBefore = await getAccount(connection, makerATA);
+    const feeBefore = await getAccount(connection, feeCollectorATA);
+    // No loser_token_account and no delegation: the refund carries no fee
+    const sig = await program.methods
+      .resolveDispute({ makerWins: {} }, null, false, true)
+      .accounts({
+        authority: authority.publicKey,
+        escrowConfig: configPDA,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        loserTokenAccount: null,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+
+    const makerAfter = await getAccount(connection, makerATA);
+    const feeAfter = await getAccount(connection, feeCollectorATA);
+    assert.equal((makerAfter.amount - makerBefore.amount).toString(), "1000000", "maker refunded in full");
+    assert.equal(feeAfter.amount.toString(), feeBefore.amount.toString(), "no fee charged");
+
+    const tx = await connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const resolved = [...parser.parseLogs(tx!.meta!.logMessages!)].find((e) => e.name === "disputeResolved");
+    assert.ok(resolved, "DisputeResolved emitted");
+    assert.isNull(resolved!.data.feePaidBy, "nobody paid a fee");
+  });
+
+  // ===========================================================================
+  // 185. initiate_dispute - fail: nothing left in the vault to dispute
+  // ===========================================================================
+  it("185. initiate_dispute: fails once every milestone is paid out (NoRefundableAmount)", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      amount: TOTAL_AMOUNT,
+    });
+
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .releaseMilestone(0, null, false)
+      .accounts({
+        payer: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.releasedAmount.eq(escrow.amount), "Nothing remains");
+
+    for (const initiator of [maker, taker]) {
+      try {
+        await program.methods
+          .initiateDispute(createDescriptionHash("too late"))
+          .accounts({
+            initiator: initiator.publicKey,
+            escrowState: escrowPDA,
+            escrowConfig: configPDA,
+          })
+          .signers([initiator])
+          .rpc();
+        assert.fail("Should have thrown NoRefundableAmount");
+      } catch (err: any) {
+        assert.include(err.message, "NoRefundableAmount");
+      }
+    }
+  });
+
+  // ===========================================================================
+  // 187. release_milestone auto_close - best effort when the close is blocked
+  // ===========================================================================
+  it("187. release_milestone: auto_close leaves the escrow open when a close precondition is unmet", async () => {
+    const { escrowPDA, vault } = await setupEscrow({
+      milestoneAmounts: [TOTAL_AMOUNT],
+      amount: TOTAL_AMOUNT,
+    });
+    const [metadataPDA] = findEscrowMetadataPDA(escrowPDA);
+    await program.methods
+      .attachMetadata(Buffer.from("auto-close"))
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowMetadata: metadataPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([maker])
+      .rpc();
+    await program.methods
+      .approveMilestone(0, ANY_AMOUNT, ANY_HASH, null)
+      .accounts({ maker: maker.publicKey, escrowState: escrowPDA })
+      .signers([maker])
+      .rpc();
+
+    // The metadata account isn't passed, so the close can't happen; the
+    // final release still goes through
+    const takerBefore = await getAccount(connection, takerATA);
+    await program.methods
+      .releaseMilestone(0, null, true)
+      .accounts({
+        payer: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        beneficiaryTokenAccount: takerATA,
+        feeCollectorTokenAccount: feeCollectorATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        maker: maker.publicKey,
+        makerTokenAccount: makerATA,
+      })
+      .signers([maker])
+      .rpc();
+
+    const takerAfter = await getAccount(connection, takerATA);
+    assert.isTrue(takerAfter.amount > takerBefore.amount, "milestone paid out");
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.completed !== undefined);
+    assert.isNotNull(await connection.getAccountInfo(vault), "vault left open");
+
+    // close_escrow finishes the job once given the metadata account
+    await program.methods
+      .closeEscrow()
+      .accounts({
+        maker: maker.publicKey,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        makerTokenAccount: makerATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        escrowMetadata: metadataPDA,
+      })
+      .signers([maker])
+      .rpc();
+    assert.isNull(await connection.getAccountInfo(escrowPDA));
+    assert.isNull(await connection.getAccountInfo(metadataPDA));
+  });
 });
@@ -0,0 +1,236 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Escrow } from "../target/types/escrow";
+import { EscrowParty } from "../target/types/escrow_party";
+import {
+  createMint,
+  mintTo,
+  getAccount,
+  TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  createAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  TOKEN_METADATA_PROGRAM_ID,
+  findEscrowConfigPDA,
+  findEscrowPDA,
+  findMakerProfilePDA,
+  findReceiptMintPDA,
+  findMetadataPDA,
+  findMasterEditionPDA,
+  createDescriptionHash,
+  makeMilestones,
+  DISPUTE_INITIATORS_ALL,
+} from "../client/pda";
+
+// ---------------------------------------------------------------------------
+// Escrow parties that are programs: a wrapper program's data-holding PDA signs
+// via CPI as maker, dispute initiator and receipt beneficiary, while the
+// wrapper's owner pays the rent.
+// ---------------------------------------------------------------------------
+
+const TOTAL_AMOUNT = new BN(1_000_000);
+
+describe("escrow: program parties via CPI", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Escrow as Program<Escrow>;
+  const wrapper = anchor.workspace.EscrowParty as Program<EscrowParty>;
+  const connection = provider.connection;
+
+  let owner: Keypair;
+  let mint: PublicKey;
+  let partyPDA: PublicKey;
+  let partyATA: PublicKey;
+  let ownerATA: PublicKey;
+  const [configPDA] = findEscrowConfigPDA();
+
+  let seedCounter = 0;
+  const nextSeed = () => new BN(Date.now()).muln(1000).addn(seedCounter++);
+  const expiresAt = () => new BN(Math.floor(Date.now() / 1000) + 3600);
+
+  before(async () => {
+    owner = Keypair.generate();
+    const sig = await connection.requestAirdrop(owner.publicKey, 10 * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(sig, "confirmed");
+
+    // Runs standalone too: the config is a singleton shared with the main suite
+    if ((await program.account.escrowConfig.fetchNullable(configPDA)) === null) {
+      await program.methods
+        .initializeConfig(250, new BN(86400))
+        .accounts({
+          authority: owner.publicKey,
+          escrowConfig: configPDA,
+          feeCollector: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+    }
+
+    [partyPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("party"), owner.publicKey.toBuffer()],
+      wrapper.programId
+    );
+    await wrapper.methods
+      .initParty()
+      .accounts({ owner: owner.publicKey, party: partyPDA, systemProgram: SystemProgram.programId })
+      .signers([owner])
+      .rpc();
+
+    mint = await createMint(connection, owner, owner.publicKey, null, 6);
+    partyATA = await createAssociatedTokenAccount(connection, owner, mint, partyPDA, undefined, undefined, undefined, true);
+    ownerATA = await createAssociatedTokenAccount(connection, owner, mint, owner.publicKey);
+    await mintTo(connection, owner, mint, partyATA, owner, 10_000_000);
+    await mintTo(connection, owner, mint, ownerATA, owner, 10_000_000);
+  });
+
+  // The owner funds an escrow whose taker (and beneficiary) is the party PDA
+  async function escrowWithPartyTaker(): Promise<PublicKey> {
+    const seed = nextSeed();
+    const [escrowPDA] = findEscrowPDA(owner.publicKey, seed);
+    await program.methods
+      .createEscrow(seed, TOTAL_AMOUNT, makeMilestones([TOTAL_AMOUNT], ["cpi-taker"]), expiresAt(), 0, null, 0, null, false, new BN(0), true, DISPUTE_INITIATORS_ALL, false, null, new BN(0))
+      .accounts({
+        maker: owner.publicKey,
+        payer: owner.publicKey,
+        taker: partyPDA,
+        mint,
+        escrowState: escrowPDA,
+        vault: getAssociatedTokenAddressSync(mint, escrowPDA, true),
+        makerTokenAccount: ownerATA,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+    return escrowPDA;
+  }
+
+  it("1. PDA maker: creates, cancels and closes an escrow; its owner pays rent and the PDA gets it back", async () => {
+    const taker = Keypair.generate();
+    const seed = nextSeed();
+    const [escrowPDA] = findEscrowPDA(partyPDA, seed);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPDA, true);
+    const [makerProfile] = findMakerProfilePDA(partyPDA);
+
+    const partyLamportsBefore = await connection.getBalance(partyPDA);
+    await wrapper.methods
+      .createEscrow(seed, TOTAL_AMOUNT, makeMilestones([TOTAL_AMOUNT], ["cpi-maker"]), expiresAt())
+      .accounts({
+        owner: owner.publicKey,
+        party: partyPDA,
+        taker: taker.publicKey,
+        escrowConfig: configPDA,
+        mint,
+        escrowState: escrowPDA,
+        makerProfile,
+        vault,
+        partyTokenAccount: partyATA,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        escrowProgram: program.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.maker.equals(partyPDA), "PDA is the maker");
+    assert.equal((await getAccount(connection, vault)).amount.toString(), TOTAL_AMOUNT.toString());
+    assert.equal(await connection.getBalance(partyPDA), partyLamportsBefore, "Party paid no rent");
+
+    await wrapper.methods
+      .cancelEscrow()
+      .accounts({
+        owner: owner.publicKey,
+        party: partyPDA,
+        escrowState: escrowPDA,
+        mint,
+        vault,
+        partyTokenAccount: partyATA,
+        escrowConfig: configPDA,
+        cancelFeeTokenAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        makerProfile,
+        escrowProgram: program.programId,
+      })
+      .signers([owner])
+      .rpc();
+    assert.ok((await program.account.escrowState.fetch(escrowPDA)).status.cancelled !== undefined);
+
+    await wrapper.methods
+      .closeEscrow()
+      .accounts({
+        owner: owner.publicKey,
+        party: partyPDA,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        mint,
+        vault,
+        partyTokenAccount: partyATA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        escrowProgram: program.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    assert.isNull(await connection.getAccountInfo(escrowPDA), "Escrow closed");
+    assert.isAbove(await connection.getBalance(partyPDA), partyLamportsBefore, "Rent refunded to the PDA maker");
+  });
+
+  it("2. PDA taker: initiates a dispute by signing through the wrapper", async () => {
+    const escrowPDA = await escrowWithPartyTaker();
+
+    await wrapper.methods
+      .initiateDispute(createDescriptionHash("cpi-dispute"))
+      .accounts({
+        owner: owner.publicKey,
+        party: partyPDA,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        escrowProgram: program.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    const escrow = await program.account.escrowState.fetch(escrowPDA);
+    assert.ok(escrow.status.disputed !== undefined);
+  });
+
+  it("3. PDA beneficiary: mints a receipt NFT with its owner as payer", async () => {
+    const escrowPDA = await escrowWithPartyTaker();
+    const [receiptMint] = findReceiptMintPDA(escrowPDA);
+    const [metadata] = findMetadataPDA(receiptMint);
+    const [masterEdition] = findMasterEditionPDA(receiptMint);
+    const partyReceiptAta = getAssociatedTokenAddressSync(receiptMint, partyPDA, true);
+
+    await wrapper.methods
+      .mintReceipt()
+      .accounts({
+        owner: owner.publicKey,
+        party: partyPDA,
+        escrowState: escrowPDA,
+        escrowConfig: configPDA,
+        receiptMint,
+        partyReceiptAta,
+        metadata,
+        masterEdition,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+        escrowProgram: program.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    assert.equal((await getAccount(connection, partyReceiptAta)).amount.toString(), "1");
+    assert.ok((await program.account.escrowState.fetch(escrowPDA)).receiptMint?.equals(receiptMint));
+  });
+});